@@ -482,7 +482,7 @@ export const ShadowProtocolProvider: React.FC<ShadowProtocolProviderProps> = ({
       const bidsResponse = await fetch(`/api/bids?auctionId=${auctionId}`);
       const allBids = await bidsResponse.json();
       
-      const { queueAuctionComputation, pollComputationResult, verifyComputationProof, encryptReservePriceForMXE, initializeMXECluster, getComputationStatus } = await import('@/lib/arciumMPC');
+      const { queueAuctionComputation, pollComputationResult, verifyComputationProof, initializeMXECluster, getComputationStatus } = await import('@/lib/arciumMPC');
       
       const encryptedBids = allBids.map((bid: any) => ({
         bidder: bid.bidder,
@@ -492,9 +492,6 @@ export const ShadowProtocolProvider: React.FC<ShadowProtocolProviderProps> = ({
         timestamp: new Date(bid.createdAt).getTime(),
       }));
       
-      const minBid = auction.minimumBid ? parseFloat(auction.minimumBid) / 1e9 : 0.01;
-      const reservePrice = minBid;
-      
       toast.dismiss(loadingToast);
       const processingToast = toast.loading('⚡ Processing bids with Arcium MPC...');
       
@@ -505,14 +502,12 @@ export const ShadowProtocolProvider: React.FC<ShadowProtocolProviderProps> = ({
         }
         
         const mxeCluster = await initializeMXECluster(connection);
-        const { encryptedPrice } = await encryptReservePriceForMXE(reservePrice, mxeCluster);
         
         const computationSignature = await queueAuctionComputation(
           provider,
           program,
           auctionId,
           encryptedBids,
-          encryptedPrice,
           mxeCluster
         );
         
@@ -741,12 +736,9 @@ export const ShadowProtocolProvider: React.FC<ShadowProtocolProviderProps> = ({
       toast.dismiss(mainToast);
       const mpcToast = toast.loading('🔐 Executing MPC computation...');
       
-      const { queueAuctionComputation, monitorComputationProgress, verifyComputationProof, encryptReservePriceForMXE, initializeMXECluster } = await import('@/lib/arciumMPC');
+      const { queueAuctionComputation, monitorComputationProgress, verifyComputationProof, initializeMXECluster } = await import('@/lib/arciumMPC');
       
       const mxeCluster = await initializeMXECluster(connection);
-      const minimumBidValue = typeof auction.minimumBid === 'string' ? parseFloat(auction.minimumBid) : auction.minimumBid;
-      const reservePrice = (auction as any).reservePrice || minimumBidValue / 1e9;
-      const { encryptedPrice } = await encryptReservePriceForMXE(reservePrice, mxeCluster);
       
       console.log('Queueing MPC computation...');
       const computationSignature = await queueAuctionComputation(
@@ -754,7 +746,6 @@ export const ShadowProtocolProvider: React.FC<ShadowProtocolProviderProps> = ({
         program,
         auctionId,
         encryptedBids,
-        encryptedPrice,
         mxeCluster
       );
       
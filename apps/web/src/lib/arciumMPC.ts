@@ -8,6 +8,8 @@ const ARCIUM_CONFIG = {
   arciumProgramId: new PublicKey(process.env.NEXT_PUBLIC_ARCIUM_PROGRAM_ID || 'ArCiUMC5wQJBBJ1kSCaZgPhqVAPKjPqj2Z3mhzeMdNQc'),
   computationGas: parseInt(process.env.NEXT_PUBLIC_ARCIUM_COMPUTATION_GAS || '1000000'),
   clusterOffset: parseInt(process.env.NEXT_PUBLIC_ARCIUM_CLUSTER_OFFSET || '2326510165'),
+  // Must match SEALED_BID_CIRCUIT_CAPACITY in the program
+  circuitBidCapacity: 64,
 };
 
 export interface EncryptedBid {
@@ -67,19 +69,26 @@ export async function queueAuctionComputation(
   program: Program,
   auctionId: string,
   encryptedBids: EncryptedBid[],
-  encryptedReservePrice: Uint8Array,
   mxeCluster: MXECluster
 ): Promise<string> {
-  const computationInstruction = await createComputationInstruction(
-    program,
-    auctionId,
-    encryptedBids,
-    encryptedReservePrice,
-    mxeCluster
-  );
+  const chunkCount = Math.max(1, Math.ceil(encryptedBids.length / ARCIUM_CONFIG.circuitBidCapacity));
+  let signature = '';
+
+  // Each circuit invocation takes a fixed-size chunk of bids
+  for (let chunkIndex = 0; chunkIndex < chunkCount; chunkIndex++) {
+    const start = chunkIndex * ARCIUM_CONFIG.circuitBidCapacity;
+    const computationInstruction = await createComputationInstruction(
+      program,
+      auctionId,
+      encryptedBids.slice(start, start + ARCIUM_CONFIG.circuitBidCapacity),
+      mxeCluster,
+      chunkIndex
+    );
+
+    const transaction = new Transaction().add(computationInstruction);
+    signature = await provider.sendAndConfirm(transaction);
+  }
 
-  const transaction = new Transaction().add(computationInstruction);
-  const signature = await provider.sendAndConfirm(transaction);
   return signature;
 }
 
@@ -88,38 +97,52 @@ async function createComputationInstruction(
   program: Program,
   auctionId: string,
   encryptedBids: EncryptedBid[],
-  encryptedReservePrice: Uint8Array,
-  mxeCluster: MXECluster
+  mxeCluster: MXECluster,
+  chunkIndex: number
 ): Promise<TransactionInstruction> {
   const auctionIdNum = parseInt(auctionId);
   const bidsCount = encryptedBids.length;
 
-  const encryptedBidsForProgram = encryptedBids.map(bid => ({
+  // Collateral and submission time are checked against each bid account,
+  // which is passed in the same order as the bids
+  const bidPDAs = encryptedBids.map(bid => PublicKey.findProgramAddressSync(
+    [Buffer.from('bid'), new BN(auctionIdNum).toArrayLike(Buffer, 'le', 8), new PublicKey(bid.bidder).toBuffer()],
+    program.programId
+  )[0]);
+  const bidAccounts = await (program.account as any).bidAccount.fetchMultiple(bidPDAs);
+
+  const encryptedBidsForProgram = encryptedBids.map((bid, i) => ({
     bidder: new PublicKey(bid.bidder),
     encryptedAmount: Array.from(bid.encryptedAmount),
     nonce: Array.from(bid.nonce.slice(0, 16)),
-    publicKey: Array.from(bid.publicKey)
+    publicKey: Array.from(bid.publicKey),
+    collateralAmount: bidAccounts[i].collateralAmount,
+    timestamp: bidAccounts[i].timestamp,
   }));
 
-  const [auctionPDA] = PublicKey.findProgramAddressSync(
-    [Buffer.from('auction'), new BN(auctionIdNum).toArrayLike(Buffer, 'le', 8)],
-    program.programId
-  );
+  // Auction addresses are seeded by creator and nonce; look it up by id
+  const [{ publicKey: auctionPDA }] = await (program.account as any).auctionAccount.all([
+    { memcmp: { offset: 8, bytes: new BN(auctionIdNum).toArrayLike(Buffer, 'le', 8).toString('base64') } },
+  ]);
 
   const [protocolPDA] = PublicKey.findProgramAddressSync(
     [Buffer.from('protocol')],
     program.programId
   );
 
+  // The program only accepts the reserve committed when the auction was created
+  const auction = await (program.account as any).auctionAccount.fetch(auctionPDA);
+
   return await program.methods
-    .queueMpcComputation(
-      new BN(auctionIdNum),
+    .queueMpcComputation(new BN(auctionIdNum), {
       bidsCount,
-      encryptedBidsForProgram,
-      Array.from(encryptedReservePrice),
-      mxeCluster.address,
-      new BN(ARCIUM_CONFIG.computationGas)
-    )
+      encryptedBids: encryptedBidsForProgram,
+      encryptedReservePrice: auction.reservePriceEncrypted,
+      reservePriceNonce: auction.reservePriceNonce,
+      mxeCluster: mxeCluster.address,
+      gasLimit: new BN(ARCIUM_CONFIG.computationGas),
+      chunkIndex,
+    } as any)
     .accounts({
       authority: program.provider.publicKey!,
       auction: auctionPDA,
@@ -128,6 +151,7 @@ async function createComputationInstruction(
       arciumProgram: ARCIUM_CONFIG.arciumProgramId,
       systemProgram: SystemProgram.programId,
     })
+    .remainingAccounts(bidPDAs.map(pubkey => ({ pubkey, isSigner: false, isWritable: false })))
     .instruction();
 }
 
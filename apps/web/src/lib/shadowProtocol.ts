@@ -23,6 +23,8 @@ export const AUCTION_SEED = Buffer.from('auction');
 export const BID_SEED = Buffer.from('bid');
 export const ASSET_VAULT_SEED = Buffer.from('asset_vault');
 export const BID_ESCROW_SEED = Buffer.from('bid_escrow');
+export const BATCH_SEED = Buffer.from('batch');
+export const CREATOR_INDEX_SEED = Buffer.from('creator_index');
 
 export interface CreateAuctionParams {
   assetMint: PublicKey;
@@ -48,16 +50,35 @@ export function getProtocolPDA(): [PublicKey, number] {
   );
 }
 
-export function getAuctionPDA(auctionId: BN): [PublicKey, number] {
+// Mirrors AuctionAccount::address_digest: auctions live at an address seeded
+// by their creator and a nonce of the creator's choosing
+export async function getAuctionPDA(creator: PublicKey, creatorNonce: BN): Promise<[PublicKey, number]> {
+  const seed = await crypto.subtle.digest('SHA-256', Buffer.concat([
+    Buffer.from('shadow_auction_address'),
+    creator.toBuffer(),
+    creatorNonce.toArrayLike(Buffer, 'le', 8),
+  ]));
   return PublicKey.findProgramAddressSync(
-    [AUCTION_SEED, auctionId.toArrayLike(Buffer, 'le', 8)],
+    [AUCTION_SEED, Buffer.from(seed)],
     PROGRAM_ID
   );
 }
 
-export function getAssetVaultPDA(auctionId: BN): [PublicKey, number] {
+// Auction ids are assigned on creation, so an existing auction is found by
+// the id at the start of its account data
+export async function findAuctionPDA(program: Program, auctionId: BN): Promise<PublicKey> {
+  const auctions = await (program.account as any).auctionAccount.all([
+    { memcmp: { offset: 8, bytes: auctionId.toArrayLike(Buffer, 'le', 8).toString('base64') } },
+  ]);
+  if (auctions.length === 0) {
+    throw new Error(`Auction ${auctionId.toString()} not found`);
+  }
+  return auctions[0].publicKey;
+}
+
+export function getAssetVaultPDA(auction: PublicKey): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
-    [ASSET_VAULT_SEED, auctionId.toArrayLike(Buffer, 'le', 8)],
+    [ASSET_VAULT_SEED, auction.toBuffer()],
     PROGRAM_ID
   );
 }
@@ -76,6 +97,20 @@ export function getBidEscrowPDA(auctionId: BN): [PublicKey, number] {
   );
 }
 
+export function getBatchPDA(batchId: BN): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [BATCH_SEED, batchId.toArrayLike(Buffer, 'le', 8)],
+    PROGRAM_ID
+  );
+}
+
+export function getCreatorIndexPDA(creator: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [CREATOR_INDEX_SEED, creator.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
 export async function encryptBidAmount(
   amount: number,
   mxePublicKey?: Uint8Array
@@ -182,6 +217,35 @@ export async function encryptReservePrice(
   throw new Error('Valid MXE public key is required for reserve price encryption');
 }
 
+// Mirrors CryptoUtils::generate_reserve_commitment in the program
+export async function reserveCommitment(
+  price: number,
+  blinding: Uint8Array,
+  encrypted: Uint8Array,
+  nonce: bigint
+): Promise<Uint8Array> {
+  const digest = await crypto.subtle.digest('SHA-256', Buffer.concat([
+    Buffer.from('shadow_reserve_commitment_v1'),
+    new BN(Math.floor(price * LAMPORTS_PER_SOL).toString()).toArrayLike(Buffer, 'le', 8),
+    Buffer.from(blinding),
+    Buffer.from(encrypted),
+    new BN(nonce.toString()).toArrayLike(Buffer, 'le', 16),
+  ]));
+  return new Uint8Array(digest);
+}
+
+// Mirrors CryptoUtils::derive_encryption_key (v2); the auction's creator is
+// no longer part of it, and v1 keys are rejected with RetiredEncryptionKey
+export async function bidEncryptionKey(auctionId: BN, bidder: PublicKey, nonce: BN): Promise<Uint8Array> {
+  const digest = await crypto.subtle.digest('SHA-256', Buffer.concat([
+    Buffer.from('shadow_bid_encryption_v2'),
+    auctionId.toArrayLike(Buffer, 'le', 8),
+    bidder.toBuffer(),
+    nonce.toArrayLike(Buffer, 'le', 16),
+  ]));
+  return new Uint8Array(digest);
+}
+
 export class ShadowProtocol {
   private program: Program;
   private provider: AnchorProvider;
@@ -226,31 +290,53 @@ export class ShadowProtocol {
     const wallet = this.provider.wallet;
     const [protocolPDA] = getProtocolPDA();
     
-    const auctionId = new BN(Date.now());
-    const [auctionPDA] = getAuctionPDA(auctionId);
-    const [assetVaultPDA] = getAssetVaultPDA(auctionId);
+    // A random nonce keeps the auction's address from being guessed ahead of the listing
+    const creatorNonce = new BN(randomBytes(8), 'le');
+    const [auctionPDA] = await getAuctionPDA(wallet.publicKey, creatorNonce);
+    const [assetVaultPDA] = getAssetVaultPDA(auctionPDA);
     const creatorTokenAccount = await getAssociatedTokenAddress(
       params.assetMint,
       wallet.publicKey
     );
     const { encrypted: reservePriceEncrypted, nonce: reservePriceNonce } = 
       await encryptReservePrice(params.reservePrice, this.mxePublicKey);
+    const reservePriceCommitment = await reserveCommitment(
+      params.reservePrice,
+      randomBytes(32),
+      reservePriceEncrypted,
+      reservePriceNonce
+    );
+    const reserve = {
+      reservePriceEncrypted: Array.from(reservePriceEncrypted),
+      reservePriceNonce: new BN(reservePriceNonce.toString()),
+      reservePriceCommitment: Array.from(reservePriceCommitment),
+    };
     
     if (params.auctionType === 'SEALED') {
       const tx = await this.program.methods
-        .createSealedAuction(
-          auctionId,
-          params.assetMint,
-          new BN(params.duration),
-          new BN(params.minimumBid * LAMPORTS_PER_SOL),
-          Array.from(reservePriceEncrypted) as any,
-          new BN(reservePriceNonce.toString())
-        )
+        .createSealedAuction(creatorNonce, {
+          assetMint: params.assetMint,
+          assetAmount: new BN(params.assetAmount),
+          duration: new BN(params.duration),
+          minimumBid: new BN(params.minimumBid * LAMPORTS_PER_SOL),
+          bidIncrement: new BN(1),
+          ...reserve,
+          timingMode: { unixTimestamp: {} },
+          privateWinner: false,
+          collateralConfig: null,
+          minCollateralRatio: 10000,
+          maxBids: 100,
+          bidCollateralBps: 0,
+          bidCutoff: 0,
+          feeVoucher: null,
+        } as any)
         .accounts({
           creator: wallet.publicKey,
           auction: auctionPDA,
           protocolState: protocolPDA,
           assetMint: params.assetMint,
+          // Prices are quoted in SOL, so the winner pays in wrapped SOL
+          paymentMint: WRAPPED_SOL_MINT,
           assetVault: assetVaultPDA,
           creatorAssetAccount: creatorTokenAccount,
           tokenProgram: TOKEN_PROGRAM_ID,
@@ -261,22 +347,28 @@ export class ShadowProtocol {
       return tx;
     } else {
       const tx = await this.program.methods
-        .createDutchAuction(
-          auctionId,
-          params.assetMint,
-          new BN(params.assetAmount),
-          new BN((params.startingPrice || params.reservePrice * 2) * LAMPORTS_PER_SOL),
-          new BN((params.priceDecreaseRate || 0.01) * LAMPORTS_PER_SOL),
-          new BN((params.minimumPriceFloor || params.minimumBid) * LAMPORTS_PER_SOL),
-          new BN(params.duration),
-          Array.from(reservePriceEncrypted) as any,
-          new BN(reservePriceNonce.toString())
-        )
+        .createDutchAuction(creatorNonce, {
+          assetMint: params.assetMint,
+          assetAmount: new BN(params.assetAmount),
+          startingPrice: new BN((params.startingPrice || params.reservePrice * 2) * LAMPORTS_PER_SOL),
+          priceDecreaseRate: new BN((params.priceDecreaseRate || 0.01) * LAMPORTS_PER_SOL),
+          minimumPriceFloor: new BN((params.minimumPriceFloor || params.minimumBid) * LAMPORTS_PER_SOL),
+          duration: new BN(params.duration),
+          ...reserve,
+          priceCurve: { linear: {} },
+          timingMode: { unixTimestamp: {} },
+          collateralConfig: null,
+          lbp: null,
+          tranches: null,
+          feeVoucher: null,
+        } as any)
         .accounts({
           creator: wallet.publicKey,
           auction: auctionPDA,
           protocolState: protocolPDA,
           assetMint: params.assetMint,
+          // Prices are quoted in SOL, so the winner pays in wrapped SOL
+          paymentMint: WRAPPED_SOL_MINT,
           assetVault: assetVaultPDA,
           creatorAssetAccount: creatorTokenAccount,
           tokenProgram: TOKEN_PROGRAM_ID,
@@ -292,20 +384,23 @@ export class ShadowProtocol {
     const wallet = this.provider.wallet;
     const auctionId = new BN(params.auctionId);
     
-    const [auctionPDA] = getAuctionPDA(auctionId);
+    const auctionPDA = await findAuctionPDA(this.program, auctionId);
     const [bidPDA] = getBidPDA(auctionId, wallet.publicKey);
-    const { encryptedAmount, publicKey, nonce } = 
+    const { encryptedAmount, nonce } = 
       await encryptBidAmount(params.bidAmount, this.mxePublicKey);
+    const bidNonce = new BN(BigInt('0x' + Array.from(nonce.slice(0, 8)).map(b => b.toString(16).padStart(2, '0')).join('')).toString());
+    const encryptionKey = await bidEncryptionKey(auctionId, wallet.publicKey, bidNonce);
     const computationOffset = new BN(randomBytes(8));
     
     const tx = await this.program.methods
-      .submitEncryptedBid(
-        auctionId,
-        Array.from(encryptedAmount) as any,
-        Array.from(publicKey) as any,
-        new BN(BigInt('0x' + Array.from(nonce.slice(0, 8)).map(b => b.toString(16).padStart(2, '0')).join('').toString())),
-        computationOffset
-      )
+      .submitEncryptedBid(auctionId, {
+        bidAmountEncrypted: Array.from(encryptedAmount),
+        publicKey: Array.from(encryptionKey),
+        nonce: bidNonce,
+        collateralAmount: new BN(0),
+        computationOffset,
+        inviteCode: null,
+      } as any)
       .accounts({
         bidder: wallet.publicKey,
         auction: auctionPDA,
@@ -320,7 +415,7 @@ export class ShadowProtocol {
   async settleAuction(auctionId: string): Promise<string> {
     const wallet = this.provider.wallet;
     const auctionIdBN = new BN(auctionId);
-    const [auctionPDA] = getAuctionPDA(auctionIdBN);
+    const auctionPDA = await findAuctionPDA(this.program, auctionIdBN);
     const computationOffset = new BN(randomBytes(8));
     
     const tx = await this.program.methods
@@ -339,9 +434,8 @@ export class ShadowProtocol {
   }
   
   async fetchAuction(auctionId: string) {
-    const auctionIdBN = new BN(auctionId);
-    const [auctionPDA] = getAuctionPDA(auctionIdBN);
-    return null;
+    const auctionPDA = await findAuctionPDA(this.program, new BN(auctionId));
+    return (this.program.account as any).auctionAccount.fetch(auctionPDA);
   }
   
   async fetchAllAuctions() {
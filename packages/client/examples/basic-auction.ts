@@ -29,6 +29,7 @@ async function basicAuctionExample() {
     // Create a sealed auction
     const auction = await client.createSealedAuction({
       assetMint: new PublicKey('So11111111111111111111111111111111111111112'), // Wrapped SOL
+      assetAmount: 1_000_000_000, // 1 wrapped SOL
       duration: 3600, // 1 hour
       minimumBid: 1_000_000, // 1 SOL minimum
       reservePrice: 5_000_000, // 5 SOL reserve (hidden)
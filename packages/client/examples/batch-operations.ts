@@ -31,6 +31,7 @@ async function batchOperationsExample() {
     for (let i = 0; i < 3; i++) {
       const auction = await client.createSealedAuction({
         assetMint: new PublicKey(''),
+        assetAmount: 1_000_000,
         duration: 300, // 5 minutes for demo
         minimumBid: 1_000_000 * (i + 1), // Different minimum bids
         reservePrice: 2_000_000 * (i + 1), // Different reserves
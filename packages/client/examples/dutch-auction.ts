@@ -26,6 +26,7 @@ async function dutchAuctionExample() {
     
     const auction = await client.createDutchAuction({
       assetMint: new PublicKey('So11111111111111111111111111111111111111112'),
+      assetAmount: 1_000_000_000, // 1 wrapped SOL
       duration: 1800, // 30 minutes
       startingPrice: 10_000_000, // 10 SOL starting price
       priceDecreaseRate: 100_000, // Decrease by 0.1 SOL per second
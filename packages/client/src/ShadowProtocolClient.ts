@@ -4,6 +4,7 @@ import {
     Keypair,
     Transaction,
     TransactionSignature,
+    AddressLookupTableAccount,
   } from '@solana/web3.js';
   import { AnchorProvider, Program, Wallet } from '@coral-xyz/anchor';
   import { RescueCipher, x25519 } from '@arcium-hq/client';
@@ -20,7 +21,13 @@ import {
     AuctionData,
     BidData 
   } from './types';
-  import { SHADOW_PROTOCOL_PROGRAM_ID, DEFAULT_CLUSTER_OFFSET } from './utils/constants';
+  import {
+    SHADOW_PROTOCOL_PROGRAM_ID,
+    DEFAULT_CLUSTER_OFFSET,
+    PROTOCOL_SEED,
+    ADMIN_AUDIT_LOG_SEED,
+  } from './utils/constants';
+  import { generateNonce, reserveCommitment } from './utils/helpers';
   
   export class ShadowProtocolClient {
     private connection: Connection;
@@ -58,24 +65,24 @@ import {
     }
   
   
+    // The returned blinding opens the reserve commitment; keep it to call
+    // `revealReservePrice` after settlement
     async createSealedAuction(params: CreateAuctionParams): Promise<{
       signature: TransactionSignature;
       auctionId: number;
       auctionPubkey: PublicKey;
+      reserveBlinding: Uint8Array;
     }> {
-      // Encrypt reserve price
-      const { encryptedData, nonce, publicKey } = await this.encryptionManager.encryptValue(
-        BigInt(params.reservePrice || 0)
-      );
+      const reserve = await this.encryptReserve(params.reservePrice || 0);
   
       const result = await this.auctionManager.createSealedAuction({
         ...params,
-        reservePriceEncrypted: Array.from(encryptedData),
-        reserveNonce: nonce,
-        encryptionPublicKey: Array.from(publicKey),
+        reservePriceEncrypted: reserve.encrypted,
+        reserveNonce: reserve.nonce,
+        reserveCommitment: reserve.commitment,
       });
   
-      return result;
+      return { ...result, reserveBlinding: reserve.blinding };
     }
   
     async createDutchAuction(params: CreateAuctionParams & {
@@ -85,20 +92,40 @@ import {
       signature: TransactionSignature;
       auctionId: number;
       auctionPubkey: PublicKey;
+      reserveBlinding: Uint8Array;
     }> {
-      // Encrypt reserve price
-      const { encryptedData, nonce, publicKey } = await this.encryptionManager.encryptValue(
-        BigInt(params.reservePrice || 0)
-      );
+      const reserve = await this.encryptReserve(params.reservePrice || 0);
   
       const result = await this.auctionManager.createDutchAuction({
         ...params,
-        reservePrice: params.reservePrice || 0,
-        reserveNonce: nonce,
-        encryptionPublicKey: Array.from(publicKey),
+        minimumPriceFloor: params.minimumPriceFloor ?? params.minimumBid,
+        reservePriceEncrypted: reserve.encrypted,
+        reserveNonce: reserve.nonce,
+        reserveCommitment: reserve.commitment,
       });
   
-      return result;
+      return { ...result, reserveBlinding: reserve.blinding };
+    }
+  
+    // Encrypt a reserve price for the MXE and commit to it under a fresh blinding
+    private async encryptReserve(reservePrice: number): Promise<{
+      encrypted: number[];
+      nonce: bigint;
+      commitment: number[];
+      blinding: Uint8Array;
+    }> {
+      const { encryptedData, nonce } = await this.encryptionManager.encryptValue(
+        BigInt(reservePrice)
+      );
+      const blinding = generateNonce();
+      const commitment = reserveCommitment(reservePrice, blinding, encryptedData, nonce);
+  
+      return {
+        encrypted: Array.from(encryptedData),
+        nonce,
+        commitment: Array.from(commitment),
+        blinding,
+      };
     }
   
     async submitEncryptedBid(params: SubmitBidParams): Promise<{
@@ -107,7 +134,7 @@ import {
       computationSignature?: TransactionSignature;
     }> {
       // Encrypt bid amount
-      const { encryptedData, nonce, publicKey } = await this.encryptionManager.encryptValue(
+      const { encryptedData, nonce } = await this.encryptionManager.encryptValue(
         BigInt(params.amount)
       );
   
@@ -115,7 +142,6 @@ import {
         auctionId: parseInt(params.auctionId),
         bidAmountEncrypted: Array.from(encryptedData),
         nonce,
-        publicKey: Array.from(publicKey),
       });
   
       return result;
@@ -148,6 +174,17 @@ import {
       const result = await this.auctionManager.batchSettle(auctionIds);
       return result;
     }
+
+    async createSettlementLut(auctionId: number): Promise<{
+      signature: TransactionSignature;
+      lookupTable: PublicKey;
+    }> {
+      return this.auctionManager.createSettlementLut(auctionId);
+    }
+  
+    async getSettlementLut(lookupTable: PublicKey): Promise<AddressLookupTableAccount | null> {
+      return this.auctionManager.getSettlementLut(lookupTable);
+    }
   
   
     async getAuction(auctionId: number): Promise<AuctionData | null> {
@@ -181,10 +218,7 @@ import {
         throw new Error('Invalid Dutch auction');
       }
   
-      const elapsed = Date.now() / 1000 - auction.startTime;
-      const currentPrice = (auction.startingPrice || auction.currentPrice) - (auction.priceDecreaseRate * elapsed);
-      
-      return Math.max(currentPrice, 0);
+      return this.auctionManager.getCurrentDutchPrice(auctionId);
     }
   
     async isAuctionEnded(auctionId: number): Promise<boolean> {
@@ -244,8 +278,27 @@ import {
       throw new Error('Admin method not implemented in MVP');
     }
   
-    async setPauseState(paused: boolean): Promise<TransactionSignature> {
-      throw new Error('Admin method not implemented in MVP');
+    // Pausing takes effect at once; unpausing goes through `proposeUnpause`
+    // and `confirmUnpause` on the program
+    async pause(): Promise<TransactionSignature> {
+      const [protocolState] = PublicKey.findProgramAddressSync(
+        [Buffer.from(PROTOCOL_SEED)],
+        this.program.programId
+      );
+      const [auditLog] = PublicKey.findProgramAddressSync(
+        [Buffer.from(ADMIN_AUDIT_LOG_SEED)],
+        this.program.programId
+      );
+  
+      return this.program.methods
+        .pause()
+        .accountsPartial({
+          authority: this.provider.wallet.publicKey,
+          protocolState,
+          auditLog,
+          instructionsSysvar: null,
+        })
+        .rpc();
     }
   
   
@@ -4,16 +4,27 @@ import {
   Transaction, 
   TransactionSignature,
   SystemProgram,
+  AddressLookupTableAccount,
+  AddressLookupTableProgram,
   LAMPORTS_PER_SOL
 } from '@solana/web3.js';
 import { Program, BN } from '@coral-xyz/anchor';
+import { getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from '@solana/spl-token';
 import { 
   AUCTION_SEED, 
   ASSET_VAULT_SEED, 
+  AUCTION_FEATURES_SEED,
   PROTOCOL_SEED,
+  PROTOCOL_STATS_SEED,
+  PROTOCOL_STATS_SHARDS,
+  CREATOR_INDEX_SEED,
+  MAX_BIDS_PER_AUCTION,
+  SETTLEMENT_LUT_SEED,
   COMP_DEF_OFFSET_SEALED_BID,
   COMP_DEF_OFFSET_DUTCH_AUCTION
 } from '../utils/constants';
+import { auctionAddressSeed, findAuctionAddress, randomCreatorNonce } from '../utils/helpers';
+import { PriceCurve, TimingMode } from '../types/auction';
 import { AuctionData, AuctionType, AuctionStatus, AuctionAccount, AuctionTypeEnum, AuctionStatusEnum, createTypedProgram, ShadowProtocolProgram } from '../types';
 
 export class AuctionManager {
@@ -26,60 +37,59 @@ export class AuctionManager {
   }
 
   async createSealedAuction(params: {
-    auctionId?: number;
+    creatorNonce?: BN;
     assetMint: string;
+    paymentMint: string;
+    assetAmount: number;
     duration: number;
     minimumBid: number;
+    bidIncrement?: number;
     reservePriceEncrypted: number[];
     reserveNonce: bigint;
-    encryptionPublicKey: number[];
+    reserveCommitment: number[];
+    timingMode?: TimingMode;
+    privateWinner?: boolean;
   }): Promise<{
     signature: TransactionSignature;
     auctionId: number;
     auctionPubkey: PublicKey;
   }> {
-    const auctionId = params.auctionId || Date.now();
-    
-    // Derive PDAs
-    const [auctionPubkey] = PublicKey.findProgramAddressSync(
-      [Buffer.from(AUCTION_SEED), new BN(auctionId).toArrayLike(Buffer, 'le', 8)],
-      this.program.programId
-    );
-
-    const [assetVaultPubkey] = PublicKey.findProgramAddressSync(
-      [Buffer.from(ASSET_VAULT_SEED), new BN(auctionId).toArrayLike(Buffer, 'le', 8)],
-      this.program.programId
-    );
-
-    const [protocolStatePubkey] = PublicKey.findProgramAddressSync(
-      [Buffer.from(PROTOCOL_SEED)],
-      this.program.programId
+    const creatorNonce = params.creatorNonce ?? randomCreatorNonce();
+    const accounts = this.creationAccounts(
+      creatorNonce,
+      new PublicKey(params.assetMint),
+      new PublicKey(params.paymentMint)
     );
 
     try {
       const signature = await this.program.methods
-        .createSealedAuction(
-          new BN(auctionId),
-          new PublicKey(params.assetMint),
-          new BN(params.duration),
-          new BN(params.minimumBid),
-          params.reservePriceEncrypted as any,
-          new BN(params.reserveNonce.toString())
-        )
-        .accounts({
-          creator: this.program.provider.publicKey!,
-          auction: auctionPubkey,
-          protocolState: protocolStatePubkey,
+        .createSealedAuction(creatorNonce, {
           assetMint: new PublicKey(params.assetMint),
-          assetVault: assetVaultPubkey,
-          systemProgram: SystemProgram.programId,
-        })
+          assetAmount: new BN(params.assetAmount),
+          duration: new BN(params.duration),
+          minimumBid: new BN(params.minimumBid),
+          bidIncrement: new BN(params.bidIncrement ?? 1),
+          reservePriceEncrypted: params.reservePriceEncrypted as any,
+          reservePriceNonce: new BN(params.reserveNonce.toString()),
+          reservePriceCommitment: params.reserveCommitment as any,
+          timingMode: params.timingMode ?? { unixTimestamp: {} },
+          privateWinner: params.privateWinner ?? false,
+          collateralConfig: null,
+          minCollateralRatio: 10000, // bids fully collateralized
+          maxBids: MAX_BIDS_PER_AUCTION,
+          bidCollateralBps: 0,
+          bidCutoff: 0,
+          feeVoucher: null
+        } as any)
+        .accountsPartial(accounts)
         .rpc();
+      // The id is assigned on-chain at creation
+      const { auctionId } = await this.program.account.auctionAccount.fetch(accounts.auction);
 
       return {
         signature,
-        auctionId,
-        auctionPubkey
+        auctionId: auctionId.toNumber(),
+        auctionPubkey: accounts.auction
       };
     } catch (error) {
       console.error('Failed to create sealed auction:', error);
@@ -88,61 +98,59 @@ export class AuctionManager {
   }
 
   async createDutchAuction(params: {
-    auctionId?: number;
+    creatorNonce?: BN;
     assetMint: string;
+    paymentMint: string;
+    assetAmount: number;
     startingPrice: number;
     priceDecreaseRate: number;
+    minimumPriceFloor: number;
     duration: number;
-    reservePrice: number;
+    reservePriceEncrypted: number[];
     reserveNonce: bigint;
-    encryptionPublicKey: number[];
+    reserveCommitment: number[];
+    priceCurve?: PriceCurve;
+    timingMode?: TimingMode;
   }): Promise<{
     signature: TransactionSignature;
     auctionId: number;
     auctionPubkey: PublicKey;
   }> {
-    const auctionId = params.auctionId || Date.now();
-    
-    const [auctionPubkey] = PublicKey.findProgramAddressSync(
-      [Buffer.from(AUCTION_SEED), new BN(auctionId).toArrayLike(Buffer, 'le', 8)],
-      this.program.programId
-    );
-
-    const [assetVaultPubkey] = PublicKey.findProgramAddressSync(
-      [Buffer.from(ASSET_VAULT_SEED), new BN(auctionId).toArrayLike(Buffer, 'le', 8)],
-      this.program.programId
-    );
-
-    const [protocolStatePubkey] = PublicKey.findProgramAddressSync(
-      [Buffer.from(PROTOCOL_SEED)],
-      this.program.programId
+    const creatorNonce = params.creatorNonce ?? randomCreatorNonce();
+    const accounts = this.creationAccounts(
+      creatorNonce,
+      new PublicKey(params.assetMint),
+      new PublicKey(params.paymentMint)
     );
 
     try {
       const signature = await this.program.methods
-        .createDutchAuction(
-          new BN(auctionId),
-          new PublicKey(params.assetMint),
-          new BN(params.startingPrice),
-          new BN(params.priceDecreaseRate),
-          new BN(params.duration),
-          Array(32).fill(0), // Encrypted reserve price placeholder
-          new BN(params.reserveNonce.toString())
-        )
-        .accounts({
-          creator: this.program.provider.publicKey!,
-          auction: auctionPubkey,
-          protocolState: protocolStatePubkey,
+        .createDutchAuction(creatorNonce, {
           assetMint: new PublicKey(params.assetMint),
-          assetVault: assetVaultPubkey,
-          systemProgram: SystemProgram.programId,
-        })
+          assetAmount: new BN(params.assetAmount),
+          startingPrice: new BN(params.startingPrice),
+          priceDecreaseRate: new BN(params.priceDecreaseRate),
+          minimumPriceFloor: new BN(params.minimumPriceFloor),
+          duration: new BN(params.duration),
+          reservePriceEncrypted: params.reservePriceEncrypted as any,
+          reservePriceNonce: new BN(params.reserveNonce.toString()),
+          reservePriceCommitment: params.reserveCommitment as any,
+          priceCurve: params.priceCurve ?? { linear: {} },
+          timingMode: params.timingMode ?? { unixTimestamp: {} },
+          collateralConfig: null,
+          lbp: null,
+          tranches: null,
+          feeVoucher: null
+        } as any)
+        .accountsPartial(accounts)
         .rpc();
+      // The id is assigned on-chain at creation
+      const { auctionId } = await this.program.account.auctionAccount.fetch(accounts.auction);
 
       return {
         signature,
-        auctionId,
-        auctionPubkey
+        auctionId: auctionId.toNumber(),
+        auctionPubkey: accounts.auction
       };
     } catch (error) {
       console.error('Failed to create Dutch auction:', error);
@@ -150,14 +158,60 @@ export class AuctionManager {
     }
   }
 
+  // Accounts shared by both create instructions, without an auction house
+  private creationAccounts(creatorNonce: BN, assetMint: PublicKey, paymentMint: PublicKey) {
+    const creator = this.program.provider.publicKey!;
+
+    const [auction] = PublicKey.findProgramAddressSync(
+      [Buffer.from(AUCTION_SEED), auctionAddressSeed(creator, creatorNonce)],
+      this.program.programId
+    );
+    const [assetVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from(ASSET_VAULT_SEED), auction.toBuffer()],
+      this.program.programId
+    );
+    const [features] = PublicKey.findProgramAddressSync(
+      [Buffer.from(AUCTION_FEATURES_SEED), auction.toBuffer()],
+      this.program.programId
+    );
+    const [protocolState] = PublicKey.findProgramAddressSync(
+      [Buffer.from(PROTOCOL_SEED)],
+      this.program.programId
+    );
+    // Creations are sharded by nonce, as the auction id is not yet assigned
+    const [protocolStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from(PROTOCOL_STATS_SEED), Buffer.from([creatorNonce.modn(PROTOCOL_STATS_SHARDS)])],
+      this.program.programId
+    );
+    const [creatorIndex] = PublicKey.findProgramAddressSync(
+      [Buffer.from(CREATOR_INDEX_SEED), creator.toBuffer()],
+      this.program.programId
+    );
+
+    return {
+      creator,
+      auction,
+      features,
+      protocolState,
+      protocolStats,
+      creatorIndex,
+      assetMint,
+      paymentMint,
+      assetVault,
+      creatorAssetAccount: getAssociatedTokenAddressSync(assetMint, creator),
+      auctionHouse: null,
+      houseCreator: null,
+      instructionsSysvar: null,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+  }
+
   async settleAuction(auctionId: number): Promise<{
     signature: TransactionSignature;
     settlementResult?: any;
   }> {
-    const [auctionPubkey] = PublicKey.findProgramAddressSync(
-      [Buffer.from(AUCTION_SEED), new BN(auctionId).toArrayLike(Buffer, 'le', 8)],
-      this.program.programId
-    );
+    const auctionPubkey = await findAuctionAddress(this.program, auctionId);
 
     try {
       const signature = await this.program.methods
@@ -185,6 +239,13 @@ export class AuctionManager {
   }> {
     const batchId = Date.now();
     
+    // Each auction in the batch is validated on-chain via remaining accounts
+    const auctionAccounts = await Promise.all(auctionIds.map(async id => ({
+      pubkey: await findAuctionAddress(this.program, id),
+      isWritable: true,
+      isSigner: false,
+    })));
+    
     try {
       const signature = await this.program.methods
         .batchSettle(
@@ -195,6 +256,7 @@ export class AuctionManager {
           payer: this.program.provider.publicKey!,
           systemProgram: SystemProgram.programId,
         })
+        .remainingAccounts(auctionAccounts)
         .rpc();
 
       return { signature, batchId };
@@ -204,12 +266,75 @@ export class AuctionManager {
     }
   }
 
-  async getAuction(auctionId: number): Promise<AuctionData | null> {
-    const [auctionPubkey] = PublicKey.findProgramAddressSync(
-      [Buffer.from(AUCTION_SEED), new BN(auctionId).toArrayLike(Buffer, 'le', 8)],
+  /**
+   * Register the auction's recurring settlement accounts in an address lookup
+   * table. Must be sent by the auction creator; the table becomes usable in
+   * versioned transactions from the next slot.
+   */
+  async createSettlementLut(auctionId: number): Promise<{
+    signature: TransactionSignature;
+    lookupTable: PublicKey;
+  }> {
+    const creator = this.program.provider.publicKey!;
+    const auctionPubkey = await findAuctionAddress(this.program, auctionId);
+
+    const [lutAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from(SETTLEMENT_LUT_SEED), auctionPubkey.toBuffer(), creator.toBuffer()],
       this.program.programId
     );
 
+    const recentSlot = await this.connection.getSlot('finalized');
+    const [, lookupTable] = AddressLookupTableProgram.createLookupTable({
+      authority: lutAuthority,
+      payer: creator,
+      recentSlot,
+    });
+
+    try {
+      const signature = await this.program.methods
+        .createSettlementLut(new BN(auctionId), new BN(recentSlot))
+        .accounts({
+          creator,
+          auction: auctionPubkey,
+          lutAuthority,
+          lookupTable,
+          addressLookupTableProgram: AddressLookupTableProgram.programId,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      return { signature, lookupTable };
+    } catch (error) {
+      console.error('Failed to create settlement lookup table:', error);
+      throw error;
+    }
+  }
+
+  /**
+   * Fetch a settlement lookup table for use in a versioned transaction
+   */
+  async getSettlementLut(lookupTable: PublicKey): Promise<AddressLookupTableAccount | null> {
+    const { value } = await this.connection.getAddressLookupTable(lookupTable);
+    return value;
+  }
+
+  async getCurrentDutchPrice(auctionId: number): Promise<number> {
+    const auctionPubkey = await findAuctionAddress(this.program, auctionId);
+
+    // Simulated on-chain so the decay formula lives in one place
+    const price: BN = await this.program.methods
+      .getCurrentDutchPrice(new BN(auctionId))
+      .accounts({
+        auction: auctionPubkey,
+      })
+      .view();
+
+    return price.toNumber();
+  }
+
+  async getAuction(auctionId: number): Promise<AuctionData | null> {
+    const auctionPubkey = await findAuctionAddress(this.program, auctionId);
+
     try {
       const auctionAccount = await this.program.account.auctionAccount.fetch(auctionPubkey);
       
@@ -227,7 +352,7 @@ export class AuctionManager {
         reservePriceNonce: BigInt(auctionAccount.reservePriceNonce.toString()),
         currentPrice: auctionAccount.currentPrice.toNumber(),
         priceDecreaseRate: auctionAccount.priceDecreaseRate.toNumber(),
-        bidCount: auctionAccount.bidCount.toNumber(),
+        bidCount: auctionAccount.bidCount,
         winner: auctionAccount.winner,
         winningAmount: auctionAccount.winningAmount.toNumber(),
         settledAt: auctionAccount.settledAt?.toNumber(),
@@ -312,7 +437,7 @@ export class AuctionManager {
       reservePriceNonce: BigInt(account.reservePriceNonce.toString()),
       currentPrice: account.currentPrice.toNumber(),
       priceDecreaseRate: account.priceDecreaseRate.toNumber(),
-      bidCount: account.bidCount.toNumber(),
+      bidCount: account.bidCount,
       winner: account.winner,
       winningAmount: account.winningAmount.toNumber(),
       settledAt: account.settledAt?.toNumber(),
@@ -329,7 +454,8 @@ export class AuctionManager {
     if ('created' in status) return AuctionStatus.Created;
     if ('active' in status) return AuctionStatus.Active;
     if ('ended' in status) return AuctionStatus.Ended;
-    if ('settled' in status) return AuctionStatus.Settled;
+    if ('settlementAuthorized' in status) return AuctionStatus.SettlementAuthorized;
+    if ('assetsDistributed' in status) return AuctionStatus.Settled;
     return AuctionStatus.Cancelled;
   }
 
@@ -340,6 +466,7 @@ export class AuctionManager {
       case AuctionStatus.Ended: return 2;
       case AuctionStatus.Settled: return 3;
       case AuctionStatus.Cancelled: return 4;
+      case AuctionStatus.SettlementAuthorized: return 5;
       default: return 0;
     }
   }
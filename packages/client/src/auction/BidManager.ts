@@ -8,9 +8,9 @@ import {
 import { Program, BN } from '@coral-xyz/anchor';
 import { 
   BID_SEED, 
-  AUCTION_SEED,
   COMP_DEF_OFFSET_SEALED_BID
 } from '../utils/constants';
+import { bidEncryptionKey, findAuctionAddress } from '../utils/helpers';
 import { BidData, BidAccount, createTypedProgram, ShadowProtocolProgram } from '../types';
 
 export class BidManager {
@@ -29,7 +29,8 @@ export class BidManager {
     auctionId: number;
     bidAmountEncrypted: number[];
     nonce: bigint;
-    publicKey: number[];
+    collateralAmount?: number;
+    inviteCode?: number[];
   }): Promise<{
     signature: TransactionSignature;
     bidPubkey: PublicKey;
@@ -38,9 +39,12 @@ export class BidManager {
     const bidId = Date.now();
     
     // Derive PDAs
-    const [auctionPubkey] = PublicKey.findProgramAddressSync(
-      [Buffer.from(AUCTION_SEED), new BN(params.auctionId).toArrayLike(Buffer, 'le', 8)],
-      this.program.programId
+    const auctionPubkey = await findAuctionAddress(this.program, params.auctionId);
+    // Always derived here, so bids prepared under the v1 scheme go out with a v2 key
+    const encryptionKey = bidEncryptionKey(
+      new BN(params.auctionId),
+      this.program.provider.publicKey!,
+      params.nonce
     );
 
     const [bidPubkey] = PublicKey.findProgramAddressSync(
@@ -57,15 +61,16 @@ export class BidManager {
       const computationOffset = new BN(Math.floor(Math.random() * 1000000000));
 
       const signature = await this.program.methods
-        .submitEncryptedBid(
-          new BN(params.auctionId),
-          params.bidAmountEncrypted as any,
-          params.publicKey as any,
-          new BN(params.nonce.toString()),
-          computationOffset
-        )
+        .submitEncryptedBid(new BN(params.auctionId), {
+          bidAmountEncrypted: params.bidAmountEncrypted as any,
+          publicKey: Array.from(encryptionKey) as any,
+          nonce: new BN(params.nonce.toString()),
+          collateralAmount: new BN(params.collateralAmount ?? 0),
+          computationOffset,
+          inviteCode: params.inviteCode ?? null
+        } as any)
         .accounts({
-          bidder: this.program.provider.publicKey!!,
+          bidder: this.program.provider.publicKey!,
           auction: auctionPubkey,
           bid: bidPubkey,
           systemProgram: SystemProgram.programId,
@@ -92,10 +97,7 @@ export class BidManager {
     signature: TransactionSignature;
     accepted: boolean;
   }> {
-    const [auctionPubkey] = PublicKey.findProgramAddressSync(
-      [Buffer.from(AUCTION_SEED), new BN(params.auctionId).toArrayLike(Buffer, 'le', 8)],
-      this.program.programId
-    );
+    const auctionPubkey = await findAuctionAddress(this.program, params.auctionId);
 
     try {
       const signature = await this.program.methods
@@ -104,7 +106,7 @@ export class BidManager {
           new BN(params.bidAmount)
         )
         .accounts({
-          bidder: this.program.provider.publicKey!!,
+          bidder: this.program.provider.publicKey!,
           auction: auctionPubkey,
           systemProgram: SystemProgram.programId,
         })
@@ -186,10 +188,7 @@ export class BidManager {
    * Cancel a bid (if auction allows)
    */
   async cancelBid(auctionId: number): Promise<TransactionSignature> {
-    const [auctionPubkey] = PublicKey.findProgramAddressSync(
-      [Buffer.from(AUCTION_SEED), new BN(auctionId).toArrayLike(Buffer, 'le', 8)],
-      this.program.programId
-    );
+    const auctionPubkey = await findAuctionAddress(this.program, auctionId);
 
     const [bidPubkey] = PublicKey.findProgramAddressSync(
       [
@@ -204,7 +203,7 @@ export class BidManager {
       const signature = await this.program.methods
         .cancelBid(new BN(auctionId))
         .accounts({
-          bidder: this.program.provider.publicKey!!,
+          bidder: this.program.provider.publicKey!,
           auction: auctionPubkey,
           bid: bidPubkey,
           systemProgram: SystemProgram.programId,
@@ -7,6 +7,7 @@ export interface AuctionAccount {
   creator: PublicKey;
   assetMint: PublicKey;
   assetVault: PublicKey;
+  paymentMint: PublicKey;
   auctionType: AuctionTypeEnum;
   status: AuctionStatusEnum;
   startTime: BN;
@@ -16,7 +17,7 @@ export interface AuctionAccount {
   reservePriceNonce: BN;
   currentPrice: BN;
   priceDecreaseRate: BN;
-  bidCount: BN;
+  bidCount: number;
   winner: PublicKey | null;
   winningAmount: BN;
   settledAt: BN | null;
@@ -42,14 +43,15 @@ export type AuctionStatusEnum =
   | { created: {} }
   | { active: {} }
   | { ended: {} }
-  | { settled: {} }
-  | { cancelled: {} };
+  | { assetsDistributed: {} }
+  | { cancelled: {} }
+  | { settlementAuthorized: {} };
 
 // Type guards for checking enum variants
 export function isAuctionType(type: AuctionTypeEnum, variant: 'sealedBid' | 'dutch' | 'batch'): boolean {
   return variant in type;
 }
 
-export function isAuctionStatus(status: AuctionStatusEnum, variant: 'created' | 'active' | 'ended' | 'settled' | 'cancelled'): boolean {
+export function isAuctionStatus(status: AuctionStatusEnum, variant: 'created' | 'active' | 'ended' | 'settlementAuthorized' | 'assetsDistributed' | 'cancelled'): boolean {
   return variant in status;
 }
\ No newline at end of file
@@ -11,6 +11,7 @@ export enum AuctionStatus {
   Created = 'CREATED',
   Active = 'ACTIVE',
   Ended = 'ENDED',
+  SettlementAuthorized = 'SETTLEMENT_AUTHORIZED',
   Settled = 'SETTLED',
   Cancelled = 'CANCELLED'
 }
@@ -35,12 +36,37 @@ export interface Auction {
   settledAt?: Date;
 }
 
+// Mirrors the on-chain PriceCurve enum; intervals must be non-zero
+export type PriceCurve =
+  | { linear: {} }
+  | { exponential: { halfLife: BN } }
+  | { stepwise: { stepAmount: BN; stepInterval: BN } };
+
+// Mirrors the on-chain TimingMode enum
+export type TimingMode = { unixTimestamp: {} } | { slot: {} };
+
 export interface CreateAuctionParams {
   type: AuctionType;
   assetMint: string;
+  // Mint the winner pays in; fixed for the life of the auction
+  paymentMint: string;
+  // Base units of the asset moved into the auction vault
+  assetAmount: number;
   duration: number;
   minimumBid: number;
   reservePrice?: number;
+  // Sealed-bid proxy increment; defaults to 1
+  bidIncrement?: number;
   startingPrice?: number;
   priceDecreaseRate?: number;
+  // Dutch floor price; defaults to `minimumBid`
+  minimumPriceFloor?: number;
+  // Dutch decay shape; defaults to linear
+  priceCurve?: PriceCurve;
+  // Defaults to Unix timestamps
+  timingMode?: TimingMode;
+  // Sealed-bid only: publish the winner as a commitment until they claim
+  privateWinner?: boolean;
+  // Picks the auction address; defaults to a random nonce
+  creatorNonce?: BN;
 }
\ No newline at end of file
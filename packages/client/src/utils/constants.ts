@@ -9,10 +9,15 @@ export const ARCIUM_PROGRAM_ID = new PublicKey('ArciumProgram1111111111111111111
 
 // PDA Seeds
 export const PROTOCOL_SEED = 'protocol';
+export const ADMIN_AUDIT_LOG_SEED = 'admin_audit_log';
 export const AUCTION_SEED = 'auction';
 export const BID_SEED = 'bid';
 export const ASSET_VAULT_SEED = 'asset_vault';
+export const AUCTION_FEATURES_SEED = 'auction_features';
 export const BATCH_SEED = 'batch';
+export const CREATOR_INDEX_SEED = 'creator_index';
+export const PROTOCOL_STATS_SEED = 'protocol_stats';
+export const SETTLEMENT_LUT_SEED = 'settlement_lut';
 export const MXE_SEED = 'mxe';
 
 // Network Configuration
@@ -23,6 +28,8 @@ export const ARCIUM_CLUSTER_OFFSET = parseInt(process.env.NEXT_PUBLIC_ARCIUM_CLU
 export const MAX_BIDS_PER_AUCTION = 1000;
 export const MAX_AUCTION_DURATION = 30 * 24 * 60 * 60; // 30 days in seconds
 export const MAX_PROTOCOL_FEE = 500; // 5% in basis points
+// Must match PROTOCOL_STATS_SHARDS in the program
+export const PROTOCOL_STATS_SHARDS = 16;
 
 // Computation Definition Offsets
 export const COMP_DEF_OFFSET_SEALED_BID = 0x12345678;
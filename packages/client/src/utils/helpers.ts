@@ -1,5 +1,7 @@
 import { PublicKey } from '@solana/web3.js';
 import { BN } from '@coral-xyz/anchor';
+import { sha256 } from '@noble/hashes/sha256';
+import { ShadowProtocolProgram } from '../types';
 
 export function toBN(value: number | string | bigint): BN {
   return new BN(value.toString());
@@ -16,6 +18,73 @@ export function toPublicKey(value: string | PublicKey): PublicKey {
   return value;
 }
 
+// Mirrors AuctionAccount::address_digest: the seed of the auction address a
+// creator claims with a nonce
+export function auctionAddressSeed(creator: PublicKey, creatorNonce: BN): Uint8Array {
+  return sha256(Buffer.concat([
+    Buffer.from('shadow_auction_address'),
+    creator.toBuffer(),
+    creatorNonce.toArrayLike(Buffer, 'le', 8),
+  ]));
+}
+
+// Nonces are random so a listing's address cannot be guessed ahead of time
+export function randomCreatorNonce(): BN {
+  const bytes = new Uint8Array(8);
+  crypto.getRandomValues(bytes);
+  return new BN(bytes, 'le');
+}
+
+// Auction ids are assigned on creation and no longer seed the address, so an
+// auction is looked up by the id at the start of its account data
+export async function findAuctionAddress(
+  program: ShadowProtocolProgram,
+  auctionId: number | BN
+): Promise<PublicKey> {
+  const auctions = await program.account.auctionAccount.all([
+    {
+      memcmp: {
+        offset: 8, // Skip discriminator
+        bytes: new BN(auctionId).toArrayLike(Buffer, 'le', 8).toString('base64'),
+      }
+    }
+  ]);
+  if (auctions.length === 0) {
+    throw new Error(`Auction ${auctionId.toString()} not found`);
+  }
+  return auctions[0].publicKey;
+}
+
+// Mirrors CryptoUtils::generate_reserve_commitment: binds the reserve the
+// creator may later reveal to the ciphertext submitted at creation
+export function reserveCommitment(
+  reservePrice: number | bigint,
+  blinding: Uint8Array,
+  reservePriceEncrypted: Uint8Array,
+  reservePriceNonce: bigint
+): Uint8Array {
+  return sha256(Buffer.concat([
+    Buffer.from('shadow_reserve_commitment_v1'),
+    new BN(reservePrice.toString()).toArrayLike(Buffer, 'le', 8),
+    Buffer.from(blinding),
+    Buffer.from(reservePriceEncrypted),
+    new BN(reservePriceNonce.toString()).toArrayLike(Buffer, 'le', 16),
+  ]));
+}
+
+// Mirrors CryptoUtils::derive_encryption_key (v2). The auction's creator is
+// no longer part of it; v1 keys that bound the creator are rejected with
+// RetiredEncryptionKey, and since every input is public they can simply be
+// derived again
+export function bidEncryptionKey(auctionId: BN, bidder: PublicKey, nonce: bigint): Uint8Array {
+  return sha256(Buffer.concat([
+    Buffer.from('shadow_bid_encryption_v2'),
+    auctionId.toArrayLike(Buffer, 'le', 8),
+    bidder.toBuffer(),
+    new BN(nonce.toString()).toArrayLike(Buffer, 'le', 16),
+  ]));
+}
+
 export function generateNonce(): Uint8Array {
   const nonce = new Uint8Array(32);
   crypto.getRandomValues(nonce);
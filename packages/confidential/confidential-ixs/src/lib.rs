@@ -14,6 +14,7 @@ pub struct AuctionParams {
     pub minimum_bid: u64,
     pub reserve_price: u64,
     pub auction_type: u8,
+    pub bid_increment: u64,
 }
 
 #[derive(ArcisType, Copy, Clone)]
@@ -23,11 +24,21 @@ pub struct SettlementResult {
     pub second_highest: u64,
     pub reserve_met: bool,
     pub timestamp: u64,
+    pub tie_break_rule: u8,
 }
 
+// Tie-break rule reported in SettlementResult
+pub const TIE_BREAK_NONE: u8 = 0;
+pub const TIE_BREAK_EARLIEST_BID: u8 = 1;
+pub const TIE_BREAK_RANDOM: u8 = 2;
+
+// Settlement types: 0 = first price, 1 = second price, 2 = proxy (second price plus increment)
+pub const SETTLEMENT_PROXY: u8 = 2;
+
 pub
 fn sealed_bid_auction(
     bids: [u64; 64],
+    bid_timestamps: [u64; 64],
     bid_count: u64,
     auction_params: AuctionParams,
     settlement_type: u8,
@@ -35,7 +46,9 @@ fn sealed_bid_auction(
     let mut highest_bid = auction_params.minimum_bid;
     let mut second_highest = auction_params.minimum_bid;
     let mut winner_id: u128 = 0;
+    let mut winner_timestamp: u64 = 0;
     let mut found_valid_bid = false;
+    let mut tie_break_rule = TIE_BREAK_NONE;
     
     for i in 0..64 {
         if (i as u64) < bid_count && bids[i] > 0 {
@@ -44,10 +57,24 @@ fn sealed_bid_auction(
             let bid_meets_reserve = bid_amount >= auction_params.reserve_price;
             let is_valid_bid = bid_meets_minimum && bid_meets_reserve;
             
-            if is_valid_bid && bid_amount > highest_bid {
+            // Equal highest bids: earliest timestamp wins, MPC randomness if simultaneous
+            let is_tie = found_valid_bid && bid_amount == highest_bid;
+            let same_time = bid_timestamps[i] == winner_timestamp;
+            let wins_tie = is_tie &&
+                (bid_timestamps[i] < winner_timestamp || (same_time && ArcisRNG::bool()));
+            
+            if is_valid_bid && is_tie {
+                tie_break_rule = if same_time { TIE_BREAK_RANDOM } else { TIE_BREAK_EARLIEST_BID };
+            }
+            
+            if is_valid_bid && (bid_amount > highest_bid || wins_tie) {
+                if bid_amount > highest_bid {
+                    tie_break_rule = TIE_BREAK_NONE;
+                }
                 second_highest = highest_bid;
                 highest_bid = bid_amount;
                 winner_id = i as u128;
+                winner_timestamp = bid_timestamps[i];
                 found_valid_bid = true;
             } else if is_valid_bid && bid_amount > second_highest && bid_amount <= highest_bid {
                 second_highest = bid_amount;
@@ -55,8 +82,14 @@ fn sealed_bid_auction(
         }
     }
     
+    let proxy_price = second_highest
+        .saturating_add(auction_params.bid_increment)
+        .min(highest_bid);
+    
     let winning_amount = if settlement_type == 0 {
         highest_bid
+    } else if settlement_type == SETTLEMENT_PROXY {
+        proxy_price
     } else {
         second_highest
     };
@@ -67,6 +100,7 @@ fn sealed_bid_auction(
         second_highest,
         reserve_met: highest_bid >= auction_params.reserve_price,
         timestamp: 0,
+        tie_break_rule,
     }
 }
 
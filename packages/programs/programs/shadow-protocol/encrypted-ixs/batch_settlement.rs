@@ -4,58 +4,76 @@ use arcis_imports::*;
 mod circuits {
     use arcis_imports::*;
 
+    /// Auctions one batch can hold; must match MAX_BATCH_AUCTIONS in the program
+    pub const MAX_BATCH_AUCTIONS: usize = 10;
+
+    #[derive(Copy, Clone)]
     pub struct AuctionSettlement {
         pub auction_id: u64,
-        pub winner_id: u128,
+        pub winner: [u8; 32],
         pub winning_amount: u64,
         pub reserve_met: bool,
     }
 
     pub struct BatchData {
         pub batch_id: u64,
-        pub auction_settlements: Vec<AuctionSettlement>,
-        pub total_volume: u64,
-        pub total_fees: u64,
+        /// Settlements in batch order; entries past `auction_count` are ignored
+        pub auction_settlements: [AuctionSettlement; MAX_BATCH_AUCTIONS],
     }
 
+    /// Per-auction outcome, revealed as 32 + 8 + 1 bytes in `BatchResult`
+    #[derive(Copy, Clone)]
+    pub struct SettledAuction {
+        pub winner: [u8; 32],
+        pub winning_amount: u64,
+        pub met_reserve: bool,
+    }
+
+    /// Revealed batch outcome: the settled count followed by one entry per
+    /// batch slot, the layout `batch_settlement_callback` parses
+    pub struct BatchResult {
+        pub settled_count: u64,
+        pub settlements: [SettledAuction; MAX_BATCH_AUCTIONS],
+    }
+
+    // Only the per-auction outcomes leave the cluster, which settlement needs
+    // in the clear anyway; inactive slots are revealed zeroed.
     #[instruction]
     pub fn process_batch_settlement(
         batch: Enc<Mxe, BatchData>,
-        protocol_fee_bps: u16,
-    ) -> Enc<Shared, BatchResult> {
+        auction_count: u64,
+    ) -> BatchResult {
         let batch_data = batch.to_arcis();
         
-        let mut successful_count = 0u64;
-        let mut failed_count = 0u64;
-        let mut total_volume = 0u64;
-        let mut total_fees = 0u64;
+        let mut settled_count = 0u64;
+        let mut settlements = [SettledAuction {
+            winner: [0u8; 32],
+            winning_amount: 0,
+            met_reserve: false,
+        }; MAX_BATCH_AUCTIONS];
         
-        // Process each auction settlement
-        for settlement in batch_data.auction_settlements.iter() {
-            if settlement.reserve_met && settlement.winner_id != 0 {
-                successful_count += 1;
-                total_volume += settlement.winning_amount;
-                
-                // Calculate protocol fee
-                let fee = (settlement.winning_amount as u128)
-                    .saturating_mul(protocol_fee_bps as u128)
-                    .saturating_div(10000) as u64;
-                total_fees += fee;
-            } else {
-                failed_count += 1;
+        for i in 0..MAX_BATCH_AUCTIONS {
+            let settlement = batch_data.auction_settlements[i];
+            let is_active = (i as u64) < auction_count;
+            let has_winner = settlement.winner != [0u8; 32];
+        
+            if is_active {
+                settlements[i] = SettledAuction {
+                    winner: settlement.winner,
+                    winning_amount: settlement.winning_amount,
+                    met_reserve: settlement.reserve_met,
+                };
+            }
+            if is_active && settlement.reserve_met && has_winner {
+                settled_count += 1;
             }
         }
         
-        let result = BatchResult {
-            batch_id: batch_data.batch_id,
-            successful_settlements: successful_count,
-            failed_settlements: failed_count,
-            total_volume,
-            total_fees,
-            computation_complete: true,
-        };
-        
-        batch.owner.from_arcis(result)
+        BatchResult {
+            settled_count,
+            settlements,
+        }
+        .reveal()
     }
 
     #[instruction]
@@ -65,35 +83,21 @@ mod circuits {
     ) -> Enc<Shared, bool> {
         let batch_data = batch.to_arcis();
         
-        // Verify batch has expected number of auctions
-        let count_matches = batch_data.auction_settlements.len() as u64 == expected_count;
-        
-        // Verify no duplicate auction IDs
-        let mut seen_ids = Vec::new();
+        // Verify no duplicate auction IDs among the active slots
         let mut has_duplicates = false;
         
-        for settlement in batch_data.auction_settlements.iter() {
-            for seen_id in seen_ids.iter() {
-                if *seen_id == settlement.auction_id {
+        for i in 0..MAX_BATCH_AUCTIONS {
+            for j in 0..MAX_BATCH_AUCTIONS {
+                let both_active = (i as u64) < expected_count && (j as u64) < expected_count;
+                if both_active && i < j
+                    && batch_data.auction_settlements[i].auction_id == batch_data.auction_settlements[j].auction_id
+                {
                     has_duplicates = true;
-                    break;
                 }
             }
-            if !has_duplicates {
-                seen_ids.push(settlement.auction_id);
-            }
         }
         
-        let is_valid = count_matches && !has_duplicates;
+        let is_valid = expected_count as usize <= MAX_BATCH_AUCTIONS && !has_duplicates;
         batch.owner.from_arcis(is_valid)
     }
-
-    pub struct BatchResult {
-        pub batch_id: u64,
-        pub successful_settlements: u64,
-        pub failed_settlements: u64,
-        pub total_volume: u64,
-        pub total_fees: u64,
-        pub computation_complete: bool,
-    }
-}
\ No newline at end of file
+}
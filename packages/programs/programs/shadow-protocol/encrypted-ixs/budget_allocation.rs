@@ -0,0 +1,42 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+
+    /// Auctions one budget can span; must match MAX_LINKED_AUCTIONS in the program
+    pub const MAX_LINKED_AUCTIONS: usize = 8;
+
+    // Keep linked bids in priority order while their maximums still fit in
+    // the budget. A bid never pays more than its maximum, so whatever the
+    // kept bids end up paying, the total stays within the budget. The bids
+    // are the ciphertexts submitted to each linked auction, each under its
+    // own key, so the allocation covers exactly the bids that were placed.
+    // Only the allocation is revealed; the budget and bid amounts stay
+    // encrypted.
+    #[instruction]
+    pub fn allocate_budget(
+        budget: Enc<Shared, u64>,
+        bids: [Enc<Shared, u64>; MAX_LINKED_AUCTIONS],
+        linked_count: u64,
+    ) -> [bool; MAX_LINKED_AUCTIONS] {
+        let budget = budget.to_arcis();
+        
+        let mut committed: u64 = 0;
+        let mut allocation = [false; MAX_LINKED_AUCTIONS];
+        
+        for i in 0..MAX_LINKED_AUCTIONS {
+            let is_active = (i as u64) < linked_count;
+            let bid = bids[i].to_arcis();
+            // `committed <= budget` holds throughout, so this cannot underflow
+            let fits = bid <= budget - committed;
+        
+            if is_active && fits {
+                committed += bid;
+                allocation[i] = true;
+            }
+        }
+        
+        allocation
+    }
+}
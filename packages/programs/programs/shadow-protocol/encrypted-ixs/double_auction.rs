@@ -0,0 +1,95 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+
+    /// Orders per clearing round; must match MAX_MARKET_ORDERS in the program
+    pub const MAX_ORDERS: usize = 32;
+
+    pub const SIDE_BUY: u8 = 0;
+    pub const SIDE_SELL: u8 = 1;
+
+    #[derive(Copy, Clone)]
+    pub struct MarketOrder {
+        /// Limit price in quote units per base unit
+        pub price: u64,
+        /// Base units to buy or sell
+        pub quantity: u64,
+    }
+
+    // An order is only matched if its escrow covers it: buyers escrow quote
+    // for `price * quantity`, sellers escrow `quantity` base units. The side
+    // is the one recorded on chain, which is also the one settlement pays by.
+    fn is_funded(order: MarketOrder, side: u8, escrow: u64) -> bool {
+        let buy_cost = order.price as u128 * order.quantity as u128;
+        if side == SIDE_BUY {
+            buy_cost <= escrow as u128
+        } else {
+            order.quantity <= escrow
+        }
+    }
+
+    // Periodic double auction: every order's limit is a candidate clearing
+    // price and the one that matches the most volume wins. All matched orders
+    // trade at that single price, filled in submission order. Only the price,
+    // the volume and the per-order fills are revealed. Sides and escrows are
+    // public: they are read from the orders' accounts, not from the bidders.
+    #[instruction]
+    pub fn clear_double_auction(
+        orders: Enc<Shared, [MarketOrder; MAX_ORDERS]>,
+        sides: [u8; MAX_ORDERS],
+        escrows: [u64; MAX_ORDERS],
+        order_count: u64,
+    ) -> (u64, u64, [u64; MAX_ORDERS]) { // (clearing_price, matched_volume, fills)
+        let order_list = orders.to_arcis();
+        
+        let mut clearing_price: u64 = 0;
+        let mut matched_volume: u64 = 0;
+        
+        for j in 0..MAX_ORDERS {
+            let candidate = order_list[j].price;
+            let mut demand: u64 = 0;
+            let mut supply: u64 = 0;
+        
+            for i in 0..MAX_ORDERS {
+                let order = order_list[i];
+                let is_live = (i as u64) < order_count && is_funded(order, sides[i], escrows[i]);
+        
+                if is_live && sides[i] == SIDE_BUY && order.price >= candidate {
+                    demand += order.quantity;
+                } else if is_live && sides[i] == SIDE_SELL && order.price <= candidate {
+                    supply += order.quantity;
+                }
+            }
+        
+            let volume = demand.min(supply);
+            if (j as u64) < order_count && volume > matched_volume {
+                matched_volume = volume;
+                clearing_price = candidate;
+            }
+        }
+        
+        // Fill each side up to the matched volume, earliest orders first
+        let mut buy_remaining = matched_volume;
+        let mut sell_remaining = matched_volume;
+        let mut fills = [0u64; MAX_ORDERS];
+        
+        for i in 0..MAX_ORDERS {
+            let order = order_list[i];
+            let is_live = (i as u64) < order_count && is_funded(order, sides[i], escrows[i]);
+        
+            if is_live && sides[i] == SIDE_BUY && order.price >= clearing_price {
+                let fill = order.quantity.min(buy_remaining);
+                buy_remaining -= fill;
+                fills[i] = fill;
+            } else if is_live && sides[i] == SIDE_SELL && order.price <= clearing_price {
+                let fill = order.quantity.min(sell_remaining);
+                sell_remaining -= fill;
+                fills[i] = fill;
+            }
+        }
+        
+        (clearing_price, matched_volume, fills)
+    }
+}
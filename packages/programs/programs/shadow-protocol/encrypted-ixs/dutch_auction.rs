@@ -4,11 +4,27 @@ use arcis_imports::*;
 mod circuits {
     use arcis_imports::*;
 
+    pub const PRICE_CURVE_LINEAR: u8 = 0;
+    pub const PRICE_CURVE_EXPONENTIAL: u8 = 1;
+    pub const PRICE_CURVE_STEPWISE: u8 = 2;
+
+    // Mirrors the on-chain PriceCurve enum:
+    // - linear: `rate` per second
+    // - exponential: halves every `interval` seconds
+    // - stepwise: drops by `rate` every `interval` seconds
+    pub struct PriceCurve {
+        pub kind: u8,
+        pub rate: u64,
+        pub interval: u64,
+    }
+
     pub struct DutchAuctionData {
         pub auction_id: u64,
         pub reserve_price: u64,
-        pub current_price: u64,
+        pub starting_price: u64,
+        pub start_time: i64,
         pub minimum_floor: u64,
+        pub price_curve: PriceCurve,
     }
 
     pub struct DutchBid {
@@ -18,49 +34,91 @@ mod circuits {
         pub timestamp: i64,
     }
 
+    // Only acceptance and the clearing price are revealed so the program can
+    // award the auction; a rejected claim reveals nothing about its amount.
     #[instruction]
     pub fn process_dutch_auction_bid(
         auction: Enc<Mxe, DutchAuctionData>,
         bid: Enc<Shared, DutchBid>,
-    ) -> Enc<Shared, (bool, u64, u128)> { // (is_valid, final_price, winner_id)
+    ) -> (bool, u64) { // (is_valid, final_price)
         let auction_data = auction.to_arcis();
         let bid_data = bid.to_arcis();
         
+        // Price at the moment the claim was submitted
+        let elapsed_time = if bid_data.timestamp > auction_data.start_time {
+            (bid_data.timestamp - auction_data.start_time) as u64
+        } else {
+            0
+        };
+        let current_price = curve_price(
+            auction_data.starting_price,
+            &auction_data.price_curve,
+            elapsed_time,
+            auction_data.minimum_floor,
+        );
+        
         // Verify bid meets current price
-        let meets_price = bid_data.bid_amount >= auction_data.current_price;
+        let meets_price = bid_data.bid_amount >= current_price;
         
-        // Verify bid meets reserve price
-        let meets_reserve = bid_data.bid_amount >= auction_data.reserve_price;
+        // The price has fallen below the reserve: reject without revealing it
+        let meets_reserve = current_price >= auction_data.reserve_price;
         
         // Verify price is above minimum floor
-        let above_floor = auction_data.current_price >= auction_data.minimum_floor;
+        let above_floor = current_price >= auction_data.minimum_floor;
         
         // Bid is valid if it meets all conditions
         let is_valid = meets_price && meets_reserve && above_floor;
         
         // Winner pays the current auction price (not their bid amount)
         let final_price = if is_valid {
-            auction_data.current_price.max(auction_data.reserve_price)
+            current_price
         } else {
             0
         };
         
-        let winner_id = if is_valid { bid_data.bidder_id } else { 0 };
-        
-        bid.owner.from_arcis((is_valid, final_price, winner_id))
+        (is_valid.reveal(), final_price.reveal())
     }
 
     #[instruction]
     pub fn calculate_dutch_price(
         starting_price: u64,
-        decrease_rate: u64,
+        price_curve: PriceCurve,
         elapsed_time: u64,
         minimum_floor: u64,
     ) -> Enc<Shared, u64> {
-        let price_decrease = decrease_rate.saturating_mul(elapsed_time);
-        let current_price = starting_price.saturating_sub(price_decrease);
-        let final_price = current_price.max(minimum_floor);
+        let final_price = curve_price(starting_price, &price_curve, elapsed_time, minimum_floor);
         
         Shared.from_arcis(final_price)
     }
+
+    // Must stay in sync with `calculate_dutch_price_at` in the program
+    fn curve_price(
+        starting_price: u64,
+        price_curve: &PriceCurve,
+        elapsed_time: u64,
+        minimum_floor: u64,
+    ) -> u64 {
+        // Every branch below is evaluated, so the divisor can never be zero
+        let interval = price_curve.interval.max(1);
+        let decayed_price = if price_curve.kind == PRICE_CURVE_EXPONENTIAL {
+            let halvings = elapsed_time / interval;
+            if halvings >= 64 {
+                0
+            } else {
+                let upper = starting_price >> halvings;
+                let lower = upper >> 1;
+                let progress = ((upper - lower) as u128)
+                    .saturating_mul((elapsed_time % interval) as u128)
+                    / interval as u128;
+                upper - progress as u64
+            }
+        } else if price_curve.kind == PRICE_CURVE_STEPWISE {
+            let steps = elapsed_time / interval;
+            starting_price.saturating_sub(steps.saturating_mul(price_curve.rate))
+        } else {
+            starting_price.saturating_sub(price_curve.rate.saturating_mul(elapsed_time))
+        };
+        
+        decayed_price.max(minimum_floor)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,61 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+
+    pub struct LbpPurchase {
+        pub quantity: u64,
+        pub max_price: u64,
+    }
+
+    // Public state of the sale when the purchase is processed. `lot_price`
+    // is the decayed curve price of the whole lot at submission time.
+    pub struct LbpMarket {
+        pub lot_price: u64,
+        pub price_impact: u64,
+        pub asset_amount: u64,
+        pub tokens_sold: u64,
+        pub escrow_amount: u64,
+    }
+
+    // Only filled purchases reveal their quantity and cost; the buyer's price
+    // limit never leaves the cluster.
+    #[instruction]
+    pub fn process_lbp_purchase(
+        purchase: Enc<Shared, LbpPurchase>,
+        market: LbpMarket,
+    ) -> (bool, u64, u64) { // (accepted, quantity, cost)
+        let purchase = purchase.to_arcis();
+        
+        let remaining = market.asset_amount - market.tokens_sold;
+        let (average_price, cost) = fill_price(&market, purchase.quantity);
+        
+        let accepted = purchase.quantity > 0
+            && purchase.quantity <= remaining
+            && average_price <= purchase.max_price
+            && cost <= market.escrow_amount;
+        
+        let quantity = if accepted { purchase.quantity } else { 0 };
+        let cost = if accepted { cost } else { 0 };
+        
+        (accepted.reveal(), quantity.reveal(), cost.reveal())
+    }
+
+    // Must stay in sync with `LbpSale::fill_cost` in the program.
+    // Every lot sold lifts the lot price by `price_impact`, so a purchase pays
+    // the average of the price before and after it.
+    fn fill_price(market: &LbpMarket, quantity: u64) -> (u64, u64) {
+        let asset_amount = market.asset_amount as u128;
+        let premium = market.price_impact as u128
+            * (2 * market.tokens_sold as u128 + quantity as u128)
+            / (2 * asset_amount);
+        let average_price = market.lot_price as u128 + premium;
+        let cost = quantity as u128
+            * (2 * asset_amount * market.lot_price as u128
+                + market.price_impact as u128 * (2 * market.tokens_sold as u128 + quantity as u128))
+            / (2 * asset_amount * asset_amount);
+        
+        (average_price as u64, cost as u64)
+    }
+}
@@ -0,0 +1,98 @@
+use arcis_imports::*;
+
+#[encrypted]
+mod circuits {
+    use arcis_imports::*;
+
+    /// Bids per clearing; must match MAX_QUANTITY_BIDS in the program
+    pub const MAX_BIDS: usize = 32;
+
+    #[derive(Copy, Clone)]
+    pub struct QuantityBid {
+        /// Highest price accepted, in payment units per asset base unit
+        pub price: u64,
+        /// Asset base units wanted
+        pub quantity: u64,
+    }
+
+    // A bid only counts if its escrow covers `price * quantity` and its price
+    // meets the auction's public minimum.
+    fn is_live(bid: QuantityBid, escrow: u64, minimum_price: u64) -> bool {
+        let cost = bid.price as u128 * bid.quantity as u128;
+        bid.quantity > 0 && bid.price >= minimum_price && cost <= escrow as u128
+    }
+
+    // Uniform-price multi-unit auction: the clearing price is the highest bid
+    // price at which demand covers the supply, or the lowest live bid price
+    // when demand never does. Bids above the clearing price are filled in
+    // full and bids at it share the remainder in submission order. Only the
+    // price, the units sold and the per-bid allocations are revealed.
+    #[instruction]
+    pub fn clear_uniform_price_auction(
+        bids: Enc<Shared, [QuantityBid; MAX_BIDS]>,
+        escrows: [u64; MAX_BIDS],
+        bid_count: u64,
+        supply: u64,
+        minimum_price: u64,
+    ) -> (u64, u64, [u64; MAX_BIDS]) { // (clearing_price, units_sold, allocations)
+        let bid_list = bids.to_arcis();
+        
+        let mut clearing_price: u64 = 0;
+        let mut lowest_live: u64 = 0;
+        
+        for j in 0..MAX_BIDS {
+            let candidate = bid_list[j].price;
+            let candidate_live = (j as u64) < bid_count
+                && is_live(bid_list[j], escrows[j], minimum_price);
+            let mut demand: u64 = 0;
+        
+            for i in 0..MAX_BIDS {
+                let bid = bid_list[i];
+                let counts = (i as u64) < bid_count && is_live(bid, escrows[i], minimum_price);
+        
+                if counts && bid.price >= candidate {
+                    demand += bid.quantity;
+                }
+            }
+        
+            if candidate_live && demand >= supply && candidate > clearing_price {
+                clearing_price = candidate;
+            }
+            if candidate_live && (lowest_live == 0 || candidate < lowest_live) {
+                lowest_live = candidate;
+            }
+        }
+        
+        // Undersubscribed: everyone live is filled at the lowest live price
+        if clearing_price == 0 {
+            clearing_price = lowest_live;
+        }
+        
+        let mut remaining = supply;
+        let mut allocations = [0u64; MAX_BIDS];
+        
+        // Strictly higher bids first, then bids at the clearing price
+        for i in 0..MAX_BIDS {
+            let bid = bid_list[i];
+            let counts = (i as u64) < bid_count && is_live(bid, escrows[i], minimum_price);
+        
+            if counts && clearing_price > 0 && bid.price > clearing_price {
+                let allocation = bid.quantity.min(remaining);
+                remaining -= allocation;
+                allocations[i] = allocation;
+            }
+        }
+        for i in 0..MAX_BIDS {
+            let bid = bid_list[i];
+            let counts = (i as u64) < bid_count && is_live(bid, escrows[i], minimum_price);
+        
+            if counts && clearing_price > 0 && bid.price == clearing_price {
+                let allocation = bid.quantity.min(remaining);
+                remaining -= allocation;
+                allocations[i] = allocation;
+            }
+        }
+        
+        (clearing_price, supply - remaining, allocations)
+    }
+}
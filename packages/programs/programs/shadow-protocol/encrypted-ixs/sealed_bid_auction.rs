@@ -4,55 +4,383 @@ use arcis_imports::*;
 mod circuits {
     use arcis_imports::*;
 
+    /// Bids processed per invocation; must match SEALED_BID_CIRCUIT_CAPACITY
+    /// in the program. Larger auctions are queued in chunks of this size.
+    pub const MAX_BIDS: usize = 64;
+    
+    /// Capacities of the smaller single-round circuits; must match
+    /// SEALED_BID_CIRCUITS in the program
+    pub const SMALL_BIDS: usize = 5;
+    pub const MEDIUM_BIDS: usize = 16;
+
+    // Tie-break rule applied to the winning bid; mirrors the on-chain TieBreakRule
+    pub const TIE_BREAK_NONE: u8 = 0;
+    pub const TIE_BREAK_EARLIEST_BID: u8 = 1;
+    pub const TIE_BREAK_RANDOM: u8 = 2;
+
+    /// Winner reveal data a bidder seals into a privacy-mode bid: the
+    /// commitment to their key the program publishes if they win, and the
+    /// blinding opening it encrypted to their own key. Zeroed otherwise.
+    /// The 32-byte values are packed as little-endian u128 halves so a
+    /// `ChunkWinner` stays at CHUNK_WINNER_CIPHERTEXTS ciphertexts.
+    #[derive(Copy, Clone)]
+    pub struct PrivateWinner {
+        pub ciphertext: [u128; 2],
+        pub nonce: u128,
+        pub commitment: [u128; 2],
+    }
+
+    #[derive(Copy, Clone)]
     pub struct BidData {
         pub auction_id: u64,
+        /// Proxy bid: the bidder's maximum willingness to pay
         pub bid_amount: u64,
         pub bidder_id: u128,
+        pub private_winner: PrivateWinner,
     }
 
     pub struct AuctionData {
         pub reserve_price: u64,
-        pub bids: Vec<BidData>,
         pub bid_count: u64,
+        pub bid_increment: u64,
     }
 
-    #[instruction]
-    pub fn process_sealed_bid_auction(
-        auction_data: Enc<Mxe, AuctionData>,
-        bids: Enc<Shared, Vec<BidData>>,
-    ) -> Enc<Shared, (u128, u64, bool)> { // (winner_id, winning_amount, auction_met_reserve)
-        let auction = auction_data.to_arcis();
-        let bid_list = bids.to_arcis();
+    /// Settlement outcome revealed to the program (result format version 2)
+    pub struct SettlementResult {
+        pub winner_id: u128,
+        pub clearing_price: u64,
+        pub met_reserve: bool,
+        pub tie_break_rule: u8,
+        pub price_capped: bool,
+        pub second_highest: u64,
+        pub participating_bids: u64,
+    }
+
+    /// Privacy-mode settlement outcome (result format version 3): the
+    /// version 2 fields with `winner_id` zeroed, followed by the winner's
+    /// reveal data, which they open with `claim_win`
+    pub struct PrivateSettlementResult {
+        pub base: SettlementResult,
+        pub private_winner: PrivateWinner,
+    }
+
+    // Proxy bidding: the winner pays one increment above the runner-up's
+    // maximum, never more than their own maximum. Returns (price, capped)
+    // where `capped` means the increment was cut short by the winner's maximum.
+    fn proxy_clearing_price(highest_bid: u64, second_highest: u64, bid_increment: u64) -> (u64, bool) {
+        let raised = second_highest.saturating_add(bid_increment);
+        let capped = raised > highest_bid;
+        let price = if second_highest == 0 || capped { highest_bid } else { raised };
+        
+        (price, second_highest > 0 && capped)
+    }
+
+    // Decide whether a bid takes the lead. Equal amounts go to the earlier
+    // bid; equal amounts at the same timestamp are settled by MPC randomness.
+    // Returns (takes_lead, tie_break_rule) where the rule is TIE_BREAK_NONE
+    // unless the bid tied the current leader.
+    fn outbids(
+        amount: u64,
+        timestamp: u64,
+        best_amount: u64,
+        best_timestamp: u64,
+    ) -> (bool, u8) {
+        let is_tie = best_amount > 0 && amount == best_amount;
+        let is_earlier = timestamp < best_timestamp;
+        let same_time = timestamp == best_timestamp;
+        let coin = ArcisRNG::bool();
+        
+        let takes_lead = amount > best_amount
+            || (is_tie && is_earlier)
+            || (is_tie && same_time && coin);
         
-        // Find highest bid
+        let rule = if is_tie && same_time {
+            TIE_BREAK_RANDOM
+        } else if is_tie {
+            TIE_BREAK_EARLIEST_BID
+        } else {
+            TIE_BREAK_NONE
+        };
+        
+        (takes_lead, rule)
+    }
+
+    // A bid only counts if its escrowed collateral covers `collateral_bps`
+    // of its amount; zero disables the check
+    fn collateral_covers(bid_amount: u64, collateral: u64, collateral_bps: u64) -> bool {
+        collateral_bps == 0 || (collateral as u128) * 10_000 >= (bid_amount as u128) * (collateral_bps as u128)
+    }
+
+    // Best bid, runner-up and tie-break rule among the first `bid_count`
+    // slots; the rest are padding and ignored, as are under-collateralized bids.
+    // Ties are broken on `timestamps`, the submission times the program
+    // recorded, never on a time the bidder encrypted.
+    fn scan_bids<const N: usize>(
+        bid_list: [BidData; N],
+        bid_count: u64,
+        timestamps: [u64; N],
+        collaterals: [u64; N],
+        collateral_bps: u64,
+    ) -> ChunkWinner {
         let mut highest_bid: u64 = 0;
         let mut winner_id: u128 = 0;
+        let mut winner_timestamp: u64 = 0;
         let mut second_highest: u64 = 0;
+        let mut tie_break_rule: u8 = TIE_BREAK_NONE;
+        let mut participating_bids: u64 = 0;
+        let mut private_winner = bid_list[0].private_winner;
         
-        for bid in bid_list.iter() {
-            if bid.bid_amount > highest_bid {
+        for i in 0..N {
+            let bid = bid_list[i];
+            let is_active = (i as u64) < bid_count
+                && collateral_covers(bid.bid_amount, collaterals[i], collateral_bps);
+            if is_active && bid.bid_amount > 0 {
+                participating_bids += 1;
+            }
+            let (takes_lead, rule) = outbids(bid.bid_amount, timestamps[i], highest_bid, winner_timestamp);
+        
+            if is_active && (takes_lead || rule != TIE_BREAK_NONE) {
+                tie_break_rule = rule;
+            }
+        
+            if is_active && takes_lead {
                 second_highest = highest_bid;
                 highest_bid = bid.bid_amount;
                 winner_id = bid.bidder_id;
-            } else if bid.bid_amount > second_highest {
+                winner_timestamp = timestamps[i];
+                private_winner = bid.private_winner;
+            } else if is_active && bid.bid_amount > second_highest {
                 second_highest = bid.bid_amount;
             }
         }
         
-        // Vickrey auction: winner pays second-highest price
-        let winning_amount = if second_highest > 0 { second_highest } else { highest_bid };
-        let met_reserve = winning_amount >= auction.reserve_price;
+        ChunkWinner {
+            winner_id,
+            winner_timestamp,
+            highest_bid,
+            second_highest,
+            tie_break_rule,
+            participating_bids,
+            private_winner,
+        }
+    }
+
+    // Proxy auction: winner pays one increment above the second-highest maximum
+    fn settle<const N: usize>(
+        auction: AuctionData,
+        bid_list: [BidData; N],
+        timestamps: [u64; N],
+        collaterals: [u64; N],
+        collateral_bps: u64,
+    ) -> SettlementResult {
+        let best = scan_bids(bid_list, auction.bid_count, timestamps, collaterals, collateral_bps);
+        let (clearing_price, price_capped) = proxy_clearing_price(best.highest_bid, best.second_highest, auction.bid_increment);
+        
+        SettlementResult {
+            winner_id: best.winner_id,
+            clearing_price,
+            met_reserve: clearing_price >= auction.reserve_price,
+            tie_break_rule: best.tie_break_rule,
+            price_capped,
+            second_highest: best.second_highest,
+            participating_bids: best.participating_bids,
+        }
+    }
+
+    // Privacy mode: the winner is only published as their sealed commitment
+    fn settle_private<const N: usize>(
+        auction: AuctionData,
+        bid_list: [BidData; N],
+        timestamps: [u64; N],
+        collaterals: [u64; N],
+        collateral_bps: u64,
+    ) -> PrivateSettlementResult {
+        let best = scan_bids(bid_list, auction.bid_count, timestamps, collaterals, collateral_bps);
+        let (clearing_price, price_capped) = proxy_clearing_price(best.highest_bid, best.second_highest, auction.bid_increment);
+        
+        PrivateSettlementResult {
+            base: SettlementResult {
+                winner_id: 0,
+                clearing_price,
+                met_reserve: clearing_price >= auction.reserve_price,
+                tie_break_rule: best.tie_break_rule,
+                price_capped,
+                second_highest: best.second_highest,
+                participating_bids: best.participating_bids,
+            },
+            private_winner: best.private_winner,
+        }
+    }
+
+    #[instruction]
+    pub fn process_sealed_bid_auction(
+        auction_data: Enc<Mxe, AuctionData>,
+        bids: Enc<Shared, [BidData; MAX_BIDS]>,
+        timestamps: [u64; MAX_BIDS],
+        collaterals: [u64; MAX_BIDS],
+        collateral_bps: u64,
+    ) -> Enc<Shared, SettlementResult> {
+        let result = settle(auction_data.to_arcis(), bids.to_arcis(), timestamps, collaterals, collateral_bps);
+        bids.owner.from_arcis(result)
+    }
+
+    // Same settlement for small auctions, so they don't pay for 64 slots
+    #[instruction]
+    pub fn process_sealed_bid_auction_5(
+        auction_data: Enc<Mxe, AuctionData>,
+        bids: Enc<Shared, [BidData; SMALL_BIDS]>,
+        timestamps: [u64; SMALL_BIDS],
+        collaterals: [u64; SMALL_BIDS],
+        collateral_bps: u64,
+    ) -> Enc<Shared, SettlementResult> {
+        let result = settle(auction_data.to_arcis(), bids.to_arcis(), timestamps, collaterals, collateral_bps);
+        bids.owner.from_arcis(result)
+    }
+
+    #[instruction]
+    pub fn process_sealed_bid_auction_16(
+        auction_data: Enc<Mxe, AuctionData>,
+        bids: Enc<Shared, [BidData; MEDIUM_BIDS]>,
+        timestamps: [u64; MEDIUM_BIDS],
+        collaterals: [u64; MEDIUM_BIDS],
+        collateral_bps: u64,
+    ) -> Enc<Shared, SettlementResult> {
+        let result = settle(auction_data.to_arcis(), bids.to_arcis(), timestamps, collaterals, collateral_bps);
+        bids.owner.from_arcis(result)
+    }
+
+    #[instruction]
+    pub fn process_sealed_bid_auction_private(
+        auction_data: Enc<Mxe, AuctionData>,
+        bids: Enc<Shared, [BidData; MAX_BIDS]>,
+        timestamps: [u64; MAX_BIDS],
+        collaterals: [u64; MAX_BIDS],
+        collateral_bps: u64,
+    ) -> Enc<Shared, PrivateSettlementResult> {
+        let result = settle_private(auction_data.to_arcis(), bids.to_arcis(), timestamps, collaterals, collateral_bps);
+        bids.owner.from_arcis(result)
+    }
+
+    /// Chunks needed to cover the program's MAX_BIDS_PER_AUCTION
+    pub const MAX_CHUNKS: usize = 16;
+
+    /// One ciphertext per scalar field when encrypted: 6 plus 5 for
+    /// `private_winner`, matching CHUNK_WINNER_CIPHERTEXTS in the program
+    #[derive(Copy, Clone)]
+    pub struct ChunkWinner {
+        pub winner_id: u128,
+        pub winner_timestamp: u64,
+        pub highest_bid: u64,
+        pub second_highest: u64,
+        pub tie_break_rule: u8,
+        pub participating_bids: u64,
+        pub private_winner: PrivateWinner,
+    }
+
+    // First round of a multi-chunk settlement: the chunk winner stays
+    // encrypted to the MXE so it can only be consumed by the final round.
+    #[instruction]
+    pub fn process_bid_chunk(
+        bids: Enc<Shared, [BidData; MAX_BIDS]>,
+        bid_count: u64,
+        timestamps: [u64; MAX_BIDS],
+        collaterals: [u64; MAX_BIDS],
+        collateral_bps: u64,
+    ) -> Enc<Mxe, ChunkWinner> {
+        Mxe::get().from_arcis(scan_bids(bids.to_arcis(), bid_count, timestamps, collaterals, collateral_bps))
+    }
+
+    // Final round: the overall second price is the best of the winning
+    // chunk's runner-up and every other chunk's top bid.
+    fn final_round(
+        auction: AuctionData,
+        chunks: [ChunkWinner; MAX_CHUNKS],
+        chunk_count: u64,
+    ) -> PrivateSettlementResult {
+        let mut highest_bid: u64 = 0;
+        let mut winner_id: u128 = 0;
+        let mut winner_timestamp: u64 = 0;
+        let mut second_highest: u64 = 0;
+        let mut tie_break_rule: u8 = TIE_BREAK_NONE;
+        let mut participating_bids: u64 = 0;
+        let mut private_winner = chunks[0].private_winner;
+        
+        for i in 0..MAX_CHUNKS {
+            let chunk = chunks[i];
+            let is_active = (i as u64) < chunk_count;
+            if is_active {
+                participating_bids += chunk.participating_bids;
+            }
+            let (takes_lead, rule) = outbids(chunk.highest_bid, chunk.winner_timestamp, highest_bid, winner_timestamp);
+        
+            // A tie across chunks overrides whatever rule decided inside a chunk
+            if is_active && rule != TIE_BREAK_NONE {
+                tie_break_rule = rule;
+            } else if is_active && takes_lead {
+                tie_break_rule = chunk.tie_break_rule;
+            }
+        
+            if is_active && takes_lead {
+                second_highest = highest_bid.max(chunk.second_highest);
+                highest_bid = chunk.highest_bid;
+                winner_id = chunk.winner_id;
+                winner_timestamp = chunk.winner_timestamp;
+                private_winner = chunk.private_winner;
+            } else if is_active && chunk.highest_bid > second_highest {
+                second_highest = chunk.highest_bid;
+            }
+        }
+        
+        let (clearing_price, price_capped) = proxy_clearing_price(highest_bid, second_highest, auction.bid_increment);
+        let met_reserve = clearing_price >= auction.reserve_price;
         
-        bids.owner.from_arcis((winner_id, winning_amount, met_reserve))
+        PrivateSettlementResult {
+            base: SettlementResult {
+                winner_id,
+                clearing_price,
+                met_reserve,
+                tie_break_rule,
+                price_capped,
+                second_highest,
+                participating_bids,
+            },
+            private_winner,
+        }
+    }
+
+    #[instruction]
+    pub fn process_final_round(
+        auction_data: Enc<Mxe, AuctionData>,
+        chunk_winners: Enc<Mxe, [ChunkWinner; MAX_CHUNKS]>,
+        chunk_count: u64,
+        owner: Shared,
+    ) -> Enc<Shared, SettlementResult> {
+        let result = final_round(auction_data.to_arcis(), chunk_winners.to_arcis(), chunk_count);
+        owner.from_arcis(result.base)
+    }
+
+    // Privacy-mode final round: the winner id never leaves the cluster
+    #[instruction]
+    pub fn process_final_round_private(
+        auction_data: Enc<Mxe, AuctionData>,
+        chunk_winners: Enc<Mxe, [ChunkWinner; MAX_CHUNKS]>,
+        chunk_count: u64,
+        owner: Shared,
+    ) -> Enc<Shared, PrivateSettlementResult> {
+        let mut result = final_round(auction_data.to_arcis(), chunk_winners.to_arcis(), chunk_count);
+        result.base.winner_id = 0;
+        owner.from_arcis(result)
     }
 
+    // Revealed so the program can slash malformed bids before settlement;
+    // only validity leaves the cluster, never the amount.
     #[instruction]
     pub fn verify_bid_encryption(
         bid: Enc<Shared, BidData>,
         auction_id: u64,
-    ) -> Enc<Shared, bool> {
+    ) -> bool {
         let bid_data = bid.to_arcis();
         let is_valid = bid_data.auction_id == auction_id && bid_data.bid_amount > 0;
-        bid.owner.from_arcis(is_valid)
+        is_valid.reveal()
     }
-}
\ No newline at end of file
+}
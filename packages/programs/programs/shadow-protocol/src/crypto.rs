@@ -5,20 +5,20 @@ use crate::error::ShadowProtocolError;
 pub struct CryptoUtils;
 
 impl CryptoUtils {
-    /// Derive deterministic encryption key from auction and bidder context
+    /// Derive deterministic encryption key from auction and bidder context.
+    /// The creator is left out: ownership of a listing can change hands while
+    /// bids are open, and the auction id alone identifies the listing.
     pub fn derive_encryption_key(
         auction_id: u64,
         bidder: Pubkey,
-        auction_creator: Pubkey,
         nonce: u128,
     ) -> Result<[u8; 32]> {
         use anchor_lang::solana_program::hash::{hash, Hash};
         
         let mut data = Vec::new();
-        data.extend_from_slice(b"shadow_bid_encryption_v1");
+        data.extend_from_slice(b"shadow_bid_encryption_v2");
         data.extend_from_slice(&auction_id.to_le_bytes());
         data.extend_from_slice(&bidder.to_bytes());
-        data.extend_from_slice(&auction_creator.to_bytes());
         data.extend_from_slice(&nonce.to_le_bytes());
         
         let hash_result: Hash = hash(&data);
@@ -30,43 +30,57 @@ impl CryptoUtils {
         provided_key: [u8; 32],
         auction_id: u64,
         bidder: Pubkey,
-        auction_creator: Pubkey,
         nonce: u128,
     ) -> Result<bool> {
-        let expected_key = Self::derive_encryption_key(
-            auction_id,
-            bidder,
-            auction_creator,
-            nonce,
-        )?;
+        let expected_key = Self::derive_encryption_key(auction_id, bidder, nonce)?;
         
         Ok(provided_key == expected_key)
     }
     
-    /// Generate auction-specific public key for MPC computation
-    pub fn derive_auction_public_key(
+    /// Key as derived before v2, which also bound the auction's creator.
+    /// Only used to tell bids prepared under the old scheme apart.
+    pub fn derive_legacy_encryption_key(
         auction_id: u64,
-        creator: Pubkey,
-        start_time: i64,
+        bidder: Pubkey,
+        auction_creator: Pubkey,
+        nonce: u128,
     ) -> Result<[u8; 32]> {
         use anchor_lang::solana_program::hash::{hash, Hash};
         
         let mut data = Vec::new();
-        data.extend_from_slice(b"shadow_auction_pubkey_v1");
+        data.extend_from_slice(b"shadow_bid_encryption_v1");
         data.extend_from_slice(&auction_id.to_le_bytes());
-        data.extend_from_slice(&creator.to_bytes());
-        data.extend_from_slice(&start_time.to_le_bytes());
+        data.extend_from_slice(&bidder.to_bytes());
+        data.extend_from_slice(&auction_creator.to_bytes());
+        data.extend_from_slice(&nonce.to_le_bytes());
         
         let hash_result: Hash = hash(&data);
         Ok(hash_result.to_bytes())
     }
     
+    /// Fail unless `provided_key` is the current derivation. A key derived
+    /// under v1 gets its own error, so clients know to derive it again
+    /// rather than treat the bid as malformed.
+    pub fn require_encryption_key(
+        provided_key: [u8; 32],
+        auction_id: u64,
+        bidder: Pubkey,
+        auction_creator: Pubkey,
+        nonce: u128,
+    ) -> Result<()> {
+        if Self::verify_encryption_key(provided_key, auction_id, bidder, nonce)? {
+            return Ok(());
+        }
+        let legacy_key = Self::derive_legacy_encryption_key(auction_id, bidder, auction_creator, nonce)?;
+        require!(provided_key != legacy_key, ShadowProtocolError::RetiredEncryptionKey);
+        err!(ShadowProtocolError::InvalidEncryption)
+    }
+    
     /// Validate bid encryption format and constraints
     pub fn validate_encrypted_bid(
         encrypted_data: &[u8; 32],
         public_key: &[u8; 32],
         nonce: u128,
-        minimum_bid: u64,
     ) -> Result<()> {
         // Validate nonce is within reasonable bounds (prevent replay attacks)
         require!(
@@ -96,110 +110,181 @@ impl CryptoUtils {
         Ok(())
     }
     
-    /// Verify MPC computation authenticity
-    pub fn verify_mpc_computation(
-        computation_result: &[u8],
+    /// Hash stored on an invite-only auction; bidders present `invite_code`
+    pub fn hash_invite_code(
         auction_id: u64,
-        bid_count: u64,
-        end_time: i64,
-        expected_hash: [u8; 32],
-    ) -> Result<bool> {
+        invite_code: &[u8; 32],
+    ) -> Result<[u8; 32]> {
         use anchor_lang::solana_program::hash::{hash, Hash};
         
-        let mut verification_data = Vec::new();
-        verification_data.extend_from_slice(b"shadow_mpc_verification_v1");
-        verification_data.extend_from_slice(&auction_id.to_le_bytes());
-        verification_data.extend_from_slice(&bid_count.to_le_bytes());
-        verification_data.extend_from_slice(&end_time.to_le_bytes());
-        verification_data.extend_from_slice(computation_result);
+        let mut data = Vec::new();
+        data.extend_from_slice(b"shadow_invite_code_v1");
+        data.extend_from_slice(&auction_id.to_le_bytes());
+        data.extend_from_slice(invite_code);
         
-        let computed_hash: Hash = hash(&verification_data);
-        Ok(computed_hash.to_bytes() == expected_hash)
+        let hash_result: Hash = hash(&data);
+        Ok(hash_result.to_bytes())
     }
     
-    /// Generate secure bid commitment hash
-    pub fn generate_bid_commitment(
-        bidder: Pubkey,
-        amount: u64,
-        nonce: u128,
+    /// Claim ticket for an anonymous listing. The seller generates a fresh
+    /// `claim_key` and publishes only this hash; signing with that key later
+    /// proves ownership without revealing anything a front-runner could reuse.
+    pub fn derive_claim_ticket(
         auction_id: u64,
+        claim_key: Pubkey,
     ) -> Result<[u8; 32]> {
         use anchor_lang::solana_program::hash::{hash, Hash};
         
         let mut data = Vec::new();
-        data.extend_from_slice(b"shadow_bid_commitment_v1");
-        data.extend_from_slice(&bidder.to_bytes());
-        data.extend_from_slice(&amount.to_le_bytes());
-        data.extend_from_slice(&nonce.to_le_bytes());
+        data.extend_from_slice(b"shadow_claim_ticket_v1");
         data.extend_from_slice(&auction_id.to_le_bytes());
+        data.extend_from_slice(&claim_key.to_bytes());
         
         let hash_result: Hash = hash(&data);
         Ok(hash_result.to_bytes())
     }
     
-    /// Validate auction encryption parameters
-    pub fn validate_auction_encryption(
-        reserve_price_encrypted: &[u8; 32],
-        reserve_price_nonce: u128,
-        creator: Pubkey,
-        auction_id: u64,
-    ) -> Result<()> {
-        // Validate reserve price encryption is not trivial
-        require!(
-            *reserve_price_encrypted != [0u8; 32],
-            ShadowProtocolError::InvalidEncryption
-        );
-        
-        // Validate nonce uniqueness per auction
-        require!(
-            reserve_price_nonce > 0,
-            ShadowProtocolError::InvalidEncryption
-        );
+    /// Leaf of an auction's bid-set Merkle tree
+    pub fn hash_bid_leaf(
+        bidder: Pubkey,
+        amount_encrypted: &[u8; 32],
+        nonce: u128,
+    ) -> [u8; 32] {
+        use anchor_lang::solana_program::hash::{hash, Hash};
         
-        // Generate expected encryption context
-        let expected_context = Self::derive_auction_encryption_context(
-            auction_id,
-            creator,
-            reserve_price_nonce,
-        )?;
+        let mut data = Vec::new();
+        data.extend_from_slice(b"shadow_bid_leaf_v1");
+        data.extend_from_slice(&bidder.to_bytes());
+        data.extend_from_slice(amount_encrypted);
+        data.extend_from_slice(&nonce.to_le_bytes());
         
-        // Verify encryption follows expected pattern
-        let context_hash = Self::hash_encryption_context(&expected_context)?;
-        let encryption_prefix = &reserve_price_encrypted[0..8];
-        let context_prefix = &context_hash[0..8];
+        let hash_result: Hash = hash(&data);
+        hash_result.to_bytes()
+    }
+    
+    /// Leaf recording a compressed account: its address and full data,
+    /// discriminator included
+    pub fn hash_compressed_account(address: Pubkey, data: &[u8]) -> [u8; 32] {
+        use anchor_lang::solana_program::hash::{hash, hashv};
         
-        // Encrypted data should be deterministically related to context
-        require!(
-            encryption_prefix != context_prefix, // Should be encrypted, not raw context
-            ShadowProtocolError::InvalidEncryption
-        );
+        hashv(&[b"shadow_compressed_account_v1", &address.to_bytes(), &hash(data).to_bytes()]).to_bytes()
+    }
+    
+    /// Interior node of a Merkle tree (bid sets, compressed accounts)
+    pub fn hash_merkle_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        use anchor_lang::solana_program::hash::hashv;
         
-        Ok(())
+        hashv(&[left, right]).to_bytes()
+    }
+    
+    /// Add `leaf` as leaf number `leaf_count` of an append-only Merkle tree
+    /// kept as its right edge: `branch[h]` is the last complete left subtree
+    /// at height h. The tree is `branch.len()` levels deep.
+    pub fn merkle_frontier_append(branch: &mut [[u8; 32]], leaf_count: u64, leaf: [u8; 32]) {
+        let mut node = leaf;
+        let mut size = leaf_count + 1;
+        for sibling in branch.iter_mut() {
+            if size & 1 == 1 {
+                *sibling = node;
+                break;
+            }
+            node = Self::hash_merkle_node(sibling, &node);
+            size >>= 1;
+        }
     }
     
-    /// Derive auction encryption context
-    fn derive_auction_encryption_context(
+    /// Root of a tree kept by `merkle_frontier_append`, padded with zero leaves
+    pub fn merkle_frontier_root(branch: &[[u8; 32]], leaf_count: u64) -> [u8; 32] {
+        let mut node = [0u8; 32];
+        let mut zero = [0u8; 32];
+        let mut size = leaf_count;
+        for sibling in branch {
+            node = if size & 1 == 1 {
+                Self::hash_merkle_node(sibling, &node)
+            } else {
+                Self::hash_merkle_node(&node, &zero)
+            };
+            zero = Self::hash_merkle_node(&zero, &zero);
+            size >>= 1;
+        }
+        node
+    }
+    
+    /// Root implied by `leaf` sitting at `index` with sibling hashes `proof`
+    pub fn merkle_path_root(leaf: [u8; 32], index: u64, proof: &[[u8; 32]]) -> [u8; 32] {
+        let mut node = leaf;
+        for (height, sibling) in proof.iter().enumerate() {
+            node = if (index >> height) & 1 == 0 {
+                Self::hash_merkle_node(&node, sibling)
+            } else {
+                Self::hash_merkle_node(sibling, &node)
+            };
+        }
+        node
+    }
+    
+    /// Commitment a privacy-mode settlement publishes in place of the winner.
+    /// The winner opens it in `claim_win` with the blinding they decrypt
+    /// from the circuit output.
+    pub fn generate_winner_commitment(
         auction_id: u64,
-        creator: Pubkey,
-        nonce: u128,
+        winner: Pubkey,
+        blinding: [u8; 32],
     ) -> Result<[u8; 32]> {
         use anchor_lang::solana_program::hash::{hash, Hash};
         
         let mut data = Vec::new();
-        data.extend_from_slice(b"shadow_auction_encryption_context_v1");
+        data.extend_from_slice(b"shadow_winner_commitment_v1");
         data.extend_from_slice(&auction_id.to_le_bytes());
-        data.extend_from_slice(&creator.to_bytes());
-        data.extend_from_slice(&nonce.to_le_bytes());
+        data.extend_from_slice(&winner.to_bytes());
+        data.extend_from_slice(&blinding);
         
         let hash_result: Hash = hash(&data);
         Ok(hash_result.to_bytes())
     }
     
-    /// Hash encryption context for verification
-    fn hash_encryption_context(context: &[u8; 32]) -> Result<[u8; 32]> {
+    /// Generate the commitment a creator publishes alongside an encrypted
+    /// reserve price. Binding it to the ciphertext and its nonce ties the
+    /// opened value to the reserve the MPC settlement actually saw.
+    pub fn generate_reserve_commitment(
+        reserve_price: u64,
+        blinding: [u8; 32],
+        reserve_price_encrypted: &[u8; 32],
+        reserve_price_nonce: u128,
+    ) -> Result<[u8; 32]> {
         use anchor_lang::solana_program::hash::{hash, Hash};
         
-        let hash_result: Hash = hash(context);
+        let mut data = Vec::new();
+        data.extend_from_slice(b"shadow_reserve_commitment_v1");
+        data.extend_from_slice(&reserve_price.to_le_bytes());
+        data.extend_from_slice(&blinding);
+        data.extend_from_slice(reserve_price_encrypted);
+        data.extend_from_slice(&reserve_price_nonce.to_le_bytes());
+        
+        let hash_result: Hash = hash(&data);
         Ok(hash_result.to_bytes())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn v1_encryption_keys_are_rejected_as_retired() {
+        let bidder = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let current = CryptoUtils::derive_encryption_key(7, bidder, 9).unwrap();
+        let legacy = CryptoUtils::derive_legacy_encryption_key(7, bidder, creator, 9).unwrap();
+        
+        assert!(CryptoUtils::require_encryption_key(current, 7, bidder, creator, 9).is_ok());
+        assert_eq!(
+            CryptoUtils::require_encryption_key(legacy, 7, bidder, creator, 9),
+            Err(ShadowProtocolError::RetiredEncryptionKey.into()),
+        );
+        assert_eq!(
+            CryptoUtils::require_encryption_key([1u8; 32], 7, bidder, creator, 9),
+            Err(ShadowProtocolError::InvalidEncryption.into()),
+        );
+    }
+}
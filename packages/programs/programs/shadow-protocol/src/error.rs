@@ -131,4 +131,439 @@ pub enum ShadowProtocolError {
 
     #[msg("Computation definition inactive")]
     ComputationDefinitionInactive,
-}
\ No newline at end of file
+
+    #[msg("Invalid remaining accounts")]
+    InvalidRemainingAccounts,
+
+    #[msg("Account is not eligible for storage reclamation")]
+    AccountNotReclaimable,
+
+    #[msg("Cleanup grace period out of bounds")]
+    InvalidCleanupGracePeriod,
+
+    #[msg("Auction has received bids")]
+    AuctionHasBids,
+
+    #[msg("Auction ownership transfer already pending")]
+    OwnershipTransferPending,
+
+    #[msg("No pending auction ownership transfer")]
+    NoPendingOwnershipTransfer,
+
+    #[msg("Settlement chunk already completed")]
+    ChunkAlreadyCompleted,
+
+    #[msg("Not all settlement chunks have completed")]
+    SettlementRoundsIncomplete,
+
+    #[msg("Bid is already linked to a budget")]
+    BidAlreadyLinked,
+
+    #[msg("Budget already spans the maximum number of auctions")]
+    TooManyLinkedAuctions,
+
+    #[msg("Invalid budget status for this operation")]
+    InvalidBudgetStatus,
+
+    #[msg("Winning bid was not allocated within its budget")]
+    BudgetAllocationExceeded,
+
+    #[msg("Invalid market round duration")]
+    InvalidRoundDuration,
+
+    #[msg("Market round is closed to new orders")]
+    MarketRoundClosed,
+
+    #[msg("Market round has not ended yet")]
+    MarketRoundNotEnded,
+
+    #[msg("Market round has reached the maximum number of orders")]
+    MarketFull,
+
+    #[msg("Invalid market status for this operation")]
+    InvalidMarketStatus,
+
+    #[msg("Token account does not match the expected mint or owner")]
+    InvalidTokenAccount,
+
+    #[msg("Batch results have already been distributed")]
+    BatchResultsAlreadyDistributed,
+
+    #[msg("Batch cannot be cancelled in its current state")]
+    BatchNotCancellable,
+
+    #[msg("Batch has not reached its expiry period")]
+    BatchNotExpired,
+
+    #[msg("Reserve price has already been revealed")]
+    ReserveAlreadyRevealed,
+
+    #[msg("Revealed reserve price does not match its commitment")]
+    ReserveCommitmentMismatch,
+
+    #[msg("Winning bid did not meet the reserve price")]
+    ReserveNotMet,
+
+    #[msg("Dispute window out of bounds")]
+    InvalidDisputeWindow,
+
+    #[msg("Settlement is frozen by an open dispute")]
+    SettlementDisputed,
+
+    #[msg("Settlement dispute window has not elapsed")]
+    DisputeWindowOpen,
+
+    #[msg("Settlement dispute window has closed")]
+    DisputeWindowClosed,
+
+    #[msg("No open dispute for this auction")]
+    NoOpenDispute,
+
+    #[msg("Juror stake is below the minimum")]
+    InsufficientJurorStake,
+
+    #[msg("Arbitration case is closed")]
+    ArbitrationClosed,
+
+    #[msg("Arbitration voting has not ended")]
+    ArbitrationVotingOpen,
+
+    #[msg("Arbitration case has reached the maximum evidence entries")]
+    TooMuchEvidence,
+
+    #[msg("Parties to the settlement cannot vote on it")]
+    JurorConflict,
+
+    #[msg("Juror has already voted on this case")]
+    AlreadyVoted,
+
+    #[msg("Arbitration case has reached the maximum number of jurors")]
+    JuryFull,
+
+    #[msg("Auction duration is below the protocol minimum")]
+    AuctionDurationTooShort,
+
+    #[msg("Minimum bid is below the protocol floor")]
+    MinimumBidBelowFloor,
+
+    #[msg("Invalid auction parameter bounds")]
+    InvalidAuctionBounds,
+
+    #[msg("Auction status transition is not allowed")]
+    InvalidStatusTransition,
+
+    #[msg("Computation has already been marked completed or failed")]
+    ComputationAlreadyResolved,
+
+    #[msg("MPC result version is not supported")]
+    UnsupportedMpcResultVersion,
+
+    #[msg("Winner claim does not open the settlement commitment")]
+    WinnerCommitmentMismatch,
+
+    #[msg("Claim ticket does not match the anonymous listing")]
+    InvalidClaimTicket,
+
+    #[msg("Invite code is missing or does not match this auction")]
+    InvalidInviteCode,
+
+    #[msg("Compliance program account is missing or does not match")]
+    InvalidComplianceProgram,
+
+    #[msg("Wallet is on the protocol deny-list")]
+    WalletDenied,
+
+    #[msg("Unknown deny-list flags")]
+    InvalidDenyListFlags,
+
+    #[msg("Price feed account is missing or does not match the auction")]
+    InvalidPriceFeed,
+
+    #[msg("Price feed is older than the auction allows")]
+    StalePriceFeed,
+
+    #[msg("Winning amount is out of band with the oracle price")]
+    OraclePriceOutOfBand,
+
+    #[msg("Payout conversion accounts are missing or do not match the auction")]
+    InvalidPayoutConversion,
+
+    #[msg("Swap output is below the auction's minimum rate")]
+    SlippageExceeded,
+
+    #[msg("Collateral is not in the auction's collateral mint")]
+    InvalidCollateralMint,
+
+    #[msg("Winner can still execute settlement")]
+    SettlementDeadlineNotReached,
+
+    #[msg("Yield escrow is not enabled for this auction")]
+    YieldNotAvailable,
+
+    #[msg("Escrowed funds are still deposited with the yield adapter")]
+    YieldPositionsOpen,
+
+    #[msg("Vesting schedule is invalid or conflicts with the payout settings")]
+    InvalidVestingSchedule,
+
+    #[msg("Nothing has vested since the last claim")]
+    NothingToClaim,
+
+    #[msg("LBP purchases must be filled in submission order")]
+    LbpPurchaseOutOfOrder,
+
+    #[msg("LBP purchases are still waiting to be filled")]
+    LbpPurchasesPending,
+
+    #[msg("Market batch configuration is invalid or missing")]
+    InvalidMarketConfig,
+
+    #[msg("Auction house is paused")]
+    AuctionHousePaused,
+
+    #[msg("Creator is not allowlisted on this auction house")]
+    CreatorNotAllowlisted,
+
+    #[msg("Auction house does not match the auction")]
+    InvalidAuctionHouse,
+
+    #[msg("Auction house fee exceeds the maximum")]
+    InvalidHouseFee,
+
+    #[msg("Fee voucher is invalid or not signed by the protocol authority")]
+    InvalidFeeVoucher,
+
+    #[msg("Fee voucher has expired")]
+    FeeVoucherExpired,
+
+    #[msg("Fee tiers must rise in volume and fall in fee")]
+    InvalidFeeTiers,
+
+    #[msg("Governance account or proposal execution is invalid")]
+    InvalidGovernance,
+
+    #[msg("Stake is still within its lock period")]
+    StakeLocked,
+
+    #[msg("No change is pending confirmation")]
+    NoPendingChange,
+
+    #[msg("Confirmation delay has not elapsed")]
+    ChangeDelayNotElapsed,
+
+    #[msg("Protocol is not paused")]
+    ProtocolNotPaused,
+
+    #[msg("Protocol fee increase exceeds the per-update limit")]
+    FeeIncreaseTooLarge,
+
+    #[msg("Collateral escrow accounts are missing or do not match the bid")]
+    InvalidCollateralEscrow,
+
+    #[msg("Auctions that convert or stream proceeds, or carry a position, settle through execute_settlement")]
+    PullSettlementUnsupported,
+
+    #[msg("No proceeds are waiting to be claimed")]
+    NoProceedsToClaim,
+
+    #[msg("Auction ended without a sale")]
+    AuctionUnsold,
+
+    #[msg("Auction metadata name, symbol or URI is too long")]
+    InvalidAuctionMetadata,
+
+    #[msg("Auction account is not in the legacy layout or cannot be converted")]
+    InvalidAuctionLayout,
+
+    #[msg("Relayed bid is not signed by the bidder or its collateral is not delegated to the relayer")]
+    InvalidRelayedBid,
+
+    #[msg("Relayed bid has expired")]
+    RelayedBidExpired,
+
+    #[msg("Settlement transaction has expired")]
+    SettlementExpired,
+
+    #[msg("Protocol fee exceeds the maximum the settlement was signed for")]
+    SettlementFeeTooHigh,
+
+    #[msg("Lookup table address does not match its authority and slot")]
+    InvalidLookupTable,
+
+    #[msg("Protocol stats shard index out of range")]
+    InvalidStatsShard,
+
+    #[msg("Asset vault holds nothing beyond the recorded deposit")]
+    NoVaultSurplus,
+
+    #[msg("Emergency withdrawal has already been executed")]
+    EmergencyWithdrawalExecuted,
+
+    #[msg("Minimum collateral ratio must be positive")]
+    InvalidCollateralRatio,
+
+    #[msg("Bid passed encryption verification")]
+    BidEncryptionValid,
+
+    #[msg("Gas schedule is invalid or belongs to another circuit")]
+    InvalidGasSchedule,
+
+    #[msg("Gas limit is below what the circuit requires")]
+    InsufficientGasLimit,
+
+    #[msg("MXE cluster list is empty, too long or has duplicates")]
+    InvalidClusterList,
+
+    #[msg("MXE cluster is not approved")]
+    ClusterNotApproved,
+
+    #[msg("MXE cluster is marked unhealthy")]
+    ClusterUnhealthy,
+
+    #[msg("No other healthy MXE cluster to fail over to")]
+    NoHealthyCluster,
+
+    #[msg("Queued computation has not timed out yet")]
+    ComputationNotTimedOut,
+
+    #[msg("A different result was already applied for this computation")]
+    ComputationAlreadyConsumed,
+
+    #[msg("Reported collateral does not match the bid's escrow")]
+    CollateralMismatch,
+
+    #[msg("Bid set has already been finalized")]
+    BidSetFinalized,
+
+    #[msg("Bids must be appended once each, in ascending bidder order")]
+    InvalidBidSetOrder,
+
+    #[msg("Bid set has not been finalized")]
+    BidSetNotFinalized,
+
+    #[msg("Bid is not included in the committed bid set")]
+    InvalidInclusionProof,
+
+    #[msg("Compressed state tree is full")]
+    CompressedTreeFull,
+
+    #[msg("Compressed account data does not match the account being restored")]
+    InvalidCompressedAccount,
+
+    #[msg("Auction has no automated step due")]
+    NothingToAdvance,
+
+    #[msg("Bid cutoff must leave part of the auction open for bidding")]
+    InvalidBidCutoff,
+
+    #[msg("Settlements are soft-paused by the circuit breaker")]
+    SettlementsSoftPaused,
+
+    #[msg("Invalid circuit breaker configuration")]
+    InvalidCircuitBreaker,
+
+    #[msg("A resumable settlement is in progress for this auction")]
+    SettlementInProgress,
+
+    #[msg("No pending settlement step was given its accounts")]
+    NoSettlementStepReady,
+
+    #[msg("Withdrawal exceeds the fees outstanding in the ledger")]
+    FeeWithdrawalExceedsOutstanding,
+
+    #[msg("Invalid tranche configuration")]
+    InvalidTrancheConfig,
+
+    #[msg("Auction takes quantity bids")]
+    QuantityBidsEnabled,
+
+    #[msg("Quantity allocations are still being distributed")]
+    QuantityDistributionPending,
+
+    #[msg("Auction vault is locked until the auction settles or is cancelled")]
+    VaultLocked,
+
+    #[msg("Settlement hook program is missing or does not match the auction's")]
+    InvalidSettlementHook,
+
+    #[msg("Auction's proceeds are not paid out in one settlement, so it cannot carry a position")]
+    AuctionPositionUnsupported,
+
+    #[msg("Position account does not hold the auction's position")]
+    InvalidAuctionPosition,
+
+    #[msg("Bids drawing on a budget cannot carry a position")]
+    BidPositionUnsupported,
+
+    #[msg("Position account does not hold the bid's position")]
+    InvalidBidPosition,
+
+    #[msg("Juror stake is locked until their open cases resolve")]
+    JurorStakeLocked,
+
+    #[msg("Auction already has a settlement computation queued")]
+    ComputationAlreadyQueued,
+
+    #[msg("Private winner can still claim the win")]
+    WinClaimWindowOpen,
+
+    #[msg("Settlement chunk reported out of order")]
+    UnexpectedChunkIndex,
+
+    #[msg("Settlement rounds are still in use")]
+    SettlementRoundsInUse,
+
+    #[msg("Payment is not in the auction's payment mint")]
+    InvalidPaymentMint,
+
+    #[msg("Bid encryption key was derived under the retired v1 scheme; derive it again")]
+    RetiredEncryptionKey,
+}
+
+/// Emitted when a settlement or bidding instruction fails, so the failing
+/// check can be identified from transaction logs alone
+#[cfg(feature = "error-events")]
+#[event]
+pub struct GuardFailed {
+    pub instruction: String,
+    pub auction_id: u64,
+    /// Program error code, as in the IDL
+    pub error_code: u32,
+    /// `file:line` of the failing check, when known
+    pub origin: Option<String>,
+}
+
+/// Pass an instruction's result through, first emitting `GuardFailed` if it
+/// is an error and the `error-events` feature is enabled. Account constraint
+/// failures happen before the instruction runs and are not reported here.
+pub fn guard_context<T>(instruction: &str, auction_id: u64, result: Result<T>) -> Result<T> {
+    #[cfg(feature = "error-events")]
+    if let Err(error) = &result {
+        let origin = match error {
+            Error::AnchorError(error) => error.error_origin.as_ref(),
+            Error::ProgramError(error) => error.error_origin.as_ref(),
+        };
+        let origin = origin.map(|origin| match origin {
+            ErrorOrigin::Source(source) => format!("{}:{}", source.filename, source.line),
+            ErrorOrigin::AccountName(account) => account.clone(),
+        });
+        emit!(GuardFailed {
+            instruction: instruction.to_string(),
+            auction_id,
+            error_code: error_code(error),
+            origin,
+        });
+    }
+    #[cfg(not(feature = "error-events"))]
+    let _ = (instruction, auction_id);
+    
+    result
+}
+
+/// Program error code of `error`, as in the IDL
+pub fn error_code(error: &Error) -> u32 {
+    match error {
+        Error::AnchorError(error) => error.error_code_number,
+        Error::ProgramError(error) => u64::from(error.program_error.clone()) as u32,
+    }
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use crate::state::*;
+
+/// View-style instruction returning the addresses an auction listed by
+/// `creator` under `creator_nonce` lives at. The auction id itself is only
+/// assigned on creation, so it is read from the auction account afterwards.
+///
+/// Like the other views the result is set as return data, so clients that
+/// do not carry the program's seeds can simulate this instead.
+pub fn derive_auction_addresses(
+    _ctx: Context<DeriveAddresses>,
+    creator: Pubkey,
+    creator_nonce: u64,
+    asset_mint: Pubkey,
+) -> Result<AuctionAddresses> {
+    let auction = pda(&[AUCTION_SEED, AuctionAccount::address_digest(&creator, creator_nonce).as_ref()]);
+    
+    Ok(AuctionAddresses {
+        auction,
+        features: pda(&[AUCTION_FEATURES_SEED, auction.as_ref()]),
+        asset_vault: pda(&[ASSET_VAULT_SEED, auction.as_ref()]),
+        creator_asset_account: get_associated_token_address(&creator, &asset_mint),
+        protocol_stats: pda(&[PROTOCOL_STATS_SEED, ProtocolStats::shard_for(creator_nonce).as_ref()]),
+        creator_index: pda(&[CREATOR_INDEX_SEED, creator.as_ref()]),
+    })
+}
+
+/// View-style instruction returning `bidder`'s bid PDA on an auction
+pub fn derive_bid_address(
+    _ctx: Context<DeriveAddresses>,
+    auction_id: u64,
+    bidder: Pubkey,
+) -> Result<Pubkey> {
+    Ok(pda(&[BID_SEED, auction_id.to_le_bytes().as_ref(), bidder.as_ref()]))
+}
+
+/// View-style instruction returning the PDA of settlement batch `batch_id`
+pub fn derive_batch_address(
+    _ctx: Context<DeriveAddresses>,
+    batch_id: u64,
+) -> Result<Pubkey> {
+    Ok(pda(&[BATCH_SEED, batch_id.to_le_bytes().as_ref()]))
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &crate::ID).0
+}
+
+/// Accounts of one auction, as derived by `derive_auction_addresses`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AuctionAddresses {
+    pub auction: Pubkey,
+    /// Optional configuration created alongside the auction
+    pub features: Pubkey,
+    /// Program-owned vault holding the listed asset
+    pub asset_vault: Pubkey,
+    /// Creator's associated account the asset is deposited from
+    pub creator_asset_account: Pubkey,
+    /// Stats shard the auction's creation is counted in
+    pub protocol_stats: Pubkey,
+    pub creator_index: Pubkey,
+}
+
+/// Address derivation reads no accounts
+#[derive(Accounts)]
+pub struct DeriveAddresses {}
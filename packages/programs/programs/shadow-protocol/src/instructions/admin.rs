@@ -1,6 +1,14 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::error::ShadowProtocolError;
+use crate::instructions::governance::{require_governance_execution, GovernanceAction};
+use crate::instructions::auction_cleanup::{
+    MIN_CLEANUP_GRACE_PERIOD,
+    MAX_CLEANUP_GRACE_PERIOD,
+};
+use crate::instructions::dispute::{DEFAULT_DISPUTE_WINDOW, DEFAULT_DISPUTE_BOND, MAX_DISPUTE_WINDOW};
+use crate::instructions::insurance::DEFAULT_INSURANCE_FEE_SHARE;
+use crate::instructions::auction_management::DEFAULT_CREATION_DEPOSIT;
 
 pub fn initialize_protocol(ctx: Context<InitializeProtocol>) -> Result<()> {
     let protocol = &mut ctx.accounts.protocol_state;
@@ -13,7 +21,35 @@ pub fn initialize_protocol(ctx: Context<InitializeProtocol>) -> Result<()> {
     protocol.pending_authority = None;
     protocol.authority_transfer_timelock = None;
     protocol.bump = ctx.bumps.protocol_state;
-    protocol.reserved = [0u8; 100]; // Reduced due to new fields
+    protocol.cleanup_grace_period = CLEANUP_GRACE_PERIOD;
+    protocol.next_batch_id = 0;
+    protocol.dispute_window = DEFAULT_DISPUTE_WINDOW;
+    protocol.dispute_bond = DEFAULT_DISPUTE_BOND;
+    protocol.insurance_fee_share = DEFAULT_INSURANCE_FEE_SHARE;
+    protocol.creation_deposit = DEFAULT_CREATION_DEPOSIT;
+    protocol.auction_bounds = AuctionBounds {
+        min_duration: MIN_AUCTION_DURATION,
+        max_duration: MAX_AUCTION_DURATION,
+        min_bid_floor: 0,
+        max_price_decrease_rate: 0,
+        max_asset_amount: 0,
+    };
+    protocol.callback_authority = ctx.accounts.authority.key();
+    protocol.settlement_authorizer = Pubkey::default();
+    protocol.compliance_program = Pubkey::default();
+    protocol.yield_adapter = Pubkey::default();
+    protocol.fee_tier_mint = Pubkey::default();
+    protocol.fee_tiers = Vec::new();
+    protocol.governance = None;
+    protocol.pending_fee_update = None;
+    protocol.unpause_executable_at = None;
+    protocol.circuit_breaker = CircuitBreakerConfig {
+        window: 0,
+        volume_mint: Pubkey::default(),
+        max_volume: 0,
+        max_failures: 0,
+    };
+    protocol.reserved = [0u8; 18]; // Reduced due to new fields
     
     msg!("Shadow Protocol initialized");
     msg!("Authority: {}", protocol.authority);
@@ -23,37 +59,221 @@ pub fn initialize_protocol(ctx: Context<InitializeProtocol>) -> Result<()> {
     Ok(())
 }
 
-pub fn set_pause_state(ctx: Context<SetPauseState>, paused: bool) -> Result<()> {
+/// Create one protocol stats shard; every shard below `PROTOCOL_STATS_SHARDS`
+/// must exist before auctions counted in it can be created.
+///
+/// Not routed through governance: a shard can be created only once and
+/// starts out zeroed, so there is nothing for a proposal to decide.
+pub fn initialize_protocol_stats(ctx: Context<InitializeProtocolStats>, shard: u8) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require!(shard < PROTOCOL_STATS_SHARDS, ShadowProtocolError::InvalidStatsShard);
+    
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.shard = shard;
+    stats.auctions_created = 0;
+    stats.bids_submitted = 0;
+    stats.auctions_settled = 0;
+    stats.mint_volumes = Vec::new();
+    stats.bump = ctx.bumps.protocol_stats;
+    stats.window_start = Clock::get()?.unix_timestamp;
+    stats.window_volume = [0, 0];
+    stats.window_failures = [0, 0];
+    stats.soft_paused_at = None;
+    
+    Ok(())
+}
+
+/// Create the admin audit log every admin instruction records itself in.
+/// Like the stats shards it is a one-time empty account and needs no
+/// governance proposal.
+pub fn initialize_admin_audit_log(ctx: Context<InitializeAdminAuditLog>) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    let log = &mut ctx.accounts.audit_log;
+    log.entries = Vec::new();
+    log.next_slot = 0;
+    log.total_entries = 0;
+    log.bump = ctx.bumps.audit_log;
+    
+    Ok(())
+}
+
+/// Pause the protocol immediately. Unpausing is two-step: see
+/// `propose_unpause` and `confirm_unpause`.
+pub fn pause(ctx: Context<SetPauseState>) -> Result<()> {
     let protocol = &mut ctx.accounts.protocol_state;
     
     require!(
         ctx.accounts.authority.key() == protocol.authority,
         ShadowProtocolError::Unauthorized
     );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::SetPauseState,
+    )?;
     
-    protocol.paused = paused;
+    ctx.accounts.audit_log.record(
+        AdminAction::SetPauseState,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(protocol.paused as u64),
+        AdminAuditLog::word_u64(true as u64),
+    )?;
+    protocol.paused = true;
+    // Pausing again discards any unpause still waiting for confirmation
+    protocol.unpause_executable_at = None;
     
-    msg!("Protocol pause state updated: {}", paused);
+    msg!("Protocol paused");
     
     Ok(())
 }
 
-pub fn update_protocol_fee(ctx: Context<UpdateProtocolFee>, new_fee: u16) -> Result<()> {
+/// Start the unpause delay (first step)
+pub fn propose_unpause(ctx: Context<SetPauseState>) -> Result<()> {
     let protocol = &mut ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::SetPauseState,
+    )?;
+    require!(protocol.paused, ShadowProtocolError::ProtocolNotPaused);
+    
+    let executable_at = clock.unix_timestamp + UNPAUSE_DELAY;
+    ctx.accounts.audit_log.record(
+        AdminAction::ProposeUnpause,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(protocol.paused as u64),
+        AdminAuditLog::word_u64(executable_at as u64),
+    )?;
+    protocol.unpause_executable_at = Some(executable_at);
+    
+    msg!("Unpause proposed, executable at {}", executable_at);
+    
+    Ok(())
+}
+
+/// Unpause once the proposal's delay has elapsed (second step)
+pub fn confirm_unpause(ctx: Context<SetPauseState>) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
     
     require!(
         ctx.accounts.authority.key() == protocol.authority,
         ShadowProtocolError::Unauthorized
     );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::SetPauseState,
+    )?;
+    
+    let executable_at = protocol.unpause_executable_at
+        .ok_or(ShadowProtocolError::NoPendingChange)?;
+    require!(
+        clock.unix_timestamp >= executable_at,
+        ShadowProtocolError::ChangeDelayNotElapsed
+    );
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::SetPauseState,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(protocol.paused as u64),
+        AdminAuditLog::word_u64(false as u64),
+    )?;
+    protocol.paused = false;
+    protocol.unpause_executable_at = None;
+    
+    msg!("Protocol pause state updated: false");
+    
+    Ok(())
+}
+
+/// Propose a new protocol fee (first step). Proposing again replaces the
+/// pending fee and restarts the delay.
+pub fn propose_fee_update(ctx: Context<UpdateProtocolFee>, new_fee: u16) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateProtocolFee,
+    )?;
     
     require!(
         new_fee <= MAX_PROTOCOL_FEE,
         ShadowProtocolError::InvalidProtocolFee
     );
+    require!(
+        new_fee <= protocol.protocol_fee.saturating_add(MAX_PROTOCOL_FEE_INCREASE),
+        ShadowProtocolError::FeeIncreaseTooLarge
+    );
+    
+    let executable_at = clock.unix_timestamp + FEE_UPDATE_DELAY;
+    ctx.accounts.audit_log.record(
+        AdminAction::ProposeFeeUpdate,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(protocol.protocol_fee as u64),
+        AdminAuditLog::word_u64(new_fee as u64),
+    )?;
+    protocol.pending_fee_update = Some(PendingFeeUpdate {
+        new_fee,
+        executable_at,
+    });
+    
+    msg!("Protocol fee update to {} basis points proposed, executable at {}", new_fee, executable_at);
+    
+    Ok(())
+}
+
+/// Apply the pending protocol fee once its delay has elapsed (second step)
+pub fn confirm_fee_update(ctx: Context<UpdateProtocolFee>) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateProtocolFee,
+    )?;
+    
+    let pending = protocol.pending_fee_update
+        .ok_or(ShadowProtocolError::NoPendingChange)?;
+    require!(
+        clock.unix_timestamp >= pending.executable_at,
+        ShadowProtocolError::ChangeDelayNotElapsed
+    );
     
-    protocol.protocol_fee = new_fee;
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateProtocolFee,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(protocol.protocol_fee as u64),
+        AdminAuditLog::word_u64(pending.new_fee as u64),
+    )?;
+    protocol.protocol_fee = pending.new_fee;
+    protocol.pending_fee_update = None;
     
-    msg!("Protocol fee updated to {} basis points", new_fee);
+    msg!("Protocol fee updated to {} basis points", pending.new_fee);
     
     Ok(())
 }
@@ -65,7 +285,18 @@ pub fn update_fee_recipient(ctx: Context<UpdateFeeRecipient>, new_recipient: Pub
         ctx.accounts.authority.key() == protocol.authority,
         ShadowProtocolError::Unauthorized
     );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateFeeRecipient,
+    )?;
     
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateFeeRecipient,
+        ctx.accounts.authority.key(),
+        protocol.fee_recipient.to_bytes(),
+        new_recipient.to_bytes(),
+    )?;
     protocol.fee_recipient = new_recipient;
     
     msg!("Fee recipient updated to {}", new_recipient);
@@ -73,6 +304,377 @@ pub fn update_fee_recipient(ctx: Context<UpdateFeeRecipient>, new_recipient: Pub
     Ok(())
 }
 
+pub fn update_cleanup_grace_period(ctx: Context<UpdateCleanupGracePeriod>, new_grace_period: i64) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateCleanupGracePeriod,
+    )?;
+    
+    require!(
+        (MIN_CLEANUP_GRACE_PERIOD..=MAX_CLEANUP_GRACE_PERIOD).contains(&new_grace_period),
+        ShadowProtocolError::InvalidCleanupGracePeriod
+    );
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateCleanupGracePeriod,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(protocol.cleanup_grace_period() as u64),
+        AdminAuditLog::word_u64(new_grace_period as u64),
+    )?;
+    protocol.cleanup_grace_period = new_grace_period;
+    
+    msg!("Cleanup grace period updated to {} seconds", new_grace_period);
+    
+    Ok(())
+}
+
+/// Update the settlement challenge period and dispute bond. A zero window
+/// disables disputes.
+pub fn update_dispute_config(
+    ctx: Context<UpdateDisputeConfig>,
+    dispute_window: i64,
+    dispute_bond: u64,
+) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateDisputeConfig,
+    )?;
+    
+    require!(
+        (0..=MAX_DISPUTE_WINDOW).contains(&dispute_window),
+        ShadowProtocolError::InvalidDisputeWindow
+    );
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateDisputeConfig,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&(protocol.dispute_window, protocol.dispute_bond))?,
+        AdminAuditLog::word_hash(&(dispute_window, dispute_bond))?,
+    )?;
+    protocol.dispute_window = dispute_window;
+    protocol.dispute_bond = dispute_bond;
+    
+    msg!("Dispute window updated to {} seconds, bond {} lamports", dispute_window, dispute_bond);
+    
+    Ok(())
+}
+
+/// Update the share of protocol fees, in basis points of the fee, that is
+/// routed to the insurance fund
+pub fn update_insurance_fee_share(
+    ctx: Context<UpdateInsuranceFeeShare>,
+    new_share: u16,
+) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateInsuranceFeeShare,
+    )?;
+    
+    require!(
+        new_share <= 10000,
+        ShadowProtocolError::InvalidProtocolFee
+    );
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateInsuranceFeeShare,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(protocol.insurance_fee_share as u64),
+        AdminAuditLog::word_u64(new_share as u64),
+    )?;
+    protocol.insurance_fee_share = new_share;
+    
+    msg!("Insurance fee share updated to {} basis points", new_share);
+    
+    Ok(())
+}
+
+/// Update the refundable deposit required to create an auction
+pub fn update_creation_deposit(ctx: Context<UpdateCreationDeposit>, new_deposit: u64) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateCreationDeposit,
+    )?;
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateCreationDeposit,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(protocol.creation_deposit),
+        AdminAuditLog::word_u64(new_deposit),
+    )?;
+    protocol.creation_deposit = new_deposit;
+    
+    msg!("Auction creation deposit updated to {} lamports", new_deposit);
+    
+    Ok(())
+}
+
+/// Rotate the relayer allowed to deliver MPC callbacks
+pub fn update_callback_authority(ctx: Context<UpdateCallbackAuthority>, new_callback_authority: Pubkey) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateCallbackAuthority,
+    )?;
+    
+    require!(
+        new_callback_authority != Pubkey::default(),
+        ShadowProtocolError::Unauthorized
+    );
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateCallbackAuthority,
+        ctx.accounts.authority.key(),
+        protocol.callback_authority.to_bytes(),
+        new_callback_authority.to_bytes(),
+    )?;
+    protocol.callback_authority = new_callback_authority;
+    
+    msg!("Callback authority updated to {}", new_callback_authority);
+    
+    Ok(())
+}
+
+/// Delegate settlement authorization to an operational key, so the MPC
+/// relayer infrastructure need not hold the authority. The default pubkey
+/// hands it back to the authority.
+pub fn update_settlement_authorizer(ctx: Context<UpdateSettlementAuthorizer>, new_settlement_authorizer: Pubkey) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateSettlementAuthorizer,
+    )?;
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateSettlementAuthorizer,
+        ctx.accounts.authority.key(),
+        protocol.settlement_authorizer.to_bytes(),
+        new_settlement_authorizer.to_bytes(),
+    )?;
+    protocol.settlement_authorizer = new_settlement_authorizer;
+    
+    msg!("Settlement authorizer updated to {}", new_settlement_authorizer);
+    
+    Ok(())
+}
+
+/// Assign an auction its own settlement authorizer, or with `None` return it
+/// to the protocol's. Only before its settlement has been authorized.
+pub fn set_auction_settlement_authorizer(
+    ctx: Context<SetAuctionSettlementAuthorizer>,
+    auction_id: u64,
+    authorizer: Option<Pubkey>,
+) -> Result<()> {
+    let protocol = &ctx.accounts.protocol_state;
+    let auction = &mut ctx.accounts.auction;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateSettlementAuthorizer,
+    )?;
+    require!(
+        !auction.settlement_authorized() && !auction.status.is_terminal(),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        authorizer != Some(Pubkey::default()),
+        ShadowProtocolError::Unauthorized
+    );
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::SetAuctionSettlementAuthorizer,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&(auction_id, auction.settlement_authorizer))?,
+        AdminAuditLog::word_hash(&(auction_id, authorizer))?,
+    )?;
+    auction.settlement_authorizer = authorizer;
+    
+    msg!("Auction {} settlement authorizer set to {:?}", auction_id, authorizer);
+    
+    Ok(())
+}
+
+/// Set the lending adapter auctions may opt into, or disable new opt-ins
+/// with the default pubkey. Auctions already opted in keep their adapter.
+pub fn update_yield_adapter(ctx: Context<UpdateYieldAdapter>, yield_adapter: Pubkey) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateYieldAdapter,
+    )?;
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateYieldAdapter,
+        ctx.accounts.authority.key(),
+        protocol.yield_adapter.to_bytes(),
+        yield_adapter.to_bytes(),
+    )?;
+    protocol.yield_adapter = yield_adapter;
+    
+    msg!("Yield adapter updated to {}", yield_adapter);
+    
+    Ok(())
+}
+
+/// Replace the volume fee tiers and the mint their volume is counted in.
+/// Tiers must rise in volume and fall in fee; an empty list disables them.
+pub fn update_fee_tiers(
+    ctx: Context<UpdateFeeTiers>,
+    fee_tier_mint: Pubkey,
+    fee_tiers: Vec<FeeTier>,
+) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateFeeTiers,
+    )?;
+    require!(
+        fee_tiers.len() <= MAX_FEE_TIERS
+            && fee_tiers.iter().all(|tier| tier.fee_bps <= MAX_PROTOCOL_FEE)
+            && fee_tiers.windows(2).all(|pair| {
+                pair[0].min_volume < pair[1].min_volume && pair[0].fee_bps > pair[1].fee_bps
+            }),
+        ShadowProtocolError::InvalidFeeTiers
+    );
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateFeeTiers,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&(protocol.fee_tier_mint, protocol.fee_tiers.clone()))?,
+        AdminAuditLog::word_hash(&(fee_tier_mint, fee_tiers.clone()))?,
+    )?;
+    protocol.fee_tier_mint = fee_tier_mint;
+    protocol.fee_tiers = fee_tiers;
+    
+    msg!("Fee tiers updated: {} tiers counted in {}", protocol.fee_tiers.len(), fee_tier_mint);
+    
+    Ok(())
+}
+
+/// Set the compliance program consulted by newly created auctions, or
+/// disable screening with the default pubkey. Live auctions keep the
+/// program they were created with.
+pub fn update_compliance_program(ctx: Context<UpdateComplianceProgram>, compliance_program: Pubkey) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateComplianceProgram,
+    )?;
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateComplianceProgram,
+        ctx.accounts.authority.key(),
+        protocol.compliance_program.to_bytes(),
+        compliance_program.to_bytes(),
+    )?;
+    protocol.compliance_program = compliance_program;
+    
+    msg!("Compliance program updated to {}", compliance_program);
+    
+    Ok(())
+}
+
+/// Replace the limits enforced on auction parameters at creation
+pub fn update_auction_bounds(ctx: Context<UpdateAuctionBounds>, bounds: AuctionBounds) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateAuctionBounds,
+    )?;
+    
+    require!(
+        bounds.min_duration >= MIN_AUCTION_DURATION &&
+        bounds.max_duration <= MAX_AUCTION_DURATION &&
+        (bounds.max_duration == 0 || bounds.min_duration <= bounds.max_duration),
+        ShadowProtocolError::InvalidAuctionBounds
+    );
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateAuctionBounds,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&protocol.auction_bounds)?,
+        AdminAuditLog::word_hash(&bounds)?,
+    )?;
+    protocol.auction_bounds = bounds;
+    
+    msg!(
+        "Auction bounds updated: duration {}..{}s, min bid floor {}",
+        bounds.min_duration,
+        bounds.max_duration,
+        bounds.min_bid_floor
+    );
+    
+    Ok(())
+}
+
 /// Initiate authority transfer (first step)
 pub fn initiate_authority_transfer(ctx: Context<InitiateAuthorityTransfer>, new_authority: Pubkey) -> Result<()> {
     let protocol = &mut ctx.accounts.protocol_state;
@@ -82,12 +684,23 @@ pub fn initiate_authority_transfer(ctx: Context<InitiateAuthorityTransfer>, new_
         ctx.accounts.authority.key() == protocol.authority,
         ShadowProtocolError::Unauthorized
     );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::InitiateAuthorityTransfer,
+    )?;
     
     require!(
         protocol.pending_authority.is_none(),
         ShadowProtocolError::AuthorityTransferPending
     );
     
+    ctx.accounts.audit_log.record(
+        AdminAction::InitiateAuthorityTransfer,
+        ctx.accounts.authority.key(),
+        protocol.authority.to_bytes(),
+        new_authority.to_bytes(),
+    )?;
     protocol.pending_authority = Some(new_authority);
     protocol.authority_transfer_timelock = Some(clock.unix_timestamp + AUTHORITY_TRANSFER_TIMELOCK);
     
@@ -107,6 +720,11 @@ pub fn complete_authority_transfer(ctx: Context<CompleteAuthorityTransfer>) -> R
         ctx.accounts.authority.key() == protocol.authority,
         ShadowProtocolError::Unauthorized
     );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::CompleteAuthorityTransfer,
+    )?;
     
     require!(
         protocol.pending_authority.is_some(),
@@ -118,6 +736,12 @@ pub fn complete_authority_transfer(ctx: Context<CompleteAuthorityTransfer>) -> R
         ShadowProtocolError::NoPendingAuthorityTransfer
     );
     
+    // Handovers to a governance complete through `accept_governance_authority`
+    require!(
+        protocol.governance.map(|config| config.governance) != protocol.pending_authority,
+        ShadowProtocolError::InvalidGovernance
+    );
+    
     let timelock_expires = protocol.authority_transfer_timelock.unwrap();
     require!(
         clock.unix_timestamp >= timelock_expires,
@@ -125,6 +749,12 @@ pub fn complete_authority_transfer(ctx: Context<CompleteAuthorityTransfer>) -> R
     );
     
     let new_authority = protocol.pending_authority.unwrap();
+    ctx.accounts.audit_log.record(
+        AdminAction::CompleteAuthorityTransfer,
+        ctx.accounts.authority.key(),
+        protocol.authority.to_bytes(),
+        new_authority.to_bytes(),
+    )?;
     protocol.authority = new_authority;
     protocol.pending_authority = None;
     protocol.authority_transfer_timelock = None;
@@ -142,14 +772,27 @@ pub fn cancel_authority_transfer(ctx: Context<CancelAuthorityTransfer>) -> Resul
         ctx.accounts.authority.key() == protocol.authority,
         ShadowProtocolError::Unauthorized
     );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::CancelAuthorityTransfer,
+    )?;
     
     require!(
         protocol.pending_authority.is_some(),
         ShadowProtocolError::NoPendingAuthorityTransfer
     );
     
+    ctx.accounts.audit_log.record(
+        AdminAction::CancelAuthorityTransfer,
+        ctx.accounts.authority.key(),
+        protocol.pending_authority.unwrap_or_default().to_bytes(),
+        Pubkey::default().to_bytes(),
+    )?;
     protocol.pending_authority = None;
     protocol.authority_transfer_timelock = None;
+    // A governance that never accepted is forgotten along with the transfer
+    protocol.governance = protocol.active_governance();
     
     msg!("Authority transfer cancelled");
     
@@ -177,6 +820,53 @@ pub struct InitializeProtocol<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(shard: u8)]
+pub struct InitializeProtocolStats<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProtocolStats::INIT_SPACE,
+        seeds = [PROTOCOL_STATS_SEED, &[shard]],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAdminAuditLog<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AdminAuditLog::INIT_SPACE,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct SetPauseState<'info> {
     pub authority: Signer<'info>,
@@ -187,6 +877,17 @@ pub struct SetPauseState<'info> {
         bump = protocol_state.bump
     )]
     pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(Accounts)]
@@ -199,6 +900,17 @@ pub struct UpdateProtocolFee<'info> {
         bump = protocol_state.bump
     )]
     pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(Accounts)]
@@ -212,10 +924,279 @@ pub struct UpdateFeeRecipient<'info> {
     )]
     pub protocol_state: Account<'info, ProtocolState>,
     
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
     /// CHECK: New fee recipient account
     pub new_recipient: AccountInfo<'info>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCleanupGracePeriod<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDisputeConfig<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateInsuranceFeeShare<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCreationDeposit<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCallbackAuthority<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSettlementAuthorizer<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateYieldAdapter<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeTiers<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateComplianceProgram<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAuctionBounds<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SetAuctionSettlementAuthorizer<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
 
 #[derive(Accounts)]
 pub struct InitiateAuthorityTransfer<'info> {
@@ -228,8 +1209,19 @@ pub struct InitiateAuthorityTransfer<'info> {
     )]
     pub protocol_state: Account<'info, ProtocolState>,
     
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
     /// CHECK: New authority account
     pub new_authority: AccountInfo<'info>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(Accounts)]
@@ -242,6 +1234,17 @@ pub struct CompleteAuthorityTransfer<'info> {
         bump = protocol_state.bump
     )]
     pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
 }
 
 #[derive(Accounts)]
@@ -254,4 +1257,15 @@ pub struct CancelAuthorityTransfer<'info> {
         bump = protocol_state.bump
     )]
     pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
 }
\ No newline at end of file
@@ -0,0 +1,278 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::crypto::CryptoUtils;
+use crate::instruction::{ExtendAuction, RelistAuction, RevealReservePrice, UpdateReservePrice};
+
+/// Creator-signed instructions a listing's ticket holder can issue through
+/// `act_as_listing`. Each takes the auction ID as its first argument and
+/// none needs the creator to pay for anything.
+const LISTING_PROXY_INSTRUCTIONS: [&[u8]; 4] = [
+    ExtendAuction::DISCRIMINATOR,
+    RelistAuction::DISCRIMINATOR,
+    UpdateReservePrice::DISCRIMINATOR,
+    RevealReservePrice::DISCRIMINATOR,
+];
+
+/// Hand an auction over to a per-auction listing PDA.
+///
+/// The seller creates the auction from a freshly funded ephemeral wallet and
+/// immediately calls this, after which the listing PDA is the auction's
+/// creator: proceeds, returned assets and the creation deposit all accrue to
+/// it, and creator-signed management goes through `act_as_listing`. The
+/// seller's main wallet only appears if it is later used to claim.
+pub fn create_anonymous_listing(
+    ctx: Context<CreateAnonymousListing>,
+    auction_id: u64,
+    ticket_hash: [u8; 32],
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    // Bidders must not see the creator change under them
+    require!(
+        auction.status == AuctionStatus::Active && auction.bid_count == 0,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    require!(
+        ticket_hash != [0u8; 32],
+        ShadowProtocolError::InvalidClaimTicket
+    );
+    
+    // A stream could only ever be claimed by the listing PDA
+    require!(
+        !auction.has_feature(AuctionFeatures::PAYOUT_VESTING),
+        ShadowProtocolError::InvalidVestingSchedule
+    );
+    
+    let listing = &mut ctx.accounts.listing;
+    listing.auction_id = auction_id;
+    listing.ticket_hash = ticket_hash;
+    listing.created_at = clock.unix_timestamp;
+    listing.bump = ctx.bumps.listing;
+    
+    auction.creator = listing.key();
+    auction.pending_creator = None;
+    
+    emit!(AnonymousListingCreated {
+        auction_id,
+        listing: listing.key(),
+        created_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Sweep an anonymous listing's token balance, and any lamports above rent,
+/// to accounts of the ticket holder's choosing. The claimant must sign with
+/// the claim key behind the listing's ticket. Callable once per token account
+/// the listing holds (settlement proceeds, a returned asset).
+pub fn claim_anonymous_proceeds(
+    ctx: Context<ClaimAnonymousProceeds>,
+    auction_id: u64,
+) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    
+    let ticket_hash = CryptoUtils::derive_claim_ticket(auction_id, ctx.accounts.claimant.key())?;
+    require!(
+        ticket_hash == listing.ticket_hash,
+        ShadowProtocolError::InvalidClaimTicket
+    );
+    
+    let amount = ctx.accounts.listing_token_account.amount;
+    if amount > 0 {
+        let auction_id_bytes = auction_id.to_le_bytes();
+        let listing_seeds = &[
+            ANONYMOUS_LISTING_SEED,
+            auction_id_bytes.as_ref(),
+            &[listing.bump],
+        ];
+        let signer_seeds = &[&listing_seeds[..]];
+        
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.listing_token_account.to_account_info(),
+                    to: ctx.accounts.destination_token_account.to_account_info(),
+                    authority: listing.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+    }
+    
+    // Refunded creation deposits land on the listing itself
+    let listing_info = listing.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(listing_info.data_len());
+    let lamports = listing_info.lamports().saturating_sub(rent_floor);
+    if lamports > 0 {
+        listing_info.sub_lamports(lamports)?;
+        ctx.accounts.lamport_recipient.add_lamports(lamports)?;
+    }
+    
+    emit!(AnonymousProceedsClaimed {
+        auction_id,
+        mint: ctx.accounts.listing_token_account.mint,
+        amount,
+        lamports,
+        claimed_at: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Issue a creator-signed instruction for a listed auction, with the listing
+/// PDA signing as its creator. The claimant must sign with the claim key
+/// behind the listing's ticket.
+///
+/// `data` is the inner instruction's data and the remaining accounts are its
+/// accounts, in order; the listing must appear among them as the creator.
+pub fn act_as_listing<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ActAsListing<'info>>,
+    auction_id: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+    
+    let ticket_hash = CryptoUtils::derive_claim_ticket(auction_id, ctx.accounts.claimant.key())?;
+    require!(
+        ticket_hash == listing.ticket_hash,
+        ShadowProtocolError::InvalidClaimTicket
+    );
+    
+    require!(
+        LISTING_PROXY_INSTRUCTIONS.iter().any(|discriminator| data.starts_with(discriminator)),
+        ShadowProtocolError::Unauthorized
+    );
+    // The listing may only act on its own auction
+    require!(
+        data.get(8..16) == Some(auction_id.to_le_bytes().as_ref()),
+        ShadowProtocolError::InvalidAuctionId
+    );
+    
+    let auction_id_bytes = auction_id.to_le_bytes();
+    let listing_seeds = &[
+        ANONYMOUS_LISTING_SEED,
+        auction_id_bytes.as_ref(),
+        &[listing.bump],
+    ];
+    let signer_seeds = &[&listing_seeds[..]];
+    
+    let metas = ctx.remaining_accounts
+        .iter()
+        .map(|info| {
+            let is_signer = info.is_signer || info.key() == listing.key();
+            if info.is_writable {
+                AccountMeta::new(info.key(), is_signer)
+            } else {
+                AccountMeta::new_readonly(info.key(), is_signer)
+            }
+        })
+        .collect();
+    
+    invoke_signed(
+        &Instruction {
+            program_id: crate::ID,
+            accounts: metas,
+            data,
+        },
+        ctx.remaining_accounts,
+        signer_seeds,
+    )?;
+    
+    Ok(())
+}
+
+#[event]
+pub struct AnonymousListingCreated {
+    pub auction_id: u64,
+    pub listing: Pubkey,
+    pub created_at: i64,
+}
+
+#[event]
+pub struct AnonymousProceedsClaimed {
+    pub auction_id: u64,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub lamports: u64,
+    pub claimed_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CreateAnonymousListing<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AnonymousListing::INIT_SPACE,
+        seeds = [ANONYMOUS_LISTING_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, AnonymousListing>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ClaimAnonymousProceeds<'info> {
+    pub claimant: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [ANONYMOUS_LISTING_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, AnonymousListing>,
+    
+    #[account(
+        mut,
+        constraint = listing_token_account.owner == listing.key() @ ShadowProtocolError::InvalidTokenAccount
+    )]
+    pub listing_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = listing_token_account.mint
+    )]
+    pub destination_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Receives lamports held above rent
+    #[account(mut)]
+    pub lamport_recipient: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ActAsListing<'info> {
+    pub claimant: Signer<'info>,
+    
+    #[account(
+        seeds = [ANONYMOUS_LISTING_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = listing.bump
+    )]
+    pub listing: Account<'info, AnonymousListing>,
+}
\ No newline at end of file
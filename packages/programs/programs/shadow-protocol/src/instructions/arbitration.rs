@@ -0,0 +1,684 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::collateral::release_sol_escrow;
+use crate::instructions::bid_position::{bid_holder, require_refund_account};
+use crate::instructions::dispute::void_settlement;
+
+/// Minimum lamports a juror must stake to register (1 SOL)
+pub const MIN_JUROR_STAKE: u64 = 1_000_000_000;
+
+/// How long jurors can vote on a case once it is opened (3 days)
+pub const ARBITRATION_VOTING_PERIOD: i64 = 3 * 24 * 60 * 60;
+
+/// Share of their stake jurors on the losing side of a verdict forfeit (10%)
+pub const JUROR_SLASH_BPS: u64 = 1000;
+
+/// Register as a juror by staking lamports into the juror account
+pub fn register_juror(ctx: Context<RegisterJuror>, stake: u64) -> Result<()> {
+    require!(stake >= MIN_JUROR_STAKE, ShadowProtocolError::InsufficientJurorStake);
+    
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.juror.to_account_info(),
+                to: ctx.accounts.juror_account.to_account_info(),
+            },
+        ),
+        stake,
+    )?;
+    
+    let juror = &mut ctx.accounts.juror_account;
+    juror.juror = ctx.accounts.juror.key();
+    juror.stake = stake;
+    juror.cases_voted = 0;
+    juror.open_cases = 0;
+    juror.registered_at = Clock::get()?.unix_timestamp;
+    juror.bump = ctx.bumps.juror_account;
+    
+    emit!(JurorRegistered {
+        juror: juror.juror,
+        stake,
+        registered_at: juror.registered_at,
+    });
+    
+    Ok(())
+}
+
+/// Leave the juror pool; the stake and rent are returned when the account
+/// closes. The stake stays locked while a case the juror voted on is open.
+pub fn deregister_juror(ctx: Context<DeregisterJuror>) -> Result<()> {
+    require!(
+        ctx.accounts.juror_account.open_cases == 0,
+        ShadowProtocolError::JurorStakeLocked
+    );
+    
+    msg!("Juror {} deregistered", ctx.accounts.juror.key());
+    
+    Ok(())
+}
+
+/// Escalate an open settlement dispute to a juror vote
+pub fn open_arbitration(
+    ctx: Context<OpenArbitration>,
+    auction_id: u64,
+    evidence_hash: [u8; 32],
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.protocol_state.paused, ShadowProtocolError::ProtocolPaused);
+    
+    require!(
+        auction.has_flag(AuctionAccount::DISPUTE_OPEN),
+        ShadowProtocolError::NoOpenDispute
+    );
+    
+    let case = &mut ctx.accounts.arbitration_case;
+    case.auction_id = auction_id;
+    case.disputer = ctx.accounts.disputer.key();
+    case.evidence = vec![ArbitrationEvidence {
+        submitter: ctx.accounts.disputer.key(),
+        evidence_hash,
+        submitted_at: clock.unix_timestamp,
+    }];
+    case.votes = Vec::new();
+    case.votes_void = 0;
+    case.votes_uphold = 0;
+    case.status = ArbitrationStatus::Open;
+    case.opened_at = clock.unix_timestamp;
+    case.voting_ends_at = clock.unix_timestamp + ARBITRATION_VOTING_PERIOD;
+    case.resolved_at = None;
+    case.bump = ctx.bumps.arbitration_case;
+    
+    emit!(ArbitrationOpened {
+        auction_id,
+        disputer: case.disputer,
+        voting_ends_at: case.voting_ends_at,
+    });
+    
+    Ok(())
+}
+
+/// Attach an evidence hash to an open case. Only the parties to the
+/// settlement (disputer, creator and winner) can submit evidence.
+pub fn submit_evidence(
+    ctx: Context<SubmitEvidence>,
+    auction_id: u64,
+    evidence_hash: [u8; 32],
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let case = &mut ctx.accounts.arbitration_case;
+    let submitter = ctx.accounts.submitter.key();
+    let clock = Clock::get()?;
+    
+    require!(
+        submitter == case.disputer || submitter == auction.creator || Some(submitter) == auction.winner,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        case.status == ArbitrationStatus::Open && clock.unix_timestamp < case.voting_ends_at,
+        ShadowProtocolError::ArbitrationClosed
+    );
+    
+    require!(
+        case.evidence.len() < MAX_CASE_EVIDENCE,
+        ShadowProtocolError::TooMuchEvidence
+    );
+    
+    case.evidence.push(ArbitrationEvidence {
+        submitter,
+        evidence_hash,
+        submitted_at: clock.unix_timestamp,
+    });
+    
+    emit!(EvidenceSubmitted {
+        auction_id,
+        submitter,
+        evidence_hash,
+        submitted_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Cast a juror vote on whether the disputed settlement should be voided.
+/// Votes are weighted by the juror's stake, which stays locked until the
+/// case resolves.
+pub fn cast_arbitration_vote(
+    ctx: Context<CastArbitrationVote>,
+    auction_id: u64,
+    void_settlement: bool,
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let case = &mut ctx.accounts.arbitration_case;
+    let juror = ctx.accounts.juror.key();
+    let clock = Clock::get()?;
+    
+    require!(
+        case.status == ArbitrationStatus::Open && clock.unix_timestamp < case.voting_ends_at,
+        ShadowProtocolError::ArbitrationClosed
+    );
+    
+    // Parties to the settlement cannot judge it. Bidders, the winner among
+    // them even while sealed, are kept out by their bid account.
+    require!(
+        juror != case.disputer && juror != auction.creator && Some(juror) != auction.winner,
+        ShadowProtocolError::JurorConflict
+    );
+    require!(
+        ctx.accounts.juror_bid.data_is_empty(),
+        ShadowProtocolError::JurorConflict
+    );
+    
+    require!(
+        !case.votes.iter().any(|vote| vote.juror == juror),
+        ShadowProtocolError::AlreadyVoted
+    );
+    
+    require!(
+        case.votes.len() < MAX_CASE_JURORS,
+        ShadowProtocolError::JuryFull
+    );
+    
+    let juror_account = &mut ctx.accounts.juror_account;
+    let stake = juror_account.stake;
+    case.votes.push(JurorVote {
+        juror,
+        stake,
+        void_settlement,
+    });
+    if void_settlement {
+        case.votes_void = case.votes_void.saturating_add(stake);
+    } else {
+        case.votes_uphold = case.votes_uphold.saturating_add(stake);
+    }
+    
+    juror_account.cases_voted = juror_account.cases_voted.saturating_add(1);
+    juror_account.open_cases = juror_account.open_cases.saturating_add(1);
+    
+    emit!(ArbitrationVoteCast {
+        auction_id,
+        juror,
+        void_settlement,
+        stake,
+    });
+    
+    Ok(())
+}
+
+/// Close voting and apply the verdict. A stake majority to void cancels the
+/// settlement, returns the asset to the creator and the bond to the
+/// disputer; bidders then reclaim collateral with `refund_voided_collateral`.
+/// Otherwise the settlement stands and the bond is forfeited. Jurors on the
+/// losing side forfeit `JUROR_SLASH_BPS` of their stake to the fee recipient,
+/// and every juror's stake is unlocked from the case.
+///
+/// Remaining accounts: the juror account of every vote, in voting order.
+pub fn resolve_arbitration<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ResolveArbitration<'info>>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let case = &mut ctx.accounts.arbitration_case;
+    let dispute = &ctx.accounts.dispute;
+    let clock = Clock::get()?;
+    
+    require!(
+        case.status == ArbitrationStatus::Open,
+        ShadowProtocolError::ArbitrationClosed
+    );
+    
+    require!(
+        clock.unix_timestamp >= case.voting_ends_at || case.votes.len() == MAX_CASE_JURORS,
+        ShadowProtocolError::ArbitrationVotingOpen
+    );
+    
+    require!(
+        auction.has_flag(AuctionAccount::DISPUTE_OPEN),
+        ShadowProtocolError::NoOpenDispute
+    );
+    
+    require!(
+        ctx.remaining_accounts.len() == case.votes.len(),
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    // Ties and empty juries leave the settlement in place
+    let voided = case.votes_void > case.votes_uphold;
+    
+    let mut slashed = 0u64;
+    for (info, vote) in ctx.remaining_accounts.iter().zip(case.votes.iter()) {
+        let (expected_juror, _) = Pubkey::find_program_address(
+            &[JUROR_SEED, vote.juror.as_ref()],
+            &crate::ID,
+        );
+        require!(
+            info.key() == expected_juror,
+            ShadowProtocolError::InvalidRemainingAccounts
+        );
+        
+        let mut juror_account = Account::<Juror>::try_from(info)?;
+        juror_account.open_cases = juror_account.open_cases.saturating_sub(1);
+        if vote.void_settlement != voided {
+            let slash = juror_account.stake * JUROR_SLASH_BPS / 10000;
+            juror_account.stake -= slash;
+            juror_account.sub_lamports(slash)?;
+            slashed += slash;
+        }
+        juror_account.exit(&crate::ID)?;
+    }
+    if slashed > 0 {
+        ctx.accounts.fee_recipient.add_lamports(slashed)?;
+    }
+    
+    if voided {
+        void_settlement(auction)?;
+        auction.transition(AuctionStatus::Cancelled)?;
+    
+        let returned_amount = ctx.accounts.asset_vault.amount;
+        if returned_amount > 0 {
+            let auction_seeds = &[
+                AUCTION_SEED,
+                auction.pda_seed(),
+                &[auction.bump],
+            ];
+            let signer_seeds = &[&auction_seeds[..]];
+    
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.asset_vault.to_account_info(),
+                        to: ctx.accounts.creator_asset_account.to_account_info(),
+                        authority: auction.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                returned_amount,
+            )?;
+        }
+        auction.vault_balance = 0;
+    } else if dispute.bond > 0 {
+        dispute.sub_lamports(dispute.bond)?;
+        ctx.accounts.fee_recipient.add_lamports(dispute.bond)?;
+    }
+    
+    auction.set_flag(AuctionAccount::DISPUTE_OPEN, false);
+    case.status = if voided { ArbitrationStatus::Voided } else { ArbitrationStatus::Upheld };
+    case.resolved_at = Some(clock.unix_timestamp);
+    
+    emit!(ArbitrationResolved {
+        auction_id,
+        voided,
+        votes_void: case.votes_void,
+        votes_uphold: case.votes_uphold,
+        slashed,
+        resolved_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Return a bidder's escrowed collateral from an auction voided by
+/// arbitration, to the holder of the bid's position if it has one
+pub fn refund_voided_collateral(
+    ctx: Context<RefundVoidedCollateral>,
+    auction_id: u64,
+) -> Result<()> {
+    let bid = &ctx.accounts.bid;
+    
+    require!(
+        ctx.accounts.arbitration_case.status == ArbitrationStatus::Voided,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    let holder = bid_holder(
+        bid.bidder,
+        &ctx.accounts.position_mint,
+        ctx.accounts.position_account.as_ref(),
+    )?;
+    require!(
+        ctx.accounts.bidder.key() == holder,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    if bid.sol_collateral {
+        let sol_escrow = ctx.accounts.sol_escrow.as_ref()
+            .ok_or(ShadowProtocolError::InvalidCollateralEscrow)?;
+        let refund_amount = release_sol_escrow(
+            auction_id,
+            bid,
+            sol_escrow,
+            &ctx.accounts.bidder.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+        
+        emit!(VoidedCollateralRefunded {
+            auction_id,
+            bidder: bid.bidder,
+            amount: refund_amount,
+        });
+        
+        return Ok(());
+    }
+    
+    let (bid_escrow, bidder_collateral_account) = ctx.accounts.bid_escrow.as_ref()
+        .zip(ctx.accounts.bidder_collateral_account.as_ref())
+        .ok_or(ShadowProtocolError::InvalidCollateralEscrow)?;
+    require_refund_account(bid, holder, bidder_collateral_account, bid_escrow.mint)?;
+    let refund_amount = bid_escrow.amount;
+    
+    if refund_amount > 0 {
+        let auction_id_bytes = auction_id.to_le_bytes();
+        let bid_seeds = &[
+            BID_SEED,
+            auction_id_bytes.as_ref(),
+            bid.bidder.as_ref(),
+            &[bid.bump],
+        ];
+        let signer_seeds = &[&bid_seeds[..]];
+    
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: bid_escrow.to_account_info(),
+                    to: bidder_collateral_account.to_account_info(),
+                    authority: bid.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            refund_amount,
+        )?;
+    }
+    
+    emit!(VoidedCollateralRefunded {
+        auction_id,
+        bidder: bid.bidder,
+        amount: refund_amount,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct JurorRegistered {
+    pub juror: Pubkey,
+    pub stake: u64,
+    pub registered_at: i64,
+}
+
+#[event]
+pub struct ArbitrationOpened {
+    pub auction_id: u64,
+    pub disputer: Pubkey,
+    pub voting_ends_at: i64,
+}
+
+#[event]
+pub struct EvidenceSubmitted {
+    pub auction_id: u64,
+    pub submitter: Pubkey,
+    pub evidence_hash: [u8; 32],
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct ArbitrationVoteCast {
+    pub auction_id: u64,
+    pub juror: Pubkey,
+    pub void_settlement: bool,
+    pub stake: u64,
+}
+
+#[event]
+pub struct ArbitrationResolved {
+    pub auction_id: u64,
+    pub voided: bool,
+    pub votes_void: u64,
+    pub votes_uphold: u64,
+    /// Stake forfeited by jurors on the losing side
+    pub slashed: u64,
+    pub resolved_at: i64,
+}
+
+#[event]
+pub struct VoidedCollateralRefunded {
+    pub auction_id: u64,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct RegisterJuror<'info> {
+    #[account(mut)]
+    pub juror: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = juror,
+        space = 8 + Juror::INIT_SPACE,
+        seeds = [JUROR_SEED, juror.key().as_ref()],
+        bump
+    )]
+    pub juror_account: Account<'info, Juror>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterJuror<'info> {
+    #[account(mut)]
+    pub juror: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [JUROR_SEED, juror.key().as_ref()],
+        bump = juror_account.bump,
+        close = juror
+    )]
+    pub juror_account: Account<'info, Juror>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct OpenArbitration<'info> {
+    #[account(mut)]
+    pub disputer: Signer<'info>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [SETTLEMENT_DISPUTE_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = dispute.bump,
+        has_one = disputer @ ShadowProtocolError::Unauthorized
+    )]
+    pub dispute: Account<'info, SettlementDispute>,
+    
+    #[account(
+        init,
+        payer = disputer,
+        space = 8 + ArbitrationCase::INIT_SPACE,
+        seeds = [ARBITRATION_CASE_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub arbitration_case: Account<'info, ArbitrationCase>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SubmitEvidence<'info> {
+    pub submitter: Signer<'info>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [ARBITRATION_CASE_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = arbitration_case.bump
+    )]
+    pub arbitration_case: Account<'info, ArbitrationCase>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CastArbitrationVote<'info> {
+    pub juror: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [JUROR_SEED, juror.key().as_ref()],
+        bump = juror_account.bump
+    )]
+    pub juror_account: Account<'info, Juror>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// CHECK: Juror's bid PDA on the auction; must be uninitialized
+    #[account(
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), juror.key().as_ref()],
+        bump
+    )]
+    pub juror_bid: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [ARBITRATION_CASE_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = arbitration_case.bump
+    )]
+    pub arbitration_case: Account<'info, ArbitrationCase>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ResolveArbitration<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [ARBITRATION_CASE_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = arbitration_case.bump
+    )]
+    pub arbitration_case: Account<'info, ArbitrationCase>,
+    
+    #[account(
+        mut,
+        seeds = [SETTLEMENT_DISPUTE_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = dispute.bump,
+        has_one = disputer,
+        close = disputer
+    )]
+    pub dispute: Account<'info, SettlementDispute>,
+    
+    /// CHECK: Receives the dispute account rent, and the bond if the settlement is voided
+    #[account(mut)]
+    pub disputer: AccountInfo<'info>,
+    
+    /// CHECK: Receives forfeited bonds
+    #[account(mut, address = protocol_state.fee_recipient @ ShadowProtocolError::Unauthorized)]
+    pub fee_recipient: AccountInfo<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        address = auction.asset_vault
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    /// Creator's asset account receiving the asset if the settlement is voided
+    #[account(
+        mut,
+        token::mint = auction.asset_mint,
+        token::authority = auction.creator
+    )]
+    pub creator_asset_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct RefundVoidedCollateral<'info> {
+    /// The bidder, or the holder of the bid's position
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
+    /// CHECK: Position mint PDA of the bid; uninitialized unless one was minted
+    #[account(
+        seeds = [BID_POSITION_SEED, auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump
+    )]
+    pub position_mint: UncheckedAccount<'info>,
+    
+    /// Token account holding the bid's position; required when it has one
+    pub position_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump
+    )]
+    pub bid_escrow: Option<Account<'info, TokenAccount>>,
+    
+    /// The bid's collateral account, or any of the position holder's in the
+    /// collateral mint
+    #[account(mut)]
+    pub bidder_collateral_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Native-SOL collateral escrow; required instead of the token
+    /// accounts when the bid posted SOL
+    #[account(
+        mut,
+        seeds = [SOL_ESCROW_SEED, auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump
+    )]
+    pub sol_escrow: Option<UncheckedAccount<'info>>,
+    
+    #[account(
+        seeds = [ARBITRATION_CASE_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = arbitration_case.bump
+    )]
+    pub arbitration_case: Account<'info, ArbitrationCase>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,25 +1,37 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::error::ShadowProtocolError;
+use crate::instructions::bidding::calculate_dutch_price_at;
+use crate::instructions::budget_bidding::budget_allocated;
+use crate::instructions::gas_schedule::require_gas_limit;
+use crate::instructions::auction_management::mark_auction_ended;
 
+/// Remaining accounts: the bid account of each entry in `encrypted_bids`, in
+/// order.
 pub fn queue_mpc_computation(
     ctx: Context<QueueMpcComputation>,
     auction_id: u64,
-    bids_count: u32,
-    encrypted_bids: Vec<EncryptedBidData>,
-    encrypted_reserve_price: [u8; 32],
-    mxe_cluster: Pubkey,
-    gas_limit: u64,
+    params: QueueMpcComputationParams,
 ) -> Result<()> {
+    let QueueMpcComputationParams {
+        bids_count,
+        encrypted_bids,
+        encrypted_reserve_price,
+        reserve_price_nonce,
+        mxe_cluster,
+        gas_limit,
+        chunk_index,
+    } = params;
     let auction = &mut ctx.accounts.auction;
     let protocol = &ctx.accounts.protocol_state;
     let clock = Clock::get()?;
     
     require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
     
+    // Bids are frozen from the cutoff, so the computation can be queued then
     require!(
         auction.status == AuctionStatus::Ended || 
-        (auction.status == AuctionStatus::Active && clock.unix_timestamp >= auction.end_time),
+        (auction.status == AuctionStatus::Active && auction.bidding_closed(&clock)),
         ShadowProtocolError::InvalidAuctionStatus
     );
     
@@ -33,28 +45,104 @@ pub fn queue_mpc_computation(
         ShadowProtocolError::InvalidBidCount
     );
     
+    // The circuit must compare against the reserve committed at creation
+    require!(
+        encrypted_reserve_price == auction.reserve_price_encrypted &&
+        reserve_price_nonce == auction.reserve_price_nonce,
+        ShadowProtocolError::InvalidReservePrice
+    );
+    
+    require_bid_accounts(auction_id, &encrypted_bids, ctx.remaining_accounts)?;
+    
+    queue_bid_chunk(
+        auction,
+        &ctx.accounts.gas_schedule,
+        &ctx.accounts.cluster_registry,
+        &encrypted_bids,
+        mxe_cluster,
+        gas_limit,
+        chunk_index,
+    )
+}
+
+/// Record `encrypted_bids` as chunk `chunk_index` of an ended auction's
+/// settlement computation on `mxe_cluster`, ending the auction if it is
+/// still marked active. The bids' collaterals must already be checked.
+pub(crate) fn queue_bid_chunk(
+    auction: &mut AuctionAccount,
+    gas_schedule: &GasSchedule,
+    cluster_registry: &ClusterRegistry,
+    encrypted_bids: &[EncryptedBidData],
+    mxe_cluster: Pubkey,
+    gas_limit: u64,
+    chunk_index: u32,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let auction_id = auction.auction_id;
+    let bids_count = encrypted_bids.len() as u32;
+    
+    // Quantity bids are cleared by `queue_quantity_clearing` instead
+    require!(
+        !auction.has_flag(AuctionAccount::QUANTITY_BIDS),
+        ShadowProtocolError::QuantityBidsEnabled
+    );
     require!(
         bids_count <= MAX_BIDS_PER_AUCTION as u32,
         ShadowProtocolError::TooManyBids
     );
     
-    let computation_id = generate_computation_id(auction_id, auction.end_time);
+    // Bids are fed to the circuit in fixed-size chunks
+    let chunk_count = chunk_count(auction.bid_count as u64);
+    require!(
+        chunk_index < chunk_count,
+        ShadowProtocolError::InvalidBidCount
+    );
+    
+    let chunk_start = chunk_index as u64 * SEALED_BID_CIRCUIT_CAPACITY as u64;
+    let expected_chunk_bids = (auction.bid_count as u64)
+        .saturating_sub(chunk_start)
+        .min(SEALED_BID_CIRCUIT_CAPACITY as u64);
+    require!(
+        bids_count as u64 == expected_chunk_bids,
+        ShadowProtocolError::InvalidBidCount
+    );
+    
+    let (computation_id, circuit_capacity, circuit) = if chunk_count == 1 {
+        let (capacity, circuit) = if auction.has_flag(AuctionAccount::PRIVATE_WINNER) {
+            (SEALED_BID_CIRCUIT_CAPACITY, PRIVATE_SEALED_BID_CIRCUIT)
+        } else {
+            select_sealed_bid_circuit(auction.bid_count)
+        };
+        (generate_computation_id(auction_id, auction.end_time, auction.settlement_attempt), capacity, circuit)
+    } else {
+        (
+            generate_chunk_computation_id(auction_id, auction.end_time, auction.settlement_attempt, chunk_index),
+            SEALED_BID_CIRCUIT_CAPACITY,
+            BID_CHUNK_CIRCUIT,
+        )
+    };
+    let comp_def_offset = comp_def_offset(circuit);
+    require_gas_limit(gas_schedule, comp_def_offset, bids_count, gas_limit)?;
+    cluster_registry.require_healthy(mxe_cluster)?;
 
     auction.mpc_computation_id = Some(computation_id);
+    auction.mpc_comp_def_offset = Some(comp_def_offset);
     auction.mxe_cluster = Some(mxe_cluster);
-    auction.computation_gas_limit = gas_limit;
     auction.computation_queued_at = Some(clock.unix_timestamp);
 
     if auction.status == AuctionStatus::Active {
-        auction.status = AuctionStatus::Ended;
-        auction.end_time = clock.unix_timestamp;
+        auction.end_time = auction.now(&clock);
+        mark_auction_ended(auction, &clock)?;
     }
 
     msg!(
-        "Arcium MPC computation queued for auction {}: computation_id={:?}, bids_count={}, gas_limit={}",
+        "Arcium MPC computation queued for auction {}: computation_id={:?}, bids_count={}, chunk={}/{}, circuit={}, gas_limit={}",
         auction_id,
         computation_id,
         bids_count,
+        chunk_index + 1,
+        chunk_count,
+        circuit,
         gas_limit
     );
 
@@ -62,6 +150,13 @@ pub fn queue_mpc_computation(
         auction_id,
         computation_id,
         bids_count,
+        chunk_index,
+        chunk_count,
+        comp_def_offset,
+        circuit_capacity,
+        bid_collateral_bps: auction.bid_collateral_bps,
+        reserve_price_encrypted: auction.reserve_price_encrypted,
+        reserve_price_nonce: auction.reserve_price_nonce,
         mxe_cluster,
         gas_limit,
         queued_at: clock.unix_timestamp,
@@ -70,12 +165,29 @@ pub fn queue_mpc_computation(
     Ok(())
 }
 
+/// Bids and encrypted reserve of one `queue_mpc_computation` chunk
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QueueMpcComputationParams {
+    pub bids_count: u32,
+    pub encrypted_bids: Vec<EncryptedBidData>,
+    pub encrypted_reserve_price: [u8; 32],
+    pub reserve_price_nonce: u128,
+    pub mxe_cluster: Pubkey,
+    pub gas_limit: u64,
+    pub chunk_index: u32,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct EncryptedBidData {
     pub bidder: Pubkey,
     pub encrypted_amount: [u8; 32],
     pub nonce: [u8; 16],
     pub public_key: [u8; 32],
+    /// Escrowed collateral, passed to the circuit in the clear
+    pub collateral_amount: u64,
+    /// Submission time recorded on the bid account, passed to the circuit
+    /// in the clear to break ties
+    pub timestamp: i64,
 }
 
 #[event]
@@ -83,6 +195,13 @@ pub struct MpcComputationQueued {
     pub auction_id: u64,
     pub computation_id: [u8; 32],
     pub bids_count: u32,
+    pub chunk_index: u32,
+    pub chunk_count: u32,
+    pub comp_def_offset: u32,
+    pub circuit_capacity: u32,
+    pub bid_collateral_bps: u16,
+    pub reserve_price_encrypted: [u8; 32],
+    pub reserve_price_nonce: u128,
     pub mxe_cluster: Pubkey,
     pub gas_limit: u64,
     pub queued_at: i64,
@@ -94,50 +213,113 @@ pub struct ArciumComputationCompleted {
     pub computation_id: [u8; 32],
     pub winner: Pubkey,
     pub winning_amount: u64,
+    pub second_highest: u64,
+    pub participating_bids: u64,
     pub verification_hash: [u8; 32],
+    pub tie_break_rule: TieBreakRule,
+    pub price_capped: bool,
     pub completed_at: i64,
 }
 
-pub fn arcium_callback(
-    ctx: Context<ArciumCallback>,
+#[event]
+pub struct CallbackReplayed {
+    pub auction_id: u64,
+    pub computation_id: [u8; 32],
+    pub replayed_at: i64,
+}
+
+#[event]
+pub struct DutchClaimQueued {
+    pub auction_id: u64,
+    pub bidder: Pubkey,
+    pub computation_id: [u8; 32],
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct AuctionUnsold {
+    pub auction_id: u64,
+    pub reserve_met: bool,
+    pub participating_bids: u64,
+    pub recorded_at: i64,
+}
+
+#[event]
+pub struct DutchBidAccepted {
+    pub auction_id: u64,
+    pub winner: Pubkey,
+    pub price: u64,
+    pub accepted_at: i64,
+}
+
+/// Remaining accounts: the winner's bid, followed by its budget if the bid
+/// is linked to one. A winner its budget allocation dropped leaves the
+/// auction unsold.
+///
+/// Idempotent: redelivering a result that was already applied succeeds
+/// without touching the auction, while a different result for the same
+/// computation is rejected.
+pub fn arcium_callback<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ArciumCallback<'info>>,
     computation_id: [u8; 32],
     result: Vec<u8>,
 ) -> Result<()> {
+    use anchor_lang::solana_program::hash::hash;
+
     let auction = &mut ctx.accounts.auction;
     let protocol = &ctx.accounts.protocol_state;
     let clock = Clock::get()?;
 
     require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
     require!(
-        ctx.accounts.authority.key() == protocol.authority,
+        ctx.accounts.authority.key() == protocol.callback_signer(),
         ShadowProtocolError::Unauthorized
     );
+
+    let result_hash = hash(&result).to_bytes();
+    let consumed = &mut ctx.accounts.consumed_computation;
+    if consumed.consumed_at != 0 {
+        require!(
+            consumed.result_hash == result_hash,
+            ShadowProtocolError::ComputationAlreadyConsumed
+        );
+        emit!(CallbackReplayed {
+            auction_id: auction.auction_id,
+            computation_id,
+            replayed_at: clock.unix_timestamp,
+        });
+        return Ok(());
+    }
+    consumed.computation_id = computation_id;
+    consumed.result_hash = result_hash;
+    consumed.consumed_at = clock.unix_timestamp;
+    consumed.bump = ctx.bumps.consumed_computation;
+
     require!(
         auction.status == AuctionStatus::Ended,
         ShadowProtocolError::InvalidAuctionStatus
     );
     require!(
-        !auction.settlement_authorized,
+        !auction.settlement_authorized(),
         ShadowProtocolError::AuctionAlreadySettled
     );
+    require!(!auction.has_flag(AuctionAccount::UNSOLD), ShadowProtocolError::AuctionUnsold);
 
-    let expected_computation_id = generate_computation_id(auction.auction_id, auction.end_time);
+    let expected_computation_id = generate_computation_id(auction.auction_id, auction.end_time, auction.settlement_attempt);
     require!(
         computation_id == expected_computation_id,
         ShadowProtocolError::InvalidComputationId
     );
 
     let mpc_result = parse_arcium_result(&result)?;
-    require!(
-        mpc_result.winning_amount > 0 && mpc_result.winning_amount >= auction.minimum_bid,
-        ShadowProtocolError::BidTooLow
-    );
 
     let verification_hash = compute_settlement_hash(
         auction.auction_id,
         mpc_result.winner,
         mpc_result.winning_amount,
-        auction.bid_count,
+        mpc_result.met_reserve,
+        auction.bid_increment,
+        auction.bid_count as u64,
         auction.end_time
     );
 
@@ -145,11 +327,74 @@ pub fn arcium_callback(
         verification_hash == mpc_result.verification_hash,
         ShadowProtocolError::MpcVerificationFailed
     );
+    let participating_bids = u32::try_from(mpc_result.participating_bids)
+        .map_err(|_| ShadowProtocolError::InvalidMpcResult)?;
+
+    // The clearing price is checked against the encrypted reserve inside the
+    // circuit. Without a sale (reserve missed, or no bid able to win) the
+    // outcome is recorded so `finalize_unsold` can hand the asset back
+    // straight away.
+    let mut no_winner = mpc_result.winning_amount == 0
+        || (mpc_result.winner == Pubkey::default() && mpc_result.private_winner.is_none());
+
+    // A public winner bidding from a shared budget must have been allocated
+    // this auction. If the allocation dropped it, the winning bid cannot be
+    // paid and the auction goes unsold rather than stalling here.
+    if !no_winner && mpc_result.private_winner.is_none() {
+        let winner_bid_info = ctx.remaining_accounts.first()
+            .ok_or(ShadowProtocolError::InvalidRemainingAccounts)?;
+        let (expected_winner_bid, _) = Pubkey::find_program_address(
+            &[BID_SEED, auction.auction_id.to_le_bytes().as_ref(), mpc_result.winner.as_ref()],
+            &crate::ID,
+        );
+        require!(
+            winner_bid_info.key() == expected_winner_bid,
+            ShadowProtocolError::InvalidRemainingAccounts
+        );
+        let winner_bid = Account::<BidAccount>::try_from(winner_bid_info)?;
+        no_winner = !budget_allocated(&winner_bid, ctx.remaining_accounts.get(1))?;
+    }
+
+    if !mpc_result.met_reserve || mpc_result.participating_bids == 0 || no_winner {
+        auction.set_flag(AuctionAccount::UNSOLD, true);
+        auction.set_flag(AuctionAccount::RESERVE_MET, mpc_result.met_reserve);
+        auction.participating_bids = participating_bids;
+        auction.mpc_verification_hash = Some(mpc_result.verification_hash);
+
+        emit!(AuctionUnsold {
+            auction_id: auction.auction_id,
+            reserve_met: mpc_result.met_reserve,
+            participating_bids: mpc_result.participating_bids,
+            recorded_at: clock.unix_timestamp,
+        });
+
+        return Ok(());
+    }
+
+    require!(
+        mpc_result.winning_amount >= auction.minimum_bid,
+        ShadowProtocolError::BidTooLow
+    );
 
-    auction.winner = Some(mpc_result.winner);
+    // Privacy mode keeps the winner sealed until they claim with `claim_win`
+    require!(
+        auction.has_flag(AuctionAccount::PRIVATE_WINNER) == mpc_result.private_winner.is_some(),
+        ShadowProtocolError::InvalidMpcResult
+    );
+
+    if auction.has_flag(AuctionAccount::PRIVATE_WINNER) {
+        auction.winner = None;
+        auction.encrypted_winner = mpc_result.private_winner;
+    } else {
+        auction.winner = Some(mpc_result.winner);
+    }
     auction.winning_amount = mpc_result.winning_amount;
+    auction.second_highest_bid = mpc_result.second_highest;
+    auction.set_flag(AuctionAccount::RESERVE_MET, mpc_result.met_reserve);
+    auction.participating_bids = participating_bids;
     auction.mpc_verification_hash = Some(mpc_result.verification_hash);
-    auction.settlement_authorized = true;
+    auction.transition(AuctionStatus::SettlementAuthorized)?;
+    auction.settlement_authorized_at = Some(clock.unix_timestamp);
     auction.settled_at = Some(clock.unix_timestamp);
 
     emit!(ArciumComputationCompleted {
@@ -157,7 +402,11 @@ pub fn arcium_callback(
         computation_id,
         winner: mpc_result.winner,
         winning_amount: mpc_result.winning_amount,
+        second_highest: mpc_result.second_highest,
+        participating_bids: mpc_result.participating_bids,
         verification_hash: mpc_result.verification_hash,
+        tie_break_rule: mpc_result.tie_break_rule,
+        price_capped: mpc_result.price_capped,
         completed_at: clock.unix_timestamp,
     });
 
@@ -171,31 +420,223 @@ pub fn arcium_callback(
     Ok(())
 }
 
-fn generate_computation_id(auction_id: u64, end_time: i64) -> [u8; 32] {
-    use anchor_lang::solana_program::hash::{hash, Hash};
+/// Deliver the `process_dutch_auction_bid` result for an encrypted Dutch claim.
+///
+/// Only acceptance and the clearing price are revealed. Rejected claims and
+/// claims resolved after the auction was already won leave no trace.
+pub fn dutch_bid_callback(
+    ctx: Context<DutchBidCallback>,
+    auction_id: u64,
+    bidder: Pubkey,
+    computation_id: [u8; 32],
+    result: Vec<u8>,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let bid = &mut ctx.accounts.bid;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.authority.key() == protocol.callback_signer(),
+        ShadowProtocolError::Unauthorized
+    );
+
+    require!(!bid.claim_resolved, ShadowProtocolError::ComputationAlreadyResolved);
+
+    let expected_computation_id = generate_dutch_claim_id(auction_id, bidder, bid.timestamp);
+    require!(
+        computation_id == expected_computation_id,
+        ShadowProtocolError::InvalidComputationId
+    );
+
+    let claim = parse_dutch_claim_result(&result)?;
+
+    let verification_hash = compute_dutch_claim_hash(
+        auction_id,
+        bidder,
+        claim.accepted,
+        claim.price,
+        bid.timestamp,
+    );
+    require!(
+        verification_hash == claim.verification_hash,
+        ShadowProtocolError::MpcVerificationFailed
+    );
+
+    bid.claim_resolved = true;
+
+    // First valid claim wins; anything resolved afterwards is discarded and
+    // its collateral returned through `refund_dutch_claim`
+    if !claim.accepted || auction.status != AuctionStatus::Active {
+        msg!("Dutch claim for auction {} not accepted", auction_id);
+        return Ok(());
+    }
+
+    let price_at_bid = calculate_dutch_price_at(auction, bid.timestamp)?;
+    require!(
+        claim.price >= price_at_bid && claim.price <= bid.collateral_amount,
+        ShadowProtocolError::DutchPriceNotMet
+    );
+
+    auction.transition(AuctionStatus::Ended)?;
+    auction.winner = Some(bidder);
+    auction.winning_amount = claim.price;
+    auction.mpc_verification_hash = Some(claim.verification_hash);
+    bid.is_winner = true;
+
+    emit!(DutchBidAccepted {
+        auction_id,
+        winner: bidder,
+        price: claim.price,
+        accepted_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn generate_dutch_claim_id(auction_id: u64, bidder: Pubkey, submitted_at: i64) -> [u8; 32] {
+    use anchor_lang::solana_program::hash::hash;
+
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_dutch_claim");
+    data.extend_from_slice(&auction_id.to_le_bytes());
+    data.extend_from_slice(&bidder.to_bytes());
+    data.extend_from_slice(&submitted_at.to_le_bytes());
+
+    hash(&data).to_bytes()
+}
+
+fn compute_dutch_claim_hash(
+    auction_id: u64,
+    bidder: Pubkey,
+    accepted: bool,
+    price: u64,
+    submitted_at: i64,
+) -> [u8; 32] {
+    use anchor_lang::solana_program::hash::hash;
+
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_dutch_claim_verification");
+    data.extend_from_slice(&auction_id.to_le_bytes());
+    data.extend_from_slice(&bidder.to_bytes());
+    data.push(accepted as u8);
+    data.extend_from_slice(&price.to_le_bytes());
+    data.extend_from_slice(&submitted_at.to_le_bytes());
+
+    hash(&data).to_bytes()
+}
+
+/// Check the collateral and timestamp reported for each bid against its bid
+/// account, so the circuit's deposit-weighted masking runs on what is
+/// actually escrowed and ties are broken on the recorded submission time.
+/// `bid_accounts` holds one bid account per entry of `bids`, in order.
+fn require_bid_accounts(
+    auction_id: u64,
+    bids: &[EncryptedBidData],
+    bid_accounts: &[AccountInfo],
+) -> Result<()> {
+    require!(
+        bid_accounts.len() == bids.len(),
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    for (data, info) in bids.iter().zip(bid_accounts) {
+        let (expected_bid, _) = Pubkey::find_program_address(
+            &[BID_SEED, auction_id.to_le_bytes().as_ref(), data.bidder.as_ref()],
+            &crate::ID,
+        );
+        require!(
+            info.key() == expected_bid && info.owner == &crate::ID,
+            ShadowProtocolError::InvalidRemainingAccounts
+        );
+        let bid = BidAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            bid.collateral_amount == data.collateral_amount,
+            ShadowProtocolError::CollateralMismatch
+        );
+        require!(
+            bid.timestamp == data.timestamp,
+            ShadowProtocolError::InvalidTimestamp
+        );
+    }
+    
+    Ok(())
+}
+
+/// Number of circuit invocations needed to cover `bid_count` bids
+pub(crate) fn chunk_count(bid_count: u64) -> u32 {
+    let capacity = SEALED_BID_CIRCUIT_CAPACITY as u64;
+    (bid_count.div_ceil(capacity).max(1)) as u32
+}
+
+/// Smallest single-round circuit that fits `bid_count` bids, as (capacity, name)
+pub(crate) fn select_sealed_bid_circuit(bid_count: u32) -> (u32, &'static str) {
+    SEALED_BID_CIRCUITS
+        .into_iter()
+        .find(|(capacity, _)| bid_count <= *capacity)
+        .unwrap_or(SEALED_BID_CIRCUITS[SEALED_BID_CIRCUITS.len() - 1])
+}
+
+/// Arcium computation definition offset of a circuit: the first four bytes
+/// of the SHA-256 of its name, little-endian
+pub(crate) fn comp_def_offset(circuit: &str) -> u32 {
+    use anchor_lang::solana_program::hash::hash;
+
+    let digest = hash(circuit.as_bytes()).to_bytes();
+    u32::from_le_bytes([digest[0], digest[1], digest[2], digest[3]])
+}
+
+pub(crate) fn generate_chunk_computation_id(
+    auction_id: u64,
+    end_time: i64,
+    settlement_attempt: u16,
+    chunk_index: u32,
+) -> [u8; 32] {
+    use anchor_lang::solana_program::hash::hash;
+
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_mpc_computation_chunk");
+    data.extend_from_slice(&auction_id.to_le_bytes());
+    data.extend_from_slice(&end_time.to_le_bytes());
+    data.extend_from_slice(&settlement_attempt.to_le_bytes());
+    data.extend_from_slice(&chunk_index.to_le_bytes());
+
+    hash(&data).to_bytes()
+}
+
+/// Settlement computation ID. It changes with each settlement attempt, so a
+/// settlement voided by a dispute is rerun under a fresh consumed marker.
+pub(crate) fn generate_computation_id(auction_id: u64, end_time: i64, settlement_attempt: u16) -> [u8; 32] {
+    use anchor_lang::solana_program::hash::hash;
 
     let mut data = Vec::new();
     data.extend_from_slice(b"shadow_mpc_computation");
     data.extend_from_slice(&auction_id.to_le_bytes());
     data.extend_from_slice(&end_time.to_le_bytes());
+    data.extend_from_slice(&settlement_attempt.to_le_bytes());
 
     hash(&data).to_bytes()
 }
 
-fn compute_settlement_hash(
+pub(crate) fn compute_settlement_hash(
     auction_id: u64,
     winner: Pubkey,
     winning_amount: u64,
+    met_reserve: bool,
+    bid_increment: u64,
     bid_count: u64,
     end_time: i64,
 ) -> [u8; 32] {
-    use anchor_lang::solana_program::hash::{hash, Hash};
+    use anchor_lang::solana_program::hash::hash;
 
     let mut data = Vec::new();
     data.extend_from_slice(b"shadow_settlement_verification");
     data.extend_from_slice(&auction_id.to_le_bytes());
     data.extend_from_slice(&winner.to_bytes());
     data.extend_from_slice(&winning_amount.to_le_bytes());
+    data.push(met_reserve as u8);
+    data.extend_from_slice(&bid_increment.to_le_bytes());
     data.extend_from_slice(&bid_count.to_le_bytes());
     data.extend_from_slice(&end_time.to_le_bytes());
 
@@ -220,6 +661,19 @@ pub struct QueueMpcComputation<'info> {
     )]
     pub protocol_state: Account<'info, ProtocolState>,
     
+    /// Gas schedule of the circuit the bids are queued on
+    #[account(
+        seeds = [GAS_SCHEDULE_SEED, gas_schedule.comp_def_offset.to_le_bytes().as_ref()],
+        bump = gas_schedule.bump
+    )]
+    pub gas_schedule: Account<'info, GasSchedule>,
+    
+    #[account(
+        seeds = [CLUSTER_REGISTRY_SEED],
+        bump = cluster_registry.bump
+    )]
+    pub cluster_registry: Account<'info, ClusterRegistry>,
+    
     /// CHECK: MXE program account
     pub mxe_program: AccountInfo<'info>,
     
@@ -232,46 +686,198 @@ pub struct ArciumCallback<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
+    #[account(mut)]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Created on first delivery; its presence makes redeliveries no-ops
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ConsumedComputation::INIT_SPACE,
+        seeds = [CONSUMED_COMPUTATION_SEED, computation_id.as_ref()],
+        bump
+    )]
+    pub consumed_computation: Account<'info, ConsumedComputation>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64, bidder: Pubkey)]
+pub struct DutchBidCallback<'info> {
+    pub authority: Signer<'info>,
+    
     #[account(
         mut,
-        constraint = auction.status == AuctionStatus::Ended @ ShadowProtocolError::InvalidAuctionStatus
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = auction.auction_type == AuctionType::Dutch @ ShadowProtocolError::InvalidAuctionType
     )]
     pub auction: Account<'info, AuctionAccount>,
     
+    #[account(
+        mut,
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bidder.as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
     #[account(
         seeds = [PROTOCOL_SEED],
         bump = protocol_state.bump
     )]
     pub protocol_state: Account<'info, ProtocolState>,
+}
 
-    pub system_program: Program<'info, System>,
+/// Sealed-bid result layout version 1
+pub const MPC_RESULT_V1: u8 = 1;
+
+/// Version 2 adds the runner-up bid and participating bid count
+pub const MPC_RESULT_V2: u8 = 2;
+
+/// Version 3 adds the encrypted winner of a privacy-mode auction
+pub const MPC_RESULT_V3: u8 = 3;
+
+/// No bid could win: the circuit reports only the participation count
+pub const MPC_RESULT_NO_WINNER: u8 = 4;
+
+/// Version 1 sealed-bid result, Borsh-encoded after a leading version byte
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MpcResultV1 {
+    pub winner: Pubkey,
+    pub winning_amount: u64,
+    pub verification_hash: [u8; 32],
+    /// Circuit encoding of the tie-break rule, see `TieBreakRule::from_circuit`
+    pub tie_break_rule: u8,
+    /// Set when the proxy increment was cut short by the winner's maximum
+    pub price_capped: bool,
+    pub met_reserve: bool,
+}
+
+/// Version 2 sealed-bid result: the version 1 fields followed by the
+/// runner-up maximum and the number of participating bids
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MpcResultV2 {
+    pub base: MpcResultV1,
+    pub second_highest: u64,
+    pub participating_bids: u64,
+}
+
+/// Version 3 sealed-bid result: the version 2 fields followed by the
+/// encrypted winner. `base.base.winner` is zeroed in privacy mode.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MpcResultV3 {
+    pub base: MpcResultV2,
+    pub private_winner: PrivateWinner,
 }
 
+/// No-winner result. Its verification hash commits to the default winner,
+/// a zero amount and an unmet reserve.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MpcNoWinnerResult {
+    pub verification_hash: [u8; 32],
+    pub participating_bids: u64,
+}
+
+/// Decode a versioned sealed-bid result.
+///
+/// Unknown versions and payloads that don't exactly match their version's
+/// schema are rejected rather than guessed at, so a circuit output change
+/// can never be misread as a different winner.
 fn parse_arcium_result(result: &[u8]) -> Result<ArciumMpcResult> {
-    require!(result.len() >= 72, ShadowProtocolError::InvalidMpcResult);
+    let (version, mut payload) = result.split_first()
+        .ok_or(ShadowProtocolError::InvalidMpcResult)?;
+
+    // Version 1 results don't report the runner-up or participation
+    let (decoded, second_highest, participating_bids, private_winner) = match *version {
+        MPC_RESULT_V1 => (
+            MpcResultV1::deserialize(&mut payload)
+                .map_err(|_| ShadowProtocolError::InvalidMpcResult)?,
+            0,
+            0,
+            None,
+        ),
+        MPC_RESULT_V2 => {
+            let v2 = MpcResultV2::deserialize(&mut payload)
+                .map_err(|_| ShadowProtocolError::InvalidMpcResult)?;
+            (v2.base, v2.second_highest, v2.participating_bids, None)
+        }
+        MPC_RESULT_V3 => {
+            let v3 = MpcResultV3::deserialize(&mut payload)
+                .map_err(|_| ShadowProtocolError::InvalidMpcResult)?;
+            (v3.base.base, v3.base.second_highest, v3.base.participating_bids, Some(v3.private_winner))
+        }
+        MPC_RESULT_NO_WINNER => {
+            let none = MpcNoWinnerResult::deserialize(&mut payload)
+                .map_err(|_| ShadowProtocolError::InvalidMpcResult)?;
+            let base = MpcResultV1 {
+                winner: Pubkey::default(),
+                winning_amount: 0,
+                verification_hash: none.verification_hash,
+                tie_break_rule: 0,
+                price_capped: false,
+                met_reserve: false,
+            };
+            (base, 0, none.participating_bids, None)
+        }
+        _ => return Err(ShadowProtocolError::UnsupportedMpcResultVersion.into()),
+    };
+    require!(payload.is_empty(), ShadowProtocolError::InvalidMpcResult);
 
-    let winner_bytes: [u8; 32] = result[0..32].try_into()
-        .map_err(|_| ShadowProtocolError::InvalidMpcResult)?;
-    let winner = Pubkey::new_from_array(winner_bytes);
+    Ok(ArciumMpcResult {
+        winner: decoded.winner,
+        winning_amount: decoded.winning_amount,
+        verification_hash: decoded.verification_hash,
+        tie_break_rule: TieBreakRule::from_circuit(decoded.tie_break_rule)?,
+        price_capped: decoded.price_capped,
+        met_reserve: decoded.met_reserve,
+        second_highest,
+        participating_bids,
+        private_winner,
+    })
+}
 
-    let winning_amount = u64::from_le_bytes(
-        result[32..40].try_into()
+#[derive(Debug)]
+pub struct ArciumMpcResult {
+    pub winner: Pubkey,
+    pub winning_amount: u64,
+    pub verification_hash: [u8; 32],
+    pub tie_break_rule: TieBreakRule,
+    pub price_capped: bool,
+    pub met_reserve: bool,
+    pub second_highest: u64,
+    pub participating_bids: u64,
+    pub private_winner: Option<PrivateWinner>,
+}
+
+fn parse_dutch_claim_result(result: &[u8]) -> Result<DutchClaimResult> {
+    require!(result.len() >= 41, ShadowProtocolError::InvalidMpcResult);
+
+    let accepted = result[0] != 0;
+
+    let price = u64::from_le_bytes(
+        result[1..9].try_into()
             .map_err(|_| ShadowProtocolError::InvalidMpcResult)?
     );
 
-    let verification_hash: [u8; 32] = result[40..72].try_into()
+    let verification_hash: [u8; 32] = result[9..41].try_into()
         .map_err(|_| ShadowProtocolError::InvalidMpcResult)?;
 
-    Ok(ArciumMpcResult {
-        winner,
-        winning_amount,
+    Ok(DutchClaimResult {
+        accepted,
+        price,
         verification_hash,
     })
 }
 
 #[derive(Debug)]
-pub struct ArciumMpcResult {
-    pub winner: Pubkey,
-    pub winning_amount: u64,
+pub struct DutchClaimResult {
+    pub accepted: bool,
+    pub price: u64,
     pub verification_hash: [u8; 32],
 }
\ No newline at end of file
@@ -2,19 +2,25 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount, Transfer, transfer, CloseAccount, close_account};
 use crate::state::*;
 use crate::error::ShadowProtocolError;
+use crate::instructions::auction_management::release_creation_deposit;
 
-/// Grace period after auction end before cleanup is allowed (24 hours)
-pub const CLEANUP_GRACE_PERIOD: i64 = 24 * 60 * 60;
+/// Lower bound for the configurable cleanup grace period (1 hour)
+pub const MIN_CLEANUP_GRACE_PERIOD: i64 = 60 * 60;
+
+/// Upper bound for the configurable cleanup grace period (7 days)
+pub const MAX_CLEANUP_GRACE_PERIOD: i64 = 7 * 24 * 60 * 60;
 
 /// Maximum number of expired auctions to cleanup in one transaction
 pub const MAX_CLEANUP_BATCH_SIZE: usize = 5;
 
+/// Remaining accounts expected per auction in `batch_cleanup_auctions`
+pub const CLEANUP_ACCOUNTS_PER_AUCTION: usize = 6;
+
 /// Clean up expired and settled auctions to reclaim storage
 pub fn cleanup_expired_auction(
     ctx: Context<CleanupExpiredAuction>,
     auction_id: u64,
 ) -> Result<()> {
-    let auction = &mut ctx.accounts.auction;
     let protocol = &ctx.accounts.protocol_state;
     let clock = Clock::get()?;
     
@@ -22,62 +28,143 @@ pub fn cleanup_expired_auction(
     
     // Verify auction ID matches
     require!(
-        auction.auction_id == auction_id,
+        ctx.accounts.auction.auction_id == auction_id,
         ShadowProtocolError::InvalidAuctionStatus
     );
     
+    close_out_auction(
+        &mut ctx.accounts.auction,
+        CloseOutAccounts {
+            features: &ctx.accounts.features,
+            asset_vault: &mut ctx.accounts.asset_vault,
+            creator_asset_account: &ctx.accounts.creator_asset_account,
+            creator: &ctx.accounts.creator,
+            creator_index: &ctx.accounts.creator_index,
+            fee_recipient: &ctx.accounts.fee_recipient,
+            token_program: &ctx.accounts.token_program.to_account_info(),
+        },
+        &clock,
+        protocol.cleanup_grace_period(),
+    )?;
+    
+    msg!("Cleaned up expired auction {}", auction_id);
+    
+    Ok(())
+}
+
+/// Accounts `close_out_auction` pays out of and into besides the auction
+struct CloseOutAccounts<'a, 'info> {
+    features: &'a Account<'info, AuctionFeatures>,
+    asset_vault: &'a mut Account<'info, TokenAccount>,
+    creator_asset_account: &'a Account<'info, TokenAccount>,
+    creator: &'a AccountInfo<'info>,
+    creator_index: &'a AccountInfo<'info>,
+    fee_recipient: &'a AccountInfo<'info>,
+    token_program: &'a AccountInfo<'info>,
+}
+
+/// Refund the vaulted asset to the creator, close the vault, drop the
+/// auction from the creator's index, release the creation deposit and mark
+/// the auction as cleaned up. Shared by single and batch cleanup.
+fn close_out_auction<'info>(
+    auction: &mut Account<'info, AuctionAccount>,
+    accounts: CloseOutAccounts<'_, 'info>,
+    clock: &Clock,
+    grace_period: i64,
+) -> Result<()> {
+    let CloseOutAccounts {
+        features,
+        asset_vault,
+        creator_asset_account,
+        creator,
+        creator_index,
+        fee_recipient,
+        token_program,
+    } = accounts;
+    
+    require!(
+        features.auction == auction.key() && asset_vault.key() == auction.asset_vault,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    require!(
+        creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        creator_asset_account.owner == auction.creator &&
+        creator_asset_account.mint == auction.asset_mint,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
     // Auction must be either settled or expired
-    let is_settled = auction.status == AuctionStatus::Settled;
-    let is_expired = clock.unix_timestamp >= auction.end_time + CLEANUP_GRACE_PERIOD;
+    let is_settled = auction.status == AuctionStatus::AssetsDistributed;
+    let is_expired = auction.now(clock) >= auction.end_time + auction.timing_mode.units_for_seconds(grace_period);
     
     require!(
         is_settled || is_expired,
         ShadowProtocolError::AuctionNotEnded
     );
     
-    // If auction ended but wasn't settled, refund collateral to creator
-    if !is_settled && auction.status == AuctionStatus::Ended {
-        // Return asset to creator if no settlement occurred
-        if ctx.accounts.asset_vault.amount > 0 {
-            let auction_id_bytes = auction.auction_id.to_le_bytes();
-            let auction_seeds = &[
-                AUCTION_SEED,
-                auction_id_bytes.as_ref(),
-                &[auction.bump],
-            ];
-            let signer_seeds = &[&auction_seeds[..]];
-            
-            transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.asset_vault.to_account_info(),
-                        to: ctx.accounts.creator_asset_account.to_account_info(),
-                        authority: auction.to_account_info(),
-                    },
-                    signer_seeds,
-                ),
-                ctx.accounts.asset_vault.amount,
-            )?;
-        }
+    require!(
+        auction.open_yield_positions == 0,
+        ShadowProtocolError::YieldPositionsOpen
+    );
+    require!(
+        !auction.has_flag(AuctionAccount::SETTLING),
+        ShadowProtocolError::SettlementInProgress
+    );
+    
+    if let Some(sale) = &features.lbp {
+        require!(
+            sale.purchases_processed == sale.purchases_submitted,
+            ShadowProtocolError::LbpPurchasesPending
+        );
+    }
+    // Quantity bids hold their escrow until their allocation is distributed
+    require!(
+        !auction.has_flag(AuctionAccount::QUANTITY_BIDS) || is_settled || auction.bid_count == 0,
+        ShadowProtocolError::QuantityDistributionPending
+    );
+    
+    // Copied out, as the auction is written while the seeds are in use
+    let pda_seed = auction.pda_seed().to_vec();
+    let auction_seeds = &[
+        AUCTION_SEED,
+        pda_seed.as_ref(),
+        &[auction.bump],
+    ];
+    let signer_seeds = &[&auction_seeds[..]];
+    
+    // If auction ended but wasn't settled, return the asset to the creator
+    let undistributed = matches!(
+        auction.status,
+        AuctionStatus::Ended | AuctionStatus::SettlementAuthorized
+    );
+    if !is_settled && undistributed && asset_vault.amount > 0 {
+        transfer(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                Transfer {
+                    from: asset_vault.to_account_info(),
+                    to: creator_asset_account.to_account_info(),
+                    authority: auction.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            asset_vault.amount,
+        )?;
+        asset_vault.reload()?;
+        auction.vault_balance = 0;
     }
     
     // Close asset vault if empty
-    if ctx.accounts.asset_vault.amount == 0 {
-        let auction_id_bytes = auction.auction_id.to_le_bytes();
-        let auction_seeds = &[
-            AUCTION_SEED,
-            auction_id_bytes.as_ref(),
-            &[auction.bump],
-        ];
-        let signer_seeds = &[&auction_seeds[..]];
-        
+    if asset_vault.amount == 0 {
         close_account(
             CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
+                token_program.clone(),
                 CloseAccount {
-                    account: ctx.accounts.asset_vault.to_account_info(),
-                    destination: ctx.accounts.creator.to_account_info(),
+                    account: asset_vault.to_account_info(),
+                    destination: creator.clone(),
                     authority: auction.to_account_info(),
                 },
                 signer_seeds,
@@ -85,10 +172,28 @@ pub fn cleanup_expired_auction(
         )?;
     }
     
-    // Mark auction as cleaned up
-    auction.status = AuctionStatus::Cancelled;
+    // Creators whose auctions predate the index have no account to update
+    require!(
+        creator_index.key() == CreatorIndex::address(&auction.creator).0,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    if creator_index.owner == &crate::ID {
+        let mut index = CreatorIndex::try_deserialize(&mut &creator_index.try_borrow_data()?[..])?;
+        index.remove(auction.auction_id);
+        index.try_serialize(&mut &mut creator_index.try_borrow_mut_data()?[..])?;
+    }
     
-    msg!("Cleaned up expired auction {}", auction_id);
+    // Auctions that drew bids but were never settled were abandoned;
+    // tranched sales settle each unit as it sells. A settlement voided by a
+    // dispute or arbitration is not the creator's doing, so it forfeits nothing.
+    let voided = auction.settlement_attempt > 0;
+    let abandoned = !is_settled && auction.bid_count > 0 && !auction.has_feature(AuctionFeatures::TRANCHES) && !voided;
+    release_creation_deposit(auction, if abandoned { fee_recipient } else { creator })?;
+    
+    // Mark auction as cleaned up; voided auctions are already cancelled
+    if auction.status != AuctionStatus::Cancelled {
+        auction.transition(AuctionStatus::Cancelled)?;
+    }
     
     Ok(())
 }
@@ -111,9 +216,9 @@ pub fn cleanup_expired_bids(
     );
     
     // Auction must be expired or settled for bid cleanup
-    let is_cleanup_eligible = auction.status == AuctionStatus::Settled || 
+    let is_cleanup_eligible = auction.status == AuctionStatus::AssetsDistributed || 
                              auction.status == AuctionStatus::Cancelled ||
-                             clock.unix_timestamp >= auction.end_time + CLEANUP_GRACE_PERIOD;
+                             auction.now(&clock) >= auction.end_time + auction.timing_mode.units_for_seconds(protocol.cleanup_grace_period());
     
     require!(
         is_cleanup_eligible,
@@ -125,9 +230,13 @@ pub fn cleanup_expired_bids(
     Ok(())
 }
 
-/// Batch cleanup multiple expired auctions
-pub fn batch_cleanup_auctions(
-    ctx: Context<BatchCleanupAuctions>,
+/// Batch cleanup multiple expired auctions.
+///
+/// Remaining accounts are passed in groups of `CLEANUP_ACCOUNTS_PER_AUCTION`,
+/// one group per entry in `auction_ids`:
+/// `[auction, asset_vault, creator_asset_account, creator, creator_index, features]`.
+pub fn batch_cleanup_auctions<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchCleanupAuctions<'info>>,
     auction_ids: Vec<u64>,
 ) -> Result<()> {
     let protocol = &ctx.accounts.protocol_state;
@@ -141,25 +250,173 @@ pub fn batch_cleanup_auctions(
     );
     
     require!(
-        auction_ids.len() > 0,
+        !auction_ids.is_empty(),
         ShadowProtocolError::InvalidBatchSize
     );
     
-    // Mark batch as processed
+    require!(
+        ctx.remaining_accounts.len() == auction_ids.len() * CLEANUP_ACCOUNTS_PER_AUCTION,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let fee_recipient = ctx.accounts.fee_recipient.to_account_info();
+    
+    for (auction_id, accounts) in auction_ids
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(CLEANUP_ACCOUNTS_PER_AUCTION))
+    {
+        let mut auction = Account::<AuctionAccount>::try_from(&accounts[0])?;
+        let mut asset_vault = Account::<TokenAccount>::try_from(&accounts[1])?;
+        let creator_asset_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+        let creator = &accounts[3];
+        let creator_index = &accounts[4];
+        let features = Account::<AuctionFeatures>::try_from(&accounts[5])?;
+        
+        require!(
+            auction.auction_id == *auction_id,
+            ShadowProtocolError::AuctionNotInBatch
+        );
+        require!(accounts[0].is_writable, ShadowProtocolError::InvalidRemainingAccounts);
+        
+        close_out_auction(
+            &mut auction,
+            CloseOutAccounts {
+                features: &features,
+                asset_vault: &mut asset_vault,
+                creator_asset_account: &creator_asset_account,
+                creator,
+                creator_index,
+                fee_recipient: &fee_recipient,
+                token_program: &token_program,
+            },
+            &clock,
+            protocol.cleanup_grace_period(),
+        )?;
+        
+        // Accounts loaded from remaining_accounts are not persisted automatically
+        auction.exit(&crate::ID)?;
+        
+        msg!("Cleaned up expired auction {}", auction_id);
+    }
+    
+    // Record the processed batch
     let batch = &mut ctx.accounts.cleanup_batch;
     batch.creator = ctx.accounts.cleaner.key();
     batch.auction_ids = auction_ids.clone();
     batch.processed_at = clock.unix_timestamp;
     batch.bump = ctx.bumps.cleanup_batch;
     
-    msg!("Batch cleanup initiated for {} auctions", auction_ids.len());
+    msg!("Batch cleanup completed for {} auctions", auction_ids.len());
+    
+    Ok(())
+}
+
+/// Return the asset of an auction that ended without a sale and close its
+/// vault.
+///
+/// Permissionless and free of the cleanup grace period: available as soon as
+/// the settlement callback has recorded an unsold outcome, or as soon as an
+/// auction without bids has ended. The creation deposit goes back to the
+/// creator, and losing bidders can then reclaim their collateral.
+pub fn finalize_unsold(ctx: Context<FinalizeUnsold>, auction_id: u64) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.protocol_state.paused, ShadowProtocolError::ProtocolPaused);
+    
+    let no_bids = auction.bid_count == 0 && (
+        auction.status == AuctionStatus::Ended ||
+        (auction.status == AuctionStatus::Active && auction.has_ended(&clock))
+    );
+    require!(
+        (auction.has_flag(AuctionAccount::UNSOLD) && auction.status == AuctionStatus::Ended) || no_bids,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        auction.open_yield_positions == 0,
+        ShadowProtocolError::YieldPositionsOpen
+    );
+    if let Some(sale) = &ctx.accounts.features.lbp {
+        require!(
+            sale.purchases_submitted == 0,
+            ShadowProtocolError::LbpPurchasesPending
+        );
+    }
+    
+    // Copied out, as the auction is written while the seeds are in use
+    let pda_seed = auction.pda_seed().to_vec();
+    let auction_seeds = &[
+        AUCTION_SEED,
+        pda_seed.as_ref(),
+        &[auction.bump],
+    ];
+    let signer_seeds = &[&auction_seeds[..]];
+    
+    let asset_amount = ctx.accounts.asset_vault.amount;
+    if asset_amount > 0 {
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.asset_vault.to_account_info(),
+                    to: ctx.accounts.creator_asset_account.to_account_info(),
+                    authority: auction.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            asset_amount,
+        )?;
+    }
+    auction.vault_balance = 0;
+    
+    close_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.asset_vault.to_account_info(),
+                destination: ctx.accounts.creator.to_account_info(),
+                authority: auction.to_account_info(),
+            },
+            signer_seeds,
+        ),
+    )?;
+    
+    if let Some(creator_index) = ctx.accounts.creator_index.as_deref_mut() {
+        creator_index.remove(auction_id);
+    }
+    
+    release_creation_deposit(auction, &ctx.accounts.creator)?;
+    auction.transition(AuctionStatus::Cancelled)?;
+    
+    emit!(UnsoldAuctionFinalized {
+        auction_id,
+        creator: auction.creator,
+        asset_amount,
+        reserve_met: auction.has_flag(AuctionAccount::RESERVE_MET),
+        participating_bids: auction.participating_bids as u64,
+        finalized_at: clock.unix_timestamp,
+    });
     
     Ok(())
 }
 
-/// Reclaim unused storage from the protocol
-pub fn reclaim_storage(
-    ctx: Context<ReclaimStorage>,
+/// Reclaim storage held by terminal auction, bid and batch accounts.
+///
+/// Accounts are passed as remaining accounts in groups whose layout depends
+/// on the type of the leading account:
+/// - auction: `[auction, creator, asset_vault, features]`
+/// - bid: `[bid, bidder, bid_escrow, auction]` (the SOL escrow in place of
+///   `bid_escrow` for native-SOL collateral)
+/// - batch: `[batch, batch_creator]`
+///
+/// Rent is always returned to whoever paid for the account originally.
+///
+/// Exempt from governance execution: only accounts already past their
+/// cleanup grace period qualify and no value moves to the authority, so
+/// cleanup runs as routine upkeep rather than a proposal.
+pub fn reclaim_storage<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ReclaimStorage<'info>>,
 ) -> Result<()> {
     let protocol = &ctx.accounts.protocol_state;
     
@@ -169,11 +426,253 @@ pub fn reclaim_storage(
         ShadowProtocolError::Unauthorized
     );
     
-    msg!("Storage reclamation initiated by protocol authority");
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let accounts = ctx.remaining_accounts;
+    let mut closed: u64 = 0;
+    let mut index = 0;
+    
+    while index < accounts.len() {
+        let discriminator = {
+            let data = accounts[index].try_borrow_data()?;
+            require!(data.len() >= 8, ShadowProtocolError::InvalidRemainingAccounts);
+            data[..8].to_vec()
+        };
+        
+        let group_len = if discriminator == AuctionAccount::DISCRIMINATOR {
+            RECLAIM_AUCTION_ACCOUNTS
+        } else if discriminator == BidAccount::DISCRIMINATOR {
+            RECLAIM_BID_ACCOUNTS
+        } else if discriminator == BatchSettlement::DISCRIMINATOR {
+            RECLAIM_BATCH_ACCOUNTS
+        } else {
+            return Err(ShadowProtocolError::InvalidRemainingAccounts.into());
+        };
+        
+        let group = accounts
+            .get(index..index + group_len)
+            .ok_or(ShadowProtocolError::InvalidRemainingAccounts)?;
+        
+        // Auction and bid groups are the same length, so dispatch on type
+        closed += if discriminator == AuctionAccount::DISCRIMINATOR {
+            reclaim_auction(group, &token_program)?
+        } else if discriminator == BidAccount::DISCRIMINATOR {
+            reclaim_bid(group, &token_program)?
+        } else {
+            reclaim_batch(group)?
+        };
+        
+        index += group_len;
+    }
+    
+    msg!("Storage reclamation closed {} accounts", closed);
     
     Ok(())
 }
 
+/// Remaining accounts per auction in `reclaim_storage`
+const RECLAIM_AUCTION_ACCOUNTS: usize = 4;
+/// Remaining accounts per bid in `reclaim_storage`
+const RECLAIM_BID_ACCOUNTS: usize = 4;
+/// Remaining accounts per batch in `reclaim_storage`
+const RECLAIM_BATCH_ACCOUNTS: usize = 2;
+
+fn is_closed(info: &AccountInfo) -> bool {
+    info.lamports() == 0 || info.data_is_empty()
+}
+
+/// Close a settled or cancelled auction together with its (empty) asset
+/// vault and its features
+fn reclaim_auction<'info>(
+    group: &'info [AccountInfo<'info>],
+    token_program: &AccountInfo<'info>,
+) -> Result<u64> {
+    let auction = Account::<AuctionAccount>::try_from(&group[0])?;
+    let creator = &group[1];
+    let mut closed = release_auction_storage(&auction, creator, &group[2], token_program)?;
+    
+    // Already gone if the auction was compressed and restored
+    if !is_closed(&group[3]) {
+        let features = Account::<AuctionFeatures>::try_from(&group[3])?;
+        require_keys_eq!(
+            features.auction,
+            auction.key(),
+            ShadowProtocolError::InvalidRemainingAccounts
+        );
+        features.close(creator.clone())?;
+        closed += 1;
+    }
+    
+    auction.close(creator.clone())?;
+    
+    Ok(closed + 1)
+}
+
+/// Check a terminal auction can be dropped and close its empty asset vault.
+/// Returns the number of accounts closed; the auction itself is left to the
+/// caller.
+pub(crate) fn release_auction_storage<'info>(
+    auction: &Account<'info, AuctionAccount>,
+    creator: &AccountInfo<'info>,
+    asset_vault: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<u64> {
+    let mut closed = 0;
+    
+    require!(
+        auction.status == AuctionStatus::AssetsDistributed || auction.status == AuctionStatus::Cancelled,
+        ShadowProtocolError::AccountNotReclaimable
+    );
+    // The settlement escrow is owned by the auction; it must be claimed first
+    require!(
+        auction.escrowed_proceeds == 0,
+        ShadowProtocolError::AccountNotReclaimable
+    );
+    require!(
+        creator.key() == auction.creator,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    require!(
+        asset_vault.key() == auction.asset_vault,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    if !is_closed(asset_vault) {
+        let vault = TokenAccount::try_deserialize(&mut &asset_vault.try_borrow_data()?[..])?;
+        require!(vault.amount == 0, ShadowProtocolError::AccountNotReclaimable);
+        
+        let auction_seeds = &[
+            AUCTION_SEED,
+            auction.pda_seed(),
+            &[auction.bump],
+        ];
+        let signer_seeds = &[&auction_seeds[..]];
+        
+        close_account(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                CloseAccount {
+                    account: asset_vault.clone(),
+                    destination: creator.clone(),
+                    authority: auction.to_account_info(),
+                },
+                signer_seeds,
+            ),
+        )?;
+        closed += 1;
+    }
+    
+    Ok(closed)
+}
+
+/// Close a bid (and its empty escrow) once its auction is terminal or gone
+fn reclaim_bid<'info>(
+    group: &'info [AccountInfo<'info>],
+    token_program: &AccountInfo<'info>,
+) -> Result<u64> {
+    let bid = Account::<BidAccount>::try_from(&group[0])?;
+    let bidder = &group[1];
+    let closed = release_bid_storage(&bid, bidder, &group[2], &group[3], token_program)?;
+    
+    bid.close(bidder.clone())?;
+    
+    Ok(closed + 1)
+}
+
+/// Check a bid can be dropped (its auction terminal or gone, its collateral
+/// returned) and close its empty escrow. Returns the number of accounts
+/// closed; the bid itself is left to the caller.
+pub(crate) fn release_bid_storage<'info>(
+    bid: &Account<'info, BidAccount>,
+    bidder: &AccountInfo<'info>,
+    bid_escrow: &AccountInfo<'info>,
+    auction_info: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<u64> {
+    let mut closed = 0;
+    
+    let auction_id_bytes = bid.auction_id.to_le_bytes();
+    
+    require!(
+        bidder.key() == bid.bidder,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    require!(
+        auction_info.key() == bid.auction,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    // A closed auction account has already been reclaimed and is terminal
+    if !is_closed(auction_info) {
+        let auction = AuctionAccount::try_deserialize(&mut &auction_info.try_borrow_data()?[..])?;
+        require!(
+            auction.status == AuctionStatus::AssetsDistributed || auction.status == AuctionStatus::Cancelled,
+            ShadowProtocolError::AccountNotReclaimable
+        );
+    }
+    
+    // Native-SOL bids pass their SOL escrow in the escrow slot
+    let escrow_seed: &[u8] = if bid.sol_collateral { SOL_ESCROW_SEED } else { b"bid_escrow" };
+    let (escrow_key, _) = Pubkey::find_program_address(
+        &[escrow_seed, auction_id_bytes.as_ref(), bid.bidder.as_ref()],
+        &crate::ID,
+    );
+    require!(
+        bid_escrow.key() == escrow_key,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    // SOL collateral must be refunded or slashed before the bid can go
+    if bid.sol_collateral {
+        require!(bid_escrow.lamports() == 0, ShadowProtocolError::AccountNotReclaimable);
+    } else if !is_closed(bid_escrow) {
+        let escrow = TokenAccount::try_deserialize(&mut &bid_escrow.try_borrow_data()?[..])?;
+        require!(escrow.amount == 0, ShadowProtocolError::AccountNotReclaimable);
+        
+        let bid_seeds = &[
+            BID_SEED,
+            auction_id_bytes.as_ref(),
+            bid.bidder.as_ref(),
+            &[bid.bump],
+        ];
+        let signer_seeds = &[&bid_seeds[..]];
+        
+        close_account(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                CloseAccount {
+                    account: bid_escrow.clone(),
+                    destination: bidder.clone(),
+                    authority: bid.to_account_info(),
+                },
+                signer_seeds,
+            ),
+        )?;
+        closed += 1;
+    }
+    
+    Ok(closed)
+}
+
+/// Close a batch that has finished settling, successfully or not
+fn reclaim_batch<'info>(group: &'info [AccountInfo<'info>]) -> Result<u64> {
+    let batch = Account::<BatchSettlement>::try_from(&group[0])?;
+    let creator = &group[1];
+    
+    require!(
+        batch.status == BatchStatus::Settled || batch.status == BatchStatus::Failed,
+        ShadowProtocolError::AccountNotReclaimable
+    );
+    require!(
+        creator.key() == batch.creator,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    batch.close(creator.clone())?;
+    
+    Ok(1)
+}
+
 #[derive(Accounts)]
 #[instruction(auction_id: u64)]
 pub struct CleanupExpiredAuction<'info> {
@@ -182,12 +681,17 @@ pub struct CleanupExpiredAuction<'info> {
     
     #[account(
         mut,
-        seeds = [AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
-        bump = auction.bump,
         constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionStatus
     )]
     pub auction: Account<'info, AuctionAccount>,
     
+    /// Read for a liquidity bootstrapping sale's pending purchases
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
     #[account(
         seeds = [PROTOCOL_SEED],
         bump = protocol_state.bump
@@ -211,13 +715,84 @@ pub struct CleanupExpiredAuction<'info> {
     pub creator_asset_account: Account<'info, TokenAccount>,
     
     /// CHECK: Creator account for receiving SOL refunds
-    #[account(mut)]
+    #[account(mut, address = auction.creator @ ShadowProtocolError::Unauthorized)]
     pub creator: AccountInfo<'info>,
     
+    /// CHECK: Creator's auction index; left untouched if it was never created
+    #[account(mut, seeds = [CREATOR_INDEX_SEED, auction.creator.as_ref()], bump)]
+    pub creator_index: AccountInfo<'info>,
+    
+    /// CHECK: Receives deposits forfeited by abandoned auctions
+    #[account(mut, address = protocol_state.fee_recipient @ ShadowProtocolError::Unauthorized)]
+    pub fee_recipient: AccountInfo<'info>,
+    
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+#[event]
+pub struct UnsoldAuctionFinalized {
+    pub auction_id: u64,
+    pub creator: Pubkey,
+    pub asset_amount: u64,
+    pub reserve_met: bool,
+    pub participating_bids: u64,
+    pub finalized_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct FinalizeUnsold<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Read for a liquidity bootstrapping sale's submitted purchases
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        address = auction.asset_vault @ ShadowProtocolError::InvalidTokenAccount
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    /// Creator's asset account to receive the unsold asset
+    #[account(
+        mut,
+        token::mint = auction.asset_mint,
+        token::authority = auction.creator
+    )]
+    pub creator_asset_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Auction creator, receives the vault rent and creation deposit
+    #[account(mut, address = auction.creator @ ShadowProtocolError::Unauthorized)]
+    pub creator: AccountInfo<'info>,
+    
+    /// Creator's auction index; omit if it was never created
+    #[account(
+        mut,
+        seeds = [CREATOR_INDEX_SEED, auction.creator.as_ref()],
+        bump = creator_index.bump
+    )]
+    pub creator_index: Option<Account<'info, CreatorIndex>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
 #[derive(Accounts)]
 #[instruction(auction_id: u64)]
 pub struct CleanupExpiredBids<'info> {
@@ -225,8 +800,7 @@ pub struct CleanupExpiredBids<'info> {
     pub cleaner: Signer<'info>,
     
     #[account(
-        seeds = [AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
-        bump = auction.bump
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
     )]
     pub auction: Account<'info, AuctionAccount>,
     
@@ -245,7 +819,7 @@ pub struct BatchCleanupAuctions<'info> {
     pub cleaner: Signer<'info>,
     
     #[account(
-        init,
+        init_if_needed,
         payer = cleaner,
         space = 8 + CleanupBatch::INIT_SPACE,
         seeds = [b"cleanup_batch", cleaner.key().as_ref()],
@@ -259,6 +833,11 @@ pub struct BatchCleanupAuctions<'info> {
     )]
     pub protocol_state: Account<'info, ProtocolState>,
     
+    /// CHECK: Receives deposits forfeited by abandoned auctions
+    #[account(mut, address = protocol_state.fee_recipient @ ShadowProtocolError::Unauthorized)]
+    pub fee_recipient: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
@@ -272,6 +851,7 @@ pub struct ReclaimStorage<'info> {
     )]
     pub protocol_state: Account<'info, ProtocolState>,
     
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
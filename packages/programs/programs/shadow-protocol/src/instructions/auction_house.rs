@@ -0,0 +1,261 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Open an auction house for the signing platform.
+///
+/// One house per authority. Auctions opt in by passing the house when they
+/// are created; everything past listing and bidding runs on the shared
+/// protocol.
+pub fn create_auction_house(
+    ctx: Context<CreateAuctionHouse>,
+    name: [u8; 32],
+    fee_bps: u16,
+    fee_recipient: Pubkey,
+    creator_allowlist: bool,
+) -> Result<()> {
+    require!(!ctx.accounts.protocol_state.paused, ShadowProtocolError::ProtocolPaused);
+    require!(fee_bps <= MAX_HOUSE_FEE, ShadowProtocolError::InvalidHouseFee);
+    require!(
+        fee_recipient != Pubkey::default(),
+        ShadowProtocolError::InvalidAuctionHouse
+    );
+    
+    let house = &mut ctx.accounts.auction_house;
+    house.authority = ctx.accounts.authority.key();
+    house.name = name;
+    house.fee_bps = fee_bps;
+    house.fee_recipient = fee_recipient;
+    house.creator_allowlist = creator_allowlist;
+    house.paused = false;
+    house.auctions_created = 0;
+    house.bump = ctx.bumps.auction_house;
+    
+    emit!(AuctionHouseUpdated {
+        house: house.key(),
+        authority: house.authority,
+        fee_bps,
+        fee_recipient,
+        creator_allowlist,
+        paused: false,
+    });
+    
+    Ok(())
+}
+
+/// Change a house's fee, fee recipient or allowlist mode. Auctions already
+/// listed keep the fee they were created with.
+pub fn update_auction_house(
+    ctx: Context<UpdateAuctionHouse>,
+    fee_bps: u16,
+    fee_recipient: Pubkey,
+    creator_allowlist: bool,
+) -> Result<()> {
+    require!(fee_bps <= MAX_HOUSE_FEE, ShadowProtocolError::InvalidHouseFee);
+    require!(
+        fee_recipient != Pubkey::default(),
+        ShadowProtocolError::InvalidAuctionHouse
+    );
+    
+    let house = &mut ctx.accounts.auction_house;
+    house.fee_bps = fee_bps;
+    house.fee_recipient = fee_recipient;
+    house.creator_allowlist = creator_allowlist;
+    
+    emit!(AuctionHouseUpdated {
+        house: house.key(),
+        authority: house.authority,
+        fee_bps,
+        fee_recipient,
+        creator_allowlist,
+        paused: house.paused,
+    });
+    
+    Ok(())
+}
+
+/// Pause or resume listing and bidding on a house's auctions. Settlement
+/// and refunds keep working so funds are never stuck behind a pause.
+pub fn set_auction_house_paused(ctx: Context<UpdateAuctionHouse>, paused: bool) -> Result<()> {
+    let house = &mut ctx.accounts.auction_house;
+    house.paused = paused;
+    
+    emit!(AuctionHouseUpdated {
+        house: house.key(),
+        authority: house.authority,
+        fee_bps: house.fee_bps,
+        fee_recipient: house.fee_recipient,
+        creator_allowlist: house.creator_allowlist,
+        paused,
+    });
+    
+    Ok(())
+}
+
+/// Allowlist a creator on the house
+pub fn add_house_creator(ctx: Context<AddHouseCreator>, creator: Pubkey) -> Result<()> {
+    let entry = &mut ctx.accounts.house_creator;
+    entry.house = ctx.accounts.auction_house.key();
+    entry.creator = creator;
+    entry.added_at = Clock::get()?.unix_timestamp;
+    entry.bump = ctx.bumps.house_creator;
+    
+    emit!(HouseCreatorUpdated {
+        house: entry.house,
+        creator,
+        allowed: true,
+    });
+    
+    Ok(())
+}
+
+/// Drop a creator from the house allowlist and return the entry's rent
+pub fn remove_house_creator(ctx: Context<RemoveHouseCreator>, creator: Pubkey) -> Result<()> {
+    emit!(HouseCreatorUpdated {
+        house: ctx.accounts.auction_house.key(),
+        creator,
+        allowed: false,
+    });
+    
+    Ok(())
+}
+
+/// Terms to record on an auction being created, checking the house is open
+/// and, for allowlisted houses, that `creator` holds an entry.
+pub(crate) fn list_through_house(
+    house: Option<&mut Account<AuctionHouse>>,
+    house_creator: Option<&Account<HouseCreator>>,
+    creator: Pubkey,
+) -> Result<Option<HouseTerms>> {
+    let Some(house) = house else {
+        return Ok(None);
+    };
+    
+    require!(!house.paused, ShadowProtocolError::AuctionHousePaused);
+    if house.creator_allowlist {
+        require!(
+            house_creator.is_some_and(|entry| entry.house == house.key() && entry.creator == creator),
+            ShadowProtocolError::CreatorNotAllowlisted
+        );
+    }
+    
+    house.auctions_created += 1;
+    
+    Ok(Some(HouseTerms {
+        house: house.key(),
+        fee_bps: house.fee_bps,
+    }))
+}
+
+/// Fail unless `house` is the auction's own house and accepting bids.
+/// Auctions outside any house must not be given one.
+pub(crate) fn require_house_open(auction: &AuctionAccount, house: Option<&Account<AuctionHouse>>) -> Result<()> {
+    match (auction.auction_house, house) {
+        (None, None) => Ok(()),
+        (Some(terms), Some(house)) if house.key() == terms.house => {
+            require!(!house.paused, ShadowProtocolError::AuctionHousePaused);
+            Ok(())
+        }
+        _ => err!(ShadowProtocolError::InvalidAuctionHouse),
+    }
+}
+
+#[event]
+pub struct AuctionHouseUpdated {
+    pub house: Pubkey,
+    pub authority: Pubkey,
+    pub fee_bps: u16,
+    pub fee_recipient: Pubkey,
+    pub creator_allowlist: bool,
+    pub paused: bool,
+}
+
+#[event]
+pub struct HouseCreatorUpdated {
+    pub house: Pubkey,
+    pub creator: Pubkey,
+    pub allowed: bool,
+}
+
+#[derive(Accounts)]
+pub struct CreateAuctionHouse<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AuctionHouse::INIT_SPACE,
+        seeds = [AUCTION_HOUSE_SEED, authority.key().as_ref()],
+        bump
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAuctionHouse<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [AUCTION_HOUSE_SEED, authority.key().as_ref()],
+        bump = auction_house.bump,
+        has_one = authority @ ShadowProtocolError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct AddHouseCreator<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED, authority.key().as_ref()],
+        bump = auction_house.bump,
+        has_one = authority @ ShadowProtocolError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + HouseCreator::INIT_SPACE,
+        seeds = [HOUSE_CREATOR_SEED, auction_house.key().as_ref(), creator.as_ref()],
+        bump
+    )]
+    pub house_creator: Account<'info, HouseCreator>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct RemoveHouseCreator<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [AUCTION_HOUSE_SEED, authority.key().as_ref()],
+        bump = auction_house.bump,
+        has_one = authority @ ShadowProtocolError::Unauthorized
+    )]
+    pub auction_house: Account<'info, AuctionHouse>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [HOUSE_CREATOR_SEED, auction_house.key().as_ref(), creator.as_ref()],
+        bump = house_creator.bump
+    )]
+    pub house_creator: Account<'info, HouseCreator>,
+}
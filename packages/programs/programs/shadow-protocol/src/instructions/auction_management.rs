@@ -3,65 +3,197 @@ use anchor_spl::token::{Token, TokenAccount, Mint, Transfer, transfer};
 use crate::state::*;
 use crate::error::ShadowProtocolError;
 use crate::crypto::CryptoUtils;
+use crate::instructions::bidding::calculate_dutch_price_at;
+use crate::instructions::auction_house::list_through_house;
+use crate::instructions::fee_voucher::{redeem_fee_voucher, FeeVoucherRedeemed};
+
+/// Default refundable deposit required to create an auction (0.05 SOL)
+pub const DEFAULT_CREATION_DEPOSIT: u64 = 50_000_000;
+
+/// Parameters of a new sealed-bid auction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateSealedAuctionParams {
+    pub asset_mint: Pubkey,
+    pub asset_amount: u64,
+    pub duration: u64,
+    pub minimum_bid: u64,
+    pub bid_increment: u64,
+    pub reserve_price_encrypted: [u8; 32],
+    pub reserve_price_nonce: u128,
+    pub reserve_price_commitment: [u8; 32],
+    pub timing_mode: TimingMode,
+    pub private_winner: bool,
+    pub collateral_config: Option<CollateralConfig>,
+    pub min_collateral_ratio: u16,
+    pub max_bids: u32,
+    pub bid_collateral_bps: u16,
+    pub bid_cutoff: u32,
+    pub fee_voucher: Option<FeeVoucher>,
+}
 
 pub fn create_sealed_auction(
     ctx: Context<CreateSealedAuction>,
-    asset_mint: Pubkey,
-    asset_amount: u64,
-    duration: u64,
-    minimum_bid: u64,
-    reserve_price_encrypted: [u8; 32],
-    reserve_price_nonce: u128,
+    creator_nonce: u64,
+    params: CreateSealedAuctionParams,
 ) -> Result<()> {
+    let CreateSealedAuctionParams {
+        asset_mint,
+        asset_amount,
+        duration,
+        minimum_bid,
+        bid_increment,
+        reserve_price_encrypted,
+        reserve_price_nonce,
+        reserve_price_commitment,
+        timing_mode,
+        private_winner,
+        collateral_config,
+        min_collateral_ratio,
+        max_bids,
+        bid_collateral_bps,
+        bid_cutoff,
+        fee_voucher,
+    } = params;
+    
     let protocol = &mut ctx.accounts.protocol_state;
     require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
     
     require!(asset_amount > 0, ShadowProtocolError::InvalidAssetAmount);
     
+    let bounds = &protocol.auction_bounds;
+    bounds.check_duration(timing_mode.duration_in_seconds(duration as i64) as u64)?;
+    bounds.check_minimum_bid(minimum_bid)?;
+    bounds.check_asset_amount(asset_amount)?;
+    
+    require!(
+        reserve_price_commitment != [0u8; 32],
+        ShadowProtocolError::InvalidEncryption
+    );
+    
     require!(
         ctx.accounts.creator_asset_account.amount >= asset_amount,
         ShadowProtocolError::InsufficientFunds
     );
     
+    if let Some(config) = &collateral_config {
+        require!(
+            config.mint != Pubkey::default() && config.min_amount > 0,
+            ShadowProtocolError::InvalidCollateralMint
+        );
+    }
+    // Bids must put something at stake, or fake ones cost nothing
+    require!(min_collateral_ratio > 0, ShadowProtocolError::InvalidCollateralRatio);
+    // Deposit-weighted validity compares collateral against the bid, so both
+    // must be in the payment mint
+    require!(
+        bid_collateral_bps <= 10_000 && (bid_collateral_bps == 0 || collateral_config.is_none()),
+        ShadowProtocolError::InvalidCollateralRatio
+    );
+    // Bidding has to stay open for part of the auction
+    require!(
+        (bid_cutoff as u64) < duration,
+        ShadowProtocolError::InvalidBidCutoff
+    );
+    // A lower cap keeps small sales on a small settlement computation
+    require!(
+        max_bids > 0 && max_bids <= MAX_BIDS_PER_AUCTION as u32,
+        ShadowProtocolError::InvalidBidCount
+    );
+    
+    let auction_house = list_through_house(
+        ctx.accounts.auction_house.as_mut(),
+        ctx.accounts.house_creator.as_ref(),
+        ctx.accounts.creator.key(),
+    )?;
+    
     let auction_id = protocol.next_auction_id;
+    let fee_override = fee_voucher
+        .map(|voucher| redeem_fee_voucher(
+            &voucher,
+            ctx.accounts.creator.key(),
+            creator_nonce,
+            protocol,
+            ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        ))
+        .transpose()?;
+    let creation_deposit = protocol.creation_deposit;
+    let compliance_program = Some(protocol.compliance_program)
+        .filter(|program| *program != Pubkey::default());
     
     protocol.next_auction_id = protocol.next_auction_id
         .checked_add(1)
         .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
     
     let clock = Clock::get()?;
-    let start_time = clock.unix_timestamp;
+    let start_time = timing_mode.now(&clock);
     let end_time = start_time + duration as i64;
     
-    require!(
-        duration as i64 <= MAX_AUCTION_DURATION,
-        ShadowProtocolError::AuctionDurationTooLong
-    );
-    
     let auction = &mut ctx.accounts.auction;
     auction.auction_id = auction_id;
+    auction.address_seed = AuctionAccount::address_digest(&ctx.accounts.creator.key(), creator_nonce);
     auction.creator = ctx.accounts.creator.key();
     auction.asset_mint = asset_mint;
     auction.asset_vault = ctx.accounts.asset_vault.key();
     auction.asset_amount = asset_amount;
+    auction.payment_mint = ctx.accounts.payment_mint.key();
+    auction.vault_balance = asset_amount;
     auction.auction_type = AuctionType::SealedBid;
-    auction.status = AuctionStatus::Active;
+    auction.transition(AuctionStatus::Active)?;
     auction.start_time = start_time;
     auction.end_time = end_time;
+    auction.bid_cutoff = bid_cutoff;
+    auction.timing_mode = timing_mode;
     auction.minimum_bid = minimum_bid;
+    auction.bid_increment = bid_increment;
     auction.minimum_price_floor = 0;
     auction.reserve_price_encrypted = reserve_price_encrypted;
     auction.reserve_price_nonce = reserve_price_nonce;
+    auction.reserve_price_commitment = reserve_price_commitment;
+    auction.revealed_reserve_price = None;
     auction.current_price = 0;
     auction.price_decrease_rate = 0;
+    auction.price_curve = PriceCurve::Linear;
     auction.bid_count = 0;
+    auction.max_bids = max_bids;
     auction.winner = None;
     auction.winning_amount = 0;
+    auction.second_highest_bid = 0;
+    auction.participating_bids = 0;
+    auction.flags = AuctionAccount::VAULT_LOCKED;
+    auction.set_flag(AuctionAccount::PRIVATE_WINNER, private_winner);
+    auction.encrypted_winner = None;
+    auction.invite_hash = None;
+    auction.compliance_program = compliance_program;
+    auction.min_collateral_ratio = min_collateral_ratio;
+    auction.bid_collateral_bps = bid_collateral_bps;
+    auction.open_yield_positions = 0;
+    auction.auction_house = auction_house;
+    auction.fee_override = fee_override;
+    auction.escrowed_proceeds = 0;
+    auction.proceeds_claim = None;
+    auction.settlement_authorizer = None;
+    auction.position_mint = None;
+    auction.settlement_attempt = 0;
     auction.settled_at = None;
     auction.mpc_verification_hash = None;
-    auction.settlement_authorized = false;
+    auction.pending_creator = None;
+    auction.settlement_authorized_at = None;
+    auction.creation_deposit = creation_deposit;
     auction.bump = ctx.bumps.auction;
     
+    let features = &mut ctx.accounts.features;
+    features.auction = auction.key();
+    features.price_oracle = None;
+    features.payout_conversion = None;
+    features.collateral_config = collateral_config;
+    features.yield_config = None;
+    features.payout_vesting = None;
+    features.lbp = None;
+    features.tranches = None;
+    features.settlement_hook = None;
+    features.bump = ctx.bumps.features;
+    auction.sync_features(features);
+    
     transfer(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
@@ -74,9 +206,32 @@ pub fn create_sealed_auction(
         asset_amount,
     )?;
     
+    // Held in the auction account until settlement or cleanup
+    if creation_deposit > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.auction.to_account_info(),
+                },
+            ),
+            creation_deposit,
+        )?;
+    }
+    
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.auctions_created = stats.auctions_created.saturating_add(1);
+    
+    let creator_index = &mut ctx.accounts.creator_index;
+    creator_index.creator = ctx.accounts.creator.key();
+    creator_index.bump = ctx.bumps.creator_index;
+    creator_index.push(auction_id);
+    
     emit!(AuctionCreated {
         auction_id,
         creator: ctx.accounts.creator.key(),
+        creator_nonce,
         asset_mint,
         auction_type: AuctionType::SealedBid,
         start_time,
@@ -84,32 +239,96 @@ pub fn create_sealed_auction(
         minimum_bid,
     });
     
+    if let (Some(voucher), Some(fee_bps)) = (fee_voucher, fee_override) {
+        emit!(FeeVoucherRedeemed {
+            auction_id,
+            creator: voucher.creator,
+            fee_bps,
+            expires_at: voucher.expires_at,
+        });
+    }
+    
     Ok(())
 }
 
+/// Parameters of a new Dutch auction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateDutchAuctionParams {
+    pub asset_mint: Pubkey,
+    pub asset_amount: u64,
+    pub starting_price: u64,
+    pub price_decrease_rate: u64,
+    pub minimum_price_floor: u64,
+    pub duration: u64,
+    pub reserve_price_encrypted: [u8; 32],
+    pub reserve_price_nonce: u128,
+    pub reserve_price_commitment: [u8; 32],
+    pub price_curve: PriceCurve,
+    pub timing_mode: TimingMode,
+    pub collateral_config: Option<CollateralConfig>,
+    pub lbp: Option<LbpConfig>,
+    pub tranches: Option<TrancheConfig>,
+    pub fee_voucher: Option<FeeVoucher>,
+}
+
 pub fn create_dutch_auction(
     ctx: Context<CreateDutchAuction>,
-    asset_mint: Pubkey,
-    asset_amount: u64,
-    starting_price: u64,
-    price_decrease_rate: u64,
-    minimum_price_floor: u64,
-    duration: u64,
-    reserve_price_encrypted: [u8; 32],
-    reserve_price_nonce: u128,
+    creator_nonce: u64,
+    params: CreateDutchAuctionParams,
 ) -> Result<()> {
+    let CreateDutchAuctionParams {
+        asset_mint,
+        asset_amount,
+        starting_price,
+        price_decrease_rate,
+        minimum_price_floor,
+        duration,
+        reserve_price_encrypted,
+        reserve_price_nonce,
+        reserve_price_commitment,
+        price_curve,
+        timing_mode,
+        collateral_config,
+        lbp,
+        tranches,
+        fee_voucher,
+    } = params;
+    
     let protocol = &mut ctx.accounts.protocol_state;
     require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
     
     require!(asset_amount > 0, ShadowProtocolError::InvalidAssetAmount);
     
+    let bounds = &protocol.auction_bounds;
+    bounds.check_duration(timing_mode.duration_in_seconds(duration as i64) as u64)?;
+    bounds.check_minimum_bid(minimum_price_floor)?;
+    bounds.check_price_decrease_rate(price_decrease_rate)?;
+    bounds.check_asset_amount(asset_amount)?;
+    
+    require!(
+        reserve_price_commitment != [0u8; 32],
+        ShadowProtocolError::InvalidEncryption
+    );
+    
     require!(
         ctx.accounts.creator_asset_account.amount >= asset_amount,
         ShadowProtocolError::InsufficientFunds
     );
     
+    if let Some(config) = &collateral_config {
+        require!(
+            config.mint != Pubkey::default() && config.min_amount > 0,
+            ShadowProtocolError::InvalidCollateralMint
+        );
+    }
+    
+    let valid_curve = match price_curve {
+        PriceCurve::Linear => price_decrease_rate > 0,
+        PriceCurve::Exponential { .. } => true,
+        PriceCurve::Stepwise { step_amount, .. } => step_amount > 0,
+    };
     require!(
-        price_decrease_rate > 0,
+        valid_curve && price_curve.interval() > 0,
         ShadowProtocolError::InvalidPriceDecreaseRate
     );
     
@@ -118,45 +337,132 @@ pub fn create_dutch_auction(
         ShadowProtocolError::PriceBelowMinimumFloor
     );
     
+    // LBP and tranched sales collect payment piece by piece, in the
+    // auction's payment mint
+    let payment_mint = ctx.accounts.payment_mint.key();
+    if let Some(config) = &lbp {
+        require_keys_eq!(config.payment_mint, payment_mint, ShadowProtocolError::InvalidPaymentMint);
+    }
+    // Tranched sales also split the asset evenly
+    if let Some(config) = &tranches {
+        require_keys_eq!(config.payment_mint, payment_mint, ShadowProtocolError::InvalidPaymentMint);
+        require!(
+            lbp.is_none()
+                && config.units > 1
+                && asset_amount % config.units as u64 == 0,
+            ShadowProtocolError::InvalidTrancheConfig
+        );
+    }
+    let auction_type = match lbp {
+        Some(_) => AuctionType::LiquidityBootstrapping,
+        None => AuctionType::Dutch,
+    };
+    
+    let auction_house = list_through_house(
+        ctx.accounts.auction_house.as_mut(),
+        ctx.accounts.house_creator.as_ref(),
+        ctx.accounts.creator.key(),
+    )?;
+    
     let auction_id = protocol.next_auction_id;
+    let fee_override = fee_voucher
+        .map(|voucher| redeem_fee_voucher(
+            &voucher,
+            ctx.accounts.creator.key(),
+            creator_nonce,
+            protocol,
+            ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        ))
+        .transpose()?;
+    let creation_deposit = protocol.creation_deposit;
+    let compliance_program = Some(protocol.compliance_program)
+        .filter(|program| *program != Pubkey::default());
     
     protocol.next_auction_id = protocol.next_auction_id
         .checked_add(1)
         .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
     
     let clock = Clock::get()?;
-    let start_time = clock.unix_timestamp;
+    let start_time = timing_mode.now(&clock);
     let end_time = start_time + duration as i64;
     
-    require!(
-        duration as i64 <= MAX_AUCTION_DURATION,
-        ShadowProtocolError::AuctionDurationTooLong
-    );
-    
     let auction = &mut ctx.accounts.auction;
     auction.auction_id = auction_id;
+    auction.address_seed = AuctionAccount::address_digest(&ctx.accounts.creator.key(), creator_nonce);
     auction.creator = ctx.accounts.creator.key();
     auction.asset_mint = asset_mint;
     auction.asset_vault = ctx.accounts.asset_vault.key();
     auction.asset_amount = asset_amount;
-    auction.auction_type = AuctionType::Dutch;
-    auction.status = AuctionStatus::Active;
+    auction.payment_mint = ctx.accounts.payment_mint.key();
+    auction.vault_balance = asset_amount;
+    auction.auction_type = auction_type;
+    auction.transition(AuctionStatus::Active)?;
     auction.start_time = start_time;
     auction.end_time = end_time;
+    auction.bid_cutoff = 0;
+    auction.timing_mode = timing_mode;
     auction.minimum_bid = 0;
+    auction.bid_increment = 0;
     auction.minimum_price_floor = minimum_price_floor;
     auction.reserve_price_encrypted = reserve_price_encrypted;
     auction.reserve_price_nonce = reserve_price_nonce;
+    auction.reserve_price_commitment = reserve_price_commitment;
+    auction.revealed_reserve_price = None;
     auction.current_price = starting_price;
     auction.price_decrease_rate = price_decrease_rate;
+    auction.price_curve = price_curve;
     auction.bid_count = 0;
+    auction.max_bids = MAX_BIDS_PER_AUCTION as u32;
     auction.winner = None;
     auction.winning_amount = 0;
+    auction.second_highest_bid = 0;
+    auction.participating_bids = 0;
+    auction.flags = AuctionAccount::VAULT_LOCKED;
+    auction.encrypted_winner = None;
+    auction.invite_hash = None;
+    auction.compliance_program = compliance_program;
+    auction.min_collateral_ratio = DEFAULT_MIN_COLLATERAL_RATIO;
+    auction.bid_collateral_bps = 0;
+    auction.open_yield_positions = 0;
+    auction.auction_house = auction_house;
+    auction.fee_override = fee_override;
+    auction.escrowed_proceeds = 0;
+    auction.proceeds_claim = None;
+    auction.settlement_authorizer = None;
+    auction.position_mint = None;
+    auction.settlement_attempt = 0;
     auction.settled_at = None;
     auction.mpc_verification_hash = None;
-    auction.settlement_authorized = false;
+    auction.pending_creator = None;
+    auction.settlement_authorized_at = None;
+    auction.creation_deposit = creation_deposit;
     auction.bump = ctx.bumps.auction;
     
+    let features = &mut ctx.accounts.features;
+    features.auction = auction.key();
+    features.price_oracle = None;
+    features.payout_conversion = None;
+    features.collateral_config = collateral_config;
+    features.yield_config = None;
+    features.payout_vesting = None;
+    features.lbp = lbp.map(|config| LbpSale {
+        payment_mint: config.payment_mint,
+        price_impact: config.price_impact,
+        tokens_sold: 0,
+        purchases_submitted: 0,
+        purchases_processed: 0,
+    });
+    features.tranches = tranches.map(|config| DutchTranches {
+        payment_mint: config.payment_mint,
+        unit_amount: asset_amount / config.units as u64,
+        units_total: config.units,
+        units_remaining: config.units,
+        proceeds: 0,
+    });
+    features.settlement_hook = None;
+    features.bump = ctx.bumps.features;
+    auction.sync_features(features);
+    
     transfer(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
@@ -169,103 +475,1149 @@ pub fn create_dutch_auction(
         asset_amount,
     )?;
     
+    // Held in the auction account until settlement or cleanup
+    if creation_deposit > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.auction.to_account_info(),
+                },
+            ),
+            creation_deposit,
+        )?;
+    }
+    
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.auctions_created = stats.auctions_created.saturating_add(1);
+    
+    let creator_index = &mut ctx.accounts.creator_index;
+    creator_index.creator = ctx.accounts.creator.key();
+    creator_index.bump = ctx.bumps.creator_index;
+    creator_index.push(auction_id);
+    
     emit!(AuctionCreated {
         auction_id,
         creator: ctx.accounts.creator.key(),
+        creator_nonce,
         asset_mint,
-        auction_type: AuctionType::Dutch,
+        auction_type,
         start_time,
         end_time,
         minimum_bid: 0,
     });
     
+    if let (Some(voucher), Some(fee_bps)) = (fee_voucher, fee_override) {
+        emit!(FeeVoucherRedeemed {
+            auction_id,
+            creator: voucher.creator,
+            fee_bps,
+            expires_at: voucher.expires_at,
+        });
+    }
+    
     Ok(())
 }
 
-#[derive(Accounts)]
-pub struct CreateSealedAuction<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
+/// Move the auction's creation deposit out to `recipient`. Refunds go to the
+/// creator; deposits of abandoned auctions go to the fee recipient.
+pub(crate) fn release_creation_deposit<'info>(
+    auction: &mut Account<'info, AuctionAccount>,
+    recipient: &AccountInfo<'info>,
+) -> Result<()> {
+    let amount = auction.creation_deposit;
+    if amount == 0 {
+        return Ok(());
+    }
     
-    #[account(
-        init,
-        payer = creator,
-        space = 8 + AuctionAccount::INIT_SPACE,
-        seeds = [AUCTION_SEED, protocol_state.next_auction_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub auction: Account<'info, AuctionAccount>,
+    auction.sub_lamports(amount)?;
+    recipient.add_lamports(amount)?;
+    auction.creation_deposit = 0;
     
-    #[account(
-        mut,
-        seeds = [PROTOCOL_SEED],
-        bump = protocol_state.bump
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
+    emit!(CreationDepositReleased {
+        auction_id: auction.auction_id,
+        recipient: recipient.key(),
+        amount,
+        refunded: recipient.key() == auction.creator,
+    });
     
-    pub asset_mint: Account<'info, Mint>,
+    Ok(())
+}
+
+/// Push an active auction's end time out by `additional_duration`, in the
+/// auction's timing units. The extended run must stay within the protocol's
+/// duration bounds.
+pub fn extend_auction(
+    ctx: Context<ExtendAuction>,
+    auction_id: u64,
+    additional_duration: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
     
-    #[account(
-        init,
-        payer = creator,
-        token::mint = asset_mint,
-        token::authority = auction,
-        seeds = [ASSET_VAULT_SEED, protocol_state.next_auction_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub asset_vault: Account<'info, TokenAccount>,
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
     
-    #[account(
-        mut,
-        associated_token::mint = asset_mint,
-        associated_token::authority = creator
-    )]
-    pub creator_asset_account: Account<'info, TokenAccount>,
+    require!(
+        auction.status == AuctionStatus::Active,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(!auction.has_ended(&clock), ShadowProtocolError::AuctionEnded);
     
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    require!(additional_duration > 0, ShadowProtocolError::AuctionDurationTooShort);
+    let previous_end_time = auction.end_time;
+    let end_time = i64::try_from(additional_duration)
+        .ok()
+        .and_then(|additional| previous_end_time.checked_add(additional))
+        .ok_or(ShadowProtocolError::AuctionDurationTooLong)?;
+    protocol.auction_bounds.check_duration(
+        auction.timing_mode.duration_in_seconds(end_time - auction.start_time) as u64,
+    )?;
+    
+    auction.end_time = end_time;
+    
+    emit!(AuctionExtended {
+        auction_id,
+        creator: auction.creator,
+        previous_end_time,
+        end_time,
+    });
+    
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct CreateDutchAuction<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
+/// Relist an auction that ended without any bids, reusing its PDA and vault
+pub fn relist_auction(
+    ctx: Context<RelistAuction>,
+    auction_id: u64,
+    duration: u64,
+    minimum_bid: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
     
-    #[account(
-        init,
-        payer = creator,
-        space = 8 + AuctionAccount::INIT_SPACE,
-        seeds = [AUCTION_SEED, protocol_state.next_auction_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub auction: Account<'info, AuctionAccount>,
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
     
-    #[account(
-        mut,
-        seeds = [PROTOCOL_SEED],
-        bump = protocol_state.bump
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
     
-    pub asset_mint: Account<'info, Mint>,
+    // Only auctions that ran their course without attracting a bid can be relisted
+    let has_ended = auction.status == AuctionStatus::Ended ||
+        (auction.status == AuctionStatus::Active && auction.has_ended(&clock));
+    require!(has_ended, ShadowProtocolError::AuctionNotEnded);
     
-    #[account(
-        init,
-        payer = creator,
-        token::mint = asset_mint,
-        token::authority = auction,
-        seeds = [ASSET_VAULT_SEED, protocol_state.next_auction_id.to_le_bytes().as_ref()],
-        bump
-    )]
-    pub asset_vault: Account<'info, TokenAccount>,
+    require!(
+        auction.bid_count == 0 && auction.winner.is_none(),
+        ShadowProtocolError::AuctionHasBids
+    );
     
-    #[account(
-        mut,
-        associated_token::mint = asset_mint,
-        associated_token::authority = creator
-    )]
-    pub creator_asset_account: Account<'info, TokenAccount>,
+    protocol.auction_bounds.check_duration(auction.timing_mode.duration_in_seconds(duration as i64) as u64)?;
+    protocol.auction_bounds.check_minimum_bid(minimum_bid)?;
+    require!(
+        (auction.bid_cutoff as u64) < duration,
+        ShadowProtocolError::InvalidBidCutoff
+    );
     
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+    // The original asset must still be sitting in the vault
+    auction.require_vault_covers(ctx.accounts.asset_vault.amount, auction.asset_amount)?;
+    
+    let start_time = auction.now(&clock);
+    let end_time = start_time + duration as i64;
+    
+    if auction.status == AuctionStatus::Active {
+        auction.transition(AuctionStatus::Ended)?;
+    }
+    auction.transition(AuctionStatus::Active)?;
+    auction.start_time = start_time;
+    auction.end_time = end_time;
+    auction.minimum_bid = minimum_bid;
+    auction.settled_at = None;
+    auction.mpc_verification_hash = None;
+    auction.settlement_authorized_at = None;
+    auction.set_flag(AuctionAccount::UNSOLD, false);
+    auction.mpc_computation_id = None;
+    auction.mpc_comp_def_offset = None;
+    auction.mxe_cluster = None;
+    auction.computation_queued_at = None;
+    
+    emit!(AuctionRelisted {
+        auction_id,
+        creator: auction.creator,
+        start_time,
+        end_time,
+        minimum_bid,
+    });
+    
+    Ok(())
+}
+
+/// Flip an auction whose bidding window has passed from active to ended,
+/// freezing further bids.
+///
+/// Permissionless. Dutch auctions and liquidity bootstrapping sales end
+/// through `expire_dutch_auction` instead, which also returns their asset.
+pub fn end_auction(ctx: Context<EndAuction>, auction_id: u64) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let clock = Clock::get()?;
+    
+    require!(
+        auction.status == AuctionStatus::Active,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        auction.has_ended(&clock),
+        ShadowProtocolError::AuctionNotEnded
+    );
+    
+    mark_auction_ended(auction, &clock)?;
+    msg!("Auction {} ended", auction_id);
+    
+    Ok(())
+}
+
+/// Move an active auction to `Ended` and announce it. Used wherever bidding
+/// on a sealed or batch auction closes, so tooling sees one `AuctionEnded`
+/// however the auction got there.
+pub(crate) fn mark_auction_ended(auction: &mut AuctionAccount, clock: &Clock) -> Result<()> {
+    auction.transition(AuctionStatus::Ended)?;
+    
+    emit!(AuctionEnded {
+        auction_id: auction.auction_id,
+        bid_count: auction.bid_count,
+        end_time: auction.end_time,
+        ended_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Permissionlessly close out a Dutch auction that ran to its end without a
+/// winner, returning the asset to the creator. Also ends a liquidity
+/// bootstrapping or tranched sale, returning whatever did not sell.
+pub fn expire_dutch_auction(
+    ctx: Context<ExpireDutchAuction>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let clock = Clock::get()?;
+    
+    require!(
+        auction.status == AuctionStatus::Active,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    require!(
+        auction.has_ended(&clock),
+        ShadowProtocolError::AuctionNotEnded
+    );
+    
+    require!(
+        auction.winner.is_none(),
+        ShadowProtocolError::AuctionAlreadySettled
+    );
+    
+    if let Some(sale) = &ctx.accounts.features.lbp {
+        require!(
+            sale.purchases_processed == sale.purchases_submitted,
+            ShadowProtocolError::LbpPurchasesPending
+        );
+    }
+    
+    let final_price = calculate_dutch_price_at(auction, auction.end_time)?;
+    
+    auction.transition(AuctionStatus::Ended)?;
+    
+    let returned_amount = ctx.accounts.asset_vault.amount;
+    if returned_amount > 0 {
+        let auction_seeds = &[
+            AUCTION_SEED,
+            auction.pda_seed(),
+            &[auction.bump],
+        ];
+        let signer_seeds = &[&auction_seeds[..]];
+        
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.asset_vault.to_account_info(),
+                    to: ctx.accounts.creator_asset_account.to_account_info(),
+                    authority: auction.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            returned_amount,
+        )?;
+    }
+    auction.vault_balance = 0;
+    
+    emit!(DutchAuctionExpired {
+        auction_id,
+        final_price,
+        returned_amount,
+        expired_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Permissionlessly pass anything transferred directly into an auction's
+/// asset vault on to the creator. Settlement only ever pays out the recorded
+/// deposit, so surplus would otherwise sit in the vault and keep it open.
+/// Available once the auction has settled or been cancelled.
+pub fn sweep_vault_surplus(ctx: Context<SweepVaultSurplus>, auction_id: u64) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    auction.require_vault_unlocked()?;
+    let surplus = auction.vault_surplus(ctx.accounts.asset_vault.amount);
+    require!(surplus > 0, ShadowProtocolError::NoVaultSurplus);
+    
+    let auction_seeds = &[
+        AUCTION_SEED,
+        auction.pda_seed(),
+        &[auction.bump],
+    ];
+    let signer_seeds = &[&auction_seeds[..]];
+    
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.asset_vault.to_account_info(),
+                to: ctx.accounts.creator_asset_account.to_account_info(),
+                authority: auction.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        surplus,
+    )?;
+    
+    emit!(VaultSurplusSwept {
+        auction_id,
+        creator: auction.creator,
+        amount: surplus,
+    });
+    
+    Ok(())
+}
+
+/// Offer ownership of an auction to another wallet (first step)
+pub fn transfer_auction_ownership(
+    ctx: Context<TransferAuctionOwnership>,
+    auction_id: u64,
+    new_creator: Pubkey,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        auction.status != AuctionStatus::AssetsDistributed && auction.status != AuctionStatus::Cancelled,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    require!(
+        auction.pending_creator.is_none(),
+        ShadowProtocolError::OwnershipTransferPending
+    );
+    
+    auction.pending_creator = Some(new_creator);
+    
+    msg!("Ownership transfer of auction {} initiated. New owner: {}", auction_id, new_creator);
+    
+    Ok(())
+}
+
+/// Accept a pending auction ownership transfer (second step)
+pub fn accept_auction_ownership(
+    ctx: Context<AcceptAuctionOwnership>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    
+    require!(
+        auction.pending_creator.is_some(),
+        ShadowProtocolError::NoPendingOwnershipTransfer
+    );
+    
+    let new_creator = auction.pending_creator.unwrap();
+    require!(
+        ctx.accounts.new_creator.key() == new_creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        auction.status != AuctionStatus::AssetsDistributed && auction.status != AuctionStatus::Cancelled,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    auction.creator = new_creator;
+    auction.pending_creator = None;
+    
+    msg!("Ownership transfer of auction {} completed. New owner: {}", auction_id, new_creator);
+    
+    Ok(())
+}
+
+/// Cancel a pending auction ownership transfer
+pub fn cancel_auction_ownership_transfer(
+    ctx: Context<TransferAuctionOwnership>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        auction.pending_creator.is_some(),
+        ShadowProtocolError::NoPendingOwnershipTransfer
+    );
+    
+    auction.pending_creator = None;
+    
+    msg!("Ownership transfer of auction {} cancelled", auction_id);
+    
+    Ok(())
+}
+
+/// Make a sealed-bid auction invite-only, or open it up again with `None`.
+///
+/// Only the hash is stored; the creator hands the code to invitees off-chain.
+pub fn set_invite_hash(
+    ctx: Context<SetInviteHash>,
+    auction_id: u64,
+    invite_hash: Option<[u8; 32]>,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        auction.auction_type == AuctionType::SealedBid,
+        ShadowProtocolError::InvalidAuctionType
+    );
+    
+    // Changing who may bid after bids arrived would be unfair to them
+    require!(
+        auction.status == AuctionStatus::Active && auction.bid_count == 0,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    auction.invite_hash = invite_hash;
+    
+    msg!(
+        "Auction {} is now {}",
+        auction_id,
+        if invite_hash.is_some() { "invite-only" } else { "open" }
+    );
+    
+    Ok(())
+}
+
+/// Attach or clear the price feed settlement is sanity-checked against.
+///
+/// Once set, `execute_settlement` rejects winning amounts more than
+/// `ORACLE_MAX_DEVIATION_FACTOR` away from the oracle value of the asset.
+pub fn set_price_oracle(
+    ctx: Context<SetPriceOracle>,
+    auction_id: u64,
+    price_oracle: Option<PriceOracle>,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        auction.status == AuctionStatus::Active && auction.bid_count == 0,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    if let Some(oracle) = &price_oracle {
+        require!(
+            oracle.asset_decimals == ctx.accounts.asset_mint.decimals,
+            ShadowProtocolError::InvalidPriceFeed
+        );
+    }
+    
+    let features = &mut ctx.accounts.features;
+    features.price_oracle = price_oracle;
+    auction.sync_features(features);
+    
+    msg!(
+        "Auction {} price oracle {}",
+        auction_id,
+        if price_oracle.is_some() { "set" } else { "cleared" }
+    );
+    
+    Ok(())
+}
+
+/// Ask for the proceeds to be swapped into another mint at settlement.
+///
+/// The minimum rate is the slippage bound every settlement swap is held to,
+/// so it cannot change once bidders have committed.
+pub fn set_payout_conversion(
+    ctx: Context<SetAuctionFeature>,
+    auction_id: u64,
+    payout_conversion: Option<PayoutConversion>,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        auction.status == AuctionStatus::Active && auction.bid_count == 0,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    if let Some(conversion) = &payout_conversion {
+        require!(
+            conversion.swap_program != Pubkey::default() && conversion.min_output_rate > 0,
+            ShadowProtocolError::InvalidPayoutConversion
+        );
+        require!(
+            !auction.has_feature(AuctionFeatures::PAYOUT_VESTING),
+            ShadowProtocolError::InvalidPayoutConversion
+        );
+        require!(
+            auction.position_mint.is_none(),
+            ShadowProtocolError::AuctionPositionUnsupported
+        );
+    }
+    
+    let features = &mut ctx.accounts.features;
+    features.payout_conversion = payout_conversion;
+    auction.sync_features(features);
+    
+    msg!(
+        "Auction {} payout {}",
+        auction_id,
+        match &payout_conversion {
+            Some(conversion) => format!("converts to {}", conversion.output_mint),
+            None => "in payment mint".to_string(),
+        }
+    );
+    
+    Ok(())
+}
+
+/// Have the proceeds streamed to the creator over a vesting schedule
+/// instead of paid out at settlement. Not combinable with a payout
+/// conversion.
+pub fn set_payout_vesting(
+    ctx: Context<SetAuctionFeature>,
+    auction_id: u64,
+    payout_vesting: Option<VestingSchedule>,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        auction.status == AuctionStatus::Active && auction.bid_count == 0,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    if let Some(schedule) = &payout_vesting {
+        require!(
+            schedule.duration > 0
+                && (0..=schedule.duration).contains(&schedule.cliff)
+                && !auction.has_feature(AuctionFeatures::PAYOUT_CONVERSION),
+            ShadowProtocolError::InvalidVestingSchedule
+        );
+        require!(
+            auction.position_mint.is_none(),
+            ShadowProtocolError::AuctionPositionUnsupported
+        );
+    }
+    
+    let features = &mut ctx.accounts.features;
+    features.payout_vesting = payout_vesting;
+    auction.sync_features(features);
+    
+    msg!(
+        "Auction {} payout {}",
+        auction_id,
+        if payout_vesting.is_some() { "vests" } else { "paid at settlement" }
+    );
+    
+    Ok(())
+}
+
+/// Register a program `execute_settlement` calls once the asset and payment
+/// have moved, e.g. to stake the proceeds or update game state.
+///
+/// `account_count` of the settlement's remaining accounts are passed on to
+/// the hook. A failing hook fails the settlement, so the hook is fixed
+/// before the first bid for bidders to vet.
+pub fn set_settlement_hook(
+    ctx: Context<SetAuctionFeature>,
+    auction_id: u64,
+    settlement_hook: Option<SettlementHook>,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        auction.status == AuctionStatus::Active && auction.bid_count == 0,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    if let Some(hook) = &settlement_hook {
+        require!(
+            hook.program != Pubkey::default() && hook.program != crate::ID,
+            ShadowProtocolError::InvalidSettlementHook
+        );
+    }
+    
+    let features = &mut ctx.accounts.features;
+    features.settlement_hook = settlement_hook;
+    auction.sync_features(features);
+    
+    msg!(
+        "Auction {} settlement hook {}",
+        auction_id,
+        match &settlement_hook {
+            Some(hook) => hook.program.to_string(),
+            None => "cleared".to_string(),
+        }
+    );
+    
+    Ok(())
+}
+
+/// Replace the encrypted reserve while the auction is still waiting for its
+/// first bid. Once anyone has bid against a reserve it stays fixed.
+pub fn update_reserve_price(
+    ctx: Context<UpdateReservePrice>,
+    auction_id: u64,
+    reserve_price_encrypted: [u8; 32],
+    reserve_price_nonce: u128,
+    reserve_price_commitment: [u8; 32],
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        auction.status == AuctionStatus::Active && auction.bid_count == 0,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    if let Some(sale) = &ctx.accounts.features.lbp {
+        require!(
+            sale.purchases_submitted == 0,
+            ShadowProtocolError::InvalidAuctionStatus
+        );
+    }
+    
+    require!(
+        reserve_price_commitment != [0u8; 32],
+        ShadowProtocolError::InvalidEncryption
+    );
+    
+    auction.reserve_price_encrypted = reserve_price_encrypted;
+    auction.reserve_price_nonce = reserve_price_nonce;
+    auction.reserve_price_commitment = reserve_price_commitment;
+    
+    emit!(ReservePriceUpdated {
+        auction_id,
+        creator: auction.creator,
+        reserve_price_commitment,
+        updated_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Open the reserve price commitment of a settled auction so anyone can
+/// audit whether the settlement honoured the reserve.
+pub fn reveal_reserve_price(
+    ctx: Context<RevealReservePrice>,
+    auction_id: u64,
+    reserve_price: u64,
+    blinding: [u8; 32],
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        auction.status == AuctionStatus::AssetsDistributed,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    require!(
+        auction.revealed_reserve_price.is_none(),
+        ShadowProtocolError::ReserveAlreadyRevealed
+    );
+    
+    let commitment = CryptoUtils::generate_reserve_commitment(
+        reserve_price,
+        blinding,
+        &auction.reserve_price_encrypted,
+        auction.reserve_price_nonce,
+    )?;
+    require!(
+        commitment == auction.reserve_price_commitment,
+        ShadowProtocolError::ReserveCommitmentMismatch
+    );
+    
+    auction.revealed_reserve_price = Some(reserve_price);
+    
+    emit!(ReservePriceRevealed {
+        auction_id,
+        creator: auction.creator,
+        reserve_price,
+        winning_amount: auction.winning_amount,
+        met_reserve: auction.winner.is_some() && auction.winning_amount >= reserve_price,
+        revealed_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(creator_nonce: u64)]
+pub struct CreateSealedAuction<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AuctionAccount::INIT_SPACE,
+        seeds = [AUCTION_SEED, AuctionAccount::address_digest(creator.key, creator_nonce).as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Optional configuration, empty unless given at creation
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AuctionFeatures::INIT_SPACE,
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    /// Shard picked by nonce, as the auction id is only assigned on creation
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(creator_nonce).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + CreatorIndex::INIT_SPACE,
+        seeds = [CREATOR_INDEX_SEED, creator.key().as_ref()],
+        bump
+    )]
+    pub creator_index: Account<'info, CreatorIndex>,
+    
+    pub asset_mint: Account<'info, Mint>,
+    
+    /// Mint the winner pays in
+    #[account(constraint = payment_mint.key() != asset_mint.key() @ ShadowProtocolError::InvalidPaymentMint)]
+    pub payment_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = creator,
+        token::mint = asset_mint,
+        token::authority = auction,
+        seeds = [ASSET_VAULT_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = creator
+    )]
+    pub creator_asset_account: Account<'info, TokenAccount>,
+    
+    /// House to list through; omit to list directly on the protocol
+    #[account(mut)]
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    /// Creator's allowlist entry, for houses that require one
+    pub house_creator: Option<Account<'info, HouseCreator>>,
+    
+    /// CHECK: Instructions sysvar, read to find a fee voucher's ed25519 check
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(creator_nonce: u64)]
+pub struct CreateDutchAuction<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AuctionAccount::INIT_SPACE,
+        seeds = [AUCTION_SEED, AuctionAccount::address_digest(creator.key, creator_nonce).as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Optional configuration, empty unless given at creation
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AuctionFeatures::INIT_SPACE,
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    /// Shard picked by nonce, as the auction id is only assigned on creation
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(creator_nonce).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + CreatorIndex::INIT_SPACE,
+        seeds = [CREATOR_INDEX_SEED, creator.key().as_ref()],
+        bump
+    )]
+    pub creator_index: Account<'info, CreatorIndex>,
+    
+    pub asset_mint: Account<'info, Mint>,
+    
+    /// Mint the winner pays in
+    #[account(constraint = payment_mint.key() != asset_mint.key() @ ShadowProtocolError::InvalidPaymentMint)]
+    pub payment_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = creator,
+        token::mint = asset_mint,
+        token::authority = auction,
+        seeds = [ASSET_VAULT_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = asset_mint,
+        associated_token::authority = creator
+    )]
+    pub creator_asset_account: Account<'info, TokenAccount>,
+    
+    /// House to list through; omit to list directly on the protocol
+    #[account(mut)]
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    /// Creator's allowlist entry, for houses that require one
+    pub house_creator: Option<Account<'info, HouseCreator>>,
+    
+    /// CHECK: Instructions sysvar, read to find a fee voucher's ed25519 check
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ExtendAuction<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct RelistAuction<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [ASSET_VAULT_SEED, auction_id.to_le_bytes().as_ref()],
+        bump,
+        address = auction.asset_vault
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct EndAuction<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = !matches!(
+            auction.auction_type,
+            AuctionType::Dutch | AuctionType::LiquidityBootstrapping
+        ) @ ShadowProtocolError::InvalidAuctionType
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct TransferAuctionOwnership<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct AcceptAuctionOwnership<'info> {
+    pub new_creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ExpireDutchAuction<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = matches!(
+            auction.auction_type,
+            AuctionType::Dutch | AuctionType::LiquidityBootstrapping
+        ) @ ShadowProtocolError::InvalidAuctionType
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Read for a liquidity bootstrapping sale's pending purchases
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        mut,
+        address = auction.asset_vault
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    /// Creator's asset account receiving the unsold asset
+    #[account(
+        mut,
+        token::mint = auction.asset_mint,
+        token::authority = auction.creator
+    )]
+    pub creator_asset_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SweepVaultSurplus<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        address = auction.asset_vault
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    /// Creator's asset account receiving the surplus
+    #[account(
+        mut,
+        token::mint = auction.asset_mint,
+        token::authority = auction.creator
+    )]
+    pub creator_asset_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SetInviteHash<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+}
+
+/// Creator setting one of the auction's `AuctionFeatures` entries
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SetAuctionFeature<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SetPriceOracle<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Holds the oracle
+    #[account(
+        mut,
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(address = auction.asset_mint)]
+    pub asset_mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct UpdateReservePrice<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Read for a liquidity bootstrapping sale's submitted purchases
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct RevealReservePrice<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
 }
\ No newline at end of file
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Attach display metadata to an auction.
+///
+/// Meant to ride in the same transaction as the auction's creation; allowed
+/// until the first bid so a listing cannot be redescribed under bidders.
+pub fn create_auction_metadata(
+    ctx: Context<CreateAuctionMetadata>,
+    auction_id: u64,
+    name: String,
+    symbol: String,
+    uri: String,
+    content_hash: [u8; 32],
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    
+    require!(
+        matches!(auction.status, AuctionStatus::Created | AuctionStatus::Active),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(auction.bid_count == 0, ShadowProtocolError::AuctionHasBids);
+    require!(
+        name.len() <= MAX_METADATA_NAME_LEN
+            && symbol.len() <= MAX_METADATA_SYMBOL_LEN
+            && uri.len() <= MAX_METADATA_URI_LEN,
+        ShadowProtocolError::InvalidAuctionMetadata
+    );
+    
+    let metadata = &mut ctx.accounts.auction_metadata;
+    metadata.auction_id = auction_id;
+    metadata.creator = auction.creator;
+    metadata.name = name;
+    metadata.symbol = symbol;
+    metadata.uri = uri;
+    metadata.content_hash = content_hash;
+    metadata.bump = ctx.bumps.auction_metadata;
+    
+    emit!(AuctionMetadataCreated {
+        auction_id,
+        uri: metadata.uri.clone(),
+        content_hash,
+    });
+    
+    Ok(())
+}
+
+/// Return the metadata account's rent once the auction is finished or its
+/// account has been reclaimed
+pub fn close_auction_metadata(ctx: Context<CloseAuctionMetadata>, _auction_id: u64) -> Result<()> {
+    let auction_info = &ctx.accounts.auction;
+    
+    if auction_info.owner == &crate::ID && !auction_info.data_is_empty() {
+        let auction = AuctionAccount::try_deserialize(&mut &auction_info.try_borrow_data()?[..])?;
+        require!(
+            auction.status == AuctionStatus::AssetsDistributed || auction.status == AuctionStatus::Cancelled,
+            ShadowProtocolError::AccountNotReclaimable
+        );
+    }
+    
+    Ok(())
+}
+
+#[event]
+pub struct AuctionMetadataCreated {
+    pub auction_id: u64,
+    pub uri: String,
+    pub content_hash: [u8; 32],
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CreateAuctionMetadata<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        has_one = creator @ ShadowProtocolError::Unauthorized,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AuctionMetadata::INIT_SPACE,
+        seeds = [AUCTION_METADATA_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub auction_metadata: Account<'info, AuctionMetadata>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CloseAuctionMetadata<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    /// CHECK: The auction the metadata is derived from; may already have been
+    /// closed by `reclaim_storage`
+    pub auction: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator @ ShadowProtocolError::Unauthorized,
+        seeds = [AUCTION_METADATA_SEED, auction.key().as_ref()],
+        bump = auction_metadata.bump
+    )]
+    pub auction_metadata: Account<'info, AuctionMetadata>,
+}
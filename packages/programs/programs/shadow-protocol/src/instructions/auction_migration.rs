@@ -0,0 +1,285 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token::Mint;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// `AuctionAccount` as laid out before its boolean state was packed into
+/// `flags` and its counters narrowed. Only read by `migrate_auction_layout`.
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct LegacyAuctionAccount {
+    pub auction_id: u64,
+    pub creator: Pubkey,
+    pub asset_mint: Pubkey,
+    pub asset_vault: Pubkey,
+    pub asset_amount: u64,
+    pub auction_type: AuctionType,
+    pub status: AuctionStatus,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub minimum_bid: u64,
+    pub bid_increment: u64,
+    pub minimum_price_floor: u64,
+    pub reserve_price_encrypted: [u8; 32],
+    pub reserve_price_nonce: u128,
+    pub reserve_price_commitment: [u8; 32],
+    pub revealed_reserve_price: Option<u64>,
+    pub current_price: u64,
+    pub price_decrease_rate: u64,
+    pub price_curve: PriceCurve,
+    pub bid_count: u64,
+    pub winner: Option<Pubkey>,
+    pub winning_amount: u64,
+    pub settled_at: Option<i64>,
+    pub mpc_verification_hash: Option<[u8; 32]>,
+    pub settlement_authorized: bool,
+    pub mpc_computation_id: Option<[u8; 32]>,
+    pub mxe_cluster: Option<Pubkey>,
+    /// Dropped: written at queue time but never read
+    pub _computation_gas_limit: u64,
+    pub computation_queued_at: Option<i64>,
+    pub pending_creator: Option<Pubkey>,
+    pub settlement_authorized_at: Option<i64>,
+    pub dispute_open: bool,
+    pub creation_deposit: u64,
+    pub timing_mode: TimingMode,
+    pub second_highest_bid: u64,
+    pub reserve_met: bool,
+    pub participating_bids: u64,
+    pub private_winner: bool,
+    pub encrypted_winner: Option<PrivateWinner>,
+    pub invite_hash: Option<[u8; 32]>,
+    pub compliance_program: Option<Pubkey>,
+    pub price_oracle: Option<PriceOracle>,
+    pub payout_conversion: Option<PayoutConversion>,
+    pub collateral_config: Option<CollateralConfig>,
+    pub yield_config: Option<YieldConfig>,
+    pub open_yield_positions: u16,
+    pub payout_vesting: Option<VestingSchedule>,
+    pub lbp: Option<LbpSale>,
+    pub auction_house: Option<HouseTerms>,
+    pub fee_override: Option<u16>,
+    pub escrowed_proceeds: u64,
+    pub proceeds_claim: Option<ProceedsClaim>,
+    pub unsold: bool,
+    pub bump: u8,
+    pub reserved: [u8; 7],
+}
+
+impl LegacyAuctionAccount {
+    /// Optional configuration the legacy layout held inline
+    fn features(&self, auction: Pubkey, bump: u8) -> AuctionFeatures {
+        AuctionFeatures {
+            auction,
+            price_oracle: self.price_oracle,
+            payout_conversion: self.payout_conversion,
+            collateral_config: self.collateral_config,
+            yield_config: self.yield_config,
+            payout_vesting: self.payout_vesting,
+            lbp: self.lbp,
+            tranches: None,
+            settlement_hook: None,
+            bump,
+        }
+    }
+    
+    fn into_current(self, payment_mint: Pubkey) -> Result<AuctionAccount> {
+        let mut flags = 0;
+        if self.dispute_open {
+            flags |= AuctionAccount::DISPUTE_OPEN;
+        }
+        if self.reserve_met {
+            flags |= AuctionAccount::RESERVE_MET;
+        }
+        if self.private_winner {
+            flags |= AuctionAccount::PRIVATE_WINNER;
+        }
+        if self.unsold {
+            flags |= AuctionAccount::UNSOLD;
+        }
+        if !self.status.is_terminal() {
+            flags |= AuctionAccount::VAULT_LOCKED;
+        }
+        // Legacy auctions stay at the address their id was seeded into
+        flags |= AuctionAccount::LEGACY_ADDRESS;
+        let mut address_seed = [0u8; 32];
+        address_seed[..8].copy_from_slice(&self.auction_id.to_le_bytes());
+    
+        // The legacy layout did not record the vault's deposit; it is whatever
+        // of the asset has not yet been paid out
+        let vault_balance = match self.status {
+            AuctionStatus::Created
+            | AuctionStatus::Active
+            | AuctionStatus::Ended
+            | AuctionStatus::SettlementAuthorized => {
+                let sold = self.lbp.map_or(0, |sale| sale.tokens_sold);
+                self.asset_amount.saturating_sub(sold)
+            }
+            AuctionStatus::AssetsDistributed | AuctionStatus::Cancelled => 0,
+        };
+    
+        // The authorization flag always moved together with its timestamp
+        require!(
+            self.settlement_authorized == self.settlement_authorized_at.is_some(),
+            ShadowProtocolError::InvalidAuctionLayout
+        );
+    
+        Ok(AuctionAccount {
+            auction_id: self.auction_id,
+            address_seed,
+            creator: self.creator,
+            asset_mint: self.asset_mint,
+            asset_vault: self.asset_vault,
+            asset_amount: self.asset_amount,
+            payment_mint,
+            vault_balance,
+            auction_type: self.auction_type,
+            status: self.status,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            bid_cutoff: 0,
+            minimum_bid: self.minimum_bid,
+            bid_increment: self.bid_increment,
+            minimum_price_floor: self.minimum_price_floor,
+            reserve_price_encrypted: self.reserve_price_encrypted,
+            reserve_price_nonce: self.reserve_price_nonce,
+            reserve_price_commitment: self.reserve_price_commitment,
+            revealed_reserve_price: self.revealed_reserve_price,
+            current_price: self.current_price,
+            price_decrease_rate: self.price_decrease_rate,
+            price_curve: self.price_curve,
+            bid_count: u32::try_from(self.bid_count)
+                .map_err(|_| ShadowProtocolError::InvalidAuctionLayout)?,
+            max_bids: MAX_BIDS_PER_AUCTION as u32,
+            winner: self.winner,
+            winning_amount: self.winning_amount,
+            settled_at: self.settled_at,
+            mpc_verification_hash: self.mpc_verification_hash,
+            mpc_computation_id: self.mpc_computation_id,
+            mpc_comp_def_offset: None,
+            mxe_cluster: self.mxe_cluster,
+            computation_queued_at: self.computation_queued_at,
+            pending_creator: self.pending_creator,
+            settlement_authorized_at: self.settlement_authorized_at,
+            creation_deposit: self.creation_deposit,
+            timing_mode: self.timing_mode,
+            second_highest_bid: self.second_highest_bid,
+            participating_bids: u32::try_from(self.participating_bids)
+                .map_err(|_| ShadowProtocolError::InvalidAuctionLayout)?,
+            encrypted_winner: self.encrypted_winner,
+            invite_hash: self.invite_hash,
+            compliance_program: self.compliance_program,
+            min_collateral_ratio: DEFAULT_MIN_COLLATERAL_RATIO,
+            bid_collateral_bps: 0,
+            open_yield_positions: self.open_yield_positions,
+            auction_house: self.auction_house,
+            fee_override: self.fee_override,
+            escrowed_proceeds: self.escrowed_proceeds,
+            proceeds_claim: self.proceeds_claim,
+            settlement_authorizer: None,
+            position_mint: None,
+            settlement_attempt: 0,
+            flags,
+            features: 0,
+            bump: self.bump,
+            reserved: self.reserved,
+        })
+    }
+}
+
+/// Rewrite an auction created under the legacy layout into the compact one.
+///
+/// The account shrinks in place and the rent it no longer needs goes back to
+/// the creator who paid it, less that of the `AuctionFeatures` account the
+/// optional configuration moves into. The legacy layout let the winner pay in any
+/// mint, so the creator signs and fixes the payment mint from here on.
+/// Legacy auctions cannot be loaded by any other instruction until migrated.
+pub fn migrate_auction_layout(ctx: Context<MigrateAuctionLayout>, auction_id: u64) -> Result<()> {
+    let auction_info = ctx.accounts.auction.to_account_info();
+    
+    let legacy = {
+        let data = auction_info.try_borrow_data()?;
+        require!(
+            data.len() == 8 + LegacyAuctionAccount::INIT_SPACE
+                && data[..8] == *AuctionAccount::DISCRIMINATOR,
+            ShadowProtocolError::InvalidAuctionLayout
+        );
+        LegacyAuctionAccount::deserialize(&mut &data[8..])?
+    };
+    require!(legacy.auction_id == auction_id, ShadowProtocolError::InvalidAuctionId);
+    require!(
+        legacy.creator == ctx.accounts.creator.key(),
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        ctx.accounts.payment_mint.key() != legacy.asset_mint,
+        ShadowProtocolError::InvalidPaymentMint
+    );
+    
+    let features = legacy.features(auction_info.key(), ctx.bumps.features);
+    let mut auction = legacy.into_current(ctx.accounts.payment_mint.key())?;
+    auction.sync_features(&features);
+    ctx.accounts.features.set_inner(features);
+    let new_len = 8 + AuctionAccount::INIT_SPACE;
+    auction_info.resize(new_len)?;
+    {
+        let mut data = auction_info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data[8..];
+        auction.serialize(&mut writer)?;
+    }
+    
+    // The creation deposit stays behind with the rent the account still needs
+    let retained = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_add(auction.creation_deposit);
+    let excess = auction_info.lamports().saturating_sub(retained);
+    if excess > 0 {
+        auction_info.sub_lamports(excess)?;
+        ctx.accounts.creator.add_lamports(excess)?;
+    }
+    
+    emit!(AuctionLayoutMigrated {
+        auction_id,
+        reclaimed_rent: excess,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct MigrateAuctionLayout<'info> {
+    /// CHECK: still in the legacy layout, so validated and decoded by hand
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub auction: UncheckedAccount<'info>,
+    
+    /// Takes over the optional configuration the legacy layout held inline
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AuctionFeatures::INIT_SPACE,
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    /// Auction's creator, who receives the freed rent
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    /// Mint the auction will be paid in
+    pub payment_mint: Account<'info, Mint>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct AuctionLayoutMigrated {
+    pub auction_id: u64,
+    pub reclaimed_rent: u64,
+}
@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Token, TokenAccount, Mint, MintTo, mint_to, SetAuthority, set_authority};
+use anchor_spl::token::spl_token::instruction::AuthorityType;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Mint the auction's position token to the creator.
+///
+/// The position is a single token of a mint no one can mint more of, and
+/// whoever holds it when `execute_settlement` runs is paid the proceeds. It
+/// can be sold or posted as collateral while the auction is in flight.
+/// Positions need the proceeds paid out in one settlement, so auctions that
+/// convert or stream their proceeds, or sell in tranches, lots or quantities,
+/// cannot carry one; nor can they be settled through `claim_asset`.
+pub fn mint_auction_position(
+    ctx: Context<MintAuctionPosition>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        !auction.status.is_terminal() && !auction.has_flag(AuctionAccount::SETTLING),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        !auction.has_feature(
+            AuctionFeatures::PAYOUT_CONVERSION
+                | AuctionFeatures::PAYOUT_VESTING
+                | AuctionFeatures::TRANCHES
+                | AuctionFeatures::LBP
+        )
+            && !auction.has_flag(AuctionAccount::QUANTITY_BIDS),
+        ShadowProtocolError::AuctionPositionUnsupported
+    );
+    
+    let mint = ctx.accounts.position_mint.key();
+    auction.position_mint = Some(mint);
+    
+    let auction_seeds = &[
+        AUCTION_SEED,
+        auction.pda_seed(),
+        &[auction.bump],
+    ];
+    let signer_seeds = &[&auction_seeds[..]];
+    
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.position_mint.to_account_info(),
+                to: ctx.accounts.creator_position_account.to_account_info(),
+                authority: auction.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        1,
+    )?;
+    
+    // Fix the supply at the one token just minted
+    set_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: auction.to_account_info(),
+                account_or_mint: ctx.accounts.position_mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+    
+    emit!(AuctionPositionMinted {
+        auction_id,
+        mint,
+        creator: auction.creator,
+    });
+    
+    Ok(())
+}
+
+/// Wallet settlement pays the proceeds to: the holder of the auction's
+/// position if it has one, otherwise the creator
+pub(crate) fn proceeds_recipient(
+    auction: &AuctionAccount,
+    position_account: Option<&Account<TokenAccount>>,
+) -> Result<Pubkey> {
+    let Some(mint) = auction.position_mint else {
+        return Ok(auction.creator);
+    };
+    
+    let position = position_account.ok_or(ShadowProtocolError::InvalidAuctionPosition)?;
+    require!(
+        position.mint == mint && position.amount == 1,
+        ShadowProtocolError::InvalidAuctionPosition
+    );
+    
+    Ok(position.owner)
+}
+
+#[event]
+pub struct AuctionPositionMinted {
+    pub auction_id: u64,
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct MintAuctionPosition<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// One mint per auction, so a position can only be minted once
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = 0,
+        mint::authority = auction,
+        seeds = [AUCTION_POSITION_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub position_mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = position_mint,
+        associated_token::authority = creator
+    )]
+    pub creator_position_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token::state::{Account as SplTokenAccount, AccountState};
+    
+    fn token_account_data(mint: Pubkey, owner: Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; SplTokenAccount::LEN];
+        SplTokenAccount {
+            mint,
+            owner,
+            amount,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        data
+    }
+    
+    fn positioned_auction(creator: Pubkey, position_mint: Pubkey) -> AuctionAccount {
+        let mut auction = AuctionAccount::deserialize(&mut &vec![0u8; AuctionAccount::INIT_SPACE][..]).unwrap();
+        auction.creator = creator;
+        auction.position_mint = Some(position_mint);
+        auction
+    }
+    
+    #[test]
+    fn transferred_position_receives_the_payout_stream() {
+        let creator = Pubkey::new_unique();
+        let buyer = Pubkey::new_unique();
+        let position_mint = Pubkey::new_unique();
+        let auction = positioned_auction(creator, position_mint);
+        
+        // The creator sold the position to `buyer` before settlement
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = token_account_data(position_mint, buyer, 1);
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &anchor_spl::token::ID, false, 0);
+        let position = Account::<TokenAccount>::try_from(&info).unwrap();
+        
+        let payee = proceeds_recipient(&auction, Some(&position)).unwrap();
+        assert_eq!(payee, buyer);
+        
+        let mut stream = PayoutStream::deserialize(&mut &vec![0u8; PayoutStream::INIT_SPACE][..]).unwrap();
+        let schedule = VestingSchedule { cliff: 0, duration: 100 };
+        stream.open(7, payee, Pubkey::new_unique(), 1_000, 50, schedule);
+        assert_eq!(stream.recipient, buyer);
+        assert_eq!(stream.claimed, 0);
+    }
+    
+    #[test]
+    fn position_must_hold_the_auction_token() {
+        let creator = Pubkey::new_unique();
+        let position_mint = Pubkey::new_unique();
+        let auction = positioned_auction(creator, position_mint);
+        
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = token_account_data(Pubkey::new_unique(), creator, 1);
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &anchor_spl::token::ID, false, 0);
+        let other_mint = Account::<TokenAccount>::try_from(&info).unwrap();
+        
+        assert!(proceeds_recipient(&auction, Some(&other_mint)).is_err());
+        assert!(proceeds_recipient(&auction, None).is_err());
+    }
+}
\ No newline at end of file
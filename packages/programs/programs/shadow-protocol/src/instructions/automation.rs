@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::arcium_callback::{chunk_count, queue_bid_chunk, EncryptedBidData};
+use crate::instructions::cluster_failover::fail_over_computation;
+use crate::instructions::auction_management::mark_auction_ended;
+
+/// Advance a sealed-bid auction by one step of its settlement pipeline.
+///
+/// Permissionless and guarded only by time and on-chain state, so any
+/// automation thread or bystander can drive an auction without a trusted
+/// keeper:
+/// - once bidding has closed, the auction is ended
+/// - once ended, its bids are queued for settlement on the first healthy
+///   cluster, with the gas its circuit's schedule requires
+/// - once the queued computation has timed out, it fails over to the next
+///   healthy cluster
+///
+/// Remaining accounts: for the queue step, every bid account of the auction
+/// in strictly ascending bidder order. Only auctions that settle in a single
+/// circuit round can be queued this way. The result then authorizes
+/// settlement through the callback; the winner and creator settle with
+/// `claim_asset` and `claim_proceeds`, and unsold auctions with
+/// `finalize_unsold`.
+pub fn permissionless_tick<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PermissionlessTick<'info>>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.protocol_state.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        auction.auction_type == AuctionType::SealedBid,
+        ShadowProtocolError::InvalidAuctionType
+    );
+    
+    let stage = match auction.status {
+        AuctionStatus::Active => {
+            require!(auction.has_ended(&clock), ShadowProtocolError::AuctionNotEnded);
+            mark_auction_ended(auction, &clock)?;
+            TickStage::Ended
+        }
+        AuctionStatus::Ended if auction.mpc_computation_id.is_none() => {
+            require!(
+                auction.bid_count > 0 && !auction.has_flag(AuctionAccount::UNSOLD),
+                ShadowProtocolError::NothingToAdvance
+            );
+            require!(
+                chunk_count(auction.bid_count as u64) == 1,
+                ShadowProtocolError::InvalidBidCount
+            );
+            
+            let encrypted_bids = load_bids(auction, ctx.remaining_accounts)?;
+            let registry = &ctx.accounts.cluster_registry;
+            let gas_schedule = ctx.accounts.gas_schedule
+                .as_ref()
+                .ok_or(ShadowProtocolError::InvalidGasSchedule)?;
+            let gas_limit = gas_schedule
+                .required_gas(auction.bid_count)
+                .ok_or(ShadowProtocolError::InsufficientGasLimit)?;
+            let mxe_cluster = registry
+                .next_healthy(None)
+                .ok_or(ShadowProtocolError::NoHealthyCluster)?;
+            
+            queue_bid_chunk(auction, gas_schedule, registry, &encrypted_bids, mxe_cluster, gas_limit, 0)?;
+            TickStage::ComputationQueued
+        }
+        AuctionStatus::Ended => {
+            fail_over_computation(auction, &ctx.accounts.cluster_registry)?;
+            TickStage::ComputationRetried
+        }
+        _ => return err!(ShadowProtocolError::NothingToAdvance),
+    };
+    
+    emit!(AuctionTicked {
+        auction_id,
+        stage,
+        caller: ctx.accounts.caller.key(),
+        ticked_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Read the circuit inputs of every bid on `auction` straight from its bid
+/// accounts, so a permissionless caller cannot alter or omit any
+fn load_bids(auction: &AuctionAccount, bid_accounts: &[AccountInfo]) -> Result<Vec<EncryptedBidData>> {
+    require!(
+        bid_accounts.len() == auction.bid_count as usize,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    let auction_id_bytes = auction.auction_id.to_le_bytes();
+    let mut bids: Vec<EncryptedBidData> = Vec::with_capacity(bid_accounts.len());
+    for info in bid_accounts {
+        require!(info.owner == &crate::ID, ShadowProtocolError::InvalidRemainingAccounts);
+        let bid = BidAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        
+        let (expected_bid, _) = Pubkey::find_program_address(
+            &[BID_SEED, auction_id_bytes.as_ref(), bid.bidder.as_ref()],
+            &crate::ID,
+        );
+        require!(
+            info.key() == expected_bid,
+            ShadowProtocolError::InvalidRemainingAccounts
+        );
+        // Ascending order rules out passing the same bid twice
+        require!(
+            bids.last().map_or(true, |previous| previous.bidder < bid.bidder),
+            ShadowProtocolError::InvalidRemainingAccounts
+        );
+        
+        bids.push(EncryptedBidData {
+            bidder: bid.bidder,
+            encrypted_amount: bid.amount_encrypted,
+            nonce: bid.nonce.to_le_bytes(),
+            public_key: bid.encryption_public_key,
+            collateral_amount: bid.collateral_amount,
+            timestamp: bid.timestamp,
+        });
+    }
+    
+    Ok(bids)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TickStage {
+    Ended,
+    ComputationQueued,
+    ComputationRetried,
+}
+
+#[event]
+pub struct AuctionTicked {
+    pub auction_id: u64,
+    pub stage: TickStage,
+    pub caller: Pubkey,
+    pub ticked_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct PermissionlessTick<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [CLUSTER_REGISTRY_SEED],
+        bump = cluster_registry.bump
+    )]
+    pub cluster_registry: Account<'info, ClusterRegistry>,
+    
+    /// Gas schedule of the circuit the auction's bids fit; required for the
+    /// queue step
+    #[account(
+        seeds = [GAS_SCHEDULE_SEED, gas_schedule.comp_def_offset.to_le_bytes().as_ref()],
+        bump = gas_schedule.bump
+    )]
+    pub gas_schedule: Option<Account<'info, GasSchedule>>,
+}
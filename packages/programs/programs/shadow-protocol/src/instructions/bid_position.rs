@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Token, TokenAccount, Mint, MintTo, mint_to, SetAuthority, set_authority, ID as TOKEN_PROGRAM_ID};
+use anchor_spl::token::spl_token::instruction::AuthorityType;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Mint a position token for a submitted bid to its bidder.
+///
+/// The position is a single token of a mint no one can mint more of. Whoever
+/// holds it stands in for the bidder: if the bid wins they pay and take
+/// delivery, through `execute_settlement` or `claim_asset`, and they reclaim
+/// the collateral through `refund_collateral` or `refund_voided_collateral`.
+/// The encrypted bid itself stays as submitted. Bids drawing on a
+/// cross-auction budget cannot carry a position, since the budget stays
+/// with the bidder.
+pub fn mint_bid_position(
+    ctx: Context<MintBidPosition>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let bid = &ctx.accounts.bid;
+    
+    require!(
+        !auction.status.is_terminal() && !auction.has_flag(AuctionAccount::SETTLING),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(bid.budget.is_none(), ShadowProtocolError::BidPositionUnsupported);
+    
+    let auction_id_bytes = auction_id.to_le_bytes();
+    let bid_seeds = &[
+        BID_SEED,
+        auction_id_bytes.as_ref(),
+        bid.bidder.as_ref(),
+        &[bid.bump],
+    ];
+    let signer_seeds = &[&bid_seeds[..]];
+    
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.position_mint.to_account_info(),
+                to: ctx.accounts.bidder_position_account.to_account_info(),
+                authority: bid.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        1,
+    )?;
+    
+    // Fix the supply at the one token just minted
+    set_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            SetAuthority {
+                current_authority: bid.to_account_info(),
+                account_or_mint: ctx.accounts.position_mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+    
+    emit!(BidPositionMinted {
+        auction_id,
+        bidder: bid.bidder,
+        mint: ctx.accounts.position_mint.key(),
+    });
+    
+    Ok(())
+}
+
+/// Wallet standing in for `bidder`: the holder of their bid's position if
+/// one was minted, otherwise the bidder. `position_mint` is the bid's
+/// position mint PDA, which is uninitialized when there is no position.
+pub(crate) fn bid_holder(
+    bidder: Pubkey,
+    position_mint: &AccountInfo,
+    position_account: Option<&Account<TokenAccount>>,
+) -> Result<Pubkey> {
+    if position_mint.owner != &TOKEN_PROGRAM_ID || position_mint.data_is_empty() {
+        return Ok(bidder);
+    }
+    
+    let position = position_account.ok_or(ShadowProtocolError::InvalidBidPosition)?;
+    require!(
+        position.mint == position_mint.key() && position.amount == 1,
+        ShadowProtocolError::InvalidBidPosition
+    );
+    
+    Ok(position.owner)
+}
+
+/// Check `account` may receive the bid's collateral refund: the account the
+/// collateral came from, or one of the position holder's in the same mint
+pub(crate) fn require_refund_account(
+    bid: &BidAccount,
+    holder: Pubkey,
+    account: &Account<TokenAccount>,
+    collateral_mint: Pubkey,
+) -> Result<()> {
+    let ok = if holder == bid.bidder {
+        account.key() == bid.collateral_account
+    } else {
+        account.owner == holder && account.mint == collateral_mint
+    };
+    require!(ok, ShadowProtocolError::InvalidTokenAccount);
+    Ok(())
+}
+
+#[event]
+pub struct BidPositionMinted {
+    pub auction_id: u64,
+    pub bidder: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct MintBidPosition<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
+    /// One mint per bid, so a position can only be minted once
+    #[account(
+        init,
+        payer = bidder,
+        mint::decimals = 0,
+        mint::authority = bid,
+        seeds = [BID_POSITION_SEED, auction_id.to_le_bytes().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub position_mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        associated_token::mint = position_mint,
+        associated_token::authority = bidder
+    )]
+    pub bidder_position_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
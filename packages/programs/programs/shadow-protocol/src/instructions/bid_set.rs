@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::crypto::CryptoUtils;
+
+/// Commit an ended sealed auction's bids to a Merkle root, so the exact
+/// input set handed to the MPC can be audited afterwards.
+///
+/// Permissionless and incremental: each call appends the bid accounts passed
+/// as remaining accounts, in ascending bidder order continuing from the last
+/// call. The root is stored once every accepted bid has been appended.
+pub fn finalize_bid_set<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FinalizeBidSet<'info>>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let snapshot = &mut ctx.accounts.bid_set;
+    let clock = Clock::get()?;
+    
+    require!(
+        auction.auction_type == AuctionType::SealedBid,
+        ShadowProtocolError::InvalidAuctionType
+    );
+    require!(
+        auction.status == AuctionStatus::Ended ||
+        (auction.status == AuctionStatus::Active && auction.has_ended(&clock)),
+        ShadowProtocolError::AuctionNotEnded
+    );
+    require!(snapshot.root.is_none(), ShadowProtocolError::BidSetFinalized);
+    
+    snapshot.auction_id = auction_id;
+    snapshot.bump = ctx.bumps.bid_set;
+    
+    for info in ctx.remaining_accounts {
+        let bid = Account::<BidAccount>::try_from(info)?;
+        let (expected_bid, _) = Pubkey::find_program_address(
+            &[BID_SEED, auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+            &crate::ID,
+        );
+        require!(
+            info.key() == expected_bid,
+            ShadowProtocolError::InvalidRemainingAccounts
+        );
+        // Strict ordering keeps the leaf order canonical and rules out repeats
+        require!(
+            snapshot.leaf_count == 0 || bid.bidder > snapshot.last_bidder,
+            ShadowProtocolError::InvalidBidSetOrder
+        );
+        require!(
+            snapshot.leaf_count < auction.bid_count,
+            ShadowProtocolError::InvalidBidCount
+        );
+        
+        snapshot.append(CryptoUtils::hash_bid_leaf(bid.bidder, &bid.amount_encrypted, bid.nonce));
+        snapshot.last_bidder = bid.bidder;
+    }
+    
+    if snapshot.leaf_count == auction.bid_count {
+        let root = snapshot.compute_root();
+        snapshot.root = Some(root);
+        snapshot.finalized_at = Some(clock.unix_timestamp);
+        
+        emit!(BidSetFinalized {
+            auction_id,
+            root,
+            bid_count: snapshot.leaf_count,
+            finalized_at: clock.unix_timestamp,
+        });
+    }
+    
+    Ok(())
+}
+
+/// Prove on-chain that a bid was part of its auction's committed bid set.
+///
+/// `leaf_index` is the bid's position in the set (its rank by bidder) and
+/// `proof` the BID_SET_TREE_DEPTH sibling hashes from the leaf up. A successful proof is
+/// emitted so a bidder disputing a dropped bid can point to it.
+pub fn prove_bid_included(
+    ctx: Context<ProveBidIncluded>,
+    auction_id: u64,
+    leaf_index: u32,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let bid = &ctx.accounts.bid;
+    let snapshot = &ctx.accounts.bid_set;
+    
+    require!(snapshot.root.is_some(), ShadowProtocolError::BidSetNotFinalized);
+    let proof: [[u8; 32]; BID_SET_TREE_DEPTH] = proof
+        .try_into()
+        .map_err(|_| ShadowProtocolError::InvalidInclusionProof)?;
+    
+    let leaf = CryptoUtils::hash_bid_leaf(bid.bidder, &bid.amount_encrypted, bid.nonce);
+    require!(
+        snapshot.verify_inclusion(leaf, leaf_index, &proof),
+        ShadowProtocolError::InvalidInclusionProof
+    );
+    
+    emit!(BidInclusionProven {
+        auction_id,
+        bidder: bid.bidder,
+        leaf_index,
+        root: snapshot.root.unwrap_or_default(),
+        proven_at: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct BidSetFinalized {
+    pub auction_id: u64,
+    pub root: [u8; 32],
+    pub bid_count: u32,
+    pub finalized_at: i64,
+}
+
+#[event]
+pub struct BidInclusionProven {
+    pub auction_id: u64,
+    pub bidder: Pubkey,
+    pub leaf_index: u32,
+    pub root: [u8; 32],
+    pub proven_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct FinalizeBidSet<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + BidSetSnapshot::INIT_SPACE,
+        seeds = [BID_SET_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub bid_set: Account<'info, BidSetSnapshot>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ProveBidIncluded<'info> {
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
+    #[account(
+        seeds = [BID_SET_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = bid_set.bump
+    )]
+    pub bid_set: Account<'info, BidSetSnapshot>,
+}
@@ -1,56 +1,137 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::token::{Token, TokenAccount, Mint, Transfer, transfer};
+use anchor_spl::token::spl_token::native_mint;
 use crate::state::*;
 use crate::error::ShadowProtocolError;
 use crate::crypto::CryptoUtils;
+use crate::instructions::arcium_callback::{generate_dutch_claim_id, DutchClaimQueued};
+use crate::instructions::compliance::check_compliance;
+use crate::instructions::deny_list::require_not_denied;
+use crate::instructions::auction_house::require_house_open;
+use crate::instructions::budget_bidding::link_bid_to_budget;
 
 /// Calculate current Dutch auction price based on time progression
 fn calculate_dutch_auction_price(auction: &AuctionAccount) -> Result<u64> {
     let clock = Clock::get()?;
-    let current_time = clock.unix_timestamp;
-    let elapsed_time = current_time.saturating_sub(auction.start_time);
+    calculate_dutch_price_at(auction, auction.now(&clock))
+}
+
+/// Calculate the Dutch auction price at a given time in the auction's timing mode
+pub(crate) fn calculate_dutch_price_at(auction: &AuctionAccount, timestamp: i64) -> Result<u64> {
+    let elapsed_time = timestamp.saturating_sub(auction.start_time);
     
     // Ensure elapsed time is non-negative
     let elapsed_seconds = elapsed_time.max(0) as u64;
-    
-    // Calculate price decrease based on time (price_decrease_rate is per second)
-    let price_decrease = elapsed_seconds
-        .checked_mul(auction.price_decrease_rate)
-        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    let starting_price = auction.current_price;
+    
+    let decayed_price = match auction.price_curve {
+        PriceCurve::Linear => {
+            // Calculate price decrease based on time (price_decrease_rate is per second)
+            let price_decrease = elapsed_seconds
+                .checked_mul(auction.price_decrease_rate)
+                .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+            starting_price.saturating_sub(price_decrease)
+        }
+        PriceCurve::Exponential { half_life } => {
+            // Halve once per full half-life, interpolate linearly within the current one
+            let halvings = elapsed_seconds / half_life;
+            if halvings >= u64::BITS as u64 {
+                0
+            } else {
+                let upper = starting_price >> halvings;
+                let lower = upper >> 1;
+                let progress = (upper - lower) as u128 * (elapsed_seconds % half_life) as u128
+                    / half_life as u128;
+                upper - progress as u64
+            }
+        }
+        PriceCurve::Stepwise { step_amount, step_interval } => {
+            let steps = elapsed_seconds / step_interval;
+            starting_price.saturating_sub(steps.saturating_mul(step_amount))
+        }
+    };
     
     // Calculate current price with minimum floor enforcement
-    let current_price = auction.current_price
-        .saturating_sub(price_decrease)
-        .max(auction.minimum_price_floor);
+    let current_price = decayed_price.max(auction.minimum_price_floor);
     
     Ok(current_price)
 }
 
-pub fn submit_encrypted_bid(
-    ctx: Context<SubmitBid>,
+/// View-style instruction returning the current Dutch auction price.
+///
+/// The price is set as the instruction return data, so clients can simulate
+/// this instead of re-implementing the decay formula.
+pub fn get_current_dutch_price(
+    ctx: Context<GetCurrentDutchPrice>,
+    _auction_id: u64,
+) -> Result<u64> {
+    calculate_dutch_auction_price(&ctx.accounts.auction)
+}
+
+/// Encrypted bid amount and the collateral backing it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SubmitBidParams {
+    pub bid_amount_encrypted: [u8; 32],
+    pub public_key: [u8; 32],
+    pub nonce: u128,
+    pub collateral_amount: u64,
+    pub computation_offset: u64,
+    pub invite_code: Option<[u8; 32]>,
+}
+
+pub fn submit_encrypted_bid<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SubmitBid<'info>>,
     auction_id: u64,
-    bid_amount_encrypted: [u8; 32],
-    public_key: [u8; 32],
-    nonce: u128,
-    collateral_amount: u64,
-    computation_offset: u64,
+    params: SubmitBidParams,
 ) -> Result<()> {
+    let SubmitBidParams {
+        bid_amount_encrypted,
+        public_key,
+        nonce,
+        collateral_amount,
+        computation_offset: _,
+        invite_code,
+    } = params;
+    
     let auction = &mut ctx.accounts.auction;
     let protocol = &ctx.accounts.protocol_state;
     let clock = Clock::get()?;
     
     require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require_not_denied(&ctx.accounts.bidder_deny_entry, DenyListEntry::BIDDING)?;
+    require_house_open(auction, ctx.accounts.auction_house.as_ref())?;
     
     require!(
         auction.status == AuctionStatus::Active,
         ShadowProtocolError::InvalidAuctionStatus
     );
-    
     require!(
-        collateral_amount >= auction.minimum_bid,
-        ShadowProtocolError::InsufficientCollateral
+        !auction.has_flag(AuctionAccount::QUANTITY_BIDS),
+        ShadowProtocolError::QuantityBidsEnabled
     );
     
+    if let Some(invite_hash) = auction.invite_hash {
+        let invite_code = invite_code.ok_or(ShadowProtocolError::InvalidInviteCode)?;
+        require!(
+            CryptoUtils::hash_invite_code(auction_id, &invite_code)? == invite_hash,
+            ShadowProtocolError::InvalidInviteCode
+        );
+    }
+    
+    // Collateral is either lamports in the SOL escrow or tokens in the bid escrow
+    let collateral_mint = match (&ctx.accounts.sol_escrow, &ctx.accounts.collateral_mint) {
+        (Some(_), None) => native_mint::ID,
+        (None, Some(mint)) => mint.key(),
+        _ => return err!(ShadowProtocolError::InvalidCollateralEscrow),
+    };
+    
+    ctx.accounts.features.require_collateral(
+        collateral_mint,
+        collateral_amount,
+        auction.sealed_collateral_floor()?,
+    )?;
+    
     // Validate collateral amount is reasonable (prevent overflow attacks)
     require!(
         collateral_amount <= u64::MAX / 2,
@@ -58,31 +139,47 @@ pub fn submit_encrypted_bid(
     );
     
     // Validate bidder has sufficient collateral
-    require!(
-        ctx.accounts.bidder_collateral_account.amount >= collateral_amount,
-        ShadowProtocolError::InsufficientFunds
-    );
+    match &ctx.accounts.bidder_collateral_account {
+        Some(bidder_collateral_account) => require!(
+            bidder_collateral_account.amount >= collateral_amount,
+            ShadowProtocolError::InsufficientFunds
+        ),
+        None => require!(
+            // The escrow holds no data, so it only exists while rent-exempt
+            collateral_amount >= Rent::get()?.minimum_balance(0)
+                && ctx.accounts.bidder.lamports() >= collateral_amount,
+            ShadowProtocolError::InsufficientFunds
+        ),
+    }
     
     require!(
-        clock.unix_timestamp < auction.end_time,
+        !auction.bidding_closed(&clock),
         ShadowProtocolError::AuctionEnded
     );
     
     require!(
-        auction.bid_count < MAX_BIDS_PER_AUCTION as u64,
+        auction.bid_count < auction.max_bids,
         ShadowProtocolError::MaxBidsExceeded
     );
     
+    check_compliance(
+        auction.compliance_program,
+        ctx.accounts.compliance_program.as_ref().map(|program| program.as_ref()),
+        &ctx.accounts.bidder.to_account_info(),
+        ctx.remaining_accounts,
+        collateral_amount,
+        collateral_mint,
+    )?;
+    
     // Validate encryption parameters
     CryptoUtils::validate_encrypted_bid(
         &bid_amount_encrypted,
         &public_key,
         nonce,
-        auction.minimum_bid,
     )?;
     
     // Verify encryption key is properly derived
-    let is_valid_key = CryptoUtils::verify_encryption_key(
+    CryptoUtils::require_encryption_key(
         public_key,
         auction_id,
         ctx.accounts.bidder.key(),
@@ -90,44 +187,70 @@ pub fn submit_encrypted_bid(
         nonce,
     )?;
     
-    require!(
-        is_valid_key,
-        ShadowProtocolError::InvalidEncryption
-    );
-    
     let bid = &mut ctx.accounts.bid;
     bid.auction_id = auction_id;
+    bid.auction = auction.key();
     bid.bidder = ctx.accounts.bidder.key();
     bid.amount_encrypted = bid_amount_encrypted;
     bid.encryption_public_key = public_key;
     bid.nonce = nonce;
     bid.timestamp = clock.unix_timestamp;
     bid.collateral_amount = collateral_amount;
-    bid.collateral_account = ctx.accounts.bidder_collateral_account.key();
     bid.is_winner = false;
+    bid.budget = None;
     bid.bump = ctx.bumps.bid;
     
-    // Transfer collateral to bid escrow
-    transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.bidder_collateral_account.to_account_info(),
-                to: ctx.accounts.bid_escrow.to_account_info(),
-                authority: ctx.accounts.bidder.to_account_info(),
-            },
-        ),
-        collateral_amount,
-    )?;
+    // A bid drawing on a shared budget joins it as it is placed
+    if let Some(budget) = ctx.accounts.budget.as_mut() {
+        link_bid_to_budget(budget, bid, auction)?;
+    }
+    
+    match (&ctx.accounts.sol_escrow, &ctx.accounts.bidder_collateral_account, &ctx.accounts.bid_escrow) {
+        // Lock lamports in the bid's SOL escrow
+        (Some(sol_escrow), None, None) => {
+            bid.collateral_account = sol_escrow.key();
+            bid.sol_collateral = true;
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bidder.to_account_info(),
+                        to: sol_escrow.to_account_info(),
+                    },
+                ),
+                collateral_amount,
+            )?;
+        }
+        // Transfer collateral to bid escrow
+        (None, Some(bidder_collateral_account), Some(bid_escrow)) => {
+            bid.collateral_account = bidder_collateral_account.key();
+            bid.sol_collateral = false;
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: bidder_collateral_account.to_account_info(),
+                        to: bid_escrow.to_account_info(),
+                        authority: ctx.accounts.bidder.to_account_info(),
+                    },
+                ),
+                collateral_amount,
+            )?;
+        }
+        _ => return err!(ShadowProtocolError::InvalidCollateralEscrow),
+    }
     
     auction.bid_count += 1;
     
     
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.bids_submitted = stats.bids_submitted.saturating_add(1);
+    
     emit!(BidSubmitted {
         auction_id,
         bidder: ctx.accounts.bidder.key(),
         timestamp: clock.unix_timestamp,
-        bid_count: auction.bid_count,
+        bid_count: auction.bid_count as u64,
     });
     
     Ok(())
@@ -144,12 +267,20 @@ pub fn submit_dutch_bid(
     let clock = Clock::get()?;
     
     require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require_not_denied(&ctx.accounts.bidder_deny_entry, DenyListEntry::BIDDING)?;
+    require_house_open(auction, ctx.accounts.auction_house.as_ref())?;
     
     require!(
-        auction.auction_type == AuctionType::Dutch,
+        auction.auction_type == AuctionType::Dutch && !auction.has_feature(AuctionFeatures::TRANCHES),
         ShadowProtocolError::InvalidAuctionType
     );
     
+    // Direct Dutch bids pay with their collateral, so it must be in the payment mint
+    require!(
+        !auction.has_feature(AuctionFeatures::COLLATERAL_MINT),
+        ShadowProtocolError::InvalidCollateralMint
+    );
+    
     require!(
         collateral_amount >= bid_amount,
         ShadowProtocolError::InsufficientCollateral
@@ -173,7 +304,7 @@ pub fn submit_dutch_bid(
     );
     
     require!(
-        clock.unix_timestamp < auction.end_time,
+        !auction.has_ended(&clock),
         ShadowProtocolError::AuctionEnded
     );
     
@@ -204,12 +335,15 @@ pub fn submit_dutch_bid(
     )?;
     
     // Dutch auction ends immediately when bid meets price
-    auction.status = AuctionStatus::Ended;
+    auction.transition(AuctionStatus::Ended)?;
     auction.winner = Some(ctx.accounts.bidder.key());
     auction.winning_amount = current_price;
     auction.bid_count = 1;
     
     
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.bids_submitted = stats.bids_submitted.saturating_add(1);
+    
     emit!(BidSubmitted {
         auction_id,
         bidder: ctx.accounts.bidder.key(),
@@ -220,19 +354,159 @@ pub fn submit_dutch_bid(
     Ok(())
 }
 
+/// Submit an encrypted claim on a Dutch auction.
+///
+/// The bid amount never appears in plaintext: acceptance is decided by the
+/// `process_dutch_auction_bid` circuit and delivered through
+/// `dutch_bid_callback`, which awards the auction to the first valid claim.
+pub fn submit_encrypted_dutch_bid(
+    ctx: Context<SubmitEncryptedDutchBid>,
+    auction_id: u64,
+    bid_amount_encrypted: [u8; 32],
+    public_key: [u8; 32],
+    nonce: u128,
+    collateral_amount: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require_not_denied(&ctx.accounts.bidder_deny_entry, DenyListEntry::BIDDING)?;
+    require_house_open(auction, ctx.accounts.auction_house.as_ref())?;
+    
+    require!(
+        auction.status == AuctionStatus::Active,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    require!(
+        !auction.has_ended(&clock),
+        ShadowProtocolError::AuctionEnded
+    );
+    
+    require!(
+        auction.bid_count < auction.max_bids,
+        ShadowProtocolError::MaxBidsExceeded
+    );
+    
+    // Collateral must cover the price at submission so an accepted claim can be paid
+    let current_price = calculate_dutch_auction_price(auction)?;
+    ctx.accounts.features.require_collateral(
+        ctx.accounts.collateral_mint.key(),
+        collateral_amount,
+        current_price,
+    )?;
+    
+    // Validate collateral amount is reasonable (prevent overflow attacks)
+    require!(
+        collateral_amount <= u64::MAX / 2,
+        ShadowProtocolError::InvalidAssetAmount
+    );
+    
+    require!(
+        ctx.accounts.bidder_collateral_account.amount >= collateral_amount,
+        ShadowProtocolError::InsufficientFunds
+    );
+    
+    CryptoUtils::validate_encrypted_bid(
+        &bid_amount_encrypted,
+        &public_key,
+        nonce,
+    )?;
+    
+    CryptoUtils::require_encryption_key(
+        public_key,
+        auction_id,
+        ctx.accounts.bidder.key(),
+        auction.creator,
+        nonce,
+    )?;
+    
+    // Claims are priced at submission time, measured in the auction's own clock
+    let submitted_at = auction.now(&clock);
+    
+    let bid = &mut ctx.accounts.bid;
+    bid.auction_id = auction_id;
+    bid.auction = auction.key();
+    bid.bidder = ctx.accounts.bidder.key();
+    bid.amount_encrypted = bid_amount_encrypted;
+    bid.encryption_public_key = public_key;
+    bid.nonce = nonce;
+    bid.timestamp = submitted_at;
+    bid.collateral_amount = collateral_amount;
+    bid.collateral_account = ctx.accounts.bidder_collateral_account.key();
+    bid.sol_collateral = false;
+    bid.is_winner = false;
+    bid.budget = None;
+    bid.bump = ctx.bumps.bid;
+    
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.bidder_collateral_account.to_account_info(),
+                to: ctx.accounts.bid_escrow.to_account_info(),
+                authority: ctx.accounts.bidder.to_account_info(),
+            },
+        ),
+        collateral_amount,
+    )?;
+    
+    auction.bid_count += 1;
+    
+    let computation_id = generate_dutch_claim_id(
+        auction_id,
+        ctx.accounts.bidder.key(),
+        submitted_at,
+    );
+    
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.bids_submitted = stats.bids_submitted.saturating_add(1);
+    
+    emit!(DutchClaimQueued {
+        auction_id,
+        bidder: ctx.accounts.bidder.key(),
+        computation_id,
+        submitted_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct GetCurrentDutchPrice<'info> {
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = auction.auction_type == AuctionType::Dutch @ ShadowProtocolError::InvalidAuctionType
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+}
+
 #[derive(Accounts)]
 #[instruction(auction_id: u64)]
 pub struct SubmitBid<'info> {
     #[account(mut)]
     pub bidder: Signer<'info>,
     
+    /// CHECK: Bidder's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_deny_entry: UncheckedAccount<'info>,
+    
     #[account(
         mut,
-        seeds = [AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
-        bump = auction.bump
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
     )]
     pub auction: Account<'info, AuctionAccount>,
     
+    /// Holds the collateral mint, if not the payment mint
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
     #[account(
         init,
         payer = bidder,
@@ -248,15 +522,22 @@ pub struct SubmitBid<'info> {
     )]
     pub protocol_state: Account<'info, ProtocolState>,
     
-    /// Bidder's collateral token account
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    /// Bidder's collateral token account; omitted for native-SOL collateral
     #[account(
         mut,
         associated_token::mint = collateral_mint,
         associated_token::authority = bidder
     )]
-    pub bidder_collateral_account: Account<'info, TokenAccount>,
+    pub bidder_collateral_account: Option<Account<'info, TokenAccount>>,
     
-    /// Bid escrow account to hold collateral
+    /// Bid escrow account to hold collateral; omitted for native-SOL collateral
     #[account(
         init,
         payer = bidder,
@@ -265,11 +546,33 @@ pub struct SubmitBid<'info> {
         seeds = [b"bid_escrow", auction_id.to_le_bytes().as_ref(), bidder.key().as_ref()],
         bump
     )]
-    pub bid_escrow: Account<'info, TokenAccount>,
+    pub bid_escrow: Option<Account<'info, TokenAccount>>,
     
-    /// Collateral token mint
-    pub collateral_mint: Account<'info, Mint>,
+    /// Collateral token mint; omitted for native-SOL collateral
+    pub collateral_mint: Option<Account<'info, Mint>>,
     
+    /// CHECK: Lamport-only PDA holding native-SOL collateral, in place of the
+    /// three token accounts above
+    #[account(
+        mut,
+        seeds = [SOL_ESCROW_SEED, auction_id.to_le_bytes().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub sol_escrow: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Matched against the auction's compliance program; required when one is set
+    pub compliance_program: Option<UncheckedAccount<'info>>,
+    
+    /// The auction's house; required when it was listed through one
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    /// Bidder's open budget the bid draws on, if any
+    #[account(
+        mut,
+        seeds = [BID_BUDGET_SEED, bidder.key().as_ref(), budget.budget_id.to_le_bytes().as_ref()],
+        bump = budget.bump
+    )]
+    pub budget: Option<Account<'info, BidBudget>>,
     
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
@@ -281,10 +584,13 @@ pub struct SubmitDutchBid<'info> {
     #[account(mut)]
     pub bidder: Signer<'info>,
     
+    /// CHECK: Bidder's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_deny_entry: UncheckedAccount<'info>,
+    
     #[account(
         mut,
-        seeds = [AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
-        bump = auction.bump,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
         constraint = auction.auction_type == AuctionType::Dutch @ ShadowProtocolError::InvalidAuctionType
     )]
     pub auction: Account<'info, AuctionAccount>,
@@ -295,6 +601,13 @@ pub struct SubmitDutchBid<'info> {
     )]
     pub protocol_state: Account<'info, ProtocolState>,
     
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
     /// Bidder's collateral token account
     #[account(
         mut,
@@ -313,8 +626,157 @@ pub struct SubmitDutchBid<'info> {
     /// Collateral token mint
     pub collateral_mint: Account<'info, Mint>,
     
+    /// The auction's house; required when it was listed through one
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SubmitEncryptedDutchBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    /// CHECK: Bidder's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_deny_entry: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = auction.auction_type == AuctionType::Dutch && !auction.has_feature(AuctionFeatures::TRANCHES) @ ShadowProtocolError::InvalidAuctionType
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Holds the collateral mint, if not the payment mint
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + BidAccount::INIT_SPACE,
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    /// Bidder's collateral token account
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = bidder
+    )]
+    pub bidder_collateral_account: Account<'info, TokenAccount>,
+    
+    /// Bid escrow account to hold collateral until the claim resolves
+    #[account(
+        init,
+        payer = bidder,
+        token::mint = collateral_mint,
+        token::authority = bid,
+        seeds = [b"bid_escrow", auction_id.to_le_bytes().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bid_escrow: Account<'info, TokenAccount>,
+    
+    /// Collateral token mint
+    pub collateral_mint: Account<'info, Mint>,
+    
+    /// The auction's house; required when it was listed through one
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
     
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const START: i64 = 1_000;
+    
+    fn dutch_auction(price_curve: PriceCurve, floor: u64) -> AuctionAccount {
+        let mut auction = AuctionAccount::deserialize(&mut &vec![0u8; AuctionAccount::INIT_SPACE][..]).unwrap();
+        auction.start_time = START;
+        auction.current_price = 1_000_000;
+        auction.minimum_price_floor = floor;
+        auction.price_curve = price_curve;
+        auction
+    }
+    
+    fn price_at(auction: &AuctionAccount, elapsed: i64) -> u64 {
+        calculate_dutch_price_at(auction, START + elapsed).unwrap()
+    }
+    
+    #[test]
+    fn linear_price_falls_by_rate_per_second() {
+        let mut auction = dutch_auction(PriceCurve::Linear, 0);
+        auction.price_decrease_rate = 10;
+        
+        assert_eq!(price_at(&auction, -50), 1_000_000);
+        assert_eq!(price_at(&auction, 0), 1_000_000);
+        assert_eq!(price_at(&auction, 100), 999_000);
+        assert_eq!(price_at(&auction, 200_000), 0);
+    }
+    
+    #[test]
+    fn exponential_price_halves_and_interpolates() {
+        let auction = dutch_auction(PriceCurve::Exponential { half_life: 100 }, 0);
+        
+        assert_eq!(price_at(&auction, 50), 750_000);
+        assert_eq!(price_at(&auction, 100), 500_000);
+        assert_eq!(price_at(&auction, 150), 375_000);
+        assert_eq!(price_at(&auction, 200), 250_000);
+        assert_eq!(price_at(&auction, 100 * 64), 0);
+    }
+    
+    #[test]
+    fn stepwise_price_drops_once_per_interval() {
+        let auction = dutch_auction(PriceCurve::Stepwise { step_amount: 1_000, step_interval: 60 }, 0);
+        
+        assert_eq!(price_at(&auction, 59), 1_000_000);
+        assert_eq!(price_at(&auction, 60), 999_000);
+        assert_eq!(price_at(&auction, 119), 999_000);
+        assert_eq!(price_at(&auction, 120), 998_000);
+    }
+    
+    #[test]
+    fn price_never_drops_below_floor() {
+        let mut linear = dutch_auction(PriceCurve::Linear, 400_000);
+        linear.price_decrease_rate = 1_000;
+        let exponential = dutch_auction(PriceCurve::Exponential { half_life: 100 }, 400_000);
+        let stepwise = dutch_auction(PriceCurve::Stepwise { step_amount: 100_000, step_interval: 10 }, 400_000);
+        
+        for auction in [&linear, &exponential, &stepwise] {
+            assert_eq!(price_at(auction, 1_000_000), 400_000);
+        }
+        assert_eq!(price_at(&linear, 500), 500_000);
+    }
+    
+    #[test]
+    fn linear_rate_overflow_is_an_error() {
+        let mut auction = dutch_auction(PriceCurve::Linear, 0);
+        auction.price_decrease_rate = u64::MAX;
+        
+        assert!(calculate_dutch_price_at(&auction, START + 2).is_err());
+    }
+}
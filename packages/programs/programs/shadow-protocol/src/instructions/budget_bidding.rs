@@ -0,0 +1,380 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Commit an encrypted budget that several sealed bids can share.
+///
+/// Bids are linked to the budget as they are placed and allocated by the `allocate_budget` circuit
+/// so the bidder never pays more than the budget across all linked auctions.
+pub fn create_bid_budget(
+    ctx: Context<CreateBidBudget>,
+    budget_id: u64,
+    budget_encrypted: [u8; 32],
+    budget_nonce: u128,
+    encryption_public_key: [u8; 32],
+) -> Result<()> {
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        budget_encrypted != [0u8; 32] && encryption_public_key != [0u8; 32],
+        ShadowProtocolError::InvalidEncryption
+    );
+    
+    let budget = &mut ctx.accounts.budget;
+    budget.bidder = ctx.accounts.bidder.key();
+    budget.budget_id = budget_id;
+    budget.budget_encrypted = budget_encrypted;
+    budget.budget_nonce = budget_nonce;
+    budget.encryption_public_key = encryption_public_key;
+    budget.linked_auctions = Vec::new();
+    budget.allocated_auctions = Vec::new();
+    budget.status = BudgetStatus::Open;
+    budget.computation_id = None;
+    budget.created_at = clock.unix_timestamp;
+    budget.bump = ctx.bumps.budget;
+    
+    emit!(BidBudgetCreated {
+        bidder: budget.bidder,
+        budget_id,
+        created_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Link a sealed bid being placed to its bidder's open budget. Link order
+/// is allocation priority.
+pub(crate) fn link_bid_to_budget(
+    budget: &mut Account<BidBudget>,
+    bid: &mut BidAccount,
+    auction: &AuctionAccount,
+) -> Result<()> {
+    require!(
+        budget.bidder == bid.bidder,
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        auction.auction_type == AuctionType::SealedBid,
+        ShadowProtocolError::InvalidAuctionType
+    );
+    require!(
+        budget.status == BudgetStatus::Open,
+        ShadowProtocolError::InvalidBudgetStatus
+    );
+    require!(
+        budget.linked_auctions.len() < MAX_LINKED_AUCTIONS,
+        ShadowProtocolError::TooManyLinkedAuctions
+    );
+    
+    budget.linked_auctions.push(bid.auction_id);
+    bid.budget = Some(budget.key());
+    
+    emit!(BidLinkedToBudget {
+        bidder: budget.bidder,
+        budget_id: budget.budget_id,
+        auction_id: bid.auction_id,
+        priority: (budget.linked_auctions.len() - 1) as u8,
+    });
+    
+    Ok(())
+}
+
+/// Queue the budget allocation once every linked auction has closed.
+///
+/// The allocation runs on the linked bids' own ciphertexts, which the
+/// computation ID commits to, so it covers exactly the bids that were placed.
+///
+/// Remaining accounts: the linked auctions, in link order, followed by the
+/// linked bids in the same order.
+///
+/// The authority runs this as the settlement operator; it changes no
+/// protocol configuration and is not routed through governance.
+pub fn queue_budget_allocation<'info>(
+    ctx: Context<'_, '_, 'info, 'info, QueueBudgetAllocation<'info>>,
+    budget_id: u64,
+) -> Result<()> {
+    let budget = &mut ctx.accounts.budget;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        budget.status == BudgetStatus::Open,
+        ShadowProtocolError::InvalidBudgetStatus
+    );
+    require!(
+        !budget.linked_auctions.is_empty()
+            && ctx.remaining_accounts.len() == 2 * budget.linked_auctions.len(),
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    let (auction_infos, bid_infos) = ctx.remaining_accounts.split_at(budget.linked_auctions.len());
+    let mut linked_bids = Vec::with_capacity(bid_infos.len());
+    for ((info, bid_info), auction_id) in auction_infos.iter()
+        .zip(bid_infos.iter())
+        .zip(budget.linked_auctions.iter())
+    {
+        let auction = Account::<AuctionAccount>::try_from(info)?;
+        require!(
+            auction.auction_id == *auction_id,
+            ShadowProtocolError::InvalidRemainingAccounts
+        );
+        require!(
+            auction.has_ended(&clock),
+            ShadowProtocolError::AuctionNotEnded
+        );
+        
+        let bid = Account::<BidAccount>::try_from(bid_info)?;
+        let (expected_bid, _) = Pubkey::find_program_address(
+            &[BID_SEED, auction_id.to_le_bytes().as_ref(), budget.bidder.as_ref()],
+            &crate::ID,
+        );
+        require!(
+            bid_info.key() == expected_bid && bid.budget == Some(budget.key()),
+            ShadowProtocolError::InvalidRemainingAccounts
+        );
+        linked_bids.push(bid.into_inner());
+    }
+    
+    let computation_id = generate_budget_allocation_id(budget, &linked_bids);
+    budget.computation_id = Some(computation_id);
+    budget.status = BudgetStatus::AllocationQueued;
+    
+    emit!(BudgetAllocationQueued {
+        bidder: budget.bidder,
+        budget_id,
+        computation_id,
+        linked_count: budget.linked_auctions.len() as u8,
+        queued_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Deliver the `allocate_budget` result: one flag per linked auction followed
+/// by the verification hash.
+pub fn budget_allocation_callback(
+    ctx: Context<BudgetAllocationCallback>,
+    budget_id: u64,
+    computation_id: [u8; 32],
+    result: Vec<u8>,
+) -> Result<()> {
+    let budget = &mut ctx.accounts.budget;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.authority.key() == protocol.callback_signer(),
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        budget.status == BudgetStatus::AllocationQueued,
+        ShadowProtocolError::InvalidBudgetStatus
+    );
+    require!(
+        budget.computation_id == Some(computation_id),
+        ShadowProtocolError::InvalidComputationId
+    );
+    
+    let linked_count = budget.linked_auctions.len();
+    require!(
+        result.len() == linked_count + 32,
+        ShadowProtocolError::InvalidMpcResult
+    );
+    
+    let allocation = &result[..linked_count];
+    let verification_hash: [u8; 32] = result[linked_count..].try_into()
+        .map_err(|_| ShadowProtocolError::InvalidMpcResult)?;
+    require!(
+        verification_hash == compute_budget_allocation_hash(budget.bidder, budget_id, allocation),
+        ShadowProtocolError::MpcVerificationFailed
+    );
+    
+    budget.allocated_auctions = budget.linked_auctions
+        .iter()
+        .zip(allocation.iter())
+        .filter(|(_, allocated)| **allocated != 0)
+        .map(|(auction_id, _)| *auction_id)
+        .collect();
+    budget.status = BudgetStatus::Allocated;
+    
+    emit!(BudgetAllocated {
+        bidder: budget.bidder,
+        budget_id,
+        allocated_auctions: budget.allocated_auctions.clone(),
+        allocated_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+fn generate_budget_allocation_id(budget: &BidBudget, linked_bids: &[BidAccount]) -> [u8; 32] {
+    use anchor_lang::solana_program::hash::hash;
+    
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_budget_allocation");
+    data.extend_from_slice(&budget.bidder.to_bytes());
+    data.extend_from_slice(&budget.budget_id.to_le_bytes());
+    data.extend_from_slice(&budget.budget_encrypted);
+    data.extend_from_slice(&budget.budget_nonce.to_le_bytes());
+    for bid in linked_bids {
+        data.extend_from_slice(&bid.auction_id.to_le_bytes());
+        data.extend_from_slice(&bid.amount_encrypted);
+        data.extend_from_slice(&bid.encryption_public_key);
+        data.extend_from_slice(&bid.nonce.to_le_bytes());
+    }
+    
+    hash(&data).to_bytes()
+}
+
+fn compute_budget_allocation_hash(bidder: Pubkey, budget_id: u64, allocation: &[u8]) -> [u8; 32] {
+    use anchor_lang::solana_program::hash::hash;
+    
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_budget_allocation_verification");
+    data.extend_from_slice(&bidder.to_bytes());
+    data.extend_from_slice(&budget_id.to_le_bytes());
+    data.extend_from_slice(allocation);
+    
+    hash(&data).to_bytes()
+}
+
+/// Whether a winning bid may be paid given its budget: bids not linked to one
+/// always may, linked bids only if the allocation kept this auction. Fails
+/// while the allocation is still pending, so the caller can retry once it
+/// lands.
+pub(crate) fn budget_allocated<'info>(
+    bid: &BidAccount,
+    budget_info: Option<&'info AccountInfo<'info>>,
+) -> Result<bool> {
+    let Some(budget_key) = bid.budget else {
+        return Ok(true);
+    };
+    
+    let budget_info = budget_info.ok_or(ShadowProtocolError::InvalidRemainingAccounts)?;
+    require!(
+        budget_info.key() == budget_key,
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    let budget = Account::<BidBudget>::try_from(budget_info)?;
+    require!(
+        budget.status == BudgetStatus::Allocated,
+        ShadowProtocolError::InvalidBudgetStatus
+    );
+    
+    Ok(budget.allocated_auctions.contains(&bid.auction_id))
+}
+
+/// Check that a winning bid linked to a budget was kept by its allocation
+pub(crate) fn require_budget_allocated<'info>(
+    bid: &BidAccount,
+    budget_info: Option<&'info AccountInfo<'info>>,
+) -> Result<()> {
+    require!(
+        budget_allocated(bid, budget_info)?,
+        ShadowProtocolError::BudgetAllocationExceeded
+    );
+    Ok(())
+}
+
+#[event]
+pub struct BidBudgetCreated {
+    pub bidder: Pubkey,
+    pub budget_id: u64,
+    pub created_at: i64,
+}
+
+#[event]
+pub struct BidLinkedToBudget {
+    pub bidder: Pubkey,
+    pub budget_id: u64,
+    pub auction_id: u64,
+    pub priority: u8,
+}
+
+#[event]
+pub struct BudgetAllocationQueued {
+    pub bidder: Pubkey,
+    pub budget_id: u64,
+    pub computation_id: [u8; 32],
+    pub linked_count: u8,
+    pub queued_at: i64,
+}
+
+#[event]
+pub struct BudgetAllocated {
+    pub bidder: Pubkey,
+    pub budget_id: u64,
+    pub allocated_auctions: Vec<u64>,
+    pub allocated_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(budget_id: u64)]
+pub struct CreateBidBudget<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + BidBudget::INIT_SPACE,
+        seeds = [BID_BUDGET_SEED, bidder.key().as_ref(), budget_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub budget: Account<'info, BidBudget>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(budget_id: u64)]
+pub struct QueueBudgetAllocation<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [BID_BUDGET_SEED, budget.bidder.as_ref(), budget_id.to_le_bytes().as_ref()],
+        bump = budget.bump
+    )]
+    pub budget: Account<'info, BidBudget>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+#[instruction(budget_id: u64)]
+pub struct BudgetAllocationCallback<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [BID_BUDGET_SEED, budget.bidder.as_ref(), budget_id.to_le_bytes().as_ref()],
+        bump = budget.bump
+    )]
+    pub budget: Account<'info, BidBudget>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
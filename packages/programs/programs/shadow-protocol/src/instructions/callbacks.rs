@@ -14,121 +14,40 @@ pub fn init_batch_settlement_comp_def(_ctx: Context<InitBatchSettlementCompDef>)
     Ok(())
 }
 
-pub fn sealed_bid_auction(
-    ctx: Context<SealedBidAuction>,
-    _auction_id: u64,
-    _computation_offset: u64,
-) -> Result<()> {
-    let auction = &mut ctx.accounts.auction;
-    let protocol = &ctx.accounts.protocol_state;
-    
-    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
-    require!(
-        auction.status == AuctionStatus::Ended,
-        ShadowProtocolError::InvalidAuctionStatus
-    );
-    
-    Ok(())
-}
-
-pub fn sealed_bid_auction_callback(
-    ctx: Context<SealedBidAuctionCallback>,
-    result_data: Vec<u8>,
-) -> Result<()> {
-    let auction = &mut ctx.accounts.auction;
-    
-    if result_data.len() >= 32 {
-        let winner_bytes: [u8; 32] = result_data[0..32].try_into()
-            .map_err(|_| ShadowProtocolError::InvalidWinnerDetermination)?;
-        let winner_pubkey = Pubkey::new_from_array(winner_bytes);
-        
-        let winning_amount = if result_data.len() >= 40 {
-            u64::from_le_bytes(result_data[32..40].try_into().unwrap_or([0; 8]))
-        } else {
-            0
-        };
-        
-        auction.winner = Some(winner_pubkey);
-        auction.winning_amount = winning_amount;
-        auction.status = AuctionStatus::Settled;
-        auction.settled_at = Some(Clock::get()?.unix_timestamp);
-        
-        emit!(AuctionSettled {
-            auction_id: auction.auction_id,
-            winner: Some(winner_pubkey),
-            winning_amount,
-            settled_at: Clock::get()?.unix_timestamp,
-        });
-    } else {
-        auction.status = AuctionStatus::Cancelled;
-        return Err(ShadowProtocolError::ComputationFailed.into());
-    }
-    
-    Ok(())
-}
-
-pub fn dutch_auction(
-    ctx: Context<DutchAuction>,
-    _auction_id: u64,
-    _computation_offset: u64,
-) -> Result<()> {
-    let _auction = &mut ctx.accounts.auction;
-    let protocol = &ctx.accounts.protocol_state;
-    
-    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
-    
-    Ok(())
-}
-
-pub fn dutch_auction_callback(
-    ctx: Context<DutchAuctionCallback>,
+/// Record a batch computation's revealed result: the settled count followed
+/// by one `(winner, winning_amount, met_reserve)` tuple per batch slot, all
+/// `MAX_BATCH_AUCTIONS` of them. The tuples of the batch's auctions are
+/// applied to them by `distribute_batch_results`; the zeroed slots past them
+/// are ignored.
+pub fn batch_settlement_callback(
+    ctx: Context<BatchSettlementCallbackCtx>,
     result_data: Vec<u8>,
-) -> Result<()> {
-    let auction = &mut ctx.accounts.auction;
-    
-    if result_data.len() >= 32 {
-        let winner_bytes: [u8; 32] = result_data[0..32].try_into()
-            .map_err(|_| ShadowProtocolError::InvalidWinnerDetermination)?;
-        let winner_pubkey = Pubkey::new_from_array(winner_bytes);
-        
-        auction.winner = Some(winner_pubkey);
-        auction.status = AuctionStatus::Settled;
-        auction.settled_at = Some(Clock::get()?.unix_timestamp);
-    } else {
-        auction.status = AuctionStatus::Ended;
-    }
-    
-    Ok(())
-}
-
-pub fn batch_settlement(
-    ctx: Context<BatchSettlementCtx>,
-    _batch_id: u64,
-    _computation_offset: u64,
 ) -> Result<()> {
     let batch = &mut ctx.accounts.batch_account;
     let protocol = &ctx.accounts.protocol_state;
     
-    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
     require!(
-        batch.status == BatchStatus::Created,
-        ShadowProtocolError::InvalidBatchSize
+        ctx.accounts.authority.key() == protocol.callback_signer(),
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        batch.status == BatchStatus::Settling,
+        ShadowProtocolError::BatchSettlementFailed
     );
     
-    Ok(())
-}
-
-pub fn batch_settlement_callback(
-    ctx: Context<BatchSettlementCallbackCtx>,
-    result_data: Vec<u8>,
-) -> Result<()> {
-    let batch = &mut ctx.accounts.batch_account;
+    let expected_len = 8 + MAX_BATCH_AUCTIONS * BATCH_RESULT_LEN;
     
-    if result_data.len() >= 8 {
+    if result_data.len() == expected_len {
         let settled_count = u64::from_le_bytes(
             result_data[0..8].try_into().unwrap_or([0; 8])
         );
         
+        batch.results = batch.auction_ids
+            .iter()
+            .zip(result_data[8..].chunks_exact(BATCH_RESULT_LEN))
+            .map(|(auction_id, tuple)| parse_batch_result(*auction_id, tuple))
+            .collect::<Result<Vec<_>>>()?;
+        batch.results_distributed = false;
         batch.status = BatchStatus::Settled;
         batch.settled_at = Some(Clock::get()?.unix_timestamp);
         
@@ -145,6 +64,27 @@ pub fn batch_settlement_callback(
     Ok(())
 }
 
+/// Bytes per batch slot in a batch result: winner, winning amount, met
+/// reserve; matches the circuit's `SettledAuction`
+const BATCH_RESULT_LEN: usize = 32 + 8 + 1;
+
+fn parse_batch_result(auction_id: u64, tuple: &[u8]) -> Result<BatchAuctionResult> {
+    let winner_bytes: [u8; 32] = tuple[0..32].try_into()
+        .map_err(|_| ShadowProtocolError::InvalidMpcResult)?;
+    
+    let winning_amount = u64::from_le_bytes(
+        tuple[32..40].try_into()
+            .map_err(|_| ShadowProtocolError::InvalidMpcResult)?
+    );
+    
+    Ok(BatchAuctionResult {
+        auction_id,
+        winner: Pubkey::new_from_array(winner_bytes),
+        winning_amount,
+        met_reserve: tuple[40] != 0,
+    })
+}
+
 #[derive(Accounts)]
 pub struct InitSealedBidCompDef<'info> {
     #[account(mut)]
@@ -166,68 +106,10 @@ pub struct InitBatchSettlementCompDef<'info> {
     pub system_program: Program<'info, System>,
 }
 
-#[derive(Accounts)]
-pub struct SealedBidAuction<'info> {
-    #[account(mut)]
-    pub auction: Account<'info, AuctionAccount>,
-    
-    #[account(
-        seeds = [PROTOCOL_SEED],
-        bump = protocol_state.bump
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-}
-
-#[derive(Accounts)]
-pub struct SealedBidAuctionCallback<'info> {
-    #[account(mut)]
-    pub auction: Account<'info, AuctionAccount>,
-    
-    #[account(
-        seeds = [PROTOCOL_SEED],
-        bump = protocol_state.bump
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-}
-
-#[derive(Accounts)]
-pub struct DutchAuction<'info> {
-    #[account(mut)]
-    pub auction: Account<'info, AuctionAccount>,
-    
-    #[account(
-        seeds = [PROTOCOL_SEED],
-        bump = protocol_state.bump
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-}
-
-#[derive(Accounts)]
-pub struct DutchAuctionCallback<'info> {
-    #[account(mut)]
-    pub auction: Account<'info, AuctionAccount>,
-    
-    #[account(
-        seeds = [PROTOCOL_SEED],
-        bump = protocol_state.bump
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-}
-
-#[derive(Accounts)]
-pub struct BatchSettlementCtx<'info> {
-    #[account(mut)]
-    pub batch_account: Account<'info, BatchSettlement>,
-    
-    #[account(
-        seeds = [PROTOCOL_SEED],
-        bump = protocol_state.bump
-    )]
-    pub protocol_state: Account<'info, ProtocolState>,
-}
-
 #[derive(Accounts)]
 pub struct BatchSettlementCallbackCtx<'info> {
+    pub authority: Signer<'info>,
+    
     #[account(mut)]
     pub batch_account: Account<'info, BatchSettlement>,
     
@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::governance::{require_governance_execution, GovernanceAction};
+
+/// Set the settlement activity that trips the circuit breaker. A zero window
+/// turns it off; shards already tripped stay paused until cleared.
+pub fn update_circuit_breaker(
+    ctx: Context<UpdateCircuitBreaker>,
+    config: CircuitBreakerConfig,
+) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateCircuitBreaker,
+    )?;
+    require!(
+        config.window >= 0 && (config.window == 0 || config.max_volume > 0 || config.max_failures > 0),
+        ShadowProtocolError::InvalidCircuitBreaker
+    );
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateCircuitBreaker,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&protocol.circuit_breaker)?,
+        AdminAuditLog::word_hash(&config)?,
+    )?;
+    protocol.circuit_breaker = config;
+    
+    emit!(CircuitBreakerUpdated {
+        window: config.window,
+        volume_mint: config.volume_mint,
+        max_volume: config.max_volume,
+        max_failures: config.max_failures,
+    });
+    
+    Ok(())
+}
+
+/// Lift a shard's soft pause. Its window starts over, so the activity that
+/// tripped it does not trip it again straight away.
+pub fn clear_circuit_breaker(ctx: Context<ClearCircuitBreaker>, shard: u8) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        &ctx.accounts.protocol_state,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::ClearCircuitBreaker,
+    )?;
+    
+    let stats = &mut ctx.accounts.protocol_stats;
+    let tripped_at = stats.soft_paused_at.ok_or(ShadowProtocolError::InvalidCircuitBreaker)?;
+    let now = Clock::get()?.unix_timestamp;
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::ClearCircuitBreaker,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&(shard, tripped_at))?,
+        AdminAuditLog::word_hash(&(shard, now))?,
+    )?;
+    stats.soft_paused_at = None;
+    stats.window_start = now;
+    stats.window_volume = [0, 0];
+    stats.window_failures = [0, 0];
+    
+    emit!(CircuitBreakerCleared {
+        shard,
+        tripped_at,
+        cleared_at: now,
+    });
+    
+    Ok(())
+}
+
+/// Count a settlement's volume toward its shard's breaker
+pub(crate) fn record_breaker_volume(
+    stats: &mut ProtocolStats,
+    protocol: &ProtocolState,
+    mint: Pubkey,
+    volume: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if stats.record_breaker_volume(&protocol.circuit_breaker, mint, volume, now) {
+        emit_tripped(stats, protocol, now);
+    }
+    Ok(())
+}
+
+/// Count a failed settlement computation toward its shard's breaker
+pub(crate) fn record_breaker_failure(stats: &mut ProtocolStats, protocol: &ProtocolState) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if stats.record_breaker_failure(&protocol.circuit_breaker, now) {
+        emit_tripped(stats, protocol, now);
+    }
+    Ok(())
+}
+
+fn emit_tripped(stats: &ProtocolStats, protocol: &ProtocolState, now: i64) {
+    let (volume, failures) = stats.rolling_activity(protocol.circuit_breaker.window, now);
+    emit!(CircuitBreakerTripped {
+        shard: stats.shard,
+        volume,
+        failures,
+        tripped_at: now,
+    });
+}
+
+#[event]
+pub struct CircuitBreakerUpdated {
+    pub window: i64,
+    pub volume_mint: Pubkey,
+    pub max_volume: u64,
+    pub max_failures: u32,
+}
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub shard: u8,
+    pub volume: u64,
+    pub failures: u32,
+    pub tripped_at: i64,
+}
+
+#[event]
+pub struct CircuitBreakerCleared {
+    pub shard: u8,
+    pub tripped_at: i64,
+    pub cleared_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(shard: u8)]
+pub struct ClearCircuitBreaker<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, &[shard]],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCircuitBreaker<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
\ No newline at end of file
@@ -0,0 +1,252 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::governance::{require_governance_execution, GovernanceAction};
+
+/// Replace the protocol's approved MXE clusters.
+///
+/// Computations may only be queued on clusters in this list, and
+/// `retry_mpc_computation` fails over through it in order once a queued
+/// computation has gone `retry_timeout` seconds without a result. Every
+/// cluster starts out healthy.
+pub fn set_mxe_clusters(
+    ctx: Context<SetMxeClusters>,
+    clusters: Vec<Pubkey>,
+    retry_timeout: i64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        &ctx.accounts.protocol_state,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::SetMxeClusters,
+    )?;
+    require!(
+        !clusters.is_empty()
+            && clusters.len() <= MAX_MXE_CLUSTERS
+            && clusters.iter().enumerate().all(|(i, cluster)| !clusters[..i].contains(cluster)),
+        ShadowProtocolError::InvalidClusterList
+    );
+    require!(retry_timeout > 0, ShadowProtocolError::InvalidClusterList);
+    
+    let registry = &mut ctx.accounts.cluster_registry;
+    ctx.accounts.audit_log.record(
+        AdminAction::SetMxeClusters,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&(registry.clusters.clone(), registry.retry_timeout))?,
+        AdminAuditLog::word_hash(&(clusters.clone(), retry_timeout))?,
+    )?;
+    registry.clusters = clusters
+        .iter()
+        .map(|&cluster| MxeClusterEntry { cluster, healthy: true })
+        .collect();
+    registry.retry_timeout = retry_timeout;
+    registry.bump = ctx.bumps.cluster_registry;
+    
+    emit!(MxeClustersUpdated {
+        clusters,
+        retry_timeout,
+    });
+    
+    Ok(())
+}
+
+/// Mark an approved cluster healthy or unhealthy. Unhealthy clusters are
+/// skipped by failover and cannot have new computations queued on them.
+pub fn set_mxe_cluster_health(
+    ctx: Context<SetMxeClusterHealth>,
+    cluster: Pubkey,
+    healthy: bool,
+) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        &ctx.accounts.protocol_state,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::SetMxeClusterHealth,
+    )?;
+    
+    let registry = &mut ctx.accounts.cluster_registry;
+    let entry = registry
+        .clusters
+        .iter_mut()
+        .find(|entry| entry.cluster == cluster)
+        .ok_or(ShadowProtocolError::ClusterNotApproved)?;
+    ctx.accounts.audit_log.record(
+        AdminAction::SetMxeClusterHealth,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&(cluster, entry.healthy))?,
+        AdminAuditLog::word_hash(&(cluster, healthy))?,
+    )?;
+    entry.healthy = healthy;
+    
+    emit!(MxeClusterHealthUpdated { cluster, healthy });
+    
+    Ok(())
+}
+
+/// Move an auction's queued computation to the next healthy cluster once it
+/// has gone unanswered for the registry's retry timeout.
+///
+/// Permissionless so keepers can unstick settlements. The computation id is
+/// unchanged, so a late result from the previous cluster still verifies.
+pub fn retry_mpc_computation(ctx: Context<RetryMpcComputation>, auction_id: u64) -> Result<()> {
+    require!(!ctx.accounts.protocol_state.paused, ShadowProtocolError::ProtocolPaused);
+    
+    fail_over_computation(&mut ctx.accounts.auction, &ctx.accounts.cluster_registry)?;
+    msg!("Computation for auction {} moved to the next cluster", auction_id);
+    
+    Ok(())
+}
+
+/// Move an ended auction's timed-out computation to the next healthy cluster
+pub(crate) fn fail_over_computation(
+    auction: &mut AuctionAccount,
+    registry: &ClusterRegistry,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(
+        auction.status == AuctionStatus::Ended,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    let computation_id = auction
+        .mpc_computation_id
+        .ok_or(ShadowProtocolError::InvalidComputationId)?;
+    let queued_at = auction
+        .computation_queued_at
+        .ok_or(ShadowProtocolError::InvalidComputationId)?;
+    require!(
+        clock.unix_timestamp >= queued_at.saturating_add(registry.retry_timeout),
+        ShadowProtocolError::ComputationNotTimedOut
+    );
+    
+    let previous_cluster = auction.mxe_cluster;
+    let mxe_cluster = registry
+        .next_healthy(previous_cluster)
+        .ok_or(ShadowProtocolError::NoHealthyCluster)?;
+    auction.mxe_cluster = Some(mxe_cluster);
+    auction.computation_queued_at = Some(clock.unix_timestamp);
+    
+    emit!(MpcComputationRetried {
+        auction_id: auction.auction_id,
+        computation_id,
+        previous_cluster,
+        mxe_cluster,
+        retried_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct MxeClustersUpdated {
+    pub clusters: Vec<Pubkey>,
+    pub retry_timeout: i64,
+}
+
+#[event]
+pub struct MxeClusterHealthUpdated {
+    pub cluster: Pubkey,
+    pub healthy: bool,
+}
+
+#[event]
+pub struct MpcComputationRetried {
+    pub auction_id: u64,
+    pub computation_id: [u8; 32],
+    pub previous_cluster: Option<Pubkey>,
+    pub mxe_cluster: Pubkey,
+    pub retried_at: i64,
+}
+
+#[derive(Accounts)]
+pub struct SetMxeClusters<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ClusterRegistry::INIT_SPACE,
+        seeds = [CLUSTER_REGISTRY_SEED],
+        bump
+    )]
+    pub cluster_registry: Account<'info, ClusterRegistry>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMxeClusterHealth<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [CLUSTER_REGISTRY_SEED],
+        bump = cluster_registry.bump
+    )]
+    pub cluster_registry: Account<'info, ClusterRegistry>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct RetryMpcComputation<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [CLUSTER_REGISTRY_SEED],
+        bump = cluster_registry.bump
+    )]
+    pub cluster_registry: Account<'info, ClusterRegistry>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
@@ -0,0 +1,641 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::system_program;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer, CloseAccount, close_account};
+use anchor_spl::token::spl_token::native_mint;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::bid_position::{bid_holder, require_refund_account};
+
+/// Return a bidder's escrowed collateral once the auction is over.
+///
+/// Losing bidders may reclaim after settlement or cancellation. The winner
+/// only gets collateral back once they have paid; until then it stays
+/// available to `slash_collateral`. When the bid has a position, its holder
+/// reclaims instead, into a collateral account of their own.
+pub fn refund_collateral(ctx: Context<RefundCollateral>, auction_id: u64) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let bid = &ctx.accounts.bid;
+    
+    let holder = bid_holder(
+        bid.bidder,
+        &ctx.accounts.position_mint,
+        ctx.accounts.position_account.as_ref(),
+    )?;
+    require!(
+        ctx.accounts.bidder.key() == holder,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        auction.status == AuctionStatus::AssetsDistributed || auction.status == AuctionStatus::Cancelled,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    if auction.winner == Some(bid.bidder) {
+        require!(
+            auction.status == AuctionStatus::AssetsDistributed,
+            ShadowProtocolError::InvalidAuctionStatus
+        );
+    }
+    
+    require!(
+        auction.open_yield_positions == 0,
+        ShadowProtocolError::YieldPositionsOpen
+    );
+    
+    let (mint, amount) = if bid.sol_collateral {
+        let sol_escrow = ctx.accounts.sol_escrow.as_ref()
+            .ok_or(ShadowProtocolError::InvalidCollateralEscrow)?;
+        let amount = release_sol_escrow(
+            auction_id,
+            bid,
+            sol_escrow,
+            &ctx.accounts.bidder.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+        (native_mint::ID, amount)
+    } else {
+        let (bid_escrow, bidder_collateral_account) = ctx.accounts.bid_escrow.as_ref()
+            .zip(ctx.accounts.bidder_collateral_account.as_ref())
+            .ok_or(ShadowProtocolError::InvalidCollateralEscrow)?;
+        require_refund_account(bid, holder, bidder_collateral_account, bid_escrow.mint)?;
+        let amount = bid_escrow.amount;
+        release_escrow(
+            auction_id,
+            bid,
+            bid_escrow,
+            bidder_collateral_account,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        (bid_escrow.mint, amount)
+    };
+    
+    emit!(CollateralRefunded {
+        auction_id,
+        bidder: bid.bidder,
+        mint,
+        amount,
+    });
+    
+    Ok(())
+}
+
+/// Return the collateral of an encrypted Dutch claim that can no longer win,
+/// without waiting for the auction to be settled: the claim was resolved
+/// and not accepted, or another claim has already won.
+pub fn refund_dutch_claim(ctx: Context<RefundDutchClaim>, auction_id: u64) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let bid = &ctx.accounts.bid;
+    
+    let won_by_other = auction.winner.is_some() && auction.winner != Some(bid.bidder);
+    require!(
+        !bid.is_winner && (bid.claim_resolved || won_by_other),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    let bid_escrow = &ctx.accounts.bid_escrow;
+    let amount = bid_escrow.amount;
+    release_escrow(
+        auction_id,
+        bid,
+        bid_escrow,
+        &ctx.accounts.bidder_collateral_account,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+    
+    emit!(CollateralRefunded {
+        auction_id,
+        bidder: bid.bidder,
+        mint: bid_escrow.mint,
+        amount,
+    });
+    
+    Ok(())
+}
+
+/// Forfeit the winner's collateral to the creator when the winner lets the
+/// settlement deadline pass without paying.
+///
+/// Permissionless. The deadline is the dispute window plus the cleanup
+/// grace period, counted from when settlement was authorized. Still
+/// possible after cleanup has cancelled the unpaid auction.
+pub fn slash_collateral(ctx: Context<SlashCollateral>, auction_id: u64) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let bid = &ctx.accounts.bid;
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(
+        auction.status == AuctionStatus::SettlementAuthorized || auction.status == AuctionStatus::Cancelled,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        auction.winner == Some(bid.bidder),
+        ShadowProtocolError::InvalidWinnerDetermination
+    );
+    require!(!auction.has_flag(AuctionAccount::DISPUTE_OPEN), ShadowProtocolError::SettlementDisputed);
+    require!(
+        !auction.has_flag(AuctionAccount::SETTLING),
+        ShadowProtocolError::SettlementInProgress
+    );
+    
+    let authorized_at = auction.settlement_authorized_at
+        .ok_or(ShadowProtocolError::SettlementNotAuthorized)?;
+    require!(
+        now >= authorized_at + protocol.dispute_window + protocol.cleanup_grace_period(),
+        ShadowProtocolError::SettlementDeadlineNotReached
+    );
+    
+    require!(
+        auction.open_yield_positions == 0,
+        ShadowProtocolError::YieldPositionsOpen
+    );
+    
+    let (mint, amount) = if bid.sol_collateral {
+        let (sol_escrow, creator) = ctx.accounts.sol_escrow.as_ref()
+            .zip(ctx.accounts.creator.as_ref())
+            .ok_or(ShadowProtocolError::InvalidCollateralEscrow)?;
+        let amount = release_sol_escrow(
+            auction_id,
+            bid,
+            sol_escrow,
+            &creator.to_account_info(),
+            &ctx.accounts.system_program,
+        )?;
+        (native_mint::ID, amount)
+    } else {
+        let (bid_escrow, creator_collateral_account) = ctx.accounts.bid_escrow.as_ref()
+            .zip(ctx.accounts.creator_collateral_account.as_ref())
+            .ok_or(ShadowProtocolError::InvalidCollateralEscrow)?;
+        require!(
+            creator_collateral_account.mint == bid_escrow.mint,
+            ShadowProtocolError::InvalidTokenAccount
+        );
+        let amount = bid_escrow.amount;
+        release_escrow(
+            auction_id,
+            bid,
+            bid_escrow,
+            creator_collateral_account,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        (bid_escrow.mint, amount)
+    };
+    
+    emit!(CollateralSlashed {
+        auction_id,
+        bidder: bid.bidder,
+        mint,
+        amount,
+        slashed_at: now,
+    });
+    
+    Ok(())
+}
+
+/// Deliver a `verify_bid_encryption` result that found a bid malformed,
+/// forfeiting its collateral to the creator and removing it from the auction.
+///
+/// Only possible before the bids are queued for settlement, so the bid never
+/// takes a circuit slot. The bid and its escrow are closed, their rent also
+/// going to the creator.
+pub fn slash_invalid_bid(
+    ctx: Context<SlashInvalidBid>,
+    auction_id: u64,
+    computation_id: [u8; 32],
+    result: Vec<u8>,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let bid = &ctx.accounts.bid;
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.authority.key() == protocol.callback_signer(),
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        matches!(auction.status, AuctionStatus::Active | AuctionStatus::Ended)
+            && auction.mpc_computation_id.is_none(),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(bid.budget.is_none(), ShadowProtocolError::BidAlreadyLinked);
+    require!(
+        auction.open_yield_positions == 0,
+        ShadowProtocolError::YieldPositionsOpen
+    );
+    
+    require!(
+        computation_id == generate_bid_verification_id(auction_id, bid.bidder, bid.timestamp),
+        ShadowProtocolError::InvalidComputationId
+    );
+    let (valid, verification_hash) = parse_bid_verification_result(&result)?;
+    require!(
+        verification_hash == compute_bid_verification_hash(auction_id, bid.bidder, valid),
+        ShadowProtocolError::MpcVerificationFailed
+    );
+    require!(!valid, ShadowProtocolError::BidEncryptionValid);
+    
+    let creator = ctx.accounts.creator.to_account_info();
+    let (mint, amount) = if bid.sol_collateral {
+        let sol_escrow = ctx.accounts.sol_escrow.as_ref()
+            .ok_or(ShadowProtocolError::InvalidCollateralEscrow)?;
+        let amount = release_sol_escrow(
+            auction_id,
+            bid,
+            sol_escrow,
+            &creator,
+            &ctx.accounts.system_program,
+        )?;
+        (native_mint::ID, amount)
+    } else {
+        let (bid_escrow, creator_collateral_account) = ctx.accounts.bid_escrow.as_ref()
+            .zip(ctx.accounts.creator_collateral_account.as_ref())
+            .ok_or(ShadowProtocolError::InvalidCollateralEscrow)?;
+        require!(
+            creator_collateral_account.mint == bid_escrow.mint,
+            ShadowProtocolError::InvalidTokenAccount
+        );
+        let amount = bid_escrow.amount;
+        release_escrow(
+            auction_id,
+            bid,
+            bid_escrow,
+            creator_collateral_account,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        
+        let auction_id_bytes = auction_id.to_le_bytes();
+        let bid_seeds = &[
+            BID_SEED,
+            auction_id_bytes.as_ref(),
+            bid.bidder.as_ref(),
+            &[bid.bump],
+        ];
+        let signer_seeds = &[&bid_seeds[..]];
+        
+        close_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: bid_escrow.to_account_info(),
+                    destination: creator.clone(),
+                    authority: bid.to_account_info(),
+                },
+                signer_seeds,
+            ),
+        )?;
+        (bid_escrow.mint, amount)
+    };
+    
+    auction.bid_count = auction.bid_count.saturating_sub(1);
+    
+    emit!(InvalidBidSlashed {
+        auction_id,
+        bidder: bid.bidder,
+        mint,
+        amount,
+        slashed_at: now,
+    });
+    
+    Ok(())
+}
+
+/// Computation id of the `verify_bid_encryption` run for a bid
+pub(crate) fn generate_bid_verification_id(auction_id: u64, bidder: Pubkey, submitted_at: i64) -> [u8; 32] {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_bid_verification");
+    data.extend_from_slice(&auction_id.to_le_bytes());
+    data.extend_from_slice(bidder.as_ref());
+    data.extend_from_slice(&submitted_at.to_le_bytes());
+    
+    hash(&data).to_bytes()
+}
+
+fn compute_bid_verification_hash(auction_id: u64, bidder: Pubkey, valid: bool) -> [u8; 32] {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_bid_verification_result");
+    data.extend_from_slice(&auction_id.to_le_bytes());
+    data.extend_from_slice(bidder.as_ref());
+    data.push(valid as u8);
+    
+    hash(&data).to_bytes()
+}
+
+/// `verify_bid_encryption` output: valid flag, verification hash
+fn parse_bid_verification_result(result: &[u8]) -> Result<(bool, [u8; 32])> {
+    require!(result.len() == 33, ShadowProtocolError::InvalidMpcResult);
+    let verification_hash = result[1..33].try_into()
+        .map_err(|_| ShadowProtocolError::InvalidMpcResult)?;
+    Ok((result[0] != 0, verification_hash))
+}
+
+/// Move `amount` out of a bid's escrow, signed by the bid account
+fn release_escrow<'info>(
+    auction_id: u64,
+    bid: &Account<'info, BidAccount>,
+    bid_escrow: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    
+    let auction_id_bytes = auction_id.to_le_bytes();
+    let bid_seeds = &[
+        BID_SEED,
+        auction_id_bytes.as_ref(),
+        bid.bidder.as_ref(),
+        &[bid.bump],
+    ];
+    let signer_seeds = &[&bid_seeds[..]];
+    
+    transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: bid_escrow.to_account_info(),
+                to: to.to_account_info(),
+                authority: bid.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+/// Empty a bid's SOL escrow into `to`, signed by the escrow PDA. Returns the
+/// lamports moved; the escrow account is gone afterwards.
+pub(crate) fn release_sol_escrow<'info>(
+    auction_id: u64,
+    bid: &Account<'info, BidAccount>,
+    sol_escrow: &UncheckedAccount<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<u64> {
+    let amount = sol_escrow.lamports();
+    if amount == 0 {
+        return Ok(0);
+    }
+    
+    let auction_id_bytes = auction_id.to_le_bytes();
+    let (escrow_key, escrow_bump) = Pubkey::find_program_address(
+        &[SOL_ESCROW_SEED, auction_id_bytes.as_ref(), bid.bidder.as_ref()],
+        &crate::ID,
+    );
+    require!(
+        sol_escrow.key() == escrow_key,
+        ShadowProtocolError::InvalidCollateralEscrow
+    );
+    
+    let escrow_seeds = &[
+        SOL_ESCROW_SEED,
+        auction_id_bytes.as_ref(),
+        bid.bidder.as_ref(),
+        &[escrow_bump],
+    ];
+    let signer_seeds = &[&escrow_seeds[..]];
+    
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: sol_escrow.to_account_info(),
+                to: to.clone(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+    
+    Ok(amount)
+}
+
+#[event]
+pub struct CollateralRefunded {
+    pub auction_id: u64,
+    pub bidder: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CollateralSlashed {
+    pub auction_id: u64,
+    pub bidder: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub slashed_at: i64,
+}
+
+#[event]
+pub struct InvalidBidSlashed {
+    pub auction_id: u64,
+    pub bidder: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub slashed_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct RefundCollateral<'info> {
+    /// The bidder, or the holder of the bid's position
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
+    /// CHECK: Position mint PDA of the bid; uninitialized unless one was minted
+    #[account(
+        seeds = [BID_POSITION_SEED, auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump
+    )]
+    pub position_mint: UncheckedAccount<'info>,
+    
+    /// Token account holding the bid's position; required when it has one
+    pub position_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump
+    )]
+    pub bid_escrow: Option<Account<'info, TokenAccount>>,
+    
+    /// The bid's collateral account, or any of the position holder's in the
+    /// collateral mint
+    #[account(mut)]
+    pub bidder_collateral_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Native-SOL collateral escrow; required instead of the token
+    /// accounts when the bid posted SOL
+    #[account(
+        mut,
+        seeds = [SOL_ESCROW_SEED, auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump
+    )]
+    pub sol_escrow: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct RefundDutchClaim<'info> {
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = auction.auction_type == AuctionType::Dutch @ ShadowProtocolError::InvalidAuctionType
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", auction_id.to_le_bytes().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bid_escrow: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = bid.collateral_account @ ShadowProtocolError::InvalidCollateralEscrow
+    )]
+    pub bidder_collateral_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SlashCollateral<'info> {
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump
+    )]
+    pub bid_escrow: Option<Account<'info, TokenAccount>>,
+    
+    /// Creator's account in the collateral mint
+    #[account(
+        mut,
+        token::authority = auction.creator
+    )]
+    pub creator_collateral_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Native-SOL collateral escrow; required instead of the token
+    /// accounts when the bid posted SOL
+    #[account(
+        mut,
+        seeds = [SOL_ESCROW_SEED, auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump
+    )]
+    pub sol_escrow: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Auction creator, receives slashed native-SOL collateral
+    #[account(mut, address = auction.creator @ ShadowProtocolError::Unauthorized)]
+    pub creator: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SlashInvalidBid<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = auction.auction_type == AuctionType::SealedBid @ ShadowProtocolError::InvalidAuctionType
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        close = creator,
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"bid_escrow", auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump
+    )]
+    pub bid_escrow: Option<Account<'info, TokenAccount>>,
+    
+    /// Creator's account in the collateral mint
+    #[account(
+        mut,
+        token::authority = auction.creator
+    )]
+    pub creator_collateral_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Native-SOL collateral escrow; required instead of the token
+    /// accounts when the bid posted SOL
+    #[account(
+        mut,
+        seeds = [SOL_ESCROW_SEED, auction_id.to_le_bytes().as_ref(), bid.bidder.as_ref()],
+        bump
+    )]
+    pub sol_escrow: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Auction creator, receives the forfeited collateral and rent
+    #[account(mut, address = auction.creator @ ShadowProtocolError::Unauthorized)]
+    pub creator: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+use crate::error::ShadowProtocolError;
+
+/// Arguments of the standardized compliance `check` instruction.
+///
+/// Compliance programs expose `check(wallet, amount, mint)` under the Anchor
+/// discriminator for `global:check` and fail the instruction to reject the
+/// wallet. The wallet is passed as the first (read-only) account, followed by
+/// any remaining accounts supplied to the calling instruction.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ComplianceCheckArgs {
+    pub wallet: Pubkey,
+    pub amount: u64,
+    pub mint: Pubkey,
+}
+
+/// Screen `wallet` through the auction's compliance program, if it has one
+pub(crate) fn check_compliance<'info>(
+    expected_program: Option<Pubkey>,
+    compliance_program: Option<&AccountInfo<'info>>,
+    wallet: &AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    mint: Pubkey,
+) -> Result<()> {
+    let Some(expected_program) = expected_program else {
+        return Ok(());
+    };
+    
+    let program = compliance_program.ok_or(ShadowProtocolError::InvalidComplianceProgram)?;
+    require!(
+        program.key() == expected_program && program.executable,
+        ShadowProtocolError::InvalidComplianceProgram
+    );
+    
+    let mut data = hash(b"global:check").to_bytes()[..8].to_vec();
+    ComplianceCheckArgs {
+        wallet: wallet.key(),
+        amount,
+        mint,
+    }.serialize(&mut data)?;
+    
+    let mut accounts = vec![AccountMeta::new_readonly(wallet.key(), false)];
+    accounts.extend(extra_accounts.iter().map(|info| AccountMeta::new_readonly(info.key(), false)));
+    
+    let mut infos = vec![wallet.clone()];
+    infos.extend(extra_accounts.iter().cloned());
+    infos.push(program.clone());
+    
+    invoke(
+        &Instruction {
+            program_id: expected_program,
+            accounts,
+            data,
+        },
+        &infos,
+    )?;
+    
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::circuit_breaker::record_breaker_failure;
+
+/// Open a tracking record for a queued MPC computation.
+///
+/// Permissionless: the computation must be the one currently queued on the
+/// given auction or batch, so keepers can track any outstanding job.
+pub fn track_computation(
+    ctx: Context<TrackComputation>,
+    computation_id: [u8; 32],
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    let (subject, mxe_cluster, queued_at) = match (&ctx.accounts.auction, &ctx.accounts.batch) {
+        (Some(auction), None) => {
+            require!(
+                auction.mpc_computation_id == Some(computation_id),
+                ShadowProtocolError::InvalidComputationId
+            );
+            (
+                ComputationSubject::Auction { auction_id: auction.auction_id },
+                auction.mxe_cluster,
+                auction.computation_queued_at,
+            )
+        }
+        (None, Some(batch)) => {
+            require!(
+                batch.computation_id == Some(computation_id),
+                ShadowProtocolError::InvalidComputationId
+            );
+            (ComputationSubject::Batch { batch_id: batch.batch_id }, None, None)
+        }
+        _ => return Err(ShadowProtocolError::InvalidRemainingAccounts.into()),
+    };
+    
+    let record = &mut ctx.accounts.computation_record;
+    record.computation_id = computation_id;
+    record.subject = subject;
+    record.mxe_cluster = mxe_cluster;
+    record.queued_at = queued_at;
+    record.tracked_at = clock.unix_timestamp;
+    record.status = ComputationStatus::Queued;
+    record.result_hash = None;
+    record.resolved_at = None;
+    record.payer = ctx.accounts.payer.key();
+    record.bump = ctx.bumps.computation_record;
+    
+    emit!(ComputationTracked {
+        computation_id,
+        subject,
+        tracked_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Mark a tracked computation as completed with the hash of its result
+pub fn complete_computation(
+    ctx: Context<ResolveComputation>,
+    computation_id: [u8; 32],
+    result_hash: [u8; 32],
+) -> Result<()> {
+    resolve_computation(ctx, computation_id, ComputationStatus::Completed, Some(result_hash))
+}
+
+/// Mark a tracked computation as failed
+pub fn fail_computation(
+    ctx: Context<ResolveComputation>,
+    computation_id: [u8; 32],
+) -> Result<()> {
+    resolve_computation(ctx, computation_id, ComputationStatus::Failed, None)
+}
+
+fn resolve_computation(
+    ctx: Context<ResolveComputation>,
+    computation_id: [u8; 32],
+    status: ComputationStatus,
+    result_hash: Option<[u8; 32]>,
+) -> Result<()> {
+    let record = &mut ctx.accounts.computation_record;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.callback_signer(),
+        ShadowProtocolError::Unauthorized
+    );
+    
+    require!(
+        record.status == ComputationStatus::Queued,
+        ShadowProtocolError::ComputationAlreadyResolved
+    );
+    
+    record.status = status;
+    record.result_hash = result_hash;
+    record.resolved_at = Some(clock.unix_timestamp);
+    
+    if status == ComputationStatus::Failed {
+        record_breaker_failure(&mut ctx.accounts.protocol_stats, protocol)?;
+    }
+    
+    emit!(ComputationResolved {
+        computation_id,
+        subject: record.subject,
+        status,
+        result_hash,
+        resolved_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ComputationTracked {
+    pub computation_id: [u8; 32],
+    pub subject: ComputationSubject,
+    pub tracked_at: i64,
+}
+
+#[event]
+pub struct ComputationResolved {
+    pub computation_id: [u8; 32],
+    pub subject: ComputationSubject,
+    pub status: ComputationStatus,
+    pub result_hash: Option<[u8; 32]>,
+    pub resolved_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(computation_id: [u8; 32])]
+pub struct TrackComputation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ComputationRecord::INIT_SPACE,
+        seeds = [COMPUTATION_RECORD_SEED, computation_id.as_ref()],
+        bump
+    )]
+    pub computation_record: Account<'info, ComputationRecord>,
+    
+    /// Auction the computation settles (exclusive with `batch`)
+    pub auction: Option<Account<'info, AuctionAccount>>,
+    
+    /// Batch the computation settles (exclusive with `auction`)
+    pub batch: Option<Account<'info, BatchSettlement>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(computation_id: [u8; 32])]
+pub struct ResolveComputation<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [COMPUTATION_RECORD_SEED, computation_id.as_ref()],
+        bump = computation_record.bump
+    )]
+    pub computation_record: Account<'info, ComputationRecord>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    /// Stats shard of the computation's subject; failures count toward its
+    /// circuit breaker
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, computation_record.subject.stats_shard().as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
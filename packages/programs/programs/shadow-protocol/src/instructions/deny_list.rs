@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::governance::{require_governance_execution, GovernanceAction};
+
+/// Create or update a wallet's deny-list entry.
+///
+/// Entries are consulted by bidding and settlement, so a wallet can be
+/// blocked protocol-wide without pausing everything. Clearing every flag
+/// leaves the entry in place; `remove_deny_list_entry` reclaims its rent.
+pub fn set_deny_list_entry(
+    ctx: Context<SetDenyListEntry>,
+    wallet: Pubkey,
+    flags: u8,
+) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        &ctx.accounts.protocol_state,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::SetDenyListEntry,
+    )?;
+    require!(
+        flags & !DenyListEntry::ALL == 0,
+        ShadowProtocolError::InvalidDenyListFlags
+    );
+    
+    let now = Clock::get()?.unix_timestamp;
+    let entry = &mut ctx.accounts.deny_list_entry;
+    ctx.accounts.audit_log.record(
+        AdminAction::SetDenyListEntry,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&(wallet, entry.flags))?,
+        AdminAuditLog::word_hash(&(wallet, flags))?,
+    )?;
+    entry.wallet = wallet;
+    entry.flags = flags;
+    entry.updated_at = now;
+    entry.bump = ctx.bumps.deny_list_entry;
+    
+    emit!(DenyListUpdated {
+        wallet,
+        flags,
+        updated_at: now,
+    });
+    
+    Ok(())
+}
+
+/// Drop a wallet from the deny-list and return the entry's rent
+pub fn remove_deny_list_entry(ctx: Context<RemoveDenyListEntry>, wallet: Pubkey) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        &ctx.accounts.protocol_state,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::RemoveDenyListEntry,
+    )?;
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::RemoveDenyListEntry,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&(wallet, ctx.accounts.deny_list_entry.flags))?,
+        AdminAuditLog::word_hash(&(wallet, 0u8))?,
+    )?;
+    
+    emit!(DenyListUpdated {
+        wallet,
+        flags: 0,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Fail if `entry`, the wallet's deny-list PDA, exists and carries `flag`.
+/// Callers pin the address with seeds; an uninitialized PDA means the
+/// wallet has never been listed.
+pub(crate) fn require_not_denied(entry: &AccountInfo, flag: u8) -> Result<()> {
+    if entry.owner != &crate::ID || entry.data_is_empty() {
+        return Ok(());
+    }
+    
+    let entry = DenyListEntry::try_deserialize(&mut &entry.try_borrow_data()?[..])?;
+    require!(!entry.denies(flag), ShadowProtocolError::WalletDenied);
+    
+    Ok(())
+}
+
+#[event]
+pub struct DenyListUpdated {
+    pub wallet: Pubkey,
+    pub flags: u8,
+    pub updated_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct SetDenyListEntry<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + DenyListEntry::INIT_SPACE,
+        seeds = [DENY_LIST_SEED, wallet.as_ref()],
+        bump
+    )]
+    pub deny_list_entry: Account<'info, DenyListEntry>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct RemoveDenyListEntry<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [DENY_LIST_SEED, wallet.as_ref()],
+        bump = deny_list_entry.bump,
+        close = authority
+    )]
+    pub deny_list_entry: Account<'info, DenyListEntry>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
@@ -0,0 +1,257 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::governance::{require_governance_execution, GovernanceAction};
+
+/// Default challenge period between settlement authorization and execution (24 hours)
+pub const DEFAULT_DISPUTE_WINDOW: i64 = 24 * 60 * 60;
+
+/// Upper bound for the configurable challenge period (7 days)
+pub const MAX_DISPUTE_WINDOW: i64 = 7 * 24 * 60 * 60;
+
+/// Default bond posted to open a dispute (0.1 SOL)
+pub const DEFAULT_DISPUTE_BOND: u64 = 100_000_000;
+
+/// Challenge an authorized settlement during the dispute window.
+///
+/// The disputer posts `protocol_state.dispute_bond` lamports, which are held
+/// by the dispute account. Execution of the settlement is frozen until the
+/// dispute is resolved.
+pub fn dispute_settlement(
+    ctx: Context<DisputeSettlement>,
+    auction_id: u64,
+    evidence_hash: [u8; 32],
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    
+    require!(
+        auction.status == AuctionStatus::SettlementAuthorized,
+        ShadowProtocolError::SettlementNotAuthorized
+    );
+    
+    require!(
+        !auction.has_flag(AuctionAccount::DISPUTE_OPEN),
+        ShadowProtocolError::SettlementDisputed
+    );
+    
+    let authorized_at = auction.settlement_authorized_at
+        .ok_or(ShadowProtocolError::DisputeWindowClosed)?;
+    require!(
+        clock.unix_timestamp < authorized_at + protocol.dispute_window,
+        ShadowProtocolError::DisputeWindowClosed
+    );
+    
+    let bond = protocol.dispute_bond;
+    if bond > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.disputer.to_account_info(),
+                    to: ctx.accounts.dispute.to_account_info(),
+                },
+            ),
+            bond,
+        )?;
+    }
+    
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.auction_id = auction_id;
+    dispute.disputer = ctx.accounts.disputer.key();
+    dispute.bond = bond;
+    dispute.evidence_hash = evidence_hash;
+    dispute.opened_at = clock.unix_timestamp;
+    dispute.bump = ctx.bumps.dispute;
+    
+    auction.set_flag(AuctionAccount::DISPUTE_OPEN, true);
+    
+    emit!(SettlementDisputed {
+        auction_id,
+        disputer: dispute.disputer,
+        bond,
+        evidence_hash,
+        opened_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Resolve an open dispute.
+///
+/// Upholding the dispute voids the settlement so the auction can be
+/// recomputed, and returns the bond. Rejecting it forfeits the bond to the
+/// protocol fee recipient and lets the settlement execute. Either way the
+/// dispute account is closed to the disputer.
+pub fn resolve_dispute(
+    ctx: Context<ResolveDispute>,
+    auction_id: u64,
+    uphold: bool,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let dispute = &ctx.accounts.dispute;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::ResolveDispute,
+    )?;
+    
+    require!(
+        auction.has_flag(AuctionAccount::DISPUTE_OPEN),
+        ShadowProtocolError::NoOpenDispute
+    );
+    
+    if uphold {
+        void_settlement(auction)?;
+    } else if dispute.bond > 0 {
+        dispute.sub_lamports(dispute.bond)?;
+        ctx.accounts.fee_recipient.add_lamports(dispute.bond)?;
+    }
+    
+    auction.set_flag(AuctionAccount::DISPUTE_OPEN, false);
+    
+    emit!(DisputeResolved {
+        auction_id,
+        disputer: dispute.disputer,
+        upheld: uphold,
+        bond: dispute.bond,
+        resolved_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Clear an authorized settlement so the auction can be computed again
+pub(crate) fn void_settlement(auction: &mut AuctionAccount) -> Result<()> {
+    auction.transition(AuctionStatus::Ended)?;
+    auction.settlement_attempt = auction.settlement_attempt.saturating_add(1);
+    auction.winner = None;
+    auction.winning_amount = 0;
+    auction.second_highest_bid = 0;
+    auction.set_flag(AuctionAccount::RESERVE_MET, false);
+    auction.participating_bids = 0;
+    auction.encrypted_winner = None;
+    auction.mpc_verification_hash = None;
+    auction.settlement_authorized_at = None;
+    auction.settled_at = None;
+    Ok(())
+}
+
+/// Fails while a dispute is open or the challenge period is still running
+pub(crate) fn require_dispute_window_passed(
+    auction: &AuctionAccount,
+    protocol: &ProtocolState,
+    now: i64,
+) -> Result<()> {
+    require!(
+        !auction.has_flag(AuctionAccount::DISPUTE_OPEN),
+        ShadowProtocolError::SettlementDisputed
+    );
+    
+    if let Some(authorized_at) = auction.settlement_authorized_at {
+        require!(
+            now >= authorized_at + protocol.dispute_window,
+            ShadowProtocolError::DisputeWindowOpen
+        );
+    }
+    
+    Ok(())
+}
+
+#[event]
+pub struct SettlementDisputed {
+    pub auction_id: u64,
+    pub disputer: Pubkey,
+    pub bond: u64,
+    pub evidence_hash: [u8; 32],
+    pub opened_at: i64,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub auction_id: u64,
+    pub disputer: Pubkey,
+    pub upheld: bool,
+    pub bond: u64,
+    pub resolved_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct DisputeSettlement<'info> {
+    #[account(mut)]
+    pub disputer: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        init,
+        payer = disputer,
+        space = 8 + SettlementDispute::INIT_SPACE,
+        seeds = [SETTLEMENT_DISPUTE_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, SettlementDispute>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ResolveDispute<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [SETTLEMENT_DISPUTE_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = dispute.bump,
+        has_one = disputer,
+        close = disputer
+    )]
+    pub dispute: Account<'info, SettlementDispute>,
+    
+    /// CHECK: Receives the dispute account rent, and the bond if upheld
+    #[account(mut)]
+    pub disputer: AccountInfo<'info>,
+    
+    /// CHECK: Receives forfeited bonds
+    #[account(mut, address = protocol_state.fee_recipient @ ShadowProtocolError::Unauthorized)]
+    pub fee_recipient: AccountInfo<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
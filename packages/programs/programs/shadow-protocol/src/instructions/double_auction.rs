@@ -0,0 +1,817 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Mint, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Open a periodic double-auction market between two mints.
+///
+/// Orders are collected for `round_duration` seconds, then cleared by the
+/// `clear_double_auction` circuit at a single uniform price.
+pub fn create_market(
+    ctx: Context<CreateMarket>,
+    market_id: u64,
+    round_duration: i64,
+) -> Result<()> {
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        (MIN_MARKET_ROUND_DURATION..=MAX_AUCTION_DURATION).contains(&round_duration),
+        ShadowProtocolError::InvalidRoundDuration
+    );
+    require!(
+        ctx.accounts.base_mint.key() != ctx.accounts.quote_mint.key(),
+        ShadowProtocolError::InvalidAssetAmount
+    );
+    
+    let market = &mut ctx.accounts.market;
+    market.market_id = market_id;
+    market.creator = ctx.accounts.creator.key();
+    market.base_mint = ctx.accounts.base_mint.key();
+    market.quote_mint = ctx.accounts.quote_mint.key();
+    market.base_vault = ctx.accounts.base_vault.key();
+    market.quote_vault = ctx.accounts.quote_vault.key();
+    market.round_duration = round_duration;
+    market.round = 0;
+    market.round_end = clock.unix_timestamp + round_duration;
+    market.order_count = 0;
+    market.orders_settled = 0;
+    market.status = MarketStatus::Collecting;
+    market.computation_id = None;
+    market.clearing_price = 0;
+    market.matched_volume = 0;
+    market.fills = Vec::new();
+    market.market_config = None;
+    market.bump = ctx.bumps.market;
+    
+    emit!(MarketCreated {
+        market_id,
+        creator: market.creator,
+        base_mint: market.base_mint,
+        quote_mint: market.quote_mint,
+        round_duration,
+        round_end: market.round_end,
+    });
+    
+    Ok(())
+}
+
+/// Run a market as a frequent batch auction on a fixed interval.
+///
+/// The current round keeps its close time, which becomes the first batch
+/// boundary; every later batch closes `batch_interval` seconds after the
+/// previous boundary regardless of when clearing actually ran.
+pub fn configure_market_batches(
+    ctx: Context<ConfigureMarketBatches>,
+    market_id: u64,
+    batch_interval: i64,
+    min_order_escrow: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    
+    require!(
+        ctx.accounts.creator.key() == market.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        market.market_config.is_none()
+            && market.status == MarketStatus::Collecting
+            && market.order_count == 0,
+        ShadowProtocolError::InvalidMarketStatus
+    );
+    require!(
+        (MIN_MARKET_ROUND_DURATION..=MAX_AUCTION_DURATION).contains(&batch_interval),
+        ShadowProtocolError::InvalidRoundDuration
+    );
+    
+    let config = &mut ctx.accounts.market_config;
+    config.market_id = market_id;
+    config.batch_interval = batch_interval;
+    config.genesis = market.round_end;
+    config.min_order_escrow = min_order_escrow;
+    config.batches_cleared = 0;
+    config.cumulative_volume = 0;
+    config.bump = ctx.bumps.market_config;
+    
+    market.round_duration = batch_interval;
+    market.market_config = Some(config.key());
+    
+    emit!(MarketBatchesConfigured {
+        market_id,
+        batch_interval,
+        genesis: config.genesis,
+        min_order_escrow,
+    });
+    
+    Ok(())
+}
+
+/// Encrypted order and its escrow
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MarketOrderParams {
+    pub side: OrderSide,
+    pub price_encrypted: [u8; 32],
+    pub quantity_encrypted: [u8; 32],
+    pub encryption_public_key: [u8; 32],
+    pub nonce: u128,
+    pub escrow_amount: u64,
+}
+
+/// Post an encrypted bid or ask for the current round.
+///
+/// Buyers escrow quote tokens covering `price * quantity`; sellers escrow the
+/// base tokens they offer. Underfunded orders are left unmatched by the circuit.
+pub fn submit_market_order(
+    ctx: Context<SubmitMarketOrder>,
+    market_id: u64,
+    params: MarketOrderParams,
+) -> Result<()> {
+    let MarketOrderParams {
+        side,
+        price_encrypted,
+        quantity_encrypted,
+        encryption_public_key,
+        nonce,
+        escrow_amount,
+    } = params;
+    
+    let market = &mut ctx.accounts.market;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        market.status == MarketStatus::Collecting && clock.unix_timestamp < market.round_end,
+        ShadowProtocolError::MarketRoundClosed
+    );
+    require!(
+        (market.order_count as usize) < MAX_MARKET_ORDERS,
+        ShadowProtocolError::MarketFull
+    );
+    require!(escrow_amount > 0, ShadowProtocolError::InsufficientCollateral);
+    require_market_config(market, ctx.accounts.market_config.as_deref())?;
+    if let Some(config) = &ctx.accounts.market_config {
+        require!(
+            escrow_amount >= config.min_order_escrow,
+            ShadowProtocolError::InsufficientCollateral
+        );
+    }
+    require!(
+        price_encrypted != [0u8; 32]
+            && quantity_encrypted != [0u8; 32]
+            && encryption_public_key != [0u8; 32],
+        ShadowProtocolError::InvalidEncryption
+    );
+    
+    // Buyers escrow quote, sellers escrow base
+    let (escrow_mint, vault) = match side {
+        OrderSide::Buy => (market.quote_mint, &ctx.accounts.quote_vault),
+        OrderSide::Sell => (market.base_mint, &ctx.accounts.base_vault),
+    };
+    require!(
+        ctx.accounts.owner_token_account.mint == escrow_mint,
+        ShadowProtocolError::InvalidTokenAccount
+    );
+    require!(
+        ctx.accounts.owner_token_account.amount >= escrow_amount,
+        ShadowProtocolError::InsufficientFunds
+    );
+    
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.owner_token_account.to_account_info(),
+                to: vault.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        escrow_amount,
+    )?;
+    
+    let order = &mut ctx.accounts.order;
+    order.market_id = market_id;
+    order.round = market.round;
+    order.order_index = market.order_count;
+    order.owner = ctx.accounts.owner.key();
+    order.side = side;
+    order.price_encrypted = price_encrypted;
+    order.quantity_encrypted = quantity_encrypted;
+    order.encryption_public_key = encryption_public_key;
+    order.nonce = nonce;
+    order.escrow_amount = escrow_amount;
+    order.submitted_at = clock.unix_timestamp;
+    order.bump = ctx.bumps.order;
+    
+    market.order_count += 1;
+    
+    emit!(MarketOrderSubmitted {
+        market_id,
+        round: order.round,
+        order_index: order.order_index,
+        owner: order.owner,
+        side,
+        escrow_amount,
+    });
+    
+    Ok(())
+}
+
+/// Close the current round and queue the clearing computation.
+/// A round with no orders simply rolls over to the next one.
+///
+/// Batch-scheduled markets can be cleared by anyone once the batch closes;
+/// other markets only by the protocol authority, acting as operator rather
+/// than through governance, since clearing a round changes no configuration.
+pub fn queue_market_clearing(
+    ctx: Context<QueueMarketClearing>,
+    market_id: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require_market_config(market, ctx.accounts.market_config.as_deref())?;
+    require!(
+        market.market_config.is_some() || ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        market.status == MarketStatus::Collecting,
+        ShadowProtocolError::InvalidMarketStatus
+    );
+    require!(
+        clock.unix_timestamp >= market.round_end,
+        ShadowProtocolError::MarketRoundNotEnded
+    );
+    
+    if market.order_count == 0 {
+        start_next_round(market, ctx.accounts.market_config.as_deref(), clock.unix_timestamp);
+        return Ok(());
+    }
+    
+    let computation_id = generate_market_clearing_id(market_id, market.round, market.order_count);
+    market.computation_id = Some(computation_id);
+    market.status = MarketStatus::ClearingQueued;
+    
+    emit!(MarketClearingQueued {
+        market_id,
+        round: market.round,
+        computation_id,
+        order_count: market.order_count,
+        queued_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Deliver the `clear_double_auction` result: clearing price, matched volume,
+/// one fill per order and the verification hash.
+pub fn market_clearing_callback(
+    ctx: Context<MarketClearingCallback>,
+    market_id: u64,
+    computation_id: [u8; 32],
+    result: Vec<u8>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.authority.key() == protocol.callback_signer(),
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        market.status == MarketStatus::ClearingQueued,
+        ShadowProtocolError::InvalidMarketStatus
+    );
+    require!(
+        market.computation_id == Some(computation_id),
+        ShadowProtocolError::InvalidComputationId
+    );
+    
+    let clearing = parse_market_clearing_result(&result, market.order_count as usize)?;
+    
+    let verification_hash = compute_market_clearing_hash(
+        market_id,
+        market.round,
+        clearing.clearing_price,
+        clearing.matched_volume,
+        &clearing.fills,
+    );
+    require!(
+        verification_hash == clearing.verification_hash,
+        ShadowProtocolError::MpcVerificationFailed
+    );
+    
+    market.clearing_price = clearing.clearing_price;
+    market.matched_volume = clearing.matched_volume;
+    market.fills = clearing.fills;
+    market.orders_settled = 0;
+    market.status = MarketStatus::Cleared;
+    
+    emit!(MarketCleared {
+        market_id,
+        round: market.round,
+        clearing_price: market.clearing_price,
+        matched_volume: market.matched_volume,
+        cleared_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Pay out one order of a cleared round and close it. Permissionless.
+///
+/// Buyers receive their filled base tokens plus unspent quote; sellers
+/// receive quote for their fill plus any unsold base. A fill the order's
+/// escrow cannot cover is dropped and the escrow refunded in full, so one bad
+/// order cannot hold up the round. Once every order is settled the market
+/// opens the next round.
+pub fn settle_market_order(
+    ctx: Context<SettleMarketOrder>,
+    market_id: u64,
+    order_index: u32,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let order = &ctx.accounts.order;
+    let clock = Clock::get()?;
+    
+    require!(
+        market.status == MarketStatus::Cleared,
+        ShadowProtocolError::InvalidMarketStatus
+    );
+    require_market_config(market, ctx.accounts.market_config.as_deref())?;
+    
+    let mut fill = market.fills
+        .get(order_index as usize)
+        .copied()
+        .ok_or(ShadowProtocolError::InvalidMpcResult)?;
+    let cost = fill.checked_mul(market.clearing_price);
+    
+    let payout = match (order.side, cost) {
+        (OrderSide::Buy, Some(cost)) => order.escrow_amount
+            .checked_sub(cost)
+            .map(|refund| (fill, refund)),
+        (OrderSide::Sell, Some(cost)) => order.escrow_amount
+            .checked_sub(fill)
+            .map(|unsold| (unsold, cost)),
+        _ => None,
+    };
+    let (base_out, quote_out) = match payout {
+        Some(payout) => payout,
+        None => {
+            fill = 0;
+            match order.side {
+                OrderSide::Buy => (0, order.escrow_amount),
+                OrderSide::Sell => (order.escrow_amount, 0),
+            }
+        }
+    };
+    
+    let market_id_bytes = market.market_id.to_le_bytes();
+    let market_seeds = &[
+        MARKET_SEED,
+        market_id_bytes.as_ref(),
+        &[market.bump],
+    ];
+    let signer_seeds = &[&market_seeds[..]];
+    
+    if base_out > 0 {
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.base_vault.to_account_info(),
+                    to: ctx.accounts.owner_base_account.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            base_out,
+        )?;
+    }
+    
+    if quote_out > 0 {
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.quote_vault.to_account_info(),
+                    to: ctx.accounts.owner_quote_account.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            quote_out,
+        )?;
+    }
+    
+    emit!(MarketOrderSettled {
+        market_id,
+        round: order.round,
+        order_index,
+        owner: order.owner,
+        side: order.side,
+        filled: fill,
+        price: market.clearing_price,
+    });
+    
+    market.orders_settled += 1;
+    if market.orders_settled == market.order_count {
+        if let Some(config) = ctx.accounts.market_config.as_deref_mut() {
+            config.batches_cleared += 1;
+            config.cumulative_volume = config.cumulative_volume.saturating_add(market.matched_volume);
+        }
+        start_next_round(market, ctx.accounts.market_config.as_deref(), clock.unix_timestamp);
+    }
+    
+    Ok(())
+}
+
+/// The config account must be passed exactly when the market has a batch schedule
+fn require_market_config(market: &DoubleAuctionMarket, config: Option<&MarketConfig>) -> Result<()> {
+    require!(
+        market.market_config.is_some() == config.is_some(),
+        ShadowProtocolError::InvalidMarketConfig
+    );
+    Ok(())
+}
+
+fn start_next_round(market: &mut DoubleAuctionMarket, config: Option<&MarketConfig>, now: i64) {
+    market.round += 1;
+    market.round_end = match config {
+        Some(config) => config.next_batch_end(now),
+        None => now + market.round_duration,
+    };
+    market.order_count = 0;
+    market.orders_settled = 0;
+    market.computation_id = None;
+    market.fills = Vec::new();
+    market.status = MarketStatus::Collecting;
+    
+    msg!("Market {} opened round {}", market.market_id, market.round);
+}
+
+fn generate_market_clearing_id(market_id: u64, round: u64, order_count: u32) -> [u8; 32] {
+    use anchor_lang::solana_program::hash::hash;
+    
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_market_clearing");
+    data.extend_from_slice(&market_id.to_le_bytes());
+    data.extend_from_slice(&round.to_le_bytes());
+    data.extend_from_slice(&order_count.to_le_bytes());
+    
+    hash(&data).to_bytes()
+}
+
+fn compute_market_clearing_hash(
+    market_id: u64,
+    round: u64,
+    clearing_price: u64,
+    matched_volume: u64,
+    fills: &[u64],
+) -> [u8; 32] {
+    use anchor_lang::solana_program::hash::hash;
+    
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_market_clearing_verification");
+    data.extend_from_slice(&market_id.to_le_bytes());
+    data.extend_from_slice(&round.to_le_bytes());
+    data.extend_from_slice(&clearing_price.to_le_bytes());
+    data.extend_from_slice(&matched_volume.to_le_bytes());
+    for fill in fills {
+        data.extend_from_slice(&fill.to_le_bytes());
+    }
+    
+    hash(&data).to_bytes()
+}
+
+fn parse_market_clearing_result(result: &[u8], order_count: usize) -> Result<MarketClearingResult> {
+    let fills_end = 16 + order_count * 8;
+    require!(result.len() == fills_end + 32, ShadowProtocolError::InvalidMpcResult);
+    
+    let clearing_price = u64::from_le_bytes(
+        result[0..8].try_into()
+            .map_err(|_| ShadowProtocolError::InvalidMpcResult)?
+    );
+    
+    let matched_volume = u64::from_le_bytes(
+        result[8..16].try_into()
+            .map_err(|_| ShadowProtocolError::InvalidMpcResult)?
+    );
+    
+    let fills = result[16..fills_end]
+        .chunks_exact(8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .collect::<Vec<u64>>();
+    
+    let verification_hash: [u8; 32] = result[fills_end..].try_into()
+        .map_err(|_| ShadowProtocolError::InvalidMpcResult)?;
+    
+    Ok(MarketClearingResult {
+        clearing_price,
+        matched_volume,
+        fills,
+        verification_hash,
+    })
+}
+
+#[derive(Debug)]
+pub struct MarketClearingResult {
+    pub clearing_price: u64,
+    pub matched_volume: u64,
+    pub fills: Vec<u64>,
+    pub verification_hash: [u8; 32],
+}
+
+#[event]
+pub struct MarketCreated {
+    pub market_id: u64,
+    pub creator: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub round_duration: i64,
+    pub round_end: i64,
+}
+
+#[event]
+pub struct MarketBatchesConfigured {
+    pub market_id: u64,
+    pub batch_interval: i64,
+    pub genesis: i64,
+    pub min_order_escrow: u64,
+}
+
+#[event]
+pub struct MarketOrderSubmitted {
+    pub market_id: u64,
+    pub round: u64,
+    pub order_index: u32,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub escrow_amount: u64,
+}
+
+#[event]
+pub struct MarketClearingQueued {
+    pub market_id: u64,
+    pub round: u64,
+    pub computation_id: [u8; 32],
+    pub order_count: u32,
+    pub queued_at: i64,
+}
+
+#[event]
+pub struct MarketCleared {
+    pub market_id: u64,
+    pub round: u64,
+    pub clearing_price: u64,
+    pub matched_volume: u64,
+    pub cleared_at: i64,
+}
+
+#[event]
+pub struct MarketOrderSettled {
+    pub market_id: u64,
+    pub round: u64,
+    pub order_index: u32,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub filled: u64,
+    pub price: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct CreateMarket<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + DoubleAuctionMarket::INIT_SPACE,
+        seeds = [MARKET_SEED, market_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub market: Account<'info, DoubleAuctionMarket>,
+    
+    pub base_mint: Account<'info, Mint>,
+    
+    pub quote_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = creator,
+        token::mint = base_mint,
+        token::authority = market,
+        seeds = [MARKET_BASE_VAULT_SEED, market_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub base_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = creator,
+        token::mint = quote_mint,
+        token::authority = market,
+        seeds = [MARKET_QUOTE_VAULT_SEED, market_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct ConfigureMarketBatches<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [MARKET_SEED, market_id.to_le_bytes().as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, DoubleAuctionMarket>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MarketConfig::INIT_SPACE,
+        seeds = [MARKET_CONFIG_SEED, market_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub market_config: Account<'info, MarketConfig>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct SubmitMarketOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [MARKET_SEED, market_id.to_le_bytes().as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, DoubleAuctionMarket>,
+    
+    #[account(
+        seeds = [MARKET_CONFIG_SEED, market_id.to_le_bytes().as_ref()],
+        bump = market_config.bump
+    )]
+    pub market_config: Option<Account<'info, MarketConfig>>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + MarketOrder::INIT_SPACE,
+        seeds = [
+            MARKET_ORDER_SEED,
+            market_id.to_le_bytes().as_ref(),
+            market.round.to_le_bytes().as_ref(),
+            market.order_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub order: Account<'info, MarketOrder>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    /// Owner's quote account for buys, base account for sells
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == owner.key() @ ShadowProtocolError::InvalidTokenAccount
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = market.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = market.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct QueueMarketClearing<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [MARKET_SEED, market_id.to_le_bytes().as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, DoubleAuctionMarket>,
+    
+    #[account(
+        seeds = [MARKET_CONFIG_SEED, market_id.to_le_bytes().as_ref()],
+        bump = market_config.bump
+    )]
+    pub market_config: Option<Account<'info, MarketConfig>>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct MarketClearingCallback<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [MARKET_SEED, market_id.to_le_bytes().as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, DoubleAuctionMarket>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+#[instruction(market_id: u64, order_index: u32)]
+pub struct SettleMarketOrder<'info> {
+    #[account(
+        mut,
+        seeds = [MARKET_SEED, market_id.to_le_bytes().as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, DoubleAuctionMarket>,
+    
+    #[account(
+        mut,
+        seeds = [MARKET_CONFIG_SEED, market_id.to_le_bytes().as_ref()],
+        bump = market_config.bump
+    )]
+    pub market_config: Option<Account<'info, MarketConfig>>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            MARKET_ORDER_SEED,
+            market_id.to_le_bytes().as_ref(),
+            market.round.to_le_bytes().as_ref(),
+            order_index.to_le_bytes().as_ref()
+        ],
+        bump = order.bump
+    )]
+    pub order: Account<'info, MarketOrder>,
+    
+    /// CHECK: Order owner, receives the order account's rent
+    #[account(mut, address = order.owner)]
+    pub owner: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        token::mint = market.base_mint,
+        token::authority = order.owner
+    )]
+    pub owner_base_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = market.quote_mint,
+        token::authority = order.owner
+    )]
+    pub owner_quote_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = market.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = market.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,245 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::bidding::calculate_dutch_price_at;
+use crate::instructions::compliance::check_compliance;
+use crate::instructions::deny_list::require_not_denied;
+use crate::instructions::auction_house::require_house_open;
+use crate::instructions::circuit_breaker::record_breaker_volume;
+
+/// Buy one unit of a tranched Dutch auction at the current price.
+///
+/// `max_price` is the most the buyer will pay; the unit goes at the price on
+/// the curve when the transaction lands. Each purchase settles on the spot:
+/// the unit is delivered and the price, less the protocol fee, paid to the
+/// creator. The sale ends once the last unit sells; units left at the end
+/// time go back to the creator through `expire_dutch_auction`.
+pub fn buy_dutch_tranche<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BuyDutchTranche<'info>>,
+    auction_id: u64,
+    max_price: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let buyer = ctx.accounts.buyer.key();
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require_not_denied(&ctx.accounts.buyer_deny_entry, DenyListEntry::BIDDING)?;
+    require_house_open(auction, ctx.accounts.auction_house.as_ref())?;
+    // Each unit settles as it sells, so a tripped breaker stops sales too
+    ctx.accounts.protocol_stats.require_settlements_open()?;
+    
+    let mut sale = ctx.accounts.features.tranches.ok_or(ShadowProtocolError::InvalidAuctionType)?;
+    require!(
+        auction.status == AuctionStatus::Active && sale.units_remaining > 0,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(!auction.has_ended(&clock), ShadowProtocolError::AuctionEnded);
+    require!(
+        ctx.accounts.buyer_payment_account.mint == sale.payment_mint,
+        ShadowProtocolError::InvalidTokenAccount
+    );
+    
+    let now = auction.now(&clock);
+    let price = calculate_dutch_price_at(auction, now)?;
+    require!(price <= max_price, ShadowProtocolError::DutchPriceNotMet);
+    
+    check_compliance(
+        auction.compliance_program,
+        ctx.accounts.compliance_program.as_ref().map(|program| program.as_ref()),
+        &ctx.accounts.buyer.to_account_info(),
+        ctx.remaining_accounts,
+        price,
+        sale.payment_mint,
+    )?;
+    
+    let fee_amount = price
+        .checked_mul(auction.protocol_fee_bps(protocol.protocol_fee) as u64)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?
+        / 10000;
+    
+    for (to, amount) in [
+        (ctx.accounts.creator_payment_account.to_account_info(), price - fee_amount),
+        (ctx.accounts.protocol_fee_account.to_account_info(), fee_amount),
+    ] {
+        if amount > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.buyer_payment_account.to_account_info(),
+                        to,
+                        authority: ctx.accounts.buyer.to_account_info(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+    }
+    
+    let auction_seeds = &[
+        AUCTION_SEED,
+        auction.pda_seed(),
+        &[auction.bump],
+    ];
+    let signer_seeds = &[&auction_seeds[..]];
+    
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.asset_vault.to_account_info(),
+                to: ctx.accounts.buyer_asset_account.to_account_info(),
+                authority: auction.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        sale.unit_amount,
+    )?;
+    auction.record_vault_withdrawal(sale.unit_amount)?;
+    
+    sale.units_remaining -= 1;
+    sale.proceeds = sale.proceeds.saturating_add(price);
+    ctx.accounts.features.tranches = Some(sale);
+    auction.bid_count += 1;
+    if sale.units_remaining == 0 {
+        auction.transition(AuctionStatus::Ended)?;
+    }
+    
+    let claim = &mut ctx.accounts.tranche_claim;
+    if claim.units == 0 {
+        claim.auction_id = auction_id;
+        claim.buyer = buyer;
+        claim.bump = ctx.bumps.tranche_claim;
+    }
+    claim.units += 1;
+    claim.total_paid = claim.total_paid.saturating_add(price);
+    claim.last_price = price;
+    
+    let fee_ledger = &mut ctx.accounts.fee_ledger;
+    fee_ledger.open(sale.payment_mint, ctx.bumps.fee_ledger);
+    fee_ledger.accrue(fee_amount, clock.unix_timestamp);
+    
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.bids_submitted = stats.bids_submitted.saturating_add(1);
+    stats.record_settlement(sale.payment_mint, price, fee_amount);
+    record_breaker_volume(stats, protocol, sale.payment_mint, price)?;
+    
+    emit!(DutchTrancheSold {
+        auction_id,
+        buyer,
+        price,
+        unit_amount: sale.unit_amount,
+        units_remaining: sale.units_remaining,
+        buyer_units: claim.units,
+        sold_at: now,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct DutchTrancheSold {
+    pub auction_id: u64,
+    pub buyer: Pubkey,
+    pub price: u64,
+    pub unit_amount: u64,
+    pub units_remaining: u32,
+    /// Units this buyer now holds from the sale
+    pub buyer_units: u32,
+    /// In the auction's timing mode
+    pub sold_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct BuyDutchTranche<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    /// CHECK: Buyer's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, buyer.key().as_ref()], bump)]
+    pub buyer_deny_entry: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = auction.auction_type == AuctionType::Dutch @ ShadowProtocolError::InvalidAuctionType
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Holds the sale state
+    #[account(
+        mut,
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    /// Units the buyer has taken from this sale
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + TrancheClaim::INIT_SPACE,
+        seeds = [TRANCHE_CLAIM_SEED, auction_id.to_le_bytes().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub tranche_claim: Account<'info, TrancheClaim>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    #[account(mut, address = auction.asset_vault @ ShadowProtocolError::InvalidTokenAccount)]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    /// Receives the unit
+    #[account(mut, token::mint = auction.asset_mint)]
+    pub buyer_asset_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, token::authority = buyer)]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = buyer_payment_account.mint,
+        token::authority = auction.creator
+    )]
+    pub creator_payment_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = protocol_state.fee_recipient
+    )]
+    pub protocol_fee_account: Account<'info, TokenAccount>,
+    
+    /// Protocol fees accrued in the payment mint
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + FeeLedger::INIT_SPACE,
+        seeds = [FEE_LEDGER_SEED, buyer_payment_account.mint.as_ref()],
+        bump
+    )]
+    pub fee_ledger: Account<'info, FeeLedger>,
+    
+    /// CHECK: Matched against the auction's compliance program; required when one is set
+    pub compliance_program: Option<UncheckedAccount<'info>>,
+    
+    /// The auction's house; required when it was listed through one
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
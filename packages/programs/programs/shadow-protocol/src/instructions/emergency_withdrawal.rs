@@ -0,0 +1,327 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::governance::{require_governance_execution, GovernanceAction};
+
+/// Announce the recovery of `amount` from an auction's asset vault to
+/// `recipient_account` (first step). For catastrophic bugs that leave assets
+/// stuck; executable only after EMERGENCY_WITHDRAW_DELAY, giving the creator
+/// and bidders time to react. `reason` is an off-chain incident reference.
+///
+/// This is the one path allowed past the vault lock: it exists for assets
+/// stuck in live auctions, and is held back by the delay and governance.
+pub fn announce_emergency_withdraw(
+    ctx: Context<AnnounceEmergencyWithdrawal>,
+    auction_id: u64,
+    amount: u64,
+    reason: [u8; 32],
+) -> Result<()> {
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::EmergencyWithdrawal,
+    )?;
+    
+    require!(amount > 0, ShadowProtocolError::InvalidAssetAmount);
+    require!(
+        ctx.accounts.asset_vault.amount >= amount,
+        ShadowProtocolError::InsufficientFunds
+    );
+    
+    let recipient = ctx.accounts.recipient_account.key();
+    let executable_at = clock.unix_timestamp + EMERGENCY_WITHDRAW_DELAY;
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::AnnounceEmergencyWithdrawal,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(auction_id),
+        AdminAuditLog::word_hash(&(recipient, amount, reason))?,
+    )?;
+    
+    let withdrawal = &mut ctx.accounts.withdrawal;
+    withdrawal.auction_id = auction_id;
+    withdrawal.authority = ctx.accounts.authority.key();
+    withdrawal.recipient = recipient;
+    withdrawal.amount = amount;
+    withdrawal.reason = reason;
+    withdrawal.announced_at = clock.unix_timestamp;
+    withdrawal.executable_at = executable_at;
+    withdrawal.executed_at = None;
+    withdrawal.bump = ctx.bumps.withdrawal;
+    
+    emit!(EmergencyWithdrawalAnnounced {
+        auction_id,
+        recipient,
+        amount,
+        reason,
+        executable_at,
+    });
+    
+    Ok(())
+}
+
+/// Move the announced amount out of the vault once the delay has elapsed
+/// (second step). The withdrawal account stays behind as the record.
+pub fn emergency_withdraw(
+    ctx: Context<EmergencyWithdraw>,
+    auction_id: u64,
+) -> Result<()> {
+    let protocol = &ctx.accounts.protocol_state;
+    let auction = &mut ctx.accounts.auction;
+    let withdrawal = &mut ctx.accounts.withdrawal;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::EmergencyWithdrawal,
+    )?;
+    
+    require!(
+        withdrawal.executed_at.is_none(),
+        ShadowProtocolError::EmergencyWithdrawalExecuted
+    );
+    require!(
+        clock.unix_timestamp >= withdrawal.executable_at,
+        ShadowProtocolError::ChangeDelayNotElapsed
+    );
+    require!(
+        ctx.accounts.asset_vault.amount >= withdrawal.amount,
+        ShadowProtocolError::InsufficientFunds
+    );
+    
+    let auction_seeds = &[
+        AUCTION_SEED,
+        auction.pda_seed(),
+        &[auction.bump],
+    ];
+    let signer_seeds = &[&auction_seeds[..]];
+    
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.asset_vault.to_account_info(),
+                to: ctx.accounts.recipient_account.to_account_info(),
+                authority: auction.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        withdrawal.amount,
+    )?;
+    // Recovered assets may include surplus the deposit never counted
+    auction.vault_balance = auction.vault_balance.saturating_sub(withdrawal.amount);
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::ExecuteEmergencyWithdrawal,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(auction_id),
+        AdminAuditLog::word_u64(withdrawal.amount),
+    )?;
+    withdrawal.executed_at = Some(clock.unix_timestamp);
+    
+    emit!(EmergencyWithdrawalExecuted {
+        auction_id,
+        recipient: withdrawal.recipient,
+        amount: withdrawal.amount,
+        executed_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Withdraw an announcement that has not been executed, closing its account
+pub fn cancel_emergency_withdraw(
+    ctx: Context<CancelEmergencyWithdrawal>,
+    auction_id: u64,
+) -> Result<()> {
+    let protocol = &ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::EmergencyWithdrawal,
+    )?;
+    
+    require!(
+        ctx.accounts.withdrawal.executed_at.is_none(),
+        ShadowProtocolError::EmergencyWithdrawalExecuted
+    );
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::CancelEmergencyWithdrawal,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(auction_id),
+        AdminAuditLog::word_u64(0),
+    )?;
+    
+    emit!(EmergencyWithdrawalCancelled { auction_id });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct AnnounceEmergencyWithdrawal<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(address = auction.asset_vault)]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    #[account(token::mint = auction.asset_mint)]
+    pub recipient_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + EmergencyWithdrawal::INIT_SPACE,
+        seeds = [EMERGENCY_WITHDRAWAL_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub withdrawal: Account<'info, EmergencyWithdrawal>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct EmergencyWithdraw<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        address = auction.asset_vault
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = withdrawal.recipient
+    )]
+    pub recipient_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [EMERGENCY_WITHDRAWAL_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = withdrawal.bump
+    )]
+    pub withdrawal: Account<'info, EmergencyWithdrawal>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CancelEmergencyWithdrawal<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [EMERGENCY_WITHDRAWAL_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = withdrawal.bump
+    )]
+    pub withdrawal: Account<'info, EmergencyWithdrawal>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[event]
+pub struct EmergencyWithdrawalAnnounced {
+    pub auction_id: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub reason: [u8; 32],
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct EmergencyWithdrawalExecuted {
+    pub auction_id: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub executed_at: i64,
+}
+
+#[event]
+pub struct EmergencyWithdrawalCancelled {
+    pub auction_id: u64,
+}
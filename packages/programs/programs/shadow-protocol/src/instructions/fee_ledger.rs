@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::governance::{require_governance_execution, GovernanceAction};
+
+/// Record fees the treasury has moved out of the fee recipient in `mint`.
+///
+/// The fee recipient is not a program account, so withdrawals cannot be
+/// observed directly; the authority records each one here, bounded by what
+/// the ledger shows as outstanding.
+pub fn record_fee_withdrawal(ctx: Context<RecordFeeWithdrawal>, mint: Pubkey, amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        &ctx.accounts.protocol_state,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::RecordFeeWithdrawal,
+    )?;
+    require!(amount > 0, ShadowProtocolError::InvalidAssetAmount);
+    
+    let ledger = &mut ctx.accounts.fee_ledger;
+    require!(
+        amount <= ledger.outstanding(),
+        ShadowProtocolError::FeeWithdrawalExceedsOutstanding
+    );
+    
+    let withdrawn = ledger.withdrawn + amount;
+    ctx.accounts.audit_log.record(
+        AdminAction::RecordFeeWithdrawal,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&(mint, ledger.withdrawn))?,
+        AdminAuditLog::word_hash(&(mint, withdrawn))?,
+    )?;
+    ledger.withdrawn = withdrawn;
+    
+    emit!(FeeWithdrawalRecorded {
+        mint,
+        amount,
+        withdrawn,
+        outstanding: ledger.outstanding(),
+        recorded_at: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct FeeWithdrawalRecorded {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub withdrawn: u64,
+    pub outstanding: u64,
+    pub recorded_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct RecordFeeWithdrawal<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [FEE_LEDGER_SEED, mint.as_ref()],
+        bump = fee_ledger.bump
+    )]
+    pub fee_ledger: Account<'info, FeeLedger>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Size of the ed25519 program's per-signature offsets record
+const ED25519_OFFSETS_LEN: usize = 14;
+/// Instruction index meaning "data lives in the ed25519 instruction itself"
+const ED25519_CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Check a fee voucher presented at creation and return the fee it grants.
+///
+/// The instruction directly before this one must be an ed25519 program
+/// instruction verifying the protocol authority's signature over
+/// `voucher.message()`, with signature, key and message all inline.
+pub(crate) fn redeem_fee_voucher(
+    voucher: &FeeVoucher,
+    creator: Pubkey,
+    creator_nonce: u64,
+    protocol: &ProtocolState,
+    instructions_sysvar: Option<&AccountInfo>,
+) -> Result<u16> {
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(
+        voucher.creator == creator
+            && voucher.creator_nonce.map_or(true, |nonce| nonce == creator_nonce)
+            && voucher.fee_bps < protocol.protocol_fee,
+        ShadowProtocolError::InvalidFeeVoucher
+    );
+    require!(now < voucher.expires_at, ShadowProtocolError::FeeVoucherExpired);
+    
+    require_ed25519_signed(
+        instructions_sysvar,
+        &protocol.authority,
+        &voucher.message(),
+        ShadowProtocolError::InvalidFeeVoucher,
+    )?;
+    
+    Ok(voucher.fee_bps)
+}
+
+/// Require the instruction directly before this one to be an ed25519 program
+/// instruction verifying `signer`'s signature over `message`, failing with
+/// `error` otherwise.
+pub(crate) fn require_ed25519_signed(
+    instructions_sysvar: Option<&AccountInfo>,
+    signer: &Pubkey,
+    message: &[u8],
+    error: ShadowProtocolError,
+) -> Result<()> {
+    let instructions_sysvar = instructions_sysvar.ok_or(error)?;
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    if current_index == 0 {
+        return Err(error.into());
+    }
+    let ed25519_ix = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    
+    let verified = ed25519_ix.program_id == ed25519_program::ID
+        && ed25519_ix.accounts.is_empty()
+        && verified_ed25519_message(&ed25519_ix.data, signer) == Some(message);
+    if !verified {
+        return Err(error.into());
+    }
+    
+    Ok(())
+}
+
+/// Message of a single-signature ed25519 instruction signed by `signer`
+fn verified_ed25519_message<'a>(data: &'a [u8], signer: &Pubkey) -> Option<&'a [u8]> {
+    if data.len() < 2 + ED25519_OFFSETS_LEN || data[0] != 1 {
+        return None;
+    }
+    
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let public_key_offset = read_u16(6) as usize;
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    
+    // Signature, key and message must all come from this instruction's data
+    let inline = read_u16(4) == ED25519_CURRENT_INSTRUCTION
+        && read_u16(8) == ED25519_CURRENT_INSTRUCTION
+        && read_u16(14) == ED25519_CURRENT_INSTRUCTION;
+    let public_key = data.get(public_key_offset..public_key_offset + 32)?;
+    if !inline || public_key != signer.as_ref() {
+        return None;
+    }
+    
+    data.get(message_offset..message_offset + message_size)
+}
+
+#[event]
+pub struct FeeVoucherRedeemed {
+    pub auction_id: u64,
+    pub creator: Pubkey,
+    pub fee_bps: u16,
+    pub expires_at: i64,
+}
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::governance::{require_governance_execution, GovernanceAction};
+
+/// Set the gas schedule of the circuit at `comp_def_offset`.
+///
+/// Queueing a computation on that circuit then requires a gas limit of at
+/// least `base_gas + per_input_gas * inputs`, where inputs are the bids of a
+/// chunk or the chunks of a final round.
+pub fn set_gas_schedule(
+    ctx: Context<SetGasSchedule>,
+    comp_def_offset: u32,
+    base_gas: u64,
+    per_input_gas: u64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        &ctx.accounts.protocol_state,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::SetGasSchedule,
+    )?;
+    require!(base_gas > 0, ShadowProtocolError::InvalidGasSchedule);
+    
+    let now = Clock::get()?.unix_timestamp;
+    let schedule = &mut ctx.accounts.gas_schedule;
+    ctx.accounts.audit_log.record(
+        AdminAction::SetGasSchedule,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_hash(&(comp_def_offset, schedule.base_gas, schedule.per_input_gas))?,
+        AdminAuditLog::word_hash(&(comp_def_offset, base_gas, per_input_gas))?,
+    )?;
+    schedule.comp_def_offset = comp_def_offset;
+    schedule.base_gas = base_gas;
+    schedule.per_input_gas = per_input_gas;
+    schedule.updated_at = now;
+    schedule.bump = ctx.bumps.gas_schedule;
+    
+    emit!(GasScheduleUpdated {
+        comp_def_offset,
+        base_gas,
+        per_input_gas,
+        updated_at: now,
+    });
+    
+    Ok(())
+}
+
+/// Fail unless `schedule` belongs to the circuit being queued and `gas_limit`
+/// covers it for `inputs`. Returns the required limit.
+pub(crate) fn require_gas_limit(
+    schedule: &GasSchedule,
+    comp_def_offset: u32,
+    inputs: u32,
+    gas_limit: u64,
+) -> Result<u64> {
+    require!(
+        schedule.comp_def_offset == comp_def_offset,
+        ShadowProtocolError::InvalidGasSchedule
+    );
+    let required = schedule
+        .required_gas(inputs)
+        .ok_or(ShadowProtocolError::InsufficientGasLimit)?;
+    require!(gas_limit >= required, ShadowProtocolError::InsufficientGasLimit);
+    
+    Ok(required)
+}
+
+#[event]
+pub struct GasScheduleUpdated {
+    pub comp_def_offset: u32,
+    pub base_gas: u64,
+    pub per_input_gas: u64,
+    pub updated_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(comp_def_offset: u32)]
+pub struct SetGasSchedule<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + GasSchedule::INIT_SPACE,
+        seeds = [GAS_SCHEDULE_SEED, comp_def_offset.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub gas_schedule: Account<'info, GasSchedule>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,232 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Start handing protocol authority to a Realms governance.
+///
+/// Runs through the regular authority timelock, but completes only when the
+/// governance itself accepts through an executed proposal, proving it can
+/// actually sign for the protocol before the old key is dropped.
+pub fn propose_governance_authority(ctx: Context<ProposeGovernanceAuthority>, realm: Pubkey) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    let governance = &ctx.accounts.governance;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        protocol,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::ProposeGovernanceAuthority,
+    )?;
+    require!(
+        protocol.pending_authority.is_none(),
+        ShadowProtocolError::AuthorityTransferPending
+    );
+    
+    // Governance accounts start with the account type tag followed by the realm
+    require!(
+        ctx.accounts.governance_program.executable
+            && governance.owner == ctx.accounts.governance_program.key
+            && governance.try_borrow_data()?.get(1..33) == Some(realm.as_ref()),
+        ShadowProtocolError::InvalidGovernance
+    );
+    
+    protocol.governance = Some(GovernanceConfig {
+        program_id: ctx.accounts.governance_program.key(),
+        realm,
+        governance: governance.key(),
+    });
+    ctx.accounts.audit_log.record(
+        AdminAction::ProposeGovernanceAuthority,
+        ctx.accounts.authority.key(),
+        protocol.authority.to_bytes(),
+        governance.key().to_bytes(),
+    )?;
+    protocol.pending_authority = Some(governance.key());
+    protocol.authority_transfer_timelock = Some(clock.unix_timestamp + AUTHORITY_TRANSFER_TIMELOCK);
+    
+    msg!("Governance authority proposed: {} (realm {})", governance.key(), realm);
+    
+    Ok(())
+}
+
+/// Accept protocol authority from inside an executed governance proposal
+pub fn accept_governance_authority(ctx: Context<AcceptGovernanceAuthority>) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    let config = protocol.governance.ok_or(ShadowProtocolError::InvalidGovernance)?;
+    require!(
+        ctx.accounts.governance.key() == config.governance
+            && protocol.pending_authority == Some(config.governance),
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        protocol.authority_transfer_timelock.is_some_and(|expires| clock.unix_timestamp >= expires),
+        ShadowProtocolError::AuthorityTransferTimelockNotElapsed
+    );
+    
+    let proposal = executing_proposal(&config, &ctx.accounts.instructions_sysvar)?;
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::AcceptGovernanceAuthority,
+        config.governance,
+        protocol.authority.to_bytes(),
+        config.governance.to_bytes(),
+    )?;
+    protocol.authority = config.governance;
+    protocol.pending_authority = None;
+    protocol.authority_transfer_timelock = None;
+    
+    emit!(GovernanceProposalExecuted {
+        governance: config.governance,
+        proposal,
+        action: GovernanceAction::AcceptAuthority,
+        executed_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// When a governance holds the authority, check the instruction is being
+/// run by one of its executed proposals and log which one.
+pub(crate) fn require_governance_execution(
+    protocol: &ProtocolState,
+    instructions_sysvar: Option<&AccountInfo>,
+    action: GovernanceAction,
+) -> Result<()> {
+    let Some(config) = protocol.active_governance() else {
+        return Ok(());
+    };
+    
+    let instructions_sysvar = instructions_sysvar.ok_or(ShadowProtocolError::InvalidGovernance)?;
+    let proposal = executing_proposal(&config, instructions_sysvar)?;
+    
+    emit!(GovernanceProposalExecuted {
+        governance: config.governance,
+        proposal,
+        action,
+        executed_at: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Proposal whose transaction is executing, read from the top-level
+/// `ExecuteTransaction` instruction that invoked this program. Its accounts
+/// open with `[governance, proposal, proposal_transaction]`.
+fn executing_proposal(config: &GovernanceConfig, instructions_sysvar: &AccountInfo) -> Result<Pubkey> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let outer_ix = load_instruction_at_checked(current_index as usize, instructions_sysvar)?;
+    
+    require!(
+        outer_ix.program_id == config.program_id
+            && outer_ix.data.first() == Some(&GOVERNANCE_EXECUTE_TRANSACTION)
+            && outer_ix.accounts.len() >= 3
+            && outer_ix.accounts[0].pubkey == config.governance,
+        ShadowProtocolError::InvalidGovernance
+    );
+    
+    Ok(outer_ix.accounts[1].pubkey)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum GovernanceAction {
+    AcceptAuthority,
+    SetPauseState,
+    UpdateProtocolFee,
+    UpdateFeeRecipient,
+    UpdateFeeTiers,
+    UpdateCallbackAuthority,
+    EmergencyWithdrawal,
+    UpdateSettlementAuthorizer,
+    SetMxeClusters,
+    SetMxeClusterHealth,
+    UpdateCleanupGracePeriod,
+    UpdateDisputeConfig,
+    UpdateInsuranceFeeShare,
+    UpdateCreationDeposit,
+    UpdateYieldAdapter,
+    UpdateComplianceProgram,
+    UpdateAuctionBounds,
+    InitiateAuthorityTransfer,
+    CompleteAuthorityTransfer,
+    CancelAuthorityTransfer,
+    ProposeGovernanceAuthority,
+    SetDenyListEntry,
+    RemoveDenyListEntry,
+    SetGasSchedule,
+    InitializeStakingPool,
+    UpdateStakingFeeShare,
+    InitializeInsuranceFund,
+    PayInsuranceClaim,
+    RecordFeeWithdrawal,
+    UpdateCircuitBreaker,
+    ClearCircuitBreaker,
+    ResolveDispute,
+}
+
+#[event]
+pub struct GovernanceProposalExecuted {
+    pub governance: Pubkey,
+    pub proposal: Pubkey,
+    pub action: GovernanceAction,
+    pub executed_at: i64,
+}
+
+#[derive(Accounts)]
+pub struct ProposeGovernanceAuthority<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Governance account, checked against its program and realm
+    pub governance: UncheckedAccount<'info>,
+    
+    /// CHECK: SPL Governance program instance owning `governance`
+    pub governance_program: UncheckedAccount<'info>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptGovernanceAuthority<'info> {
+    /// Governance PDA, signing through an executed proposal
+    pub governance: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    /// CHECK: Instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
@@ -0,0 +1,200 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Mint, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::governance::{require_governance_execution, GovernanceAction};
+
+/// Default share of protocol fees routed to the insurance fund (10%)
+pub const DEFAULT_INSURANCE_FEE_SHARE: u16 = 1000;
+
+/// Create the insurance fund and its vault for a payment mint
+pub fn initialize_insurance_fund(ctx: Context<InitializeInsuranceFund>) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        &ctx.accounts.protocol_state,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::InitializeInsuranceFund,
+    )?;
+    
+    let fund = &mut ctx.accounts.insurance_fund;
+    fund.mint = ctx.accounts.mint.key();
+    fund.vault = ctx.accounts.insurance_vault.key();
+    fund.total_accrued = 0;
+    fund.total_paid = 0;
+    fund.claims_paid = 0;
+    fund.bump = ctx.bumps.insurance_fund;
+    
+    msg!("Insurance fund initialized for mint {}", fund.mint);
+    
+    Ok(())
+}
+
+/// Compensate a user harmed by a settlement failure or dispute
+pub fn pay_claim(
+    ctx: Context<PayClaim>,
+    amount: u64,
+    claim_reference: [u8; 32],
+) -> Result<()> {
+    let fund = &mut ctx.accounts.insurance_fund;
+    
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        &ctx.accounts.protocol_state,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::PayInsuranceClaim,
+    )?;
+    
+    require!(amount > 0, ShadowProtocolError::InvalidAssetAmount);
+    
+    require!(
+        ctx.accounts.insurance_vault.amount >= amount,
+        ShadowProtocolError::InsufficientFunds
+    );
+    
+    let mint_key = fund.mint;
+    let fund_seeds = &[
+        INSURANCE_FUND_SEED,
+        mint_key.as_ref(),
+        &[fund.bump],
+    ];
+    let signer_seeds = &[&fund_seeds[..]];
+    
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.insurance_vault.to_account_info(),
+                to: ctx.accounts.recipient_account.to_account_info(),
+                authority: fund.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::PayInsuranceClaim,
+        ctx.accounts.authority.key(),
+        claim_reference,
+        AdminAuditLog::word_u64(amount),
+    )?;
+    
+    fund.total_paid = fund.total_paid.saturating_add(amount);
+    fund.claims_paid = fund.claims_paid.saturating_add(1);
+    
+    emit!(InsuranceClaimPaid {
+        mint: fund.mint,
+        recipient: ctx.accounts.recipient_account.owner,
+        amount,
+        claim_reference,
+        paid_at: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Portion of `fee_amount` owed to the insurance fund
+pub(crate) fn insurance_share(fee_amount: u64, share_bps: u16) -> Result<u64> {
+    Ok(fee_amount
+        .checked_mul(share_bps as u64)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?
+        / 10000)
+}
+
+#[event]
+pub struct InsuranceClaimPaid {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub claim_reference: [u8; 32],
+    pub paid_at: i64,
+}
+
+#[derive(Accounts)]
+pub struct InitializeInsuranceFund<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + InsuranceFund::INIT_SPACE,
+        seeds = [INSURANCE_FUND_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint,
+        token::authority = insurance_fund,
+        seeds = [INSURANCE_VAULT_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub insurance_vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PayClaim<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    #[account(
+        mut,
+        seeds = [INSURANCE_FUND_SEED, insurance_fund.mint.as_ref()],
+        bump = insurance_fund.bump
+    )]
+    pub insurance_fund: Account<'info, InsuranceFund>,
+    
+    #[account(
+        mut,
+        address = insurance_fund.vault
+    )]
+    pub insurance_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = insurance_fund.mint
+    )]
+    pub recipient_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,494 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_spl::token::{Token, TokenAccount, Mint, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::bidding::calculate_dutch_price_at;
+use crate::instructions::deny_list::require_not_denied;
+use crate::instructions::auction_house::require_house_open;
+use crate::instructions::circuit_breaker::record_breaker_volume;
+
+/// Queue an encrypted purchase on a liquidity bootstrapping sale.
+///
+/// The buyer escrows the most they are willing to spend; the quantity and
+/// price limit stay encrypted and are evaluated by `process_lbp_purchase`
+/// against the lot price at submission and the sale's demand so far.
+pub fn submit_lbp_purchase(
+    ctx: Context<SubmitLbpPurchase>,
+    auction_id: u64,
+    quantity_encrypted: [u8; 32],
+    max_price_encrypted: [u8; 32],
+    encryption_public_key: [u8; 32],
+    nonce: u128,
+    escrow_amount: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require_not_denied(&ctx.accounts.buyer_deny_entry, DenyListEntry::BIDDING)?;
+    require_house_open(auction, ctx.accounts.auction_house.as_ref())?;
+    
+    require!(
+        auction.status == AuctionStatus::Active && !auction.has_ended(&clock),
+        ShadowProtocolError::AuctionEnded
+    );
+    
+    let mut sale = ctx.accounts.features.lbp.ok_or(ShadowProtocolError::InvalidAuctionType)?;
+    require!(
+        sale.tokens_sold < auction.asset_amount,
+        ShadowProtocolError::AuctionEnded
+    );
+    require!(
+        ctx.accounts.payment_mint.key() == sale.payment_mint,
+        ShadowProtocolError::InvalidTokenAccount
+    );
+    require!(escrow_amount > 0, ShadowProtocolError::InsufficientCollateral);
+    require!(
+        ctx.accounts.buyer_payment_account.amount >= escrow_amount,
+        ShadowProtocolError::InsufficientFunds
+    );
+    require!(
+        quantity_encrypted != [0u8; 32]
+            && max_price_encrypted != [0u8; 32]
+            && encryption_public_key != [0u8; 32],
+        ShadowProtocolError::InvalidEncryption
+    );
+    
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.buyer_payment_account.to_account_info(),
+                to: ctx.accounts.purchase_escrow.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info(),
+            },
+        ),
+        escrow_amount,
+    )?;
+    
+    let index = sale.purchases_submitted;
+    let submitted_at = auction.now(&clock);
+    
+    let purchase = &mut ctx.accounts.purchase;
+    purchase.auction_id = auction_id;
+    purchase.index = index;
+    purchase.buyer = ctx.accounts.buyer.key();
+    purchase.quantity_encrypted = quantity_encrypted;
+    purchase.max_price_encrypted = max_price_encrypted;
+    purchase.encryption_public_key = encryption_public_key;
+    purchase.nonce = nonce;
+    purchase.escrow_amount = escrow_amount;
+    purchase.buyer_asset_account = ctx.accounts.buyer_asset_account.key();
+    purchase.refund_account = ctx.accounts.buyer_payment_account.key();
+    purchase.submitted_at = submitted_at;
+    purchase.processed = false;
+    purchase.bump = ctx.bumps.purchase;
+    
+    ctx.accounts.fee_ledger.open(ctx.accounts.payment_mint.key(), ctx.bumps.fee_ledger);
+    
+    sale.purchases_submitted += 1;
+    ctx.accounts.features.lbp = Some(sale);
+    auction.bid_count += 1;
+    
+    emit!(LbpPurchaseQueued {
+        auction_id,
+        index,
+        buyer: purchase.buyer,
+        escrow_amount,
+        computation_id: generate_lbp_purchase_id(auction_id, index, submitted_at),
+        submitted_at,
+    });
+    
+    Ok(())
+}
+
+/// Deliver the `process_lbp_purchase` result for the next purchase in line.
+///
+/// A filled purchase receives its asset, the creator its cost less the
+/// protocol fee, and the buyer any unspent escrow. A rejected purchase is
+/// refunded in full and reveals nothing. The sale ends once sold out.
+pub fn lbp_purchase_callback(
+    ctx: Context<LbpPurchaseCallback>,
+    auction_id: u64,
+    index: u64,
+    computation_id: [u8; 32],
+    result: Vec<u8>,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let purchase = &mut ctx.accounts.purchase;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.authority.key() == protocol.callback_signer(),
+        ShadowProtocolError::Unauthorized
+    );
+    
+    let mut sale = ctx.accounts.features.lbp.ok_or(ShadowProtocolError::InvalidAuctionType)?;
+    require!(
+        index == sale.purchases_processed && !purchase.processed,
+        ShadowProtocolError::LbpPurchaseOutOfOrder
+    );
+    require!(
+        computation_id == generate_lbp_purchase_id(auction_id, index, purchase.submitted_at),
+        ShadowProtocolError::InvalidComputationId
+    );
+    
+    let fill = parse_lbp_fill_result(&result)?;
+    require!(
+        fill.verification_hash == compute_lbp_fill_hash(auction_id, index, fill.accepted, fill.quantity, fill.cost),
+        ShadowProtocolError::MpcVerificationFailed
+    );
+    
+    let (quantity, cost) = if fill.accepted {
+        let lot_price = calculate_dutch_price_at(auction, purchase.submitted_at)?;
+        let expected_cost = sale.fill_cost(lot_price, auction.asset_amount, fill.quantity)
+            .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+        require!(
+            fill.quantity > 0
+                && fill.quantity <= auction.asset_amount - sale.tokens_sold
+                && fill.cost == expected_cost
+                && fill.cost <= purchase.escrow_amount,
+            ShadowProtocolError::InvalidMpcResult
+        );
+        (fill.quantity, fill.cost)
+    } else {
+        (0, 0)
+    };
+    
+    let auction_id_bytes = auction_id.to_le_bytes();
+    let index_bytes = index.to_le_bytes();
+    let purchase_seeds = &[
+        LBP_PURCHASE_SEED,
+        auction_id_bytes.as_ref(),
+        index_bytes.as_ref(),
+        &[purchase.bump],
+    ];
+    let purchase_signer = &[&purchase_seeds[..]];
+    let auction_seeds = &[
+        AUCTION_SEED,
+        auction.pda_seed(),
+        &[auction.bump],
+    ];
+    let auction_signer = &[&auction_seeds[..]];
+    
+    let fee_amount = cost
+        .checked_mul(auction.protocol_fee_bps(protocol.protocol_fee) as u64)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?
+        / 10000;
+    let refund_amount = purchase.escrow_amount - cost;
+    
+    if quantity > 0 {
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.asset_vault.to_account_info(),
+                    to: ctx.accounts.buyer_asset_account.to_account_info(),
+                    authority: auction.to_account_info(),
+                },
+                auction_signer,
+            ),
+            quantity,
+        )?;
+        auction.record_vault_withdrawal(quantity)?;
+    }
+    
+    for (to, amount) in [
+        (ctx.accounts.creator_payment_account.to_account_info(), cost - fee_amount),
+        (ctx.accounts.protocol_fee_account.to_account_info(), fee_amount),
+        (ctx.accounts.refund_account.to_account_info(), refund_amount),
+    ] {
+        if amount > 0 {
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.purchase_escrow.to_account_info(),
+                        to,
+                        authority: purchase.to_account_info(),
+                    },
+                    purchase_signer,
+                ),
+                amount,
+            )?;
+        }
+    }
+    
+    purchase.processed = true;
+    sale.purchases_processed += 1;
+    sale.tokens_sold += quantity;
+    ctx.accounts.features.lbp = Some(sale);
+    
+    if sale.tokens_sold == auction.asset_amount && auction.status == AuctionStatus::Active {
+        auction.transition(AuctionStatus::Ended)?;
+    }
+    
+    if fill.accepted {
+        ctx.accounts.protocol_stats.record_settlement(sale.payment_mint, cost, fee_amount);
+        record_breaker_volume(
+            &mut ctx.accounts.protocol_stats,
+            &ctx.accounts.protocol_state,
+            sale.payment_mint,
+            cost,
+        )?;
+        ctx.accounts.fee_ledger.accrue(fee_amount, clock.unix_timestamp);
+        
+        emit!(LbpPurchaseFilled {
+            auction_id,
+            index,
+            buyer: purchase.buyer,
+            quantity,
+            cost,
+            tokens_sold: sale.tokens_sold,
+            filled_at: clock.unix_timestamp,
+        });
+    } else {
+        msg!("LBP purchase {} for auction {} not filled", index, auction_id);
+    }
+    
+    Ok(())
+}
+
+pub(crate) fn generate_lbp_purchase_id(auction_id: u64, index: u64, submitted_at: i64) -> [u8; 32] {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_lbp_purchase");
+    data.extend_from_slice(&auction_id.to_le_bytes());
+    data.extend_from_slice(&index.to_le_bytes());
+    data.extend_from_slice(&submitted_at.to_le_bytes());
+    
+    hash(&data).to_bytes()
+}
+
+fn compute_lbp_fill_hash(
+    auction_id: u64,
+    index: u64,
+    accepted: bool,
+    quantity: u64,
+    cost: u64,
+) -> [u8; 32] {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_lbp_fill_verification");
+    data.extend_from_slice(&auction_id.to_le_bytes());
+    data.extend_from_slice(&index.to_le_bytes());
+    data.push(accepted as u8);
+    data.extend_from_slice(&quantity.to_le_bytes());
+    data.extend_from_slice(&cost.to_le_bytes());
+    
+    hash(&data).to_bytes()
+}
+
+/// `process_lbp_purchase` output: accepted flag, quantity, cost, verification hash
+fn parse_lbp_fill_result(result: &[u8]) -> Result<LbpFillResult> {
+    require!(result.len() == 49, ShadowProtocolError::InvalidMpcResult);
+    
+    let read_u64 = |range: std::ops::Range<usize>| -> Result<u64> {
+        Ok(u64::from_le_bytes(
+            result[range].try_into().map_err(|_| ShadowProtocolError::InvalidMpcResult)?
+        ))
+    };
+    
+    Ok(LbpFillResult {
+        accepted: result[0] != 0,
+        quantity: read_u64(1..9)?,
+        cost: read_u64(9..17)?,
+        verification_hash: result[17..49].try_into()
+            .map_err(|_| ShadowProtocolError::InvalidMpcResult)?,
+    })
+}
+
+#[derive(Debug)]
+pub struct LbpFillResult {
+    pub accepted: bool,
+    pub quantity: u64,
+    pub cost: u64,
+    pub verification_hash: [u8; 32],
+}
+
+#[event]
+pub struct LbpPurchaseQueued {
+    pub auction_id: u64,
+    pub index: u64,
+    pub buyer: Pubkey,
+    pub escrow_amount: u64,
+    pub computation_id: [u8; 32],
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct LbpPurchaseFilled {
+    pub auction_id: u64,
+    pub index: u64,
+    pub buyer: Pubkey,
+    pub quantity: u64,
+    pub cost: u64,
+    pub tokens_sold: u64,
+    pub filled_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SubmitLbpPurchase<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    /// CHECK: Buyer's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, buyer.key().as_ref()], bump)]
+    pub buyer_deny_entry: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = auction.auction_type == AuctionType::LiquidityBootstrapping @ ShadowProtocolError::InvalidAuctionType
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Holds the sale state
+    #[account(
+        mut,
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + LbpPurchase::INIT_SPACE,
+        seeds = [
+            LBP_PURCHASE_SEED,
+            auction_id.to_le_bytes().as_ref(),
+            features.lbp.map_or(0, |sale| sale.purchases_submitted).to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub purchase: Account<'info, LbpPurchase>,
+    
+    #[account(
+        init,
+        payer = buyer,
+        token::mint = payment_mint,
+        token::authority = purchase,
+        seeds = [LBP_ESCROW_SEED, purchase.key().as_ref()],
+        bump
+    )]
+    pub purchase_escrow: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = buyer
+    )]
+    pub buyer_payment_account: Account<'info, TokenAccount>,
+    
+    /// Receives the purchased asset when the purchase fills
+    #[account(token::mint = auction.asset_mint)]
+    pub buyer_asset_account: Account<'info, TokenAccount>,
+    
+    pub payment_mint: Account<'info, Mint>,
+    
+    /// Protocol fees accrued in the payment mint, opened here so the fill
+    /// can count its fee
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + FeeLedger::INIT_SPACE,
+        seeds = [FEE_LEDGER_SEED, payment_mint.key().as_ref()],
+        bump
+    )]
+    pub fee_ledger: Account<'info, FeeLedger>,
+    
+    /// The auction's house; required when it was listed through one
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64, index: u64)]
+pub struct LbpPurchaseCallback<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = auction.auction_type == AuctionType::LiquidityBootstrapping @ ShadowProtocolError::InvalidAuctionType
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Holds the sale state
+    #[account(
+        mut,
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        mut,
+        seeds = [LBP_PURCHASE_SEED, auction_id.to_le_bytes().as_ref(), index.to_le_bytes().as_ref()],
+        bump = purchase.bump
+    )]
+    pub purchase: Account<'info, LbpPurchase>,
+    
+    #[account(
+        mut,
+        seeds = [LBP_ESCROW_SEED, purchase.key().as_ref()],
+        bump
+    )]
+    pub purchase_escrow: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    #[account(mut, address = auction.asset_vault @ ShadowProtocolError::InvalidTokenAccount)]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = purchase.buyer_asset_account @ ShadowProtocolError::InvalidTokenAccount)]
+    pub buyer_asset_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = purchase.refund_account @ ShadowProtocolError::InvalidTokenAccount)]
+    pub refund_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = purchase_escrow.mint,
+        token::authority = auction.creator
+    )]
+    pub creator_payment_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = protocol_state.fee_recipient
+    )]
+    pub protocol_fee_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [FEE_LEDGER_SEED, purchase_escrow.mint.as_ref()],
+        bump = fee_ledger.bump
+    )]
+    pub fee_ledger: Account<'info, FeeLedger>,
+    
+    pub token_program: Program<'info, Token>,
+}
@@ -1,17 +1,93 @@
-use anchor_lang::prelude::*;
-
 pub mod auction_management;
 pub mod bidding;
 pub mod settlement;
+pub mod settlement_claims;
 pub mod admin;
 pub mod callbacks;
 pub mod arcium_callback;
 pub mod auction_cleanup;
+pub mod settlement_rounds;
+pub mod budget_bidding;
+pub mod double_auction;
+pub mod dispute;
+pub mod arbitration;
+pub mod insurance;
+pub mod computation_tracking;
+pub mod anonymous_listing;
+pub mod compliance;
+pub mod settlement_hook;
+pub mod deny_list;
+pub mod oracle;
+pub mod payout_conversion;
+pub mod collateral;
+pub mod yield_escrow;
+pub mod payout_stream;
+pub mod lbp;
+pub mod auction_house;
+pub mod fee_voucher;
+pub mod governance;
+pub mod staking;
+pub mod auction_metadata;
+pub mod auction_migration;
+pub mod relayed_bid;
+pub mod settlement_lut;
+pub mod emergency_withdrawal;
+pub mod gas_schedule;
+pub mod cluster_failover;
+pub mod bid_set;
+pub mod state_compression;
+pub mod automation;
+pub mod circuit_breaker;
+pub mod settlement_progress;
+pub mod fee_ledger;
+pub mod dutch_tranches;
+pub mod quantity_bids;
+pub mod settlement_preview;
+pub mod addresses;
+pub mod auction_position;
+pub mod bid_position;
 
 pub use auction_management::*;
 pub use bidding::*;
 pub use settlement::*;
+pub use settlement_claims::*;
 pub use admin::*;
 pub use callbacks::*;
 pub use arcium_callback::*;
-pub use auction_cleanup::*;
\ No newline at end of file
+pub use auction_cleanup::*;
+pub use settlement_rounds::*;
+pub use budget_bidding::*;
+pub use double_auction::*;
+pub use dispute::*;
+pub use arbitration::*;
+pub use insurance::*;
+pub use computation_tracking::*;
+pub use anonymous_listing::*;
+pub use deny_list::*;
+pub use payout_conversion::*;
+pub use collateral::*;
+pub use yield_escrow::*;
+pub use payout_stream::*;
+pub use lbp::*;
+pub use auction_house::*;
+pub use governance::*;
+pub use staking::*;
+pub use auction_metadata::*;
+pub use auction_migration::*;
+pub use relayed_bid::*;
+pub use settlement_lut::*;
+pub use emergency_withdrawal::*;
+pub use gas_schedule::*;
+pub use cluster_failover::*;
+pub use bid_set::*;
+pub use state_compression::*;
+pub use automation::*;
+pub use circuit_breaker::*;
+pub use settlement_progress::*;
+pub use fee_ledger::*;
+pub use dutch_tranches::*;
+pub use quantity_bids::*;
+pub use settlement_preview::*;
+pub use addresses::*;
+pub use auction_position::*;
+pub use bid_position::*;
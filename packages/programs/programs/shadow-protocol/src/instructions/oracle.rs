@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Pyth Solana receiver program, owner of `PriceUpdateV2` accounts
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Anchor discriminator of the receiver's `PriceUpdateV2` account
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+#[derive(AnchorDeserialize)]
+enum VerificationLevel {
+    Partial {
+        #[allow(dead_code)]
+        num_signatures: u8,
+    },
+    Full,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceFeedMessage {
+    feed_id: [u8; 32],
+    price: i64,
+    _conf: u64,
+    exponent: i32,
+    publish_time: i64,
+    _prev_publish_time: i64,
+    _ema_price: i64,
+    _ema_conf: u64,
+}
+
+/// Layout of the receiver's `PriceUpdateV2`, mirrored to avoid the SDK dependency
+#[derive(AnchorDeserialize)]
+struct PriceUpdateV2 {
+    _write_authority: Pubkey,
+    verification_level: VerificationLevel,
+    price_message: PriceFeedMessage,
+    _posted_slot: u64,
+}
+
+/// Reject `winning_amount` if it is wildly out of band with the oracle value
+/// of the auctioned asset. A no-op for auctions without a price oracle.
+pub(crate) fn check_oracle_band(
+    oracle: Option<PriceOracle>,
+    price_feed: Option<&AccountInfo>,
+    asset_amount: u64,
+    winning_amount: u64,
+    now: i64,
+) -> Result<()> {
+    let Some(oracle) = oracle else {
+        return Ok(());
+    };
+    
+    let feed = price_feed.ok_or(ShadowProtocolError::InvalidPriceFeed)?;
+    require!(
+        feed.owner == &PYTH_RECEIVER_PROGRAM_ID,
+        ShadowProtocolError::InvalidPriceFeed
+    );
+    
+    let data = feed.try_borrow_data()?;
+    require!(
+        data.len() > 8 && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR,
+        ShadowProtocolError::InvalidPriceFeed
+    );
+    let update = PriceUpdateV2::deserialize(&mut &data[8..])
+        .map_err(|_| ShadowProtocolError::InvalidPriceFeed)?;
+    let message = update.price_message;
+    
+    // Partially verified updates carry too few guardian signatures to trust
+    require!(
+        matches!(update.verification_level, VerificationLevel::Full)
+            && message.feed_id == oracle.feed_id
+            && message.price > 0,
+        ShadowProtocolError::InvalidPriceFeed
+    );
+    require!(
+        message.publish_time >= now.saturating_sub(oracle.max_staleness as i64),
+        ShadowProtocolError::StalePriceFeed
+    );
+    
+    let expected = oracle_value(
+        message.price as u128,
+        message.exponent,
+        asset_amount,
+        oracle.asset_decimals,
+        oracle.payment_decimals,
+    )?;
+    let winning_amount = winning_amount as u128;
+    
+    require!(
+        winning_amount <= expected.saturating_mul(ORACLE_MAX_DEVIATION_FACTOR)
+            && winning_amount.saturating_mul(ORACLE_MAX_DEVIATION_FACTOR) >= expected,
+        ShadowProtocolError::OraclePriceOutOfBand
+    );
+    
+    Ok(())
+}
+
+/// Value of `asset_amount` base units of the asset, in payment base units
+fn oracle_value(
+    price: u128,
+    exponent: i32,
+    asset_amount: u64,
+    asset_decimals: u8,
+    payment_decimals: u8,
+) -> Result<u128> {
+    let scale = exponent + payment_decimals as i32 - asset_decimals as i32;
+    let value = price
+        .checked_mul(asset_amount as u128)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    let factor = 10u128
+        .checked_pow(scale.unsigned_abs())
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    
+    if scale >= 0 {
+        value.checked_mul(factor).ok_or(ShadowProtocolError::FeeCalculationOverflow.into())
+    } else {
+        Ok(value / factor)
+    }
+}
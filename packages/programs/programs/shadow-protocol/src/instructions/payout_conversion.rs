@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token::{TokenAccount, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Swap instruction the settler supplies for an auction with a payout
+/// conversion. Routes are quoted off-chain, so the instruction data and its
+/// accounts come with the settlement rather than being stored up front.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PayoutSwap {
+    /// Instruction data passed verbatim to the swap program
+    pub data: Vec<u8>,
+    /// Number of trailing remaining accounts that belong to the swap
+    pub account_count: u8,
+}
+
+/// Accounts taking part in a payout conversion at settlement
+pub(crate) struct PayoutSwapAccounts<'a, 'info> {
+    /// PDA that owns both conversion token accounts and signs the swap
+    pub authority: &'a AccountInfo<'info>,
+    pub authority_bump: u8,
+    pub input_account: &'a mut Account<'info, TokenAccount>,
+    pub output_account: &'a mut Account<'info, TokenAccount>,
+    pub creator_output_account: &'a Account<'info, TokenAccount>,
+    pub swap_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub swap_accounts: &'a [AccountInfo<'info>],
+}
+
+/// Swap the proceeds sitting in the conversion input account into the
+/// creator's chosen mint and pay the output out to `recipient`, the holder
+/// of the auction's position when it has one.
+///
+/// Only the conversion PDA signs the swap, so the swap program never gains
+/// authority over the auction or its vaults. Returns the amount paid out.
+pub(crate) fn convert_payout(
+    conversion: &PayoutConversion,
+    auction_id: u64,
+    recipient: Pubkey,
+    input_amount: u64,
+    swap: PayoutSwap,
+    accounts: PayoutSwapAccounts,
+) -> Result<u64> {
+    require!(
+        accounts.swap_program.key() == conversion.swap_program
+            && accounts.output_account.mint == conversion.output_mint
+            && accounts.output_account.owner == accounts.authority.key()
+            && accounts.input_account.owner == accounts.authority.key()
+            && accounts.creator_output_account.mint == conversion.output_mint
+            && accounts.creator_output_account.owner == recipient,
+        ShadowProtocolError::InvalidPayoutConversion
+    );
+    
+    let auction_id_bytes = auction_id.to_le_bytes();
+    let authority_seeds = &[
+        PAYOUT_CONVERSION_SEED,
+        auction_id_bytes.as_ref(),
+        &[accounts.authority_bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    
+    let input_before = accounts.input_account.amount;
+    let output_before = accounts.output_account.amount;
+    
+    let metas = accounts.swap_accounts
+        .iter()
+        .map(|info| {
+            let is_signer = info.is_signer || info.key() == accounts.authority.key();
+            if info.is_writable {
+                AccountMeta::new(info.key(), is_signer)
+            } else {
+                AccountMeta::new_readonly(info.key(), is_signer)
+            }
+        })
+        .collect();
+    let mut infos = accounts.swap_accounts.to_vec();
+    infos.push(accounts.swap_program.clone());
+    
+    invoke_signed(
+        &Instruction {
+            program_id: conversion.swap_program,
+            accounts: metas,
+            data: swap.data,
+        },
+        &infos,
+        signer_seeds,
+    )?;
+    
+    accounts.output_account.reload()?;
+    accounts.input_account.reload()?;
+    
+    // Anything left unswapped would be stranded in the conversion account
+    require!(
+        input_before.saturating_sub(accounts.input_account.amount) >= input_amount,
+        ShadowProtocolError::InvalidPayoutConversion
+    );
+    
+    let output_amount = accounts.output_account.amount
+        .checked_sub(output_before)
+        .ok_or(ShadowProtocolError::SlippageExceeded)?;
+    let min_output = conversion.min_output(input_amount)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    
+    require!(output_amount >= min_output, ShadowProtocolError::SlippageExceeded);
+    
+    transfer(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            Transfer {
+                from: accounts.output_account.to_account_info(),
+                to: accounts.creator_output_account.to_account_info(),
+                authority: accounts.authority.clone(),
+            },
+            signer_seeds,
+        ),
+        output_amount,
+    )?;
+    
+    emit!(PayoutConverted {
+        auction_id,
+        input_mint: accounts.input_account.mint,
+        input_amount,
+        output_mint: conversion.output_mint,
+        output_amount,
+    });
+    
+    Ok(output_amount)
+}
+
+#[event]
+pub struct PayoutConverted {
+    pub auction_id: u64,
+    pub input_mint: Pubkey,
+    pub input_amount: u64,
+    pub output_mint: Pubkey,
+    pub output_amount: u64,
+}
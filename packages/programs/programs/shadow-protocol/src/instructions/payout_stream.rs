@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Release whatever has vested since the last claim to the recipient
+pub fn claim_stream(ctx: Context<ClaimStream>, auction_id: u64) -> Result<()> {
+    let stream = &mut ctx.accounts.payout_stream;
+    let now = Clock::get()?.unix_timestamp;
+    
+    let vested = stream.schedule.vested(stream.total, now - stream.start);
+    let amount = vested.saturating_sub(stream.claimed);
+    require!(amount > 0, ShadowProtocolError::NothingToClaim);
+    
+    let auction_id_bytes = auction_id.to_le_bytes();
+    let stream_seeds = &[
+        PAYOUT_STREAM_SEED,
+        auction_id_bytes.as_ref(),
+        &[stream.bump],
+    ];
+    let signer_seeds = &[&stream_seeds[..]];
+    
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.stream_vault.to_account_info(),
+                to: ctx.accounts.recipient_account.to_account_info(),
+                authority: stream.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+    
+    stream.claimed += amount;
+    
+    emit!(StreamClaimed {
+        auction_id,
+        recipient: stream.recipient,
+        amount,
+        claimed: stream.claimed,
+        total: stream.total,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct PayoutStreamOpened {
+    pub auction_id: u64,
+    pub recipient: Pubkey,
+    pub total: u64,
+    pub start: i64,
+    pub schedule: VestingSchedule,
+}
+
+#[event]
+pub struct StreamClaimed {
+    pub auction_id: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub claimed: u64,
+    pub total: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ClaimStream<'info> {
+    pub recipient: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [PAYOUT_STREAM_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = payout_stream.bump,
+        has_one = recipient @ ShadowProtocolError::Unauthorized
+    )]
+    pub payout_stream: Account<'info, PayoutStream>,
+    
+    #[account(mut, address = payout_stream.vault @ ShadowProtocolError::InvalidTokenAccount)]
+    pub stream_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = stream_vault.mint
+    )]
+    pub recipient_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,857 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_spl::token::{Token, TokenAccount, Mint, Transfer, CloseAccount, transfer, close_account};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::crypto::CryptoUtils;
+use crate::instructions::deny_list::require_not_denied;
+use crate::instructions::auction_house::require_house_open;
+use crate::instructions::auction_management::{mark_auction_ended, release_creation_deposit};
+use crate::instructions::circuit_breaker::record_breaker_volume;
+
+// Multi-unit sealed auctions sold at one uniform price.
+// - `enable_quantity_bids()`: the creator switches a sealed auction with no
+//   bids over to (price, quantity) bids paid in `payment_mint`
+// - `submit_quantity_bid()`: bidders escrow payment and encrypt both values
+// - `queue_quantity_clearing()`: once bidding closes, anyone queues the
+//   `clear_uniform_price_auction` circuit
+// - `quantity_clearing_callback()`: records the clearing price and one
+//   allocation per bid
+// - `distribute_quantity_allocation()`: settles one bid; the last one also
+//   returns unsold units and completes the auction
+
+/// Switch a sealed auction to quantity bids. The lot's `asset_amount` is the
+/// supply and `minimum_bid` the lowest price per unit the circuit accepts.
+pub fn enable_quantity_bids(ctx: Context<EnableQuantityBids>, auction_id: u64) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    
+    require!(!ctx.accounts.protocol_state.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        matches!(auction.status, AuctionStatus::Created | AuctionStatus::Active) && auction.bid_count == 0,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        !auction.has_flag(AuctionAccount::QUANTITY_BIDS | AuctionAccount::PRIVATE_WINNER),
+        ShadowProtocolError::InvalidAuctionType
+    );
+    require!(
+        auction.position_mint.is_none(),
+        ShadowProtocolError::AuctionPositionUnsupported
+    );
+    
+    let sale = &mut ctx.accounts.quantity_auction;
+    sale.auction_id = auction_id;
+    sale.payment_mint = ctx.accounts.payment_mint.key();
+    sale.computation_id = None;
+    sale.clearing_price = 0;
+    sale.units_sold = 0;
+    sale.allocations = Vec::new();
+    sale.bids_distributed = 0;
+    sale.fees_collected = 0;
+    sale.bump = ctx.bumps.quantity_auction;
+    
+    auction.set_flag(AuctionAccount::QUANTITY_BIDS, true);
+    
+    emit!(QuantityBidsEnabled {
+        auction_id,
+        payment_mint: sale.payment_mint,
+        supply: auction.asset_amount,
+        minimum_price: auction.minimum_bid,
+    });
+    
+    Ok(())
+}
+
+/// Encrypted per-unit price and quantity, and the escrow covering them
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuantityBidParams {
+    pub price_encrypted: [u8; 32],
+    pub quantity_encrypted: [u8; 32],
+    pub encryption_public_key: [u8; 32],
+    pub nonce: u128,
+    pub escrow_amount: u64,
+    pub invite_code: Option<[u8; 32]>,
+}
+
+/// Bid an encrypted price per unit and quantity. The escrow should cover
+/// `price * quantity`; bids it does not cover are left unallocated and
+/// refunded in full.
+pub fn submit_quantity_bid(
+    ctx: Context<SubmitQuantityBid>,
+    auction_id: u64,
+    params: QuantityBidParams,
+) -> Result<()> {
+    let QuantityBidParams {
+        price_encrypted,
+        quantity_encrypted,
+        encryption_public_key,
+        nonce,
+        escrow_amount,
+        invite_code,
+    } = params;
+    
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require_not_denied(&ctx.accounts.bidder_deny_entry, DenyListEntry::BIDDING)?;
+    require_house_open(auction, ctx.accounts.auction_house.as_ref())?;
+    
+    require!(
+        auction.status == AuctionStatus::Active,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(!auction.bidding_closed(&clock), ShadowProtocolError::AuctionEnded);
+    require!(
+        auction.bid_count < auction.max_bids.min(MAX_QUANTITY_BIDS as u32),
+        ShadowProtocolError::MaxBidsExceeded
+    );
+    
+    if let Some(invite_hash) = auction.invite_hash {
+        let invite_code = invite_code.ok_or(ShadowProtocolError::InvalidInviteCode)?;
+        require!(
+            CryptoUtils::hash_invite_code(auction_id, &invite_code)? == invite_hash,
+            ShadowProtocolError::InvalidInviteCode
+        );
+    }
+    
+    require!(escrow_amount > 0, ShadowProtocolError::InsufficientCollateral);
+    require!(
+        ctx.accounts.bidder_payment_account.amount >= escrow_amount,
+        ShadowProtocolError::InsufficientFunds
+    );
+    require!(
+        price_encrypted != [0u8; 32]
+            && quantity_encrypted != [0u8; 32]
+            && encryption_public_key != [0u8; 32],
+        ShadowProtocolError::InvalidEncryption
+    );
+    
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.bidder_payment_account.to_account_info(),
+                to: ctx.accounts.bid_escrow.to_account_info(),
+                authority: ctx.accounts.bidder.to_account_info(),
+            },
+        ),
+        escrow_amount,
+    )?;
+    
+    let bid = &mut ctx.accounts.bid;
+    bid.auction_id = auction_id;
+    bid.index = auction.bid_count;
+    bid.bidder = ctx.accounts.bidder.key();
+    bid.price_encrypted = price_encrypted;
+    bid.quantity_encrypted = quantity_encrypted;
+    bid.encryption_public_key = encryption_public_key;
+    bid.nonce = nonce;
+    bid.escrow_amount = escrow_amount;
+    bid.bidder_asset_account = ctx.accounts.bidder_asset_account.key();
+    bid.refund_account = ctx.accounts.bidder_payment_account.key();
+    bid.submitted_at = clock.unix_timestamp;
+    bid.bump = ctx.bumps.bid;
+    
+    ctx.accounts.fee_ledger.open(ctx.accounts.payment_mint.key(), ctx.bumps.fee_ledger);
+    
+    auction.bid_count += 1;
+    
+    emit!(QuantityBidSubmitted {
+        auction_id,
+        index: bid.index,
+        bidder: bid.bidder,
+        escrow_amount,
+        submitted_at: bid.submitted_at,
+    });
+    
+    Ok(())
+}
+
+/// Close bidding and queue the clearing computation. Permissionless once
+/// the bidding cutoff has passed.
+pub fn queue_quantity_clearing(ctx: Context<QueueQuantityClearing>, auction_id: u64) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let sale = &mut ctx.accounts.quantity_auction;
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.protocol_state.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        auction.status == AuctionStatus::Ended ||
+        (auction.status == AuctionStatus::Active && auction.bidding_closed(&clock)),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(sale.computation_id.is_none(), ShadowProtocolError::InvalidAuctionStatus);
+    require!(auction.bid_count > 0, ShadowProtocolError::InvalidBidCount);
+    
+    if auction.status == AuctionStatus::Active {
+        auction.end_time = auction.now(&clock);
+        mark_auction_ended(auction, &clock)?;
+    }
+    
+    let computation_id = generate_quantity_clearing_id(auction_id, auction.end_time, auction.bid_count);
+    sale.computation_id = Some(computation_id);
+    
+    emit!(QuantityClearingQueued {
+        auction_id,
+        computation_id,
+        bid_count: auction.bid_count,
+        supply: auction.asset_amount,
+        minimum_price: auction.minimum_bid,
+        queued_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Deliver the `clear_uniform_price_auction` result: clearing price, units
+/// sold, one allocation per bid and the verification hash.
+pub fn quantity_clearing_callback(
+    ctx: Context<QuantityClearingCallback>,
+    auction_id: u64,
+    computation_id: [u8; 32],
+    result: Vec<u8>,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let sale = &mut ctx.accounts.quantity_auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.authority.key() == protocol.callback_signer(),
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        auction.status == AuctionStatus::Ended,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        sale.computation_id == Some(computation_id),
+        ShadowProtocolError::InvalidComputationId
+    );
+    
+    let clearing = parse_quantity_clearing_result(&result, auction.bid_count as usize)?;
+    require!(
+        clearing.verification_hash == compute_quantity_clearing_hash(
+            auction_id,
+            clearing.clearing_price,
+            clearing.units_sold,
+            &clearing.allocations,
+        ),
+        ShadowProtocolError::MpcVerificationFailed
+    );
+    
+    let allocated = clearing.allocations
+        .iter()
+        .try_fold(0u64, |total, allocation| total.checked_add(*allocation))
+        .ok_or(ShadowProtocolError::InvalidMpcResult)?;
+    require!(
+        allocated == clearing.units_sold
+            && clearing.units_sold <= auction.asset_amount
+            && (clearing.units_sold == 0 || clearing.clearing_price >= auction.minimum_bid),
+        ShadowProtocolError::InvalidMpcResult
+    );
+    
+    sale.clearing_price = clearing.clearing_price;
+    sale.units_sold = clearing.units_sold;
+    sale.allocations = clearing.allocations;
+    sale.bids_distributed = 0;
+    
+    auction.settlement_authorized_at = Some(clock.unix_timestamp);
+    auction.transition(AuctionStatus::SettlementAuthorized)?;
+    
+    emit!(QuantityAuctionCleared {
+        auction_id,
+        clearing_price: sale.clearing_price,
+        units_sold: sale.units_sold,
+        cleared_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Settle one bid of a cleared quantity auction and close it. Permissionless.
+///
+/// The bidder receives their allocation and any escrow beyond its cost at
+/// the clearing price; the creator receives the cost less the protocol fee.
+/// Distributing the last bid returns unsold units to the creator and
+/// completes the auction.
+pub fn distribute_quantity_allocation(
+    ctx: Context<DistributeQuantityAllocation>,
+    auction_id: u64,
+    bid_index: u32,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let sale = &mut ctx.accounts.quantity_auction;
+    let bid = &ctx.accounts.bid;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    ctx.accounts.protocol_stats.require_settlements_open()?;
+    require!(
+        auction.status == AuctionStatus::SettlementAuthorized,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        !auction.has_flag(AuctionAccount::DISPUTE_OPEN),
+        ShadowProtocolError::SettlementDisputed
+    );
+    
+    let allocation = sale.allocations
+        .get(bid_index as usize)
+        .copied()
+        .ok_or(ShadowProtocolError::InvalidMpcResult)?;
+    let cost = allocation
+        .checked_mul(sale.clearing_price)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    let refund_amount = bid.escrow_amount
+        .checked_sub(cost)
+        .ok_or(ShadowProtocolError::InsufficientCollateral)?;
+    let fee_amount = cost
+        .checked_mul(auction.protocol_fee_bps(protocol.protocol_fee) as u64)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?
+        / 10000;
+    
+    let auction_id_bytes = auction_id.to_le_bytes();
+    let index_bytes = bid_index.to_le_bytes();
+    let bid_seeds = &[
+        QUANTITY_BID_SEED,
+        auction_id_bytes.as_ref(),
+        index_bytes.as_ref(),
+        &[bid.bump],
+    ];
+    let bid_signer = &[&bid_seeds[..]];
+    // Copied out, as the auction is written while the seeds are in use
+    let pda_seed = auction.pda_seed().to_vec();
+    let auction_seeds = &[
+        AUCTION_SEED,
+        pda_seed.as_ref(),
+        &[auction.bump],
+    ];
+    let auction_signer = &[&auction_seeds[..]];
+    
+    if allocation > 0 {
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.asset_vault.to_account_info(),
+                    to: ctx.accounts.bidder_asset_account.to_account_info(),
+                    authority: auction.to_account_info(),
+                },
+                auction_signer,
+            ),
+            allocation,
+        )?;
+        auction.record_vault_withdrawal(allocation)?;
+    }
+    
+    for (to, amount) in [
+        (ctx.accounts.creator_payment_account.to_account_info(), cost - fee_amount),
+        (ctx.accounts.protocol_fee_account.to_account_info(), fee_amount),
+        (ctx.accounts.refund_account.to_account_info(), refund_amount),
+    ] {
+        if amount > 0 {
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.bid_escrow.to_account_info(),
+                        to,
+                        authority: bid.to_account_info(),
+                    },
+                    bid_signer,
+                ),
+                amount,
+            )?;
+        }
+    }
+    
+    close_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.bid_escrow.to_account_info(),
+                destination: ctx.accounts.bidder.to_account_info(),
+                authority: bid.to_account_info(),
+            },
+            bid_signer,
+        ),
+    )?;
+    
+    ctx.accounts.fee_ledger.accrue(fee_amount, clock.unix_timestamp);
+    sale.fees_collected = sale.fees_collected.saturating_add(fee_amount);
+    sale.bids_distributed += 1;
+    
+    emit!(QuantityAllocationDistributed {
+        auction_id,
+        index: bid_index,
+        bidder: bid.bidder,
+        allocation,
+        cost,
+        refund: refund_amount,
+    });
+    
+    if sale.bids_distributed < auction.bid_count {
+        return Ok(());
+    }
+    
+    // Every bid is settled: whatever did not sell goes back to the creator
+    let unsold = auction.asset_amount - sale.units_sold;
+    if unsold > 0 {
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.asset_vault.to_account_info(),
+                    to: ctx.accounts.creator_asset_account.to_account_info(),
+                    authority: auction.to_account_info(),
+                },
+                auction_signer,
+            ),
+            unsold,
+        )?;
+        auction.record_vault_withdrawal(unsold)?;
+    }
+    
+    auction.transition(AuctionStatus::AssetsDistributed)?;
+    auction.settled_at = Some(clock.unix_timestamp);
+    release_creation_deposit(auction, &ctx.accounts.creator)?;
+    
+    let volume = sale.units_sold * sale.clearing_price;
+    ctx.accounts.protocol_stats.record_settlement(sale.payment_mint, volume, sale.fees_collected);
+    record_breaker_volume(&mut ctx.accounts.protocol_stats, protocol, sale.payment_mint, volume)?;
+    
+    emit!(AuctionSettled {
+        auction_id,
+        winner: None,
+        winning_amount: sale.clearing_price,
+        second_highest_bid: 0,
+        reserve_met: sale.units_sold > 0,
+        participating_bids: auction.bid_count as u64,
+        settled_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+fn generate_quantity_clearing_id(auction_id: u64, end_time: i64, bid_count: u32) -> [u8; 32] {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_quantity_clearing");
+    data.extend_from_slice(&auction_id.to_le_bytes());
+    data.extend_from_slice(&end_time.to_le_bytes());
+    data.extend_from_slice(&bid_count.to_le_bytes());
+    
+    hash(&data).to_bytes()
+}
+
+fn compute_quantity_clearing_hash(
+    auction_id: u64,
+    clearing_price: u64,
+    units_sold: u64,
+    allocations: &[u64],
+) -> [u8; 32] {
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_quantity_clearing_verification");
+    data.extend_from_slice(&auction_id.to_le_bytes());
+    data.extend_from_slice(&clearing_price.to_le_bytes());
+    data.extend_from_slice(&units_sold.to_le_bytes());
+    for allocation in allocations {
+        data.extend_from_slice(&allocation.to_le_bytes());
+    }
+    
+    hash(&data).to_bytes()
+}
+
+/// `clear_uniform_price_auction` output: price, units sold, one allocation
+/// per bid, verification hash
+fn parse_quantity_clearing_result(result: &[u8], bid_count: usize) -> Result<QuantityClearingResult> {
+    let allocations_end = 16 + bid_count * 8;
+    require!(result.len() == allocations_end + 32, ShadowProtocolError::InvalidMpcResult);
+    
+    let read_u64 = |range: std::ops::Range<usize>| -> Result<u64> {
+        Ok(u64::from_le_bytes(
+            result[range].try_into().map_err(|_| ShadowProtocolError::InvalidMpcResult)?
+        ))
+    };
+    
+    let allocations = result[16..allocations_end]
+        .chunks_exact(8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .collect::<Vec<u64>>();
+    
+    Ok(QuantityClearingResult {
+        clearing_price: read_u64(0..8)?,
+        units_sold: read_u64(8..16)?,
+        allocations,
+        verification_hash: result[allocations_end..].try_into()
+            .map_err(|_| ShadowProtocolError::InvalidMpcResult)?,
+    })
+}
+
+#[derive(Debug)]
+pub struct QuantityClearingResult {
+    pub clearing_price: u64,
+    pub units_sold: u64,
+    pub allocations: Vec<u64>,
+    pub verification_hash: [u8; 32],
+}
+
+#[event]
+pub struct QuantityBidsEnabled {
+    pub auction_id: u64,
+    pub payment_mint: Pubkey,
+    pub supply: u64,
+    pub minimum_price: u64,
+}
+
+#[event]
+pub struct QuantityBidSubmitted {
+    pub auction_id: u64,
+    pub index: u32,
+    pub bidder: Pubkey,
+    pub escrow_amount: u64,
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct QuantityClearingQueued {
+    pub auction_id: u64,
+    pub computation_id: [u8; 32],
+    pub bid_count: u32,
+    pub supply: u64,
+    pub minimum_price: u64,
+    pub queued_at: i64,
+}
+
+#[event]
+pub struct QuantityAuctionCleared {
+    pub auction_id: u64,
+    pub clearing_price: u64,
+    pub units_sold: u64,
+    pub cleared_at: i64,
+}
+
+#[event]
+pub struct QuantityAllocationDistributed {
+    pub auction_id: u64,
+    pub index: u32,
+    pub bidder: Pubkey,
+    /// Asset base units delivered
+    pub allocation: u64,
+    pub cost: u64,
+    pub refund: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct EnableQuantityBids<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = auction.auction_type == AuctionType::SealedBid @ ShadowProtocolError::InvalidAuctionType
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + QuantityAuction::INIT_SPACE,
+        seeds = [QUANTITY_AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub quantity_auction: Account<'info, QuantityAuction>,
+    
+    #[account(address = auction.payment_mint @ ShadowProtocolError::InvalidPaymentMint)]
+    pub payment_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SubmitQuantityBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    /// CHECK: Bidder's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_deny_entry: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        constraint = auction.has_flag(AuctionAccount::QUANTITY_BIDS) @ ShadowProtocolError::InvalidAuctionType
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [QUANTITY_AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = quantity_auction.bump
+    )]
+    pub quantity_auction: Account<'info, QuantityAuction>,
+    
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + QuantityBid::INIT_SPACE,
+        seeds = [
+            QUANTITY_BID_SEED,
+            auction_id.to_le_bytes().as_ref(),
+            auction.bid_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bid: Account<'info, QuantityBid>,
+    
+    #[account(
+        init,
+        payer = bidder,
+        token::mint = payment_mint,
+        token::authority = bid,
+        seeds = [QUANTITY_ESCROW_SEED, bid.key().as_ref()],
+        bump
+    )]
+    pub bid_escrow: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = bidder
+    )]
+    pub bidder_payment_account: Account<'info, TokenAccount>,
+    
+    /// Receives the allocated asset
+    #[account(token::mint = auction.asset_mint)]
+    pub bidder_asset_account: Account<'info, TokenAccount>,
+    
+    #[account(address = quantity_auction.payment_mint @ ShadowProtocolError::InvalidTokenAccount)]
+    pub payment_mint: Account<'info, Mint>,
+    
+    /// Protocol fees accrued in the payment mint, opened here so the
+    /// distribution can count its fee
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = 8 + FeeLedger::INIT_SPACE,
+        seeds = [FEE_LEDGER_SEED, payment_mint.key().as_ref()],
+        bump
+    )]
+    pub fee_ledger: Account<'info, FeeLedger>,
+    
+    /// The auction's house; required when it was listed through one
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct QueueQuantityClearing<'info> {
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [QUANTITY_AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = quantity_auction.bump
+    )]
+    pub quantity_auction: Account<'info, QuantityAuction>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct QuantityClearingCallback<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [QUANTITY_AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = quantity_auction.bump
+    )]
+    pub quantity_auction: Account<'info, QuantityAuction>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64, bid_index: u32)]
+pub struct DistributeQuantityAllocation<'info> {
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [QUANTITY_AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = quantity_auction.bump
+    )]
+    pub quantity_auction: Account<'info, QuantityAuction>,
+    
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [QUANTITY_BID_SEED, auction_id.to_le_bytes().as_ref(), bid_index.to_le_bytes().as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, QuantityBid>,
+    
+    #[account(
+        mut,
+        seeds = [QUANTITY_ESCROW_SEED, bid.key().as_ref()],
+        bump
+    )]
+    pub bid_escrow: Account<'info, TokenAccount>,
+    
+    /// CHECK: Bidder, receives the bid and escrow rent
+    #[account(mut, address = bid.bidder)]
+    pub bidder: UncheckedAccount<'info>,
+    
+    /// CHECK: Auction creator, receives the creation deposit on the last distribution
+    #[account(mut, address = auction.creator)]
+    pub creator: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    #[account(mut, address = auction.asset_vault @ ShadowProtocolError::InvalidTokenAccount)]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = bid.bidder_asset_account @ ShadowProtocolError::InvalidTokenAccount)]
+    pub bidder_asset_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = bid.refund_account @ ShadowProtocolError::InvalidTokenAccount)]
+    pub refund_account: Account<'info, TokenAccount>,
+    
+    /// Receives unsold units on the last distribution
+    #[account(
+        mut,
+        token::mint = auction.asset_mint,
+        token::authority = auction.creator
+    )]
+    pub creator_asset_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = quantity_auction.payment_mint,
+        token::authority = auction.creator
+    )]
+    pub creator_payment_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = protocol_state.fee_recipient
+    )]
+    pub protocol_fee_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [FEE_LEDGER_SEED, quantity_auction.payment_mint.as_ref()],
+        bump = fee_ledger.bump
+    )]
+    pub fee_ledger: Account<'info, FeeLedger>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn clearing_result(clearing_price: u64, units_sold: u64, allocations: &[u64]) -> Vec<u8> {
+        let mut result = Vec::new();
+        result.extend_from_slice(&clearing_price.to_le_bytes());
+        result.extend_from_slice(&units_sold.to_le_bytes());
+        for allocation in allocations {
+            result.extend_from_slice(&allocation.to_le_bytes());
+        }
+        result.extend_from_slice(&compute_quantity_clearing_hash(7, clearing_price, units_sold, allocations));
+        result
+    }
+    
+    #[test]
+    fn parses_clearing_result() {
+        let result = clearing_result(250, 9, &[4, 0, 5]);
+        let clearing = parse_quantity_clearing_result(&result, 3).unwrap();
+        
+        assert_eq!(clearing.clearing_price, 250);
+        assert_eq!(clearing.units_sold, 9);
+        assert_eq!(clearing.allocations, vec![4, 0, 5]);
+        assert_eq!(
+            clearing.verification_hash,
+            compute_quantity_clearing_hash(7, 250, 9, &[4, 0, 5])
+        );
+    }
+    
+    #[test]
+    fn rejects_result_sized_for_another_bid_count() {
+        let result = clearing_result(250, 9, &[4, 0, 5]);
+        
+        assert!(parse_quantity_clearing_result(&result, 2).is_err());
+        assert!(parse_quantity_clearing_result(&result, 4).is_err());
+        assert!(parse_quantity_clearing_result(&result[..result.len() - 1], 3).is_err());
+    }
+    
+    #[test]
+    fn verification_hash_binds_price_and_allocations() {
+        let hash = compute_quantity_clearing_hash(7, 250, 9, &[4, 0, 5]);
+        
+        assert_ne!(hash, compute_quantity_clearing_hash(7, 251, 9, &[4, 0, 5]));
+        assert_ne!(hash, compute_quantity_clearing_hash(7, 250, 9, &[5, 0, 4]));
+        assert_ne!(hash, compute_quantity_clearing_hash(8, 250, 9, &[4, 0, 5]));
+    }
+}
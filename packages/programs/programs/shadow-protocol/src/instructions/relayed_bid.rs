@@ -0,0 +1,260 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::{Token, TokenAccount, Mint, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::crypto::CryptoUtils;
+use crate::instructions::compliance::check_compliance;
+use crate::instructions::deny_list::require_not_denied;
+use crate::instructions::auction_house::require_house_open;
+use crate::instructions::fee_voucher::require_ed25519_signed;
+
+/// Submit a sealed bid on a bidder's behalf.
+///
+/// The bidder signs `relayed_bid.message()` off-chain; the instruction
+/// directly before this one must be an ed25519 program instruction verifying
+/// that signature. The relayer pays fees and rent, and moves the collateral
+/// as the SPL delegate of the bidder's collateral account, so refunds still
+/// go to the bidder. Native-SOL collateral cannot be relayed.
+pub fn submit_relayed_bid<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SubmitRelayedBid<'info>>,
+    auction_id: u64,
+    relayed_bid: RelayedBid,
+    invite_code: Option<[u8; 32]>,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    let bidder = ctx.accounts.bidder.key();
+    let collateral_amount = relayed_bid.collateral_amount;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require_not_denied(&ctx.accounts.bidder_deny_entry, DenyListEntry::BIDDING)?;
+    require_house_open(auction, ctx.accounts.auction_house.as_ref())?;
+    
+    require!(
+        relayed_bid.auction_id == auction_id,
+        ShadowProtocolError::InvalidRelayedBid
+    );
+    require!(
+        clock.unix_timestamp < relayed_bid.expires_at,
+        ShadowProtocolError::RelayedBidExpired
+    );
+    require_ed25519_signed(
+        Some(ctx.accounts.instructions_sysvar.as_ref()),
+        &bidder,
+        &relayed_bid.message(),
+        ShadowProtocolError::InvalidRelayedBid,
+    )?;
+    
+    require!(
+        auction.status == AuctionStatus::Active,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        !auction.has_flag(AuctionAccount::QUANTITY_BIDS),
+        ShadowProtocolError::QuantityBidsEnabled
+    );
+    
+    if let Some(invite_hash) = auction.invite_hash {
+        let invite_code = invite_code.ok_or(ShadowProtocolError::InvalidInviteCode)?;
+        require!(
+            CryptoUtils::hash_invite_code(auction_id, &invite_code)? == invite_hash,
+            ShadowProtocolError::InvalidInviteCode
+        );
+    }
+    
+    ctx.accounts.features.require_collateral(
+        ctx.accounts.collateral_mint.key(),
+        collateral_amount,
+        auction.sealed_collateral_floor()?,
+    )?;
+    
+    // Validate collateral amount is reasonable (prevent overflow attacks)
+    require!(
+        collateral_amount <= u64::MAX / 2,
+        ShadowProtocolError::InvalidAssetAmount
+    );
+    
+    let bidder_collateral_account = &ctx.accounts.bidder_collateral_account;
+    require!(
+        bidder_collateral_account.amount >= collateral_amount,
+        ShadowProtocolError::InsufficientFunds
+    );
+    require!(
+        bidder_collateral_account.delegate == COption::Some(ctx.accounts.relayer.key())
+            && bidder_collateral_account.delegated_amount >= collateral_amount,
+        ShadowProtocolError::InvalidRelayedBid
+    );
+    
+    require!(
+        !auction.bidding_closed(&clock),
+        ShadowProtocolError::AuctionEnded
+    );
+    
+    require!(
+        auction.bid_count < auction.max_bids,
+        ShadowProtocolError::MaxBidsExceeded
+    );
+    
+    check_compliance(
+        auction.compliance_program,
+        ctx.accounts.compliance_program.as_ref().map(|program| program.as_ref()),
+        &ctx.accounts.bidder.to_account_info(),
+        ctx.remaining_accounts,
+        collateral_amount,
+        ctx.accounts.collateral_mint.key(),
+    )?;
+    
+    CryptoUtils::validate_encrypted_bid(
+        &relayed_bid.bid_amount_encrypted,
+        &relayed_bid.public_key,
+        relayed_bid.nonce,
+    )?;
+    
+    CryptoUtils::require_encryption_key(
+        relayed_bid.public_key,
+        auction_id,
+        bidder,
+        auction.creator,
+        relayed_bid.nonce,
+    )?;
+    
+    let bid = &mut ctx.accounts.bid;
+    bid.auction_id = auction_id;
+    bid.auction = auction.key();
+    bid.bidder = bidder;
+    bid.amount_encrypted = relayed_bid.bid_amount_encrypted;
+    bid.encryption_public_key = relayed_bid.public_key;
+    bid.nonce = relayed_bid.nonce;
+    bid.timestamp = clock.unix_timestamp;
+    bid.collateral_amount = collateral_amount;
+    bid.collateral_account = bidder_collateral_account.key();
+    bid.sol_collateral = false;
+    bid.is_winner = false;
+    bid.budget = None;
+    bid.bump = ctx.bumps.bid;
+    
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: bidder_collateral_account.to_account_info(),
+                to: ctx.accounts.bid_escrow.to_account_info(),
+                authority: ctx.accounts.relayer.to_account_info(),
+            },
+        ),
+        collateral_amount,
+    )?;
+    
+    auction.bid_count += 1;
+    
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.bids_submitted = stats.bids_submitted.saturating_add(1);
+    
+    emit!(BidSubmitted {
+        auction_id,
+        bidder,
+        timestamp: clock.unix_timestamp,
+        bid_count: auction.bid_count as u64,
+    });
+    
+    emit!(BidRelayed {
+        auction_id,
+        bidder,
+        relayer: ctx.accounts.relayer.key(),
+    });
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SubmitRelayedBid<'info> {
+    /// Submits the bid and pays for its accounts
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+    
+    /// CHECK: Bidder the bid is attributed to; authenticated by the ed25519
+    /// signature over the relayed bid
+    pub bidder: UncheckedAccount<'info>,
+    
+    /// CHECK: Bidder's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_deny_entry: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Holds the collateral mint, if not the payment mint
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        init,
+        payer = relayer,
+        space = 8 + BidAccount::INIT_SPACE,
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    /// Bidder's collateral token account, delegated to the relayer
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = bidder
+    )]
+    pub bidder_collateral_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = relayer,
+        token::mint = collateral_mint,
+        token::authority = bid,
+        seeds = [b"bid_escrow", auction_id.to_le_bytes().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bid_escrow: Account<'info, TokenAccount>,
+    
+    pub collateral_mint: Account<'info, Mint>,
+    
+    /// CHECK: Matched against the auction's compliance program; required when one is set
+    pub compliance_program: Option<UncheckedAccount<'info>>,
+    
+    /// The auction's house; required when it was listed through one
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    /// CHECK: Instructions sysvar, read to find the bidder's ed25519 signature
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct BidRelayed {
+    pub auction_id: u64,
+    pub bidder: Pubkey,
+    pub relayer: Pubkey,
+}
@@ -1,13 +1,38 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Mint, Transfer, transfer};
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
 use crate::state::*;
 use crate::error::ShadowProtocolError;
+use crate::instructions::arcium_callback::{compute_settlement_hash, AuctionUnsold};
+use crate::instructions::dispute::require_dispute_window_passed;
+use crate::instructions::insurance::insurance_share;
+use crate::instructions::staking::staking_share;
+use crate::instructions::auction_management::{release_creation_deposit, mark_auction_ended};
+use crate::instructions::budget_bidding::require_budget_allocated;
+use crate::instructions::compliance::check_compliance;
+use crate::instructions::settlement_hook::invoke_settlement_hook;
+use crate::instructions::auction_position::proceeds_recipient;
+use crate::instructions::bid_position::bid_holder;
+use crate::instructions::deny_list::require_not_denied;
+use crate::instructions::oracle::check_oracle_band;
+use crate::instructions::payout_conversion::{convert_payout, PayoutSwap, PayoutSwapAccounts};
+use crate::instructions::payout_stream::PayoutStreamOpened;
+use crate::instructions::circuit_breaker::record_breaker_volume;
+use crate::crypto::CryptoUtils;
+use crate::instructions::dispute::void_settlement;
 
-/// Authorize settlement after MPC computation verification
+/// How long a privacy-mode winner has to claim the win (3 days)
+pub const WIN_CLAIM_PERIOD: i64 = 3 * 24 * 60 * 60;
+
+/// Authorize settlement after MPC computation verification.
+///
+/// `reserve_met` is the reserve verdict of the verified computation. An
+/// auction that missed its reserve is recorded as unsold instead, for
+/// `finalize_unsold` to return the asset.
 pub fn authorize_settlement(
     ctx: Context<AuthorizeSettlement>,
     auction_id: u64,
     mpc_verification_hash: [u8; 32],
+    reserve_met: bool,
 ) -> Result<()> {
     let auction = &mut ctx.accounts.auction;
     let protocol = &ctx.accounts.protocol_state;
@@ -18,9 +43,9 @@ pub fn authorize_settlement(
         ShadowProtocolError::InvalidAuctionStatus
     );
     
-    // Only protocol authority can authorize settlement
+    // Only the auction's settlement authorizer can authorize settlement
     require!(
-        ctx.accounts.authority.key() == protocol.authority,
+        ctx.accounts.authority.key() == protocol.settlement_signer(auction),
         ShadowProtocolError::Unauthorized
     );
 
@@ -30,23 +55,125 @@ pub fn authorize_settlement(
     );
 
     require!(
-        !auction.settlement_authorized,
+        !auction.settlement_authorized(),
         ShadowProtocolError::AuctionAlreadySettled
     );
+    require!(!auction.has_flag(AuctionAccount::UNSOLD), ShadowProtocolError::AuctionUnsold);
 
-    // Store MPC verification hash and authorize settlement
+    let now = Clock::get()?.unix_timestamp;
+    auction.set_flag(AuctionAccount::RESERVE_MET, reserve_met);
     auction.mpc_verification_hash = Some(mpc_verification_hash);
-    auction.settlement_authorized = true;
+    if !reserve_met {
+        auction.set_flag(AuctionAccount::UNSOLD, true);
+    
+        emit!(AuctionUnsold {
+            auction_id,
+            reserve_met,
+            participating_bids: auction.participating_bids as u64,
+            recorded_at: now,
+        });
+    
+        return Ok(());
+    }
+    
+    // Store MPC verification hash and authorize settlement
+    auction.transition(AuctionStatus::SettlementAuthorized)?;
+    auction.settlement_authorized_at = Some(now);
+    
+    emit!(SettlementResultRecorded {
+        auction_id,
+        winner: auction.winner.unwrap_or_default(),
+        winning_amount: auction.winning_amount,
+        recorded_at: now,
+    });
 
     msg!("Settlement authorized for auction {} with MPC hash: {:?}", auction_id, mpc_verification_hash);
 
     Ok(())
 }
 
+/// Reveal the winner of a privacy-mode auction.
+///
+/// The winner decrypts the blinding from the circuit output and proves it by
+/// opening the published commitment against their own key. Until then the
+/// auction has no public winner and cannot be executed. A win not claimed
+/// within `WIN_CLAIM_PERIOD` can be expired with `expire_private_win`.
+pub fn claim_win<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimWin<'info>>,
+    auction_id: u64,
+    blinding: [u8; 32],
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let winner = ctx.accounts.winner.key();
+    
+    require!(
+        auction.status == AuctionStatus::SettlementAuthorized && auction.winner.is_none(),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    let private_winner = auction.encrypted_winner
+        .ok_or(ShadowProtocolError::InvalidAuctionStatus)?;
+    
+    let commitment = CryptoUtils::generate_winner_commitment(auction_id, winner, blinding)?;
+    require!(
+        commitment == private_winner.commitment,
+        ShadowProtocolError::WinnerCommitmentMismatch
+    );
+    
+    // A winner bidding from a shared budget must have been allocated this auction
+    require_budget_allocated(&ctx.accounts.bid, ctx.remaining_accounts.first())?;
+    
+    auction.winner = Some(winner);
+    auction.encrypted_winner = None;
+    
+    emit!(WinClaimed {
+        auction_id,
+        winner,
+        winning_amount: auction.winning_amount,
+        claimed_at: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Expire a privacy-mode win nobody claimed within `WIN_CLAIM_PERIOD`.
+///
+/// Permissionless. The settlement is voided and the auction recorded as
+/// unsold, so `finalize_unsold` returns the asset to the creator and every
+/// bidder reclaims their collateral.
+pub fn expire_private_win(ctx: Context<ExpirePrivateWin>, auction_id: u64) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(
+        auction.status == AuctionStatus::SettlementAuthorized
+            && auction.winner.is_none()
+            && auction.encrypted_winner.is_some(),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    let authorized_at = auction.settlement_authorized_at
+        .ok_or(ShadowProtocolError::InvalidAuctionStatus)?;
+    require!(
+        now >= authorized_at.saturating_add(WIN_CLAIM_PERIOD),
+        ShadowProtocolError::WinClaimWindowOpen
+    );
+    
+    void_settlement(auction)?;
+    auction.set_flag(AuctionAccount::UNSOLD, true);
+    
+    emit!(PrivateWinExpired {
+        auction_id,
+        expired_at: now,
+    });
+    
+    Ok(())
+}
+
 pub fn settle_auction(
     ctx: Context<SettleAuction>,
-    auction_id: u64,
-    computation_offset: u64,
+    _auction_id: u64,
+    _computation_offset: u64,
 ) -> Result<()> {
     let auction = &mut ctx.accounts.auction;
     let protocol = &ctx.accounts.protocol_state;
@@ -58,71 +185,305 @@ pub fn settle_auction(
     );
     
     require!(
-        clock.unix_timestamp >= auction.end_time,
+        auction.has_ended(&clock),
         ShadowProtocolError::AuctionNotEnded
     );
     
-    require!(
-        auction.status != AuctionStatus::Settled,
-        ShadowProtocolError::AuctionAlreadySettled
-    );
-    
     require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
     
     // Mark as ended to trigger MPC computation
-    auction.status = AuctionStatus::Ended;
+    if auction.status != AuctionStatus::Ended {
+        mark_auction_ended(auction, &clock)?;
+    }
     
     
     Ok(())
 }
 
-pub fn batch_settle(
-    ctx: Context<BatchSettle>,
+/// Settle several ended auctions with a single `process_batch_settlement`
+/// computation. The caller must be the creator of every auction in the
+/// batch, or the protocol authority, and none may have a computation queued.
+///
+/// Remaining accounts: one auction account per entry in `auction_ids`, in order.
+pub fn batch_settle<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchSettle<'info>>,
     auction_ids: Vec<u64>,
     computation_offset: u64,
 ) -> Result<()> {
-    let batch = &mut ctx.accounts.batch;
+    let protocol = &mut ctx.accounts.protocol_state;
     let clock = Clock::get()?;
     
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    
     require!(
-        auction_ids.len() > 0 && auction_ids.len() <= 10,
+        !auction_ids.is_empty() && auction_ids.len() <= MAX_BATCH_AUCTIONS,
         ShadowProtocolError::InvalidBatchSize
     );
     
-    batch.batch_id = clock.unix_timestamp as u64;
+    require!(
+        ctx.remaining_accounts.len() == auction_ids.len(),
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    for (i, auction_id) in auction_ids.iter().enumerate() {
+        require!(
+            !auction_ids[..i].contains(auction_id),
+            ShadowProtocolError::InvalidBatchSize
+        );
+    }
+    
+    let batch_id = protocol.next_batch_id;
+    protocol.next_batch_id = protocol.next_batch_id
+        .checked_add(1)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    
+    let computation_id = generate_batch_computation_id(batch_id, &auction_ids);
+    
+    // Validate each auction and hand it over to the batch computation
+    for (info, auction_id) in ctx.remaining_accounts.iter().zip(auction_ids.iter()) {
+        let mut auction = Account::<AuctionAccount>::try_from(info)?;
+        require!(
+            auction.auction_id == *auction_id,
+            ShadowProtocolError::InvalidRemainingAccounts
+        );
+        
+        require!(
+            auction.status == AuctionStatus::Ended ||
+            (auction.status == AuctionStatus::Active && auction.has_ended(&clock)),
+            ShadowProtocolError::AuctionNotEnded
+        );
+        
+        require!(
+            !auction.settlement_authorized(),
+            ShadowProtocolError::AuctionAlreadySettled
+        );
+        
+        // The authority may queue a stuck settlement as operator; queueing
+        // changes no configuration, so it is not routed through governance
+        require!(
+            ctx.accounts.creator.key() == auction.creator
+                || ctx.accounts.creator.key() == protocol.authority,
+            ShadowProtocolError::Unauthorized
+        );
+        
+        require!(
+            auction.mpc_computation_id.is_none(),
+            ShadowProtocolError::ComputationAlreadyQueued
+        );
+        
+        if auction.status == AuctionStatus::Active {
+            mark_auction_ended(&mut auction, &clock)?;
+        }
+        auction.mpc_computation_id = Some(computation_id);
+        auction.computation_queued_at = Some(clock.unix_timestamp);
+        auction.exit(&crate::ID)?;
+    }
+    
+    let batch = &mut ctx.accounts.batch;
+    batch.batch_id = batch_id;
     batch.creator = ctx.accounts.creator.key();
     batch.auction_ids = auction_ids.clone();
-    batch.status = BatchStatus::Created;
+    batch.status = BatchStatus::Settling;
     batch.created_at = clock.unix_timestamp;
     batch.settled_at = None;
+    batch.computation_id = Some(computation_id);
+    batch.results = Vec::new();
+    batch.results_distributed = false;
     batch.bump = ctx.bumps.batch;
     
-    
-    batch.status = BatchStatus::Settling;
-    
     emit!(BatchSettlementCreated {
-        batch_id: batch.batch_id,
+        batch_id,
         creator: ctx.accounts.creator.key(),
         auction_count: auction_ids.len() as u64,
     });
     
+    emit!(BatchComputationQueued {
+        batch_id,
+        computation_id,
+        auction_count: auction_ids.len() as u64,
+        computation_offset,
+        queued_at: clock.unix_timestamp,
+    });
+    
     Ok(())
 }
 
-pub fn execute_settlement(
-    ctx: Context<ExecuteSettlement>,
+/// Write a settled batch's per-auction results into each auction and
+/// authorize settlement where the winner met the reserve.
+///
+/// Remaining accounts: the batch's auction accounts, in `auction_ids` order.
+pub fn distribute_batch_results<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeBatchResults<'info>>,
+) -> Result<()> {
+    let batch = &mut ctx.accounts.batch;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    
+    require!(
+        batch.status == BatchStatus::Settled,
+        ShadowProtocolError::BatchSettlementFailed
+    );
+    
+    require!(
+        !batch.results_distributed,
+        ShadowProtocolError::BatchResultsAlreadyDistributed
+    );
+    
+    require!(
+        ctx.remaining_accounts.len() == batch.results.len(),
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    
+    let mut authorized_count = 0u64;
+    
+    for (info, result) in ctx.remaining_accounts.iter().zip(batch.results.iter()) {
+        let mut auction = Account::<AuctionAccount>::try_from(info)?;
+        require!(
+            auction.auction_id == result.auction_id,
+            ShadowProtocolError::InvalidRemainingAccounts
+        );
+        
+        require!(
+            auction.mpc_computation_id == batch.computation_id,
+            ShadowProtocolError::AuctionNotInBatch
+        );
+        
+        require!(
+            auction.status == AuctionStatus::Ended && !auction.settlement_authorized(),
+            ShadowProtocolError::InvalidAuctionStatus
+        );
+        
+        // Auctions without a valid winner are left for `finalize_unsold`
+        let has_winner = result.met_reserve
+            && result.winner != Pubkey::default()
+            && result.winning_amount >= auction.minimum_bid;
+        auction.set_flag(AuctionAccount::RESERVE_MET, result.met_reserve);
+        
+        if has_winner {
+            auction.transition(AuctionStatus::SettlementAuthorized)?;
+            auction.winner = Some(result.winner);
+            auction.winning_amount = result.winning_amount;
+            auction.mpc_verification_hash = Some(compute_settlement_hash(
+                auction.auction_id,
+                result.winner,
+                result.winning_amount,
+                result.met_reserve,
+                auction.bid_increment,
+                auction.bid_count as u64,
+                auction.end_time,
+            ));
+            auction.settlement_authorized_at = Some(clock.unix_timestamp);
+            authorized_count += 1;
+        } else {
+            auction.set_flag(AuctionAccount::UNSOLD, true);
+        }
+        
+        auction.exit(&crate::ID)?;
+    }
+    
+    batch.results_distributed = true;
+    
+    emit!(BatchResultsDistributed {
+        batch_id: batch.batch_id,
+        authorized_count,
+        distributed_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Close one of the creator's batches and reclaim its rent.
+///
+/// A settled batch can only be cancelled once its results have been
+/// distributed, so a completed computation is never discarded.
+pub fn cancel_batch(ctx: Context<CancelBatch>) -> Result<()> {
+    let batch = &ctx.accounts.batch;
+    let clock = Clock::get()?;
+    
+    let cancellable = match batch.status {
+        BatchStatus::Created | BatchStatus::Settling | BatchStatus::Failed => true,
+        BatchStatus::Settled => batch.results_distributed,
+    };
+    require!(cancellable, ShadowProtocolError::BatchNotCancellable);
+    
+    emit!(BatchClosed {
+        batch_id: batch.batch_id,
+        creator: batch.creator,
+        status: batch.status,
+        expired: false,
+        closed_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Close a batch whose computation never reported back. Permissionless;
+/// rent is returned to the batch creator.
+pub fn expire_batch(ctx: Context<ExpireBatch>) -> Result<()> {
+    let batch = &ctx.accounts.batch;
+    let clock = Clock::get()?;
+    
+    require!(
+        batch.status == BatchStatus::Created || batch.status == BatchStatus::Settling,
+        ShadowProtocolError::BatchNotCancellable
+    );
+    
+    require!(
+        clock.unix_timestamp >= batch.created_at + BATCH_EXPIRY_PERIOD,
+        ShadowProtocolError::BatchNotExpired
+    );
+    
+    emit!(BatchClosed {
+        batch_id: batch.batch_id,
+        creator: batch.creator,
+        status: BatchStatus::Failed,
+        expired: true,
+        closed_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+pub(crate) fn generate_batch_computation_id(batch_id: u64, auction_ids: &[u64]) -> [u8; 32] {
+    use anchor_lang::solana_program::hash::hash;
+    
+    let mut data = Vec::new();
+    data.extend_from_slice(b"shadow_batch_settlement");
+    data.extend_from_slice(&batch_id.to_le_bytes());
+    for auction_id in auction_ids {
+        data.extend_from_slice(&auction_id.to_le_bytes());
+    }
+    
+    hash(&data).to_bytes()
+}
+
+/// Pay the winning bid and deliver the asset.
+///
+/// Safe to pre-sign with a durable nonce: the transaction carries its own
+/// `expires_at` in place of a recent blockhash, and `max_fee_bps` caps the
+/// protocol fee it may be charged should the fee change before it lands.
+pub fn execute_settlement<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteSettlement<'info>>,
     auction_id: u64,
     winner: Pubkey,
     winning_amount: u64,
+    payout_swap: Option<PayoutSwap>,
+    expires_at: i64,
+    max_fee_bps: u16,
 ) -> Result<()> {
     let auction = &mut ctx.accounts.auction;
+    let features = &ctx.accounts.features;
     let protocol = &ctx.accounts.protocol_state;
+    let now = Clock::get()?.unix_timestamp;
     
     require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(now < expires_at, ShadowProtocolError::SettlementExpired);
     
     // Check that settlement is authorized by MPC
     require!(
-        auction.settlement_authorized,
+        auction.settlement_authorized(),
         ShadowProtocolError::SettlementNotAuthorized
     );
     
@@ -133,56 +494,161 @@ pub fn execute_settlement(
     );
     
     require!(
-        auction.status == AuctionStatus::Ended,
+        auction.status == AuctionStatus::SettlementAuthorized,
         ShadowProtocolError::InvalidAuctionStatus
     );
     
+    require_dispute_window_passed(auction, protocol, now)?;
+    auction.require_reserve_met()?;
+    ctx.accounts.protocol_stats.require_settlements_open()?;
+    require!(
+        !auction.has_flag(AuctionAccount::SETTLING),
+        ShadowProtocolError::SettlementInProgress
+    );
+    
+    require!(
+        auction.open_yield_positions == 0,
+        ShadowProtocolError::YieldPositionsOpen
+    );
+    
     // Verify settlement parameters match MPC results
     require!(
         auction.winner == Some(winner),
         ShadowProtocolError::InvalidWinnerDetermination
     );
     
+    // Whoever holds the winning bid's position pays and takes delivery
+    let holder = bid_holder(
+        winner,
+        &ctx.accounts.winner_position_mint,
+        ctx.accounts.winner_position_account.as_ref(),
+    )?;
     require!(
-        auction.winning_amount == winning_amount,
-        ShadowProtocolError::InvalidAssetAmount
+        ctx.accounts.winner.key() == holder,
+        ShadowProtocolError::Unauthorized
     );
     
     require!(
-        winning_amount > 0,
+        auction.winning_amount == winning_amount,
         ShadowProtocolError::InvalidAssetAmount
     );
     
-    // Validate asset vault has sufficient funds and matches auction amount
     require!(
-        ctx.accounts.asset_vault.amount >= auction.asset_amount,
+        winning_amount > 0,
         ShadowProtocolError::InvalidAssetAmount
     );
     
+    // Catch corrupted MPC results before any funds move
+    check_oracle_band(
+        features.price_oracle,
+        ctx.accounts.price_feed.as_ref().map(|feed| feed.as_ref()),
+        auction.asset_amount,
+        winning_amount,
+        now,
+    )?;
+    
+    // Validate the recorded deposit covers the asset; donations don't count
+    auction.require_vault_covers(ctx.accounts.asset_vault.amount, auction.asset_amount)?;
+    
     // Validate winner is provided (already set by MPC)
     require!(
         winner != Pubkey::default(),
         ShadowProtocolError::InvalidWinnerDetermination
     );
-    auction.status = AuctionStatus::Settled;
-    auction.settled_at = Some(Clock::get()?.unix_timestamp);
     
-    // Calculate protocol fee
+    // Trailing remaining accounts belong to the payout swap, those before
+    // them to the settlement hook, and the rest to compliance
+    let swap_account_count = payout_swap.as_ref().map_or(0, |swap| swap.account_count as usize);
+    let hook_account_count = features.settlement_hook.map_or(0, |hook| hook.account_count as usize);
+    require!(
+        swap_account_count + hook_account_count <= ctx.remaining_accounts.len(),
+        ShadowProtocolError::InvalidRemainingAccounts
+    );
+    let (compliance_accounts, swap_accounts) = ctx.remaining_accounts
+        .split_at(ctx.remaining_accounts.len() - swap_account_count);
+    let (compliance_accounts, hook_accounts) = compliance_accounts
+        .split_at(compliance_accounts.len() - hook_account_count);
+    require!(
+        features.payout_conversion.is_some() == payout_swap.is_some(),
+        ShadowProtocolError::InvalidPayoutConversion
+    );
+    
+    // Proceeds go to the holder of the auction's position, if it has one
+    let payee = proceeds_recipient(auction, ctx.accounts.position_account.as_ref())?;
+    require!(
+        ctx.accounts.creator_payment_account.owner == payee,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    // Both sides of the trade are screened before anything moves
+    require_not_denied(&ctx.accounts.winner_deny_entry, DenyListEntry::SETTLEMENT)?;
+    require_not_denied(&ctx.accounts.creator_deny_entry, DenyListEntry::SETTLEMENT)?;
+    auction.require_payment_mint(ctx.accounts.winner_payment_account.mint)?;
+    let payment_mint = auction.payment_mint;
+    let compliance_program = ctx.accounts.compliance_program.as_ref().map(|program| program.as_ref());
+    check_compliance(
+        auction.compliance_program,
+        compliance_program,
+        &ctx.accounts.winner.to_account_info(),
+        compliance_accounts,
+        winning_amount,
+        payment_mint,
+    )?;
+    check_compliance(
+        auction.compliance_program,
+        compliance_program,
+        &ctx.accounts.creator,
+        compliance_accounts,
+        winning_amount,
+        payment_mint,
+    )?;
+    
+    auction.transition(AuctionStatus::AssetsDistributed)?;
+    auction.settled_at = Some(now);
+    
+    let fee_bps = settlement_fee_bps(
+        auction,
+        protocol,
+        ctx.accounts.creator_index.as_deref_mut(),
+        payment_mint,
+        winning_amount,
+        now,
+    );
+    require!(fee_bps <= max_fee_bps, ShadowProtocolError::SettlementFeeTooHigh);
     let fee_amount = winning_amount
-        .checked_mul(protocol.protocol_fee as u64)
+        .checked_mul(fee_bps as u64)
         .ok_or(ShadowProtocolError::FeeCalculationOverflow)?
         .checked_div(10000)
         .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
     
+    let house_fee_amount = house_fee(
+        auction,
+        ctx.accounts.auction_house.as_ref(),
+        ctx.accounts.house_fee_account.as_ref(),
+        payment_mint,
+        winning_amount,
+    )?;
+    
     let transfer_amount = winning_amount
         .checked_sub(fee_amount)
+        .and_then(|amount| amount.checked_sub(house_fee_amount))
         .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
     
+    let fee_split = split_fee(
+        protocol,
+        payment_mint,
+        fee_amount,
+        &ctx.accounts.insurance_fund,
+        ctx.accounts.insurance_vault.as_ref(),
+        &ctx.accounts.staking_pool,
+        ctx.accounts.staking_reward_vault.as_ref(),
+    )?;
+    let protocol_fee_amount = fee_split.protocol;
+    
     // Transfer asset to winner
-    let auction_id_bytes = auction.auction_id.to_le_bytes();
     let auction_seeds = &[
         AUCTION_SEED,
-        auction_id_bytes.as_ref(),
+        auction.pda_seed(),
         &[auction.bump],
     ];
     let signer_seeds = &[&auction_seeds[..]];
@@ -199,22 +665,106 @@ pub fn execute_settlement(
         ),
         auction.asset_amount, // Use the stored asset amount
     )?;
+    let asset_amount = auction.asset_amount;
+    auction.record_vault_withdrawal(asset_amount)?;
     
-    // Transfer payment from winner to creator (minus fees)
-    transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.winner_payment_account.to_account_info(),
-                to: ctx.accounts.creator_payment_account.to_account_info(),
-                authority: ctx.accounts.winner.to_account_info(),
-            },
-        ),
-        transfer_amount,
-    )?;
+    // Transfer payment from winner to creator (minus fees), via the swap
+    // when the creator asked to be paid in another mint
+    match (features.payout_conversion, payout_swap) {
+        (Some(conversion), Some(swap)) => {
+            let input_account = ctx.accounts.conversion_input_account.as_mut()
+                .ok_or(ShadowProtocolError::InvalidPayoutConversion)?;
+            
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.winner_payment_account.to_account_info(),
+                        to: input_account.to_account_info(),
+                        authority: ctx.accounts.winner.to_account_info(),
+                    },
+                ),
+                transfer_amount,
+            )?;
+            input_account.reload()?;
+            
+            convert_payout(
+                &conversion,
+                auction_id,
+                payee,
+                transfer_amount,
+                swap,
+                PayoutSwapAccounts {
+                    authority: ctx.accounts.conversion_authority.as_ref()
+                        .ok_or(ShadowProtocolError::InvalidPayoutConversion)?,
+                    authority_bump: ctx.bumps.conversion_authority
+                        .ok_or(ShadowProtocolError::InvalidPayoutConversion)?,
+                    input_account,
+                    output_account: ctx.accounts.conversion_output_account.as_mut()
+                        .ok_or(ShadowProtocolError::InvalidPayoutConversion)?,
+                    creator_output_account: ctx.accounts.creator_output_account.as_ref()
+                        .ok_or(ShadowProtocolError::InvalidPayoutConversion)?,
+                    swap_program: ctx.accounts.swap_program.as_ref()
+                        .ok_or(ShadowProtocolError::InvalidPayoutConversion)?,
+                    token_program: ctx.accounts.token_program.as_ref(),
+                    swap_accounts,
+                },
+            )?;
+        }
+        _ => match features.payout_vesting {
+            Some(schedule) => {
+                let stream = ctx.accounts.payout_stream.as_mut()
+                    .ok_or(ShadowProtocolError::InvalidVestingSchedule)?;
+                let stream_vault = ctx.accounts.stream_vault.as_ref()
+                    .ok_or(ShadowProtocolError::InvalidVestingSchedule)?;
+                require!(
+                    stream_vault.owner == stream.key()
+                        && stream_vault.mint == payment_mint,
+                    ShadowProtocolError::InvalidTokenAccount
+                );
+                
+                transfer(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.winner_payment_account.to_account_info(),
+                            to: stream_vault.to_account_info(),
+                            authority: ctx.accounts.winner.to_account_info(),
+                        },
+                    ),
+                    transfer_amount,
+                )?;
+                
+                stream.open(auction_id, payee, stream_vault.key(), transfer_amount, now, schedule);
+                stream.bump = ctx.bumps.payout_stream
+                    .ok_or(ShadowProtocolError::InvalidVestingSchedule)?;
+                
+                emit!(PayoutStreamOpened {
+                    auction_id,
+                    recipient: stream.recipient,
+                    total: transfer_amount,
+                    start: now,
+                    schedule,
+                });
+            }
+            None => {
+                transfer(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.winner_payment_account.to_account_info(),
+                            to: ctx.accounts.creator_payment_account.to_account_info(),
+                            authority: ctx.accounts.winner.to_account_info(),
+                        },
+                    ),
+                    transfer_amount,
+                )?;
+            }
+        },
+    }
     
     // Transfer fee to protocol
-    if fee_amount > 0 {
+    if protocol_fee_amount > 0 {
         transfer(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
@@ -224,20 +774,256 @@ pub fn execute_settlement(
                     authority: ctx.accounts.winner.to_account_info(),
                 },
             ),
-            fee_amount,
+            protocol_fee_amount,
         )?;
     }
     
+    if let Some(house_fee_account) = &ctx.accounts.house_fee_account {
+        if house_fee_amount > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.winner_payment_account.to_account_info(),
+                        to: house_fee_account.to_account_info(),
+                        authority: ctx.accounts.winner.to_account_info(),
+                    },
+                ),
+                house_fee_amount,
+            )?;
+        }
+    }
+    
+    if let Some(vault) = &ctx.accounts.insurance_vault {
+        if fee_split.insurance > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.winner_payment_account.to_account_info(),
+                        to: vault.to_account_info(),
+                        authority: ctx.accounts.winner.to_account_info(),
+                    },
+                ),
+                fee_split.insurance,
+            )?;
+        }
+    }
+    
+    if let Some(vault) = &ctx.accounts.staking_reward_vault {
+        if fee_split.staking > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.winner_payment_account.to_account_info(),
+                        to: vault.to_account_info(),
+                        authority: ctx.accounts.winner.to_account_info(),
+                    },
+                ),
+                fee_split.staking,
+            )?;
+        }
+    }
+    accrue_fee_split(&fee_split, &ctx.accounts.insurance_fund, &ctx.accounts.staking_pool)?;
+    
+    release_creation_deposit(auction, &ctx.accounts.creator)?;
+    
+    ctx.accounts.protocol_stats.record_settlement(payment_mint, winning_amount, fee_amount);
+    record_breaker_volume(&mut ctx.accounts.protocol_stats, protocol, payment_mint, winning_amount)?;
+    let fee_ledger = &mut ctx.accounts.fee_ledger;
+    fee_ledger.open(payment_mint, ctx.bumps.fee_ledger);
+    fee_ledger.accrue(protocol_fee_amount, now);
+    
+    // The hook reads the auction as settled
+    auction.exit(&crate::ID)?;
+    invoke_settlement_hook(
+        auction,
+        features.settlement_hook,
+        ctx.accounts.settlement_hook_program.as_ref().map(|program| program.as_ref()),
+        hook_accounts,
+        winner,
+        winning_amount,
+    )?;
+    
     emit!(AuctionSettled {
         auction_id,
         winner: Some(winner),
         winning_amount,
-        settled_at: Clock::get()?.unix_timestamp,
+        second_highest_bid: auction.second_highest_bid,
+        reserve_met: auction.has_flag(AuctionAccount::RESERVE_MET),
+        participating_bids: auction.participating_bids as u64,
+        settled_at: now,
     });
     
     Ok(())
 }
 
+/// Protocol fee rate for a settlement, discounted by the seller's trailing
+/// volume tier. Counts the sale toward that volume when it is in the tier mint.
+pub(crate) fn settlement_fee_bps(
+    auction: &AuctionAccount,
+    protocol: &ProtocolState,
+    creator_index: Option<&mut CreatorIndex>,
+    payment_mint: Pubkey,
+    winning_amount: u64,
+    now: i64,
+) -> u16 {
+    let mut fee_bps = auction.protocol_fee_bps(protocol.protocol_fee);
+    if let Some(creator_index) = creator_index {
+        creator_index.roll_volume(now);
+        if let Some(tier_fee) = protocol.tier_fee(creator_index.trailing_volume()) {
+            fee_bps = fee_bps.min(tier_fee);
+        }
+        if payment_mint == protocol.fee_tier_mint {
+            creator_index.record_volume(now, winning_amount);
+        }
+    }
+    fee_bps
+}
+
+/// House fee for auctions listed through an auction house, out of the
+/// creator's share
+pub(crate) fn house_fee(
+    auction: &AuctionAccount,
+    auction_house: Option<&Account<AuctionHouse>>,
+    house_fee_account: Option<&Account<TokenAccount>>,
+    payment_mint: Pubkey,
+    winning_amount: u64,
+) -> Result<u64> {
+    let Some(terms) = auction.auction_house else {
+        return Ok(0);
+    };
+    
+    let house = auction_house.ok_or(ShadowProtocolError::InvalidAuctionHouse)?;
+    let house_fee_account = house_fee_account.ok_or(ShadowProtocolError::InvalidAuctionHouse)?;
+    require!(
+        house.key() == terms.house
+            && house_fee_account.owner == house.fee_recipient
+            && house_fee_account.mint == payment_mint,
+        ShadowProtocolError::InvalidAuctionHouse
+    );
+    
+    Ok(winning_amount
+        .checked_mul(terms.fee_bps as u64)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?
+        / 10000)
+}
+
+/// A settlement fee split between the protocol, the payment mint's
+/// insurance fund and the stakers
+pub(crate) struct FeeSplit {
+    pub insurance: u64,
+    pub staking: u64,
+    pub protocol: u64,
+}
+
+/// Split `fee_amount` paid in `payment_mint`. Callers pin `insurance_fund`
+/// and `staking_pool` with seeds, so a fund for the mint or a pool rewarding
+/// in it cannot be left out: whichever exists must come with its vault.
+pub(crate) fn split_fee(
+    protocol: &ProtocolState,
+    payment_mint: Pubkey,
+    fee_amount: u64,
+    insurance_fund: &AccountInfo,
+    insurance_vault: Option<&Account<TokenAccount>>,
+    staking_pool: &AccountInfo,
+    staking_reward_vault: Option<&Account<TokenAccount>>,
+) -> Result<FeeSplit> {
+    let insurance = if insurance_fund.owner == &crate::ID && !insurance_fund.data_is_empty() {
+        let fund = InsuranceFund::try_deserialize(&mut &insurance_fund.try_borrow_data()?[..])?;
+        let vault = insurance_vault.ok_or(ShadowProtocolError::InvalidTokenAccount)?;
+        require!(
+            fund.mint == payment_mint && vault.key() == fund.vault,
+            ShadowProtocolError::InvalidTokenAccount
+        );
+        insurance_share(fee_amount, protocol.insurance_fee_share)?
+    } else {
+        0
+    };
+    
+    let mut staking = 0;
+    if staking_pool.owner == &crate::ID && !staking_pool.data_is_empty() {
+        let pool = StakingPool::try_deserialize(&mut &staking_pool.try_borrow_data()?[..])?;
+        if pool.reward_mint == payment_mint {
+            let vault = staking_reward_vault.ok_or(ShadowProtocolError::InvalidTokenAccount)?;
+            require!(vault.key() == pool.reward_vault, ShadowProtocolError::InvalidTokenAccount);
+            staking = staking_share(&pool, fee_amount - insurance)?;
+        }
+    }
+    
+    Ok(FeeSplit {
+        insurance,
+        staking,
+        protocol: fee_amount - insurance - staking,
+    })
+}
+
+/// Credit the shares of `split`, once paid, to the insurance fund and the
+/// staking pool
+pub(crate) fn accrue_fee_split(
+    split: &FeeSplit,
+    insurance_fund: &AccountInfo,
+    staking_pool: &AccountInfo,
+) -> Result<()> {
+    if split.insurance > 0 {
+        let mut fund = InsuranceFund::try_deserialize(&mut &insurance_fund.try_borrow_data()?[..])?;
+        fund.total_accrued = fund.total_accrued.saturating_add(split.insurance);
+        fund.try_serialize(&mut &mut insurance_fund.try_borrow_mut_data()?[..])?;
+    }
+    if split.staking > 0 {
+        let mut pool = StakingPool::try_deserialize(&mut &staking_pool.try_borrow_data()?[..])?;
+        pool.accrue(split.staking);
+        pool.try_serialize(&mut &mut staking_pool.try_borrow_mut_data()?[..])?;
+    }
+    
+    Ok(())
+}
+
+#[event]
+pub struct WinClaimed {
+    pub auction_id: u64,
+    pub winner: Pubkey,
+    pub winning_amount: u64,
+    pub claimed_at: i64,
+}
+
+#[event]
+pub struct PrivateWinExpired {
+    pub auction_id: u64,
+    pub expired_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ClaimWin<'info> {
+    pub winner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), winner.key().as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ExpirePrivateWin<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+}
+
 #[derive(Accounts)]
 #[instruction(auction_id: u64)]
 pub struct AuthorizeSettlement<'info> {
@@ -245,8 +1031,7 @@ pub struct AuthorizeSettlement<'info> {
     
     #[account(
         mut,
-        seeds = [AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
-        bump = auction.bump
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
     )]
     pub auction: Account<'info, AuctionAccount>,
     
@@ -265,8 +1050,7 @@ pub struct SettleAuction<'info> {
     
     #[account(
         mut,
-        seeds = [AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
-        bump = auction.bump
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
     )]
     pub auction: Account<'info, AuctionAccount>,
     
@@ -289,12 +1073,13 @@ pub struct BatchSettle<'info> {
         init,
         payer = creator,
         space = 8 + BatchSettlement::INIT_SPACE,
-        seeds = [BATCH_SEED, creator.key().as_ref()],
+        seeds = [BATCH_SEED, protocol_state.next_batch_id.to_le_bytes().as_ref()],
         bump
     )]
     pub batch: Account<'info, BatchSettlement>,
     
     #[account(
+        mut,
         seeds = [PROTOCOL_SEED],
         bump = protocol_state.bump
     )]
@@ -304,6 +1089,52 @@ pub struct BatchSettle<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct CancelBatch<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator @ ShadowProtocolError::Unauthorized,
+        seeds = [BATCH_SEED, batch.batch_id.to_le_bytes().as_ref()],
+        bump = batch.bump
+    )]
+    pub batch: Account<'info, BatchSettlement>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireBatch<'info> {
+    #[account(
+        mut,
+        close = creator,
+        seeds = [BATCH_SEED, batch.batch_id.to_le_bytes().as_ref()],
+        bump = batch.bump
+    )]
+    pub batch: Account<'info, BatchSettlement>,
+    
+    /// CHECK: Batch creator, receives the batch account's rent
+    #[account(mut, address = batch.creator)]
+    pub creator: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeBatchResults<'info> {
+    #[account(
+        mut,
+        seeds = [BATCH_SEED, batch.batch_id.to_le_bytes().as_ref()],
+        bump = batch.bump
+    )]
+    pub batch: Account<'info, BatchSettlement>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
 #[derive(Accounts)]
 #[instruction(auction_id: u64)]
 pub struct ExecuteSettlement<'info> {
@@ -312,17 +1143,30 @@ pub struct ExecuteSettlement<'info> {
     
     #[account(
         mut,
-        seeds = [AUCTION_SEED, auction_id.to_le_bytes().as_ref()],
-        bump = auction.bump
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
     )]
     pub auction: Account<'info, AuctionAccount>,
     
+    /// Oracle, payout and hook settings settlement applies
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
     #[account(
         seeds = [PROTOCOL_SEED],
         bump = protocol_state.bump
     )]
     pub protocol_state: Account<'info, ProtocolState>,
     
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
     /// Asset vault holding the auctioned item
     #[account(
         mut,
@@ -339,14 +1183,40 @@ pub struct ExecuteSettlement<'info> {
     )]
     pub winner_asset_account: Account<'info, TokenAccount>,
     
-    /// Winner's payment account
+    /// Winner's payment account, in the auction's payment mint
     #[account(mut)]
     pub winner_payment_account: Account<'info, TokenAccount>,
     
-    /// Creator's payment account
+    /// CHECK: Position mint PDA of the winning bid; uninitialized unless one was minted
+    #[account(
+        seeds = [BID_POSITION_SEED, auction_id.to_le_bytes().as_ref(), auction.winner.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub winner_position_mint: UncheckedAccount<'info>,
+    
+    /// Token account holding the winning bid's position; required when it has one
+    pub winner_position_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Creator's payment account, or the position holder's when the auction
+    /// has a position
     #[account(mut)]
     pub creator_payment_account: Account<'info, TokenAccount>,
     
+    /// Token account holding the auction's position; required when it has one
+    pub position_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Auction creator, refunded the creation deposit
+    #[account(mut, address = auction.creator @ ShadowProtocolError::Unauthorized)]
+    pub creator: AccountInfo<'info>,
+    
+    /// CHECK: Winner's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, winner.key().as_ref()], bump)]
+    pub winner_deny_entry: UncheckedAccount<'info>,
+    
+    /// CHECK: Deny-list PDA of whoever is paid the proceeds; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, creator_payment_account.owner.as_ref()], bump)]
+    pub creator_deny_entry: UncheckedAccount<'info>,
+    
     /// Protocol fee account
     #[account(
         mut,
@@ -354,7 +1224,216 @@ pub struct ExecuteSettlement<'info> {
     )]
     pub protocol_fee_account: Account<'info, TokenAccount>,
     
+    /// Protocol fees accrued in the payment mint
+    #[account(
+        init_if_needed,
+        payer = winner,
+        space = 8 + FeeLedger::INIT_SPACE,
+        seeds = [FEE_LEDGER_SEED, auction.payment_mint.as_ref()],
+        bump
+    )]
+    pub fee_ledger: Account<'info, FeeLedger>,
+    
+    /// CHECK: Insurance fund PDA for the payment mint; may be uninitialized
+    #[account(mut, seeds = [INSURANCE_FUND_SEED, auction.payment_mint.as_ref()], bump)]
+    pub insurance_fund: UncheckedAccount<'info>,
+    
+    /// Insurance fund's vault; required when the fund exists
+    #[account(mut)]
+    pub insurance_vault: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Staking pool PDA; may be uninitialized
+    #[account(mut, seeds = [STAKING_POOL_SEED], bump)]
+    pub staking_pool: UncheckedAccount<'info>,
+    
+    /// Staking pool's reward vault; required when it rewards in the payment mint
+    #[account(mut)]
+    pub staking_reward_vault: Option<Account<'info, TokenAccount>>,
+    
+    /// The auction's house; required when it was listed through one
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    /// Creator's index, carrying the trailing volume behind their fee tier;
+    /// omit to settle at the undiscounted fee
+    #[account(
+        mut,
+        seeds = [CREATOR_INDEX_SEED, auction.creator.as_ref()],
+        bump = creator_index.bump
+    )]
+    pub creator_index: Option<Account<'info, CreatorIndex>>,
+    
+    /// House fee recipient's account for the payment mint
+    #[account(mut)]
+    pub house_fee_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Matched against the auction's compliance program; required when one is set
+    pub compliance_program: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Pyth price update, parsed and matched against the auction's feed id
+    pub price_feed: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Signs the payout swap; owns the conversion token accounts
+    #[account(seeds = [PAYOUT_CONVERSION_SEED, auction_id.to_le_bytes().as_ref()], bump)]
+    pub conversion_authority: Option<UncheckedAccount<'info>>,
+    
+    /// Receives the creator's proceeds ahead of the swap
+    #[account(mut)]
+    pub conversion_input_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Receives the swap output ahead of the payout
+    #[account(mut)]
+    pub conversion_output_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Proceeds recipient's account in the conversion output mint
+    #[account(mut)]
+    pub creator_output_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Matched against the auction's payout conversion
+    pub swap_program: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Matched against the auction's settlement hook; required when one is set
+    pub settlement_hook_program: Option<UncheckedAccount<'info>>,
+    
+    /// Vesting stream opened for the proceeds, claimable by their recipient
+    #[account(
+        init,
+        payer = winner,
+        space = 8 + PayoutStream::INIT_SPACE,
+        seeds = [PAYOUT_STREAM_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub payout_stream: Option<Account<'info, PayoutStream>>,
+    
+    /// Token account owned by the stream, in the payment mint
+    #[account(mut)]
+    pub stream_vault: Option<Account<'info, TokenAccount>>,
+    
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token::state::{Account as SplTokenAccount, AccountState};
+    
+    fn token_account_data(mint: Pubkey) -> Vec<u8> {
+        let mut data = vec![0u8; SplTokenAccount::LEN];
+        SplTokenAccount {
+            mint,
+            owner: Pubkey::new_unique(),
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        data
+    }
+    
+    fn account_data<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+    
+    fn protocol(insurance_fee_share: u16) -> ProtocolState {
+        let mut protocol = ProtocolState::deserialize(&mut &vec![0u8; ProtocolState::INIT_SPACE][..]).unwrap();
+        protocol.insurance_fee_share = insurance_fee_share;
+        protocol
+    }
+    
+    fn insurance_fund(mint: Pubkey, vault: Pubkey) -> InsuranceFund {
+        let mut fund = InsuranceFund::deserialize(&mut &vec![0u8; InsuranceFund::INIT_SPACE][..]).unwrap();
+        fund.mint = mint;
+        fund.vault = vault;
+        fund
+    }
+    
+    fn staking_pool(reward_mint: Pubkey, reward_vault: Pubkey) -> StakingPool {
+        let mut pool = StakingPool::deserialize(&mut &vec![0u8; StakingPool::INIT_SPACE][..]).unwrap();
+        pool.reward_mint = reward_mint;
+        pool.reward_vault = reward_vault;
+        pool.fee_share = 5_000;
+        pool.total_staked = 1;
+        pool
+    }
+    
+    #[test]
+    fn fee_without_fund_or_pool_goes_to_the_protocol() {
+        let (fund_key, pool_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (mut fund_lamports, mut pool_lamports) = (0, 0);
+        let (mut fund_data, mut pool_data) = (Vec::new(), Vec::new());
+        let fund = AccountInfo::new(&fund_key, false, false, &mut fund_lamports, &mut fund_data, &anchor_lang::system_program::ID, false, 0);
+        let pool = AccountInfo::new(&pool_key, false, false, &mut pool_lamports, &mut pool_data, &anchor_lang::system_program::ID, false, 0);
+        
+        let split = split_fee(&protocol(1_000), Pubkey::new_unique(), 10_000, &fund, None, &pool, None).unwrap();
+        assert_eq!((split.insurance, split.staking, split.protocol), (0, 0, 10_000));
+    }
+    
+    #[test]
+    fn fee_is_split_between_insurance_stakers_and_protocol() {
+        let payment_mint = Pubkey::new_unique();
+        let (insurance_vault_key, reward_vault_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        
+        let (fund_key, pool_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (mut fund_lamports, mut pool_lamports) = (0, 0);
+        let mut fund_data = account_data(&insurance_fund(payment_mint, insurance_vault_key));
+        let mut pool_data = account_data(&staking_pool(payment_mint, reward_vault_key));
+        let fund = AccountInfo::new(&fund_key, false, false, &mut fund_lamports, &mut fund_data, &crate::ID, false, 0);
+        let pool = AccountInfo::new(&pool_key, false, false, &mut pool_lamports, &mut pool_data, &crate::ID, false, 0);
+        
+        let (mut insurance_lamports, mut reward_lamports) = (0, 0);
+        let mut insurance_data = token_account_data(payment_mint);
+        let mut reward_data = token_account_data(payment_mint);
+        let insurance_info = AccountInfo::new(&insurance_vault_key, false, false, &mut insurance_lamports, &mut insurance_data, &anchor_spl::token::ID, false, 0);
+        let reward_info = AccountInfo::new(&reward_vault_key, false, false, &mut reward_lamports, &mut reward_data, &anchor_spl::token::ID, false, 0);
+        let insurance_vault = Account::<TokenAccount>::try_from(&insurance_info).unwrap();
+        let reward_vault = Account::<TokenAccount>::try_from(&reward_info).unwrap();
+        
+        // 10% to insurance, then half of the rest to stakers
+        let split = split_fee(&protocol(1_000), payment_mint, 10_000, &fund, Some(&insurance_vault), &pool, Some(&reward_vault)).unwrap();
+        assert_eq!((split.insurance, split.staking, split.protocol), (1_000, 4_500, 4_500));
+        
+        // Each share must come with its vault
+        assert_eq!(
+            split_fee(&protocol(1_000), payment_mint, 10_000, &fund, None, &pool, Some(&reward_vault)).err(),
+            Some(ShadowProtocolError::InvalidTokenAccount.into()),
+        );
+        assert_eq!(
+            split_fee(&protocol(1_000), payment_mint, 10_000, &fund, Some(&insurance_vault), &pool, None).err(),
+            Some(ShadowProtocolError::InvalidTokenAccount.into()),
+        );
+    }
+    
+    #[test]
+    fn fee_split_is_bound_to_the_payment_mint() {
+        let payment_mint = Pubkey::new_unique();
+        let other_mint = Pubkey::new_unique();
+        let (insurance_vault_key, reward_vault_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        
+        let (fund_key, pool_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (mut fund_lamports, mut pool_lamports) = (0, 0);
+        let mut fund_data = account_data(&insurance_fund(other_mint, insurance_vault_key));
+        let mut pool_data = account_data(&staking_pool(other_mint, reward_vault_key));
+        let fund = AccountInfo::new(&fund_key, false, false, &mut fund_lamports, &mut fund_data, &crate::ID, false, 0);
+        let pool = AccountInfo::new(&pool_key, false, false, &mut pool_lamports, &mut pool_data, &crate::ID, false, 0);
+        
+        let mut insurance_lamports = 0;
+        let mut insurance_data = token_account_data(other_mint);
+        let insurance_info = AccountInfo::new(&insurance_vault_key, false, false, &mut insurance_lamports, &mut insurance_data, &anchor_spl::token::ID, false, 0);
+        let insurance_vault = Account::<TokenAccount>::try_from(&insurance_info).unwrap();
+        
+        // A fund holding another mint cannot take a share of this fee
+        assert_eq!(
+            split_fee(&protocol(1_000), payment_mint, 10_000, &fund, Some(&insurance_vault), &pool, None).err(),
+            Some(ShadowProtocolError::InvalidTokenAccount.into()),
+        );
+        
+        // A pool rewarding in another mint is passed over
+        let (empty_key, mut empty_lamports, mut empty_data) = (Pubkey::new_unique(), 0, Vec::new());
+        let no_fund = AccountInfo::new(&empty_key, false, false, &mut empty_lamports, &mut empty_data, &anchor_lang::system_program::ID, false, 0);
+        let split = split_fee(&protocol(1_000), payment_mint, 10_000, &no_fund, None, &pool, None).unwrap();
+        assert_eq!((split.insurance, split.staking, split.protocol), (0, 0, 10_000));
+    }
+}
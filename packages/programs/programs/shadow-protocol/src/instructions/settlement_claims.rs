@@ -0,0 +1,535 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Token, TokenAccount, Mint, Transfer, transfer, CloseAccount, close_account};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::dispute::require_dispute_window_passed;
+use crate::instructions::auction_management::release_creation_deposit;
+use crate::instructions::compliance::check_compliance;
+use crate::instructions::deny_list::require_not_denied;
+use crate::instructions::oracle::check_oracle_band;
+use crate::instructions::settlement::{settlement_fee_bps, house_fee, split_fee, accrue_fee_split};
+use crate::instructions::circuit_breaker::record_breaker_volume;
+use crate::instructions::bid_position::bid_holder;
+
+// Pull-based alternative to `execute_settlement`: each side of an authorized
+// auction collects on its own schedule, so neither can block the other.
+// - `claim_asset()`: the winner pays into the settlement escrow and receives the asset
+// - `claim_proceeds()`: the creator collects the escrowed payment, less fees
+//
+// Auctions that convert or stream their proceeds, or carry a position,
+// settle through `execute_settlement` instead.
+
+/// Collect the auctioned asset, paying the winning amount into escrow.
+///
+/// Screening and the oracle band are checked against the winner here; the
+/// creator is screened when they claim their proceeds.
+pub fn claim_asset<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimAsset<'info>>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let winner = ctx.accounts.winner.key();
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    
+    require!(
+        auction.settlement_authorized() && auction.mpc_verification_hash.is_some(),
+        ShadowProtocolError::SettlementNotAuthorized
+    );
+    require!(
+        auction.status == AuctionStatus::SettlementAuthorized,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require_dispute_window_passed(auction, protocol, now)?;
+    auction.require_reserve_met()?;
+    ctx.accounts.protocol_stats.require_settlements_open()?;
+    require!(
+        !auction.has_flag(AuctionAccount::SETTLING),
+        ShadowProtocolError::SettlementInProgress
+    );
+    require!(
+        auction.open_yield_positions == 0,
+        ShadowProtocolError::YieldPositionsOpen
+    );
+    require!(
+        !auction.has_feature(AuctionFeatures::PAYOUT_CONVERSION | AuctionFeatures::PAYOUT_VESTING)
+            && auction.position_mint.is_none(),
+        ShadowProtocolError::PullSettlementUnsupported
+    );
+    
+    // Whoever holds the winning bid's position claims in the bidder's place
+    let winning_bidder = auction.winner.ok_or(ShadowProtocolError::InvalidWinnerDetermination)?;
+    require!(
+        bid_holder(
+            winning_bidder,
+            &ctx.accounts.winner_position_mint,
+            ctx.accounts.winner_position_account.as_ref(),
+        )? == winner,
+        ShadowProtocolError::InvalidWinnerDetermination
+    );
+    let winning_amount = auction.winning_amount;
+    require!(winning_amount > 0, ShadowProtocolError::InvalidAssetAmount);
+    
+    check_oracle_band(
+        ctx.accounts.features.price_oracle,
+        ctx.accounts.price_feed.as_ref().map(|feed| feed.as_ref()),
+        auction.asset_amount,
+        winning_amount,
+        now,
+    )?;
+    
+    auction.require_vault_covers(ctx.accounts.asset_vault.amount, auction.asset_amount)?;
+    
+    require_not_denied(&ctx.accounts.winner_deny_entry, DenyListEntry::SETTLEMENT)?;
+    check_compliance(
+        auction.compliance_program,
+        ctx.accounts.compliance_program.as_ref().map(|program| program.as_ref()),
+        &ctx.accounts.winner.to_account_info(),
+        ctx.remaining_accounts,
+        winning_amount,
+        auction.payment_mint,
+    )?;
+    
+    auction.transition(AuctionStatus::AssetsDistributed)?;
+    auction.settled_at = Some(now);
+    auction.escrowed_proceeds = winning_amount;
+    
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.winner_payment_account.to_account_info(),
+                to: ctx.accounts.settlement_escrow.to_account_info(),
+                authority: ctx.accounts.winner.to_account_info(),
+            },
+        ),
+        winning_amount,
+    )?;
+    
+    let auction_seeds = &[
+        AUCTION_SEED,
+        auction.pda_seed(),
+        &[auction.bump],
+    ];
+    let signer_seeds = &[&auction_seeds[..]];
+    
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.asset_vault.to_account_info(),
+                to: ctx.accounts.winner_asset_account.to_account_info(),
+                authority: auction.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        auction.asset_amount,
+    )?;
+    let asset_amount = auction.asset_amount;
+    auction.record_vault_withdrawal(asset_amount)?;
+    
+    emit!(AssetClaimed {
+        auction_id,
+        winner,
+        asset_amount: auction.asset_amount,
+        escrowed: winning_amount,
+        claimed_at: now,
+    });
+    
+    emit!(AuctionSettled {
+        auction_id,
+        winner: Some(winner),
+        winning_amount,
+        second_highest_bid: auction.second_highest_bid,
+        reserve_met: auction.has_flag(AuctionAccount::RESERVE_MET),
+        participating_bids: auction.participating_bids as u64,
+        settled_at: now,
+    });
+    
+    Ok(())
+}
+
+/// Collect the escrowed winning payment once the winner has claimed.
+///
+/// Fees are split off here exactly as `execute_settlement` would, and the
+/// breakdown is kept on the auction as `proceeds_claim`. The escrow's rent
+/// goes back to the winner who opened it.
+pub fn claim_proceeds<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimProceeds<'info>>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        auction.status == AuctionStatus::AssetsDistributed,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    let winning_amount = auction.escrowed_proceeds;
+    require!(winning_amount > 0, ShadowProtocolError::NoProceedsToClaim);
+    
+    require_not_denied(&ctx.accounts.creator_deny_entry, DenyListEntry::SETTLEMENT)?;
+    let payment_mint = auction.payment_mint;
+    check_compliance(
+        auction.compliance_program,
+        ctx.accounts.compliance_program.as_ref().map(|program| program.as_ref()),
+        &ctx.accounts.creator.to_account_info(),
+        ctx.remaining_accounts,
+        winning_amount,
+        payment_mint,
+    )?;
+    
+    let fee_bps = settlement_fee_bps(
+        auction,
+        protocol,
+        ctx.accounts.creator_index.as_deref_mut(),
+        payment_mint,
+        winning_amount,
+        now,
+    );
+    let fee_amount = winning_amount
+        .checked_mul(fee_bps as u64)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?
+        / 10000;
+    
+    let house_fee_amount = house_fee(
+        auction,
+        ctx.accounts.auction_house.as_ref(),
+        ctx.accounts.house_fee_account.as_ref(),
+        payment_mint,
+        winning_amount,
+    )?;
+    
+    let creator_amount = winning_amount
+        .checked_sub(fee_amount)
+        .and_then(|amount| amount.checked_sub(house_fee_amount))
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    
+    let fee_split = split_fee(
+        protocol,
+        payment_mint,
+        fee_amount,
+        &ctx.accounts.insurance_fund,
+        ctx.accounts.insurance_vault.as_ref(),
+        &ctx.accounts.staking_pool,
+        ctx.accounts.staking_reward_vault.as_ref(),
+    )?;
+    
+    let claim = ProceedsClaim {
+        amount: creator_amount,
+        protocol_fee: fee_amount,
+        insurance_fee: fee_split.insurance,
+        staking_fee: fee_split.staking,
+        house_fee: house_fee_amount,
+        claimed_at: now,
+    };
+    auction.escrowed_proceeds = 0;
+    auction.proceeds_claim = Some(claim);
+    
+    let auction_seeds = &[
+        AUCTION_SEED,
+        auction.pda_seed(),
+        &[auction.bump],
+    ];
+    let signer_seeds = &[&auction_seeds[..]];
+    
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let escrow = ctx.accounts.settlement_escrow.to_account_info();
+    let escrow_authority = auction.to_account_info();
+    let pay_from_escrow = |to: AccountInfo<'info>, amount: u64| -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        transfer(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                Transfer {
+                    from: escrow.clone(),
+                    to,
+                    authority: escrow_authority.clone(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )
+    };
+    
+    pay_from_escrow(ctx.accounts.creator_payment_account.to_account_info(), creator_amount)?;
+    pay_from_escrow(ctx.accounts.protocol_fee_account.to_account_info(), fee_split.protocol)?;
+    if let Some(house_fee_account) = &ctx.accounts.house_fee_account {
+        pay_from_escrow(house_fee_account.to_account_info(), house_fee_amount)?;
+    }
+    if let Some(vault) = &ctx.accounts.insurance_vault {
+        pay_from_escrow(vault.to_account_info(), fee_split.insurance)?;
+    }
+    if let Some(vault) = &ctx.accounts.staking_reward_vault {
+        pay_from_escrow(vault.to_account_info(), fee_split.staking)?;
+    }
+    accrue_fee_split(&fee_split, &ctx.accounts.insurance_fund, &ctx.accounts.staking_pool)?;
+    
+    close_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.settlement_escrow.to_account_info(),
+                destination: ctx.accounts.winner.to_account_info(),
+                authority: auction.to_account_info(),
+            },
+            signer_seeds,
+        ),
+    )?;
+    
+    release_creation_deposit(auction, &ctx.accounts.creator.to_account_info())?;
+    
+    ctx.accounts.protocol_stats.record_settlement(payment_mint, winning_amount, fee_amount);
+    record_breaker_volume(&mut ctx.accounts.protocol_stats, protocol, payment_mint, winning_amount)?;
+    let fee_ledger = &mut ctx.accounts.fee_ledger;
+    fee_ledger.open(payment_mint, ctx.bumps.fee_ledger);
+    fee_ledger.accrue(fee_split.protocol, now);
+    
+    emit!(ProceedsClaimed {
+        auction_id,
+        creator: auction.creator,
+        payment_mint,
+        gross_amount: winning_amount,
+        claim,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct AssetClaimed {
+    pub auction_id: u64,
+    pub winner: Pubkey,
+    pub asset_amount: u64,
+    pub escrowed: u64,
+    pub claimed_at: i64,
+}
+
+#[event]
+pub struct ProceedsClaimed {
+    pub auction_id: u64,
+    pub creator: Pubkey,
+    pub payment_mint: Pubkey,
+    pub gross_amount: u64,
+    pub claim: ProceedsClaim,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ClaimAsset<'info> {
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Holds the price oracle settlement is checked against
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    /// Stats shard of the auction, checked for a tripped circuit breaker
+    #[account(
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    /// Asset vault holding the auctioned item
+    #[account(
+        mut,
+        address = auction.asset_vault @ ShadowProtocolError::InvalidTokenAccount
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    #[account(address = auction.asset_mint @ ShadowProtocolError::InvalidTokenAccount)]
+    pub asset_mint: Account<'info, Mint>,
+    
+    /// Winner's asset account, created if they do not have one yet
+    #[account(
+        init_if_needed,
+        payer = winner,
+        associated_token::mint = asset_mint,
+        associated_token::authority = winner
+    )]
+    pub winner_asset_account: Account<'info, TokenAccount>,
+    
+    /// Winner's payment account, in the auction's payment mint
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = winner
+    )]
+    pub winner_payment_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Position mint PDA of the winning bid; uninitialized unless one was minted
+    #[account(
+        seeds = [BID_POSITION_SEED, auction_id.to_le_bytes().as_ref(), auction.winner.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub winner_position_mint: UncheckedAccount<'info>,
+    
+    /// Token account holding the winning bid's position; required when it has one
+    pub winner_position_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(address = auction.payment_mint @ ShadowProtocolError::InvalidPaymentMint)]
+    pub payment_mint: Account<'info, Mint>,
+    
+    /// Holds the winning payment until the creator claims it
+    #[account(
+        init,
+        payer = winner,
+        token::mint = payment_mint,
+        token::authority = auction,
+        seeds = [SETTLEMENT_ESCROW_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub settlement_escrow: Account<'info, TokenAccount>,
+    
+    /// CHECK: Winner's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, winner.key().as_ref()], bump)]
+    pub winner_deny_entry: UncheckedAccount<'info>,
+    
+    /// CHECK: Matched against the auction's compliance program; required when one is set
+    pub compliance_program: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Pyth price update, parsed and matched against the auction's feed id
+    pub price_feed: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ClaimProceeds<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = creator @ ShadowProtocolError::Unauthorized,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    #[account(
+        mut,
+        seeds = [SETTLEMENT_ESCROW_SEED, auction_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = settlement_escrow.mint == auction.payment_mint @ ShadowProtocolError::InvalidPaymentMint
+    )]
+    pub settlement_escrow: Account<'info, TokenAccount>,
+    
+    #[account(address = auction.payment_mint @ ShadowProtocolError::InvalidPaymentMint)]
+    pub payment_mint: Account<'info, Mint>,
+    
+    /// Creator's payment account, created if they do not have one yet
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = payment_mint,
+        associated_token::authority = creator
+    )]
+    pub creator_payment_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Auction winner, refunded the escrow's rent
+    #[account(
+        mut,
+        constraint = auction.winner == Some(winner.key()) @ ShadowProtocolError::InvalidWinnerDetermination
+    )]
+    pub winner: UncheckedAccount<'info>,
+    
+    /// CHECK: Creator's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, creator.key().as_ref()], bump)]
+    pub creator_deny_entry: UncheckedAccount<'info>,
+    
+    /// Protocol fee account
+    #[account(
+        mut,
+        address = protocol_state.fee_recipient
+    )]
+    pub protocol_fee_account: Account<'info, TokenAccount>,
+
+    /// Protocol fees accrued in the payment mint
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + FeeLedger::INIT_SPACE,
+        seeds = [FEE_LEDGER_SEED, payment_mint.key().as_ref()],
+        bump
+    )]
+    pub fee_ledger: Account<'info, FeeLedger>,
+        
+    /// CHECK: Insurance fund PDA for the payment mint; may be uninitialized
+    #[account(mut, seeds = [INSURANCE_FUND_SEED, payment_mint.key().as_ref()], bump)]
+    pub insurance_fund: UncheckedAccount<'info>,
+    
+    /// Insurance fund's vault; required when the fund exists
+    #[account(mut)]
+    pub insurance_vault: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Staking pool PDA; may be uninitialized
+    #[account(mut, seeds = [STAKING_POOL_SEED], bump)]
+    pub staking_pool: UncheckedAccount<'info>,
+    
+    /// Staking pool's reward vault; required when it rewards in the payment mint
+    #[account(mut)]
+    pub staking_reward_vault: Option<Account<'info, TokenAccount>>,
+    
+    /// The auction's house; required when it was listed through one
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    /// House fee recipient's account for the payment mint
+    #[account(mut)]
+    pub house_fee_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Creator's index, carrying the trailing volume behind their fee tier;
+    /// omit to settle at the undiscounted fee
+    #[account(
+        mut,
+        seeds = [CREATOR_INDEX_SEED, creator.key().as_ref()],
+        bump = creator_index.bump
+    )]
+    pub creator_index: Option<Account<'info, CreatorIndex>>,
+    
+    /// CHECK: Matched against the auction's compliance program; required when one is set
+    pub compliance_program: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
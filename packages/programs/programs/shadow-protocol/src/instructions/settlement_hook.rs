@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Arguments of the standardized settlement hook instruction.
+///
+/// Hook programs expose `on_settlement(auction_id, winner, amount)` under the
+/// Anchor discriminator for `global:on_settlement`. The auction PDA is passed
+/// as the first account and signs, so the hook can tell a real settlement
+/// from a spoofed call; the hook's own accounts follow, never as signers.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SettlementHookArgs {
+    pub auction_id: u64,
+    pub winner: Pubkey,
+    pub amount: u64,
+}
+
+/// Call the auction's settlement hook, if it has one, once every transfer
+/// of the settlement has been made. A failing hook fails the settlement.
+pub(crate) fn invoke_settlement_hook<'info>(
+    auction: &Account<'info, AuctionAccount>,
+    hook: Option<SettlementHook>,
+    hook_program: Option<&AccountInfo<'info>>,
+    hook_accounts: &[AccountInfo<'info>],
+    winner: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let Some(hook) = hook else {
+        return Ok(());
+    };
+    
+    let program = hook_program.ok_or(ShadowProtocolError::InvalidSettlementHook)?;
+    require!(
+        program.key() == hook.program && program.executable,
+        ShadowProtocolError::InvalidSettlementHook
+    );
+    
+    let mut data = hash(b"global:on_settlement").to_bytes()[..8].to_vec();
+    SettlementHookArgs {
+        auction_id: auction.auction_id,
+        winner,
+        amount,
+    }.serialize(&mut data)?;
+    
+    let mut accounts = vec![AccountMeta::new_readonly(auction.key(), true)];
+    accounts.extend(hook_accounts.iter().map(|info| AccountMeta {
+        pubkey: info.key(),
+        is_signer: false,
+        is_writable: info.is_writable,
+    }));
+    
+    let mut infos = vec![auction.to_account_info()];
+    infos.extend(hook_accounts.iter().cloned());
+    infos.push(program.clone());
+    
+    let auction_seeds: &[&[u8]] = &[
+        AUCTION_SEED,
+        auction.pda_seed(),
+        &[auction.bump],
+    ];
+    
+    invoke_signed(
+        &Instruction {
+            program_id: hook.program,
+            accounts,
+            data,
+        },
+        &infos,
+        &[auction_seeds],
+    )?;
+    
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use solana_address_lookup_table_interface::instruction::{
+    close_lookup_table, create_lookup_table, deactivate_lookup_table, extend_lookup_table,
+};
+use solana_address_lookup_table_interface::program::ID as ADDRESS_LOOKUP_TABLE_PROGRAM_ID;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+/// Create an address lookup table holding the accounts every settlement of
+/// this auction passes, so settlement and batch transactions fit in a packet.
+///
+/// The table's authority is a PDA of the auction and its creator; only the
+/// creator can deactivate and close it, and its rent returns to them.
+/// `recent_slot` is any recent slot, which the table address is derived from.
+pub fn create_settlement_lut(
+    ctx: Context<CreateSettlementLut>,
+    auction_id: u64,
+    recent_slot: u64,
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let creator = ctx.accounts.creator.key();
+    let authority = ctx.accounts.lut_authority.key();
+    let auction_id_bytes = auction_id.to_le_bytes();
+    
+    let (create_ix, lookup_table) = create_lookup_table(authority, creator, recent_slot);
+    require!(
+        lookup_table == ctx.accounts.lookup_table.key(),
+        ShadowProtocolError::InvalidLookupTable
+    );
+    invoke(
+        &create_ix,
+        &[
+            ctx.accounts.lookup_table.to_account_info(),
+            ctx.accounts.lut_authority.to_account_info(),
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.address_lookup_table_program.to_account_info(),
+        ],
+    )?;
+    
+    let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &crate::ID).0;
+    let mut addresses = vec![
+        ctx.accounts.protocol_state.key(),
+        ctx.accounts.protocol_stats.key(),
+        auction.key(),
+        pda(&[AUCTION_FEATURES_SEED, auction.key().as_ref()]),
+        auction.asset_vault,
+        auction.asset_mint,
+        creator,
+        protocol.fee_recipient,
+        pda(&[DENY_LIST_SEED, creator.as_ref()]),
+        pda(&[CREATOR_INDEX_SEED, creator.as_ref()]),
+        pda(&[STAKING_POOL_SEED]),
+        pda(&[SETTLEMENT_ESCROW_SEED, auction_id_bytes.as_ref()]),
+        ctx.accounts.token_program.key(),
+        ctx.accounts.associated_token_program.key(),
+        ctx.accounts.system_program.key(),
+    ];
+    if let Some(terms) = auction.auction_house {
+        addresses.push(terms.house);
+    }
+    if let Some(compliance_program) = auction.compliance_program {
+        addresses.push(compliance_program);
+    }
+    if auction.has_feature(AuctionFeatures::PAYOUT_CONVERSION) {
+        addresses.push(pda(&[PAYOUT_CONVERSION_SEED, auction_id_bytes.as_ref()]));
+    }
+    if auction.has_feature(AuctionFeatures::PAYOUT_VESTING) {
+        addresses.push(pda(&[PAYOUT_STREAM_SEED, auction_id_bytes.as_ref()]));
+    }
+    let address_count = addresses.len() as u8;
+    
+    let auction_key = auction.key();
+    let authority_seeds: &[&[u8]] = &[
+        SETTLEMENT_LUT_SEED,
+        auction_key.as_ref(),
+        creator.as_ref(),
+        &[ctx.bumps.lut_authority],
+    ];
+    invoke_signed(
+        &extend_lookup_table(lookup_table, authority, Some(creator), addresses),
+        &[
+            ctx.accounts.lookup_table.to_account_info(),
+            ctx.accounts.lut_authority.to_account_info(),
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.address_lookup_table_program.to_account_info(),
+        ],
+        &[authority_seeds],
+    )?;
+    
+    emit!(SettlementLutCreated {
+        auction_id,
+        lookup_table,
+        address_count,
+    });
+    
+    Ok(())
+}
+
+/// Deactivate an auction's lookup table once the auction is finished or its
+/// account has been reclaimed. The table can be closed after the cooldown.
+pub fn deactivate_settlement_lut(ctx: Context<ManageSettlementLut>, auction_id: u64) -> Result<()> {
+    let auction_info = &ctx.accounts.auction;
+    
+    if auction_info.owner == &crate::ID && !auction_info.data_is_empty() {
+        let auction = AuctionAccount::try_deserialize(&mut &auction_info.try_borrow_data()?[..])?;
+        require!(auction.auction_id == auction_id, ShadowProtocolError::InvalidAuctionId);
+        require!(
+            auction.status == AuctionStatus::AssetsDistributed || auction.status == AuctionStatus::Cancelled,
+            ShadowProtocolError::AccountNotReclaimable
+        );
+    }
+    
+    let authority_seeds: &[&[u8]] = &[
+        SETTLEMENT_LUT_SEED,
+        ctx.accounts.auction.key.as_ref(),
+        ctx.accounts.creator.key.as_ref(),
+        &[ctx.bumps.lut_authority],
+    ];
+    invoke_signed(
+        &deactivate_lookup_table(ctx.accounts.lookup_table.key(), ctx.accounts.lut_authority.key()),
+        &[
+            ctx.accounts.lookup_table.to_account_info(),
+            ctx.accounts.lut_authority.to_account_info(),
+            ctx.accounts.address_lookup_table_program.to_account_info(),
+        ],
+        &[authority_seeds],
+    )?;
+    
+    Ok(())
+}
+
+/// Close a deactivated lookup table, returning its rent to the creator
+pub fn close_settlement_lut(ctx: Context<ManageSettlementLut>, _auction_id: u64) -> Result<()> {
+    let authority_seeds: &[&[u8]] = &[
+        SETTLEMENT_LUT_SEED,
+        ctx.accounts.auction.key.as_ref(),
+        ctx.accounts.creator.key.as_ref(),
+        &[ctx.bumps.lut_authority],
+    ];
+    invoke_signed(
+        &close_lookup_table(
+            ctx.accounts.lookup_table.key(),
+            ctx.accounts.lut_authority.key(),
+            ctx.accounts.creator.key(),
+        ),
+        &[
+            ctx.accounts.lookup_table.to_account_info(),
+            ctx.accounts.lut_authority.to_account_info(),
+            ctx.accounts.creator.to_account_info(),
+            ctx.accounts.address_lookup_table_program.to_account_info(),
+        ],
+        &[authority_seeds],
+    )?;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CreateSettlementLut<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        has_one = creator @ ShadowProtocolError::Unauthorized,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    /// CHECK: Signs for the lookup table; holds no data
+    #[account(
+        seeds = [SETTLEMENT_LUT_SEED, auction.key().as_ref(), creator.key().as_ref()],
+        bump
+    )]
+    pub lut_authority: UncheckedAccount<'info>,
+    
+    /// CHECK: Created by the address lookup table program; address checked
+    /// against the authority and recent slot
+    #[account(mut)]
+    pub lookup_table: UncheckedAccount<'info>,
+    
+    /// CHECK: Address lookup table program
+    #[account(address = ADDRESS_LOOKUP_TABLE_PROGRAM_ID)]
+    pub address_lookup_table_program: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ManageSettlementLut<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    /// CHECK: The auction the table's authority is derived from; may already
+    /// have been closed by `reclaim_storage`
+    pub auction: UncheckedAccount<'info>,
+    
+    /// CHECK: Signs for the lookup table; holds no data
+    #[account(
+        seeds = [SETTLEMENT_LUT_SEED, auction.key().as_ref(), creator.key().as_ref()],
+        bump
+    )]
+    pub lut_authority: UncheckedAccount<'info>,
+    
+    /// CHECK: Owned by the address lookup table program, which checks its authority
+    #[account(mut, owner = ADDRESS_LOOKUP_TABLE_PROGRAM_ID)]
+    pub lookup_table: UncheckedAccount<'info>,
+    
+    /// CHECK: Address lookup table program
+    #[account(address = ADDRESS_LOOKUP_TABLE_PROGRAM_ID)]
+    pub address_lookup_table_program: UncheckedAccount<'info>,
+}
+
+#[event]
+pub struct SettlementLutCreated {
+    pub auction_id: u64,
+    pub lookup_table: Pubkey,
+    pub address_count: u8,
+}
@@ -0,0 +1,293 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{TokenAccount, ID as TOKEN_PROGRAM_ID};
+use crate::state::*;
+use crate::error::{error_code, ShadowProtocolError};
+use crate::instructions::dispute::require_dispute_window_passed;
+use crate::instructions::insurance::insurance_share;
+use crate::instructions::staking::staking_share;
+use crate::instructions::deny_list::require_not_denied;
+use crate::instructions::oracle::check_oracle_band;
+use crate::instructions::settlement::{settlement_fee_bps, house_fee};
+use crate::instructions::auction_position::proceeds_recipient;
+
+/// View-style dry run of `execute_settlement` for `winner` paying
+/// `winning_amount` in `payment_mint`.
+///
+/// Returns the fee breakdown and transfer amounts settlement would use, with
+/// the error code of every check that would fail, including token accounts
+/// that do not exist yet. Nothing is written, so clients can simulate this
+/// before sending the real transaction. Compliance checks and the payout
+/// swap need their own CPI accounts and are not previewed.
+pub fn preview_settlement(
+    ctx: Context<PreviewSettlement>,
+    _auction_id: u64,
+    winner: Pubkey,
+    winning_amount: u64,
+    payment_mint: Pubkey,
+) -> Result<SettlementPreview> {
+    let auction = &ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let now = Clock::get()?.unix_timestamp;
+    let mut failures = Vec::new();
+    
+    let mut check = |result: Result<()>| {
+        if let Err(error) = result {
+            failures.push(error_code(&error));
+        }
+    };
+    let require = |ok: bool, error: ShadowProtocolError| -> Result<()> {
+        if ok { Ok(()) } else { Err(error.into()) }
+    };
+    
+    check(require(!protocol.paused, ShadowProtocolError::ProtocolPaused));
+    check(require(auction.settlement_authorized(), ShadowProtocolError::SettlementNotAuthorized));
+    check(require(
+        auction.mpc_verification_hash.is_some(),
+        ShadowProtocolError::MpcVerificationFailed,
+    ));
+    check(require(
+        auction.status == AuctionStatus::SettlementAuthorized,
+        ShadowProtocolError::InvalidAuctionStatus,
+    ));
+    check(require_dispute_window_passed(auction, protocol, now));
+    check(auction.require_reserve_met());
+    check(auction.require_payment_mint(payment_mint));
+    check(ctx.accounts.protocol_stats.require_settlements_open());
+    check(require(
+        !auction.has_flag(AuctionAccount::SETTLING),
+        ShadowProtocolError::SettlementInProgress,
+    ));
+    check(require(
+        auction.open_yield_positions == 0,
+        ShadowProtocolError::YieldPositionsOpen,
+    ));
+    check(require(
+        auction.winner == Some(winner) && winner != Pubkey::default(),
+        ShadowProtocolError::InvalidWinnerDetermination,
+    ));
+    check(require(
+        auction.winning_amount == winning_amount && winning_amount > 0,
+        ShadowProtocolError::InvalidAssetAmount,
+    ));
+    check(check_oracle_band(
+        ctx.accounts.features.price_oracle,
+        ctx.accounts.price_feed.as_ref().map(|feed| feed.as_ref()),
+        auction.asset_amount,
+        winning_amount,
+        now,
+    ));
+    check(auction.require_vault_covers(ctx.accounts.asset_vault.amount, auction.asset_amount));
+    check(require_not_denied(&ctx.accounts.winner_deny_entry, DenyListEntry::SETTLEMENT));
+    check(require_not_denied(&ctx.accounts.creator_deny_entry, DenyListEntry::SETTLEMENT));
+    
+    // Token accounts settlement moves funds through
+    let winner_asset_address = get_associated_token_address(&winner, &auction.asset_mint);
+    let winner_asset_account = read_token_account(&ctx.accounts.winner_asset_account);
+    check(require(
+        ctx.accounts.winner_asset_account.key() == winner_asset_address,
+        ShadowProtocolError::InvalidTokenAccount,
+    ));
+    check(missing_account(winner_asset_account.is_some()));
+    
+    let winner_payment_account = read_token_account(&ctx.accounts.winner_payment_account);
+    check(missing_account(winner_payment_account.is_some()));
+    if let Some(account) = &winner_payment_account {
+        check(require(
+            account.owner == winner && account.mint == payment_mint,
+            ShadowProtocolError::InvalidTokenAccount,
+        ));
+        check(require(
+            account.amount >= winning_amount,
+            ShadowProtocolError::InsufficientFunds,
+        ));
+    }
+    
+    let creator_payment_account = read_token_account(&ctx.accounts.creator_payment_account);
+    check(missing_account(creator_payment_account.is_some()));
+    let payee = match proceeds_recipient(auction, ctx.accounts.position_account.as_ref()) {
+        Ok(payee) => Some(payee),
+        Err(error) => {
+            check(Err(error));
+            None
+        }
+    };
+    if let (Some(account), Some(payee)) = (&creator_payment_account, payee) {
+        check(require(account.owner == payee, ShadowProtocolError::Unauthorized));
+        check(require(account.mint == payment_mint, ShadowProtocolError::InvalidTokenAccount));
+    }
+    
+    // The creator's fee tier is rolled forward on a copy; nothing is recorded
+    let mut creator_index = ctx.accounts.creator_index.as_ref().map(|index| (**index).clone());
+    let fee_bps = settlement_fee_bps(
+        auction,
+        protocol,
+        creator_index.as_mut(),
+        payment_mint,
+        winning_amount,
+        now,
+    );
+    let fee_amount = winning_amount
+        .checked_mul(fee_bps as u64)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?
+        / 10000;
+    
+    let house_fee_amount = match auction.auction_house {
+        Some(terms) => winning_amount
+            .checked_mul(terms.fee_bps as u64)
+            .ok_or(ShadowProtocolError::FeeCalculationOverflow)?
+            / 10000,
+        None => 0,
+    };
+    check(house_fee(
+        auction,
+        ctx.accounts.auction_house.as_ref(),
+        ctx.accounts.house_fee_account.as_ref(),
+        payment_mint,
+        winning_amount,
+    ).map(|_| ()));
+    
+    let creator_amount = winning_amount
+        .checked_sub(fee_amount)
+        .and_then(|amount| amount.checked_sub(house_fee_amount))
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    
+    let insurance_amount = match &ctx.accounts.insurance_fund {
+        Some(fund) if fund.mint == payment_mint => insurance_share(fee_amount, protocol.insurance_fee_share)?,
+        _ => 0,
+    };
+    let staking_amount = match &ctx.accounts.staking_pool {
+        Some(pool) if pool.reward_mint == payment_mint => staking_share(pool, fee_amount - insurance_amount)?,
+        _ => 0,
+    };
+    
+    Ok(SettlementPreview {
+        fee_bps,
+        fee_amount,
+        protocol_fee_amount: fee_amount - insurance_amount - staking_amount,
+        house_fee_amount,
+        insurance_amount,
+        staking_amount,
+        creator_amount,
+        asset_amount: auction.asset_amount,
+        payout_converted: auction.has_feature(AuctionFeatures::PAYOUT_CONVERSION),
+        payout_vested: auction.has_feature(AuctionFeatures::PAYOUT_VESTING),
+        failures,
+    })
+}
+
+/// The token account behind `account`, if it has been created
+fn read_token_account(account: &AccountInfo) -> Option<TokenAccount> {
+    if account.owner != &TOKEN_PROGRAM_ID || account.data_is_empty() {
+        return None;
+    }
+    TokenAccount::try_deserialize(&mut &account.try_borrow_data().ok()?[..]).ok()
+}
+
+/// Settlement fails on a token account that does not exist yet
+fn missing_account(exists: bool) -> Result<()> {
+    if exists {
+        Ok(())
+    } else {
+        Err(ErrorCode::AccountNotInitialized.into())
+    }
+}
+
+/// Outcome of settling an auction as previewed by `preview_settlement`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SettlementPreview {
+    /// Fee rate after any fee tier or voucher discount
+    pub fee_bps: u16,
+    /// Total fee taken from the winning amount
+    pub fee_amount: u64,
+    /// Part of the fee paid to the protocol fee recipient
+    pub protocol_fee_amount: u64,
+    pub house_fee_amount: u64,
+    /// Part of the fee paid to the insurance fund
+    pub insurance_amount: u64,
+    /// Part of the fee paid to stakers
+    pub staking_amount: u64,
+    /// Paid to the creator, or into the swap or stream below
+    pub creator_amount: u64,
+    /// Delivered to the winner
+    pub asset_amount: u64,
+    /// The creator's proceeds go through the payout swap
+    pub payout_converted: bool,
+    /// The creator's proceeds are streamed over a vesting schedule
+    pub payout_vested: bool,
+    /// Error code of every check that would fail, in check order
+    pub failures: Vec<u32>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64, winner: Pubkey)]
+pub struct PreviewSettlement<'info> {
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    #[account(address = auction.asset_vault @ ShadowProtocolError::InvalidTokenAccount)]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: Winner's associated asset account; may not exist yet
+    pub winner_asset_account: UncheckedAccount<'info>,
+    
+    /// CHECK: Winner's payment account; may not exist yet
+    pub winner_payment_account: UncheckedAccount<'info>,
+    
+    /// CHECK: Payment account of whoever is paid the proceeds; may not exist yet
+    pub creator_payment_account: UncheckedAccount<'info>,
+    
+    /// Token account holding the auction's position; required when it has one
+    pub position_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Winner's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, winner.as_ref()], bump)]
+    pub winner_deny_entry: UncheckedAccount<'info>,
+    
+    /// CHECK: Creator's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, auction.creator.as_ref()], bump)]
+    pub creator_deny_entry: UncheckedAccount<'info>,
+    
+    /// Insurance fund for the payment mint, as settlement would pass it
+    pub insurance_fund: Option<Account<'info, InsuranceFund>>,
+    
+    #[account(
+        seeds = [STAKING_POOL_SEED],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Option<Account<'info, StakingPool>>,
+    
+    /// The auction's house; required when it was listed through one
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    pub house_fee_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        seeds = [CREATOR_INDEX_SEED, auction.creator.as_ref()],
+        bump = creator_index.bump
+    )]
+    pub creator_index: Option<Account<'info, CreatorIndex>>,
+    
+    /// CHECK: Pyth price update, parsed and matched against the auction's feed id
+    pub price_feed: Option<UncheckedAccount<'info>>,
+}
@@ -0,0 +1,605 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::dispute::require_dispute_window_passed;
+use crate::instructions::auction_management::release_creation_deposit;
+use crate::instructions::compliance::check_compliance;
+use crate::instructions::deny_list::require_not_denied;
+use crate::instructions::oracle::check_oracle_band;
+use crate::instructions::settlement::{settlement_fee_bps, house_fee, split_fee};
+use crate::instructions::circuit_breaker::record_breaker_volume;
+
+// Resumable alternative to `execute_settlement` for settlements too large
+// for one transaction's compute budget.
+// - `begin_settlement()`: runs every check, fixes the amounts and recipients
+//   in a `SettlementProgress` account and locks the auction against the
+//   other settlement paths
+// - `advance_settlement()`: performs whichever pending steps were given
+//   their accounts, and finishes the settlement once none remain
+//
+// Each step sets its bit before the next call can see it, so a step is
+// never paid twice however the calls are split or retried. Auctions that
+// convert or stream their proceeds settle through `execute_settlement`.
+
+/// Validate an authorized settlement and record what it will pay.
+///
+/// Takes the same accounts as `execute_settlement` for screening and the
+/// fee split, but moves no funds. `expires_at` and `max_fee_bps` guard this
+/// call only; once begun, the settlement pays the amounts recorded here.
+pub fn begin_settlement<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BeginSettlement<'info>>,
+    auction_id: u64,
+    expires_at: i64,
+    max_fee_bps: u16,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let winner = ctx.accounts.winner.key();
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(now < expires_at, ShadowProtocolError::SettlementExpired);
+    
+    require!(
+        auction.settlement_authorized() && auction.mpc_verification_hash.is_some(),
+        ShadowProtocolError::SettlementNotAuthorized
+    );
+    require!(
+        auction.status == AuctionStatus::SettlementAuthorized,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require_dispute_window_passed(auction, protocol, now)?;
+    auction.require_reserve_met()?;
+    ctx.accounts.protocol_stats.require_settlements_open()?;
+    require!(
+        auction.open_yield_positions == 0,
+        ShadowProtocolError::YieldPositionsOpen
+    );
+    // Progress pays the creator directly, so position holders are paid
+    // through `execute_settlement`
+    require!(
+        !auction.has_feature(AuctionFeatures::PAYOUT_CONVERSION | AuctionFeatures::PAYOUT_VESTING)
+            && auction.position_mint.is_none(),
+        ShadowProtocolError::PullSettlementUnsupported
+    );
+    
+    require!(
+        auction.winner == Some(winner),
+        ShadowProtocolError::InvalidWinnerDetermination
+    );
+    let winning_amount = auction.winning_amount;
+    require!(winning_amount > 0, ShadowProtocolError::InvalidAssetAmount);
+    
+    check_oracle_band(
+        ctx.accounts.features.price_oracle,
+        ctx.accounts.price_feed.as_ref().map(|feed| feed.as_ref()),
+        auction.asset_amount,
+        winning_amount,
+        now,
+    )?;
+    
+    auction.require_vault_covers(ctx.accounts.asset_vault.amount, auction.asset_amount)?;
+    
+    require_not_denied(&ctx.accounts.winner_deny_entry, DenyListEntry::SETTLEMENT)?;
+    require_not_denied(&ctx.accounts.creator_deny_entry, DenyListEntry::SETTLEMENT)?;
+    auction.require_payment_mint(ctx.accounts.winner_payment_account.mint)?;
+    let payment_mint = auction.payment_mint;
+    let compliance_program = ctx.accounts.compliance_program.as_ref().map(|program| program.as_ref());
+    check_compliance(
+        auction.compliance_program,
+        compliance_program,
+        &ctx.accounts.winner.to_account_info(),
+        ctx.remaining_accounts,
+        winning_amount,
+        payment_mint,
+    )?;
+    check_compliance(
+        auction.compliance_program,
+        compliance_program,
+        &ctx.accounts.creator,
+        ctx.remaining_accounts,
+        winning_amount,
+        payment_mint,
+    )?;
+    
+    let fee_bps = settlement_fee_bps(
+        auction,
+        protocol,
+        ctx.accounts.creator_index.as_deref_mut(),
+        payment_mint,
+        winning_amount,
+        now,
+    );
+    require!(fee_bps <= max_fee_bps, ShadowProtocolError::SettlementFeeTooHigh);
+    let fee_amount = winning_amount
+        .checked_mul(fee_bps as u64)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?
+        / 10000;
+    
+    let house_fee_amount = house_fee(
+        auction,
+        ctx.accounts.auction_house.as_ref(),
+        ctx.accounts.house_fee_account.as_ref(),
+        payment_mint,
+        winning_amount,
+    )?;
+    
+    let creator_amount = winning_amount
+        .checked_sub(fee_amount)
+        .and_then(|amount| amount.checked_sub(house_fee_amount))
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    
+    let fee_split = split_fee(
+        protocol,
+        payment_mint,
+        fee_amount,
+        &ctx.accounts.insurance_fund,
+        ctx.accounts.insurance_vault.as_ref(),
+        &ctx.accounts.staking_pool,
+        ctx.accounts.staking_reward_vault.as_ref(),
+    )?;
+    
+    // Steps with nothing to pay start out done
+    let mut completed_steps = 0;
+    for (step, amount) in [
+        (SettlementProgress::PAYMENT, creator_amount),
+        (SettlementProgress::PROTOCOL_FEE, fee_split.protocol),
+        (SettlementProgress::HOUSE_FEE, house_fee_amount),
+        (SettlementProgress::INSURANCE, fee_split.insurance),
+        (SettlementProgress::STAKING, fee_split.staking),
+    ] {
+        if amount == 0 {
+            completed_steps |= step;
+        }
+    }
+    
+    let progress = &mut ctx.accounts.settlement_progress;
+    progress.auction_id = auction_id;
+    progress.winner = winner;
+    progress.payment_mint = payment_mint;
+    progress.winning_amount = winning_amount;
+    progress.creator_amount = creator_amount;
+    progress.fee_amount = fee_amount;
+    progress.protocol_fee_amount = fee_split.protocol;
+    progress.house_fee_amount = house_fee_amount;
+    progress.insurance_amount = fee_split.insurance;
+    progress.staking_amount = fee_split.staking;
+    progress.house_fee_account = ctx.accounts.house_fee_account.as_ref().map(|account| account.key());
+    progress.insurance_vault = ctx.accounts.insurance_vault.as_ref().map(|vault| vault.key());
+    progress.staking_reward_vault = ctx.accounts.staking_reward_vault.as_ref().map(|vault| vault.key());
+    progress.completed_steps = completed_steps;
+    progress.started_at = now;
+    progress.bump = ctx.bumps.settlement_progress;
+    
+    // Opened here so every later step can count on it
+    ctx.accounts.fee_ledger.open(payment_mint, ctx.bumps.fee_ledger);
+    
+    auction.set_flag(AuctionAccount::SETTLING, true);
+    
+    emit!(SettlementBegun {
+        auction_id,
+        winner,
+        payment_mint,
+        winning_amount,
+        creator_amount,
+        fee_amount,
+        house_fee_amount,
+        started_at: now,
+    });
+    
+    Ok(())
+}
+
+/// Perform the pending settlement steps whose accounts were passed.
+///
+/// Steps already done are skipped, so any subset can be retried. The call
+/// that completes the last step distributes the auction, releases the
+/// creation deposit and closes the progress account to the winner.
+pub fn advance_settlement<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AdvanceSettlement<'info>>,
+    auction_id: u64,
+) -> Result<()> {
+    let protocol = &ctx.accounts.protocol_state;
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.auction.has_flag(AuctionAccount::SETTLING),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    let auction = &ctx.accounts.auction;
+    let progress = &ctx.accounts.settlement_progress;
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let winner = ctx.accounts.winner.to_account_info();
+    let mut completed = Vec::new();
+    
+    if !progress.is_done(SettlementProgress::ASSET) {
+        if let (Some(asset_vault), Some(winner_asset_account)) =
+            (&ctx.accounts.asset_vault, &ctx.accounts.winner_asset_account)
+        {
+            let auction_seeds = &[
+                AUCTION_SEED,
+                auction.pda_seed(),
+                &[auction.bump],
+            ];
+            let signer_seeds = &[&auction_seeds[..]];
+    
+            transfer(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    Transfer {
+                        from: asset_vault.to_account_info(),
+                        to: winner_asset_account.to_account_info(),
+                        authority: auction.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                auction.asset_amount,
+            )?;
+            completed.push((SettlementProgress::ASSET, auction.asset_amount));
+        }
+    }
+    
+    // Every payment step draws on the winner's payment account
+    if let Some(winner_payment_account) = &ctx.accounts.winner_payment_account {
+        let pay = |to: AccountInfo<'info>, amount: u64| -> Result<()> {
+            transfer(
+                CpiContext::new(
+                    token_program.clone(),
+                    Transfer {
+                        from: winner_payment_account.to_account_info(),
+                        to,
+                        authority: winner.clone(),
+                    },
+                ),
+                amount,
+            )
+        };
+    
+        if !progress.is_done(SettlementProgress::PAYMENT) {
+            if let Some(account) = &ctx.accounts.creator_payment_account {
+                pay(account.to_account_info(), progress.creator_amount)?;
+                completed.push((SettlementProgress::PAYMENT, progress.creator_amount));
+            }
+        }
+        if !progress.is_done(SettlementProgress::PROTOCOL_FEE) {
+            if let Some(account) = &ctx.accounts.protocol_fee_account {
+                pay(account.to_account_info(), progress.protocol_fee_amount)?;
+                completed.push((SettlementProgress::PROTOCOL_FEE, progress.protocol_fee_amount));
+            }
+        }
+        if !progress.is_done(SettlementProgress::HOUSE_FEE) {
+            if let Some(account) = &ctx.accounts.house_fee_account {
+                require!(
+                    progress.house_fee_account == Some(account.key()),
+                    ShadowProtocolError::InvalidTokenAccount
+                );
+                pay(account.to_account_info(), progress.house_fee_amount)?;
+                completed.push((SettlementProgress::HOUSE_FEE, progress.house_fee_amount));
+            }
+        }
+        if !progress.is_done(SettlementProgress::INSURANCE) {
+            if let (Some(fund), Some(vault)) = (&mut ctx.accounts.insurance_fund, &ctx.accounts.insurance_vault) {
+                require!(
+                    progress.insurance_vault == Some(vault.key()) && fund.vault == vault.key(),
+                    ShadowProtocolError::InvalidTokenAccount
+                );
+                pay(vault.to_account_info(), progress.insurance_amount)?;
+                fund.total_accrued = fund.total_accrued.saturating_add(progress.insurance_amount);
+                completed.push((SettlementProgress::INSURANCE, progress.insurance_amount));
+            }
+        }
+        if !progress.is_done(SettlementProgress::STAKING) {
+            if let (Some(pool), Some(vault)) = (&mut ctx.accounts.staking_pool, &ctx.accounts.staking_reward_vault) {
+                require!(
+                    progress.staking_reward_vault == Some(vault.key()) && pool.reward_vault == vault.key(),
+                    ShadowProtocolError::InvalidTokenAccount
+                );
+                pay(vault.to_account_info(), progress.staking_amount)?;
+                pool.accrue(progress.staking_amount);
+                completed.push((SettlementProgress::STAKING, progress.staking_amount));
+            }
+        }
+    }
+    
+    require!(!completed.is_empty(), ShadowProtocolError::NoSettlementStepReady);
+    
+    let progress = &mut ctx.accounts.settlement_progress;
+    let auction = &mut ctx.accounts.auction;
+    for (step, amount) in completed {
+        progress.completed_steps |= step;
+        match step {
+            SettlementProgress::ASSET => auction.record_vault_withdrawal(amount)?,
+            SettlementProgress::PROTOCOL_FEE => ctx.accounts.fee_ledger.accrue(amount, now),
+            _ => {}
+        }
+        emit!(SettlementStepCompleted {
+            auction_id,
+            step,
+            amount,
+            completed_steps: progress.completed_steps,
+        });
+    }
+    
+    if !progress.is_complete() {
+        return Ok(());
+    }
+    
+    auction.set_flag(AuctionAccount::SETTLING, false);
+    auction.transition(AuctionStatus::AssetsDistributed)?;
+    auction.settled_at = Some(now);
+    
+    release_creation_deposit(auction, &ctx.accounts.creator)?;
+    
+    ctx.accounts.protocol_stats.record_settlement(
+        progress.payment_mint,
+        progress.winning_amount,
+        progress.fee_amount,
+    );
+    record_breaker_volume(
+        &mut ctx.accounts.protocol_stats,
+        protocol,
+        progress.payment_mint,
+        progress.winning_amount,
+    )?;
+    
+    emit!(AuctionSettled {
+        auction_id,
+        winner: Some(progress.winner),
+        winning_amount: progress.winning_amount,
+        second_highest_bid: auction.second_highest_bid,
+        reserve_met: auction.has_flag(AuctionAccount::RESERVE_MET),
+        participating_bids: auction.participating_bids as u64,
+        settled_at: now,
+    });
+    
+    ctx.accounts.settlement_progress.close(ctx.accounts.winner.to_account_info())?;
+    
+    Ok(())
+}
+
+#[event]
+pub struct SettlementBegun {
+    pub auction_id: u64,
+    pub winner: Pubkey,
+    pub payment_mint: Pubkey,
+    pub winning_amount: u64,
+    pub creator_amount: u64,
+    pub fee_amount: u64,
+    pub house_fee_amount: u64,
+    pub started_at: i64,
+}
+
+#[event]
+pub struct SettlementStepCompleted {
+    pub auction_id: u64,
+    /// One of the `SettlementProgress::*` step bits
+    pub step: u8,
+    pub amount: u64,
+    pub completed_steps: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct BeginSettlement<'info> {
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Holds the price oracle settlement is checked against
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        init,
+        payer = winner,
+        space = 8 + SettlementProgress::INIT_SPACE,
+        seeds = [SETTLEMENT_PROGRESS_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub settlement_progress: Account<'info, SettlementProgress>,
+    
+    /// Protocol fees accrued in the payment mint
+    #[account(
+        init_if_needed,
+        payer = winner,
+        space = 8 + FeeLedger::INIT_SPACE,
+        seeds = [FEE_LEDGER_SEED, auction.payment_mint.as_ref()],
+        bump
+    )]
+    pub fee_ledger: Account<'info, FeeLedger>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    /// Stats shard of the auction, checked for a tripped circuit breaker
+    #[account(
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    /// Asset vault holding the auctioned item
+    #[account(
+        associated_token::mint = auction.asset_mint,
+        associated_token::authority = auction
+    )]
+    pub asset_vault: Account<'info, TokenAccount>,
+    
+    /// Winner's payment account, in the auction's payment mint
+    #[account(token::authority = winner)]
+    pub winner_payment_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Auction creator, screened alongside the winner
+    #[account(address = auction.creator @ ShadowProtocolError::Unauthorized)]
+    pub creator: AccountInfo<'info>,
+    
+    /// CHECK: Winner's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, winner.key().as_ref()], bump)]
+    pub winner_deny_entry: UncheckedAccount<'info>,
+    
+    /// CHECK: Creator's deny-list PDA; may be uninitialized
+    #[account(seeds = [DENY_LIST_SEED, creator.key().as_ref()], bump)]
+    pub creator_deny_entry: UncheckedAccount<'info>,
+    
+    /// CHECK: Insurance fund PDA for the payment mint; may be uninitialized
+    #[account(seeds = [INSURANCE_FUND_SEED, auction.payment_mint.as_ref()], bump)]
+    pub insurance_fund: UncheckedAccount<'info>,
+    
+    /// Insurance fund's vault; required when the fund exists
+    pub insurance_vault: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Staking pool PDA; may be uninitialized
+    #[account(seeds = [STAKING_POOL_SEED], bump)]
+    pub staking_pool: UncheckedAccount<'info>,
+    
+    /// Staking pool's reward vault; required when it rewards in the payment mint
+    pub staking_reward_vault: Option<Account<'info, TokenAccount>>,
+    
+    /// The auction's house; required when it was listed through one
+    pub auction_house: Option<Account<'info, AuctionHouse>>,
+    
+    /// Creator's index, carrying the trailing volume behind their fee tier;
+    /// omit to settle at the undiscounted fee
+    #[account(
+        mut,
+        seeds = [CREATOR_INDEX_SEED, auction.creator.as_ref()],
+        bump = creator_index.bump
+    )]
+    pub creator_index: Option<Account<'info, CreatorIndex>>,
+    
+    /// House fee recipient's account for the payment mint
+    pub house_fee_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Matched against the auction's compliance program; required when one is set
+    pub compliance_program: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Pyth price update, parsed and matched against the auction's feed id
+    pub price_feed: Option<UncheckedAccount<'info>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the steps being performed are passed; the rest are omitted.
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct AdvanceSettlement<'info> {
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        has_one = winner @ ShadowProtocolError::Unauthorized,
+        seeds = [SETTLEMENT_PROGRESS_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = settlement_progress.bump
+    )]
+    pub settlement_progress: Account<'info, SettlementProgress>,
+    
+    /// Protocol fees accrued in the payment mint, opened by `begin_settlement`
+    #[account(
+        mut,
+        seeds = [FEE_LEDGER_SEED, settlement_progress.payment_mint.as_ref()],
+        bump = fee_ledger.bump
+    )]
+    pub fee_ledger: Account<'info, FeeLedger>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [PROTOCOL_STATS_SEED, ProtocolStats::shard_for(auction_id).as_ref()],
+        bump = protocol_stats.bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+    
+    /// CHECK: Auction creator, refunded the creation deposit
+    #[account(mut, address = auction.creator @ ShadowProtocolError::Unauthorized)]
+    pub creator: AccountInfo<'info>,
+    
+    /// Asset vault holding the auctioned item
+    #[account(
+        mut,
+        associated_token::mint = auction.asset_mint,
+        associated_token::authority = auction
+    )]
+    pub asset_vault: Option<Account<'info, TokenAccount>>,
+    
+    /// Winner's asset account
+    #[account(
+        mut,
+        associated_token::mint = auction.asset_mint,
+        associated_token::authority = winner
+    )]
+    pub winner_asset_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Winner's payment account; needed by every payment step
+    #[account(
+        mut,
+        constraint = winner_payment_account.mint == settlement_progress.payment_mint
+            @ ShadowProtocolError::InvalidTokenAccount
+    )]
+    pub winner_payment_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Creator's payment account
+    #[account(
+        mut,
+        constraint = creator_payment_account.owner == auction.creator @ ShadowProtocolError::Unauthorized,
+        constraint = creator_payment_account.mint == settlement_progress.payment_mint
+            @ ShadowProtocolError::InvalidTokenAccount
+    )]
+    pub creator_payment_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Protocol fee account
+    #[account(
+        mut,
+        address = protocol_state.fee_recipient
+    )]
+    pub protocol_fee_account: Option<Account<'info, TokenAccount>>,
+    
+    /// House fee account recorded when the settlement began
+    #[account(mut)]
+    pub house_fee_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub insurance_fund: Option<Account<'info, InsuranceFund>>,
+    
+    /// Insurance vault recorded when the settlement began
+    #[account(mut)]
+    pub insurance_vault: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [STAKING_POOL_SEED],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Option<Account<'info, StakingPool>>,
+    
+    /// Staking reward vault recorded when the settlement began
+    #[account(mut)]
+    pub staking_reward_vault: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
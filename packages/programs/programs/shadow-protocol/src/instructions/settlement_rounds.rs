@@ -0,0 +1,339 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::gas_schedule::require_gas_limit;
+use crate::instructions::arcium_callback::{
+    chunk_count,
+    comp_def_offset,
+    generate_chunk_computation_id,
+    generate_computation_id,
+};
+
+/// Start a multi-round settlement for an auction whose bids do not fit in a
+/// single circuit invocation. Each chunk is settled on its own and the chunk
+/// winners are then compared in a final round.
+///
+/// Like the other settlement steps this is operational, so the authority's
+/// check is not routed through governance.
+pub fn init_settlement_rounds(
+    ctx: Context<InitSettlementRounds>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        auction.status == AuctionStatus::Ended,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    let chunk_count = chunk_count(auction.bid_count as u64);
+    require!(
+        chunk_count > 1 && chunk_count as usize <= MAX_SETTLEMENT_CHUNKS,
+        ShadowProtocolError::InvalidBidCount
+    );
+    
+    let rounds = &mut ctx.accounts.settlement_rounds;
+    rounds.auction_id = auction_id;
+    rounds.chunk_count = chunk_count;
+    rounds.chunks_completed = 0;
+    rounds.settlement_attempt = auction.settlement_attempt;
+    rounds.chunk_results = Vec::new();
+    rounds.status = SettlementRoundStatus::CollectingChunks;
+    rounds.bump = ctx.bumps.settlement_rounds;
+    
+    msg!("Settlement rounds initialized for auction {}: {} chunks", auction_id, chunk_count);
+    
+    Ok(())
+}
+
+/// Record the encrypted winner of one chunk computation. Chunks are queued
+/// one at a time, so each result must be for the chunk currently queued.
+pub fn chunk_callback(
+    ctx: Context<ChunkCallback>,
+    auction_id: u64,
+    chunk_index: u32,
+    computation_id: [u8; 32],
+    encrypted_result: [[u8; 32]; CHUNK_WINNER_CIPHERTEXTS],
+    result_nonce: u128,
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let rounds = &mut ctx.accounts.settlement_rounds;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.authority.key() == protocol.callback_signer(),
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        auction.status == AuctionStatus::Ended && !auction.has_flag(AuctionAccount::UNSOLD),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        rounds.status == SettlementRoundStatus::CollectingChunks &&
+        rounds.settlement_attempt == auction.settlement_attempt,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        chunk_index < rounds.chunk_count,
+        ShadowProtocolError::InvalidBidCount
+    );
+    require!(
+        chunk_index >= rounds.chunks_completed,
+        ShadowProtocolError::ChunkAlreadyCompleted
+    );
+    require!(
+        chunk_index == rounds.chunks_completed,
+        ShadowProtocolError::UnexpectedChunkIndex
+    );
+    
+    let expected_computation_id = generate_chunk_computation_id(
+        auction_id,
+        auction.end_time,
+        auction.settlement_attempt,
+        chunk_index,
+    );
+    require!(
+        computation_id == expected_computation_id &&
+        auction.mpc_computation_id == Some(computation_id),
+        ShadowProtocolError::InvalidComputationId
+    );
+    
+    rounds.chunk_results.push(ChunkResult {
+        chunk_index,
+        encrypted_result,
+        result_nonce,
+    });
+    rounds.chunks_completed += 1;
+    
+    msg!(
+        "Chunk {}/{} settled for auction {}",
+        rounds.chunks_completed,
+        rounds.chunk_count,
+        auction_id
+    );
+    
+    Ok(())
+}
+
+/// Queue the final round over all chunk winners once every chunk has reported.
+/// Its result is delivered through `arcium_callback` like a single-chunk auction.
+/// Operational, like `init_settlement_rounds`, so no governance proposal is needed.
+pub fn queue_final_round(
+    ctx: Context<QueueFinalRound>,
+    auction_id: u64,
+    mxe_cluster: Pubkey,
+    gas_limit: u64,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let rounds = &mut ctx.accounts.settlement_rounds;
+    let clock = Clock::get()?;
+    
+    require!(!protocol.paused, ShadowProtocolError::ProtocolPaused);
+    require!(
+        ctx.accounts.authority.key() == protocol.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        auction.status == AuctionStatus::Ended && !auction.has_flag(AuctionAccount::UNSOLD),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        rounds.status == SettlementRoundStatus::CollectingChunks &&
+        rounds.settlement_attempt == auction.settlement_attempt,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        rounds.chunks_completed == rounds.chunk_count,
+        ShadowProtocolError::SettlementRoundsIncomplete
+    );
+    
+    let circuit = if auction.has_flag(AuctionAccount::PRIVATE_WINNER) {
+        PRIVATE_FINAL_ROUND_CIRCUIT
+    } else {
+        FINAL_ROUND_CIRCUIT
+    };
+    let comp_def_offset = comp_def_offset(circuit);
+    require_gas_limit(&ctx.accounts.gas_schedule, comp_def_offset, rounds.chunk_count, gas_limit)?;
+    ctx.accounts.cluster_registry.require_healthy(mxe_cluster)?;
+    
+    let computation_id = generate_computation_id(auction_id, auction.end_time, auction.settlement_attempt);
+    
+    auction.mpc_computation_id = Some(computation_id);
+    auction.mpc_comp_def_offset = Some(comp_def_offset);
+    auction.mxe_cluster = Some(mxe_cluster);
+    auction.computation_queued_at = Some(clock.unix_timestamp);
+    rounds.status = SettlementRoundStatus::FinalRoundQueued;
+    
+    emit!(FinalRoundQueued {
+        auction_id,
+        computation_id,
+        chunk_count: rounds.chunk_count,
+        reserve_price_encrypted: auction.reserve_price_encrypted,
+        reserve_price_nonce: auction.reserve_price_nonce,
+        mxe_cluster,
+        gas_limit,
+        queued_at: clock.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Close an auction's settlement rounds once the final round's result has
+/// been recorded, or once the settlement they served was voided, returning
+/// the rent to the protocol authority that opened them.
+pub fn close_settlement_rounds(
+    ctx: Context<CloseSettlementRounds>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let rounds = &ctx.accounts.settlement_rounds;
+    
+    let voided = rounds.settlement_attempt != auction.settlement_attempt;
+    let aggregated = rounds.status == SettlementRoundStatus::FinalRoundQueued &&
+        auction.mpc_verification_hash.is_some();
+    require!(voided || aggregated, ShadowProtocolError::SettlementRoundsInUse);
+    
+    msg!("Settlement rounds closed for auction {}", auction_id);
+    
+    Ok(())
+}
+
+#[event]
+pub struct FinalRoundQueued {
+    pub auction_id: u64,
+    pub computation_id: [u8; 32],
+    pub chunk_count: u32,
+    pub reserve_price_encrypted: [u8; 32],
+    pub reserve_price_nonce: u128,
+    pub mxe_cluster: Pubkey,
+    pub gas_limit: u64,
+    pub queued_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct InitSettlementRounds<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SettlementRounds::INIT_SPACE,
+        seeds = [SETTLEMENT_ROUNDS_SEED, auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub settlement_rounds: Account<'info, SettlementRounds>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct ChunkCallback<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [SETTLEMENT_ROUNDS_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = settlement_rounds.bump
+    )]
+    pub settlement_rounds: Account<'info, SettlementRounds>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct QueueFinalRound<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [SETTLEMENT_ROUNDS_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = settlement_rounds.bump
+    )]
+    pub settlement_rounds: Account<'info, SettlementRounds>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    /// Gas schedule of the final-round circuit
+    #[account(
+        seeds = [GAS_SCHEDULE_SEED, gas_schedule.comp_def_offset.to_le_bytes().as_ref()],
+        bump = gas_schedule.bump
+    )]
+    pub gas_schedule: Account<'info, GasSchedule>,
+    
+    #[account(
+        seeds = [CLUSTER_REGISTRY_SEED],
+        bump = cluster_registry.bump
+    )]
+    pub cluster_registry: Account<'info, ClusterRegistry>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CloseSettlementRounds<'info> {
+    pub caller: Signer<'info>,
+    
+    #[account(
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [SETTLEMENT_ROUNDS_SEED, auction_id.to_le_bytes().as_ref()],
+        bump = settlement_rounds.bump,
+        close = authority
+    )]
+    pub settlement_rounds: Account<'info, SettlementRounds>,
+    
+    /// CHECK: the protocol authority that paid for the rounds
+    #[account(mut, address = protocol_state.authority)]
+    pub authority: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
\ No newline at end of file
@@ -0,0 +1,386 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount, Mint, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::instructions::governance::{require_governance_execution, GovernanceAction};
+
+/// Create the staking pool sharing `fee_share` of protocol fees paid in
+/// `reward_mint` with holders who stake `stake_mint`
+pub fn initialize_staking_pool(ctx: Context<InitializeStakingPool>, fee_share: u16) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        &ctx.accounts.protocol_state,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::InitializeStakingPool,
+    )?;
+    require!(fee_share <= 10000, ShadowProtocolError::InvalidProtocolFee);
+    
+    let pool = &mut ctx.accounts.staking_pool;
+    pool.stake_mint = ctx.accounts.stake_mint.key();
+    pool.reward_mint = ctx.accounts.reward_mint.key();
+    pool.stake_vault = ctx.accounts.stake_vault.key();
+    pool.reward_vault = ctx.accounts.reward_vault.key();
+    pool.fee_share = fee_share;
+    pool.total_staked = 0;
+    pool.reward_per_share = 0;
+    pool.total_distributed = 0;
+    pool.bump = ctx.bumps.staking_pool;
+    
+    msg!("Staking pool initialized: stake {} for {} fees", pool.stake_mint, pool.reward_mint);
+    
+    Ok(())
+}
+
+/// Change the share of protocol fees routed to stakers; zero turns it off
+pub fn update_staking_fee_share(ctx: Context<UpdateStakingFeeShare>, fee_share: u16) -> Result<()> {
+    require!(
+        ctx.accounts.authority.key() == ctx.accounts.protocol_state.authority,
+        ShadowProtocolError::Unauthorized
+    );
+    require_governance_execution(
+        &ctx.accounts.protocol_state,
+        ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        GovernanceAction::UpdateStakingFeeShare,
+    )?;
+    require!(fee_share <= 10000, ShadowProtocolError::InvalidProtocolFee);
+    
+    ctx.accounts.audit_log.record(
+        AdminAction::UpdateStakingFeeShare,
+        ctx.accounts.authority.key(),
+        AdminAuditLog::word_u64(ctx.accounts.staking_pool.fee_share as u64),
+        AdminAuditLog::word_u64(fee_share as u64),
+    )?;
+    ctx.accounts.staking_pool.fee_share = fee_share;
+    
+    msg!("Staking fee share updated to {} basis points", fee_share);
+    
+    Ok(())
+}
+
+/// Lock `amount` of the stake mint. Adding stake restarts the lock period.
+pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.staking_pool;
+    let position = &mut ctx.accounts.stake_position;
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(amount > 0, ShadowProtocolError::InvalidAssetAmount);
+    
+    transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.owner_stake_account.to_account_info(),
+                to: ctx.accounts.stake_vault.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    
+    position.owner = ctx.accounts.owner.key();
+    position.bump = ctx.bumps.stake_position;
+    let staked = position.amount
+        .checked_add(amount)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    position.settle(pool);
+    position.set_amount(staked, pool);
+    position.staked_at = now;
+    
+    pool.total_staked = pool.total_staked
+        .checked_add(amount)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    
+    emit!(StakeUpdated {
+        owner: position.owner,
+        amount: position.amount,
+        total_staked: pool.total_staked,
+        updated_at: now,
+    });
+    
+    Ok(())
+}
+
+/// Withdraw `amount` of stake once the lock period has passed. Rewards
+/// earned so far stay claimable.
+pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.staking_pool;
+    let position = &mut ctx.accounts.stake_position;
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(
+        amount > 0 && amount <= position.amount,
+        ShadowProtocolError::InvalidAssetAmount
+    );
+    require!(
+        now >= position.staked_at + STAKE_LOCK_PERIOD,
+        ShadowProtocolError::StakeLocked
+    );
+    
+    let pool_seeds = &[STAKING_POOL_SEED, &[pool.bump]];
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                to: ctx.accounts.owner_stake_account.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            &[&pool_seeds[..]],
+        ),
+        amount,
+    )?;
+    
+    let remaining = position.amount - amount;
+    position.settle(pool);
+    position.set_amount(remaining, pool);
+    pool.total_staked -= amount;
+    
+    emit!(StakeUpdated {
+        owner: position.owner,
+        amount: position.amount,
+        total_staked: pool.total_staked,
+        updated_at: now,
+    });
+    
+    Ok(())
+}
+
+/// Pay out the staker's share of fees accrued so far
+pub fn claim_staking_rewards(ctx: Context<ClaimStakingRewards>) -> Result<()> {
+    let pool = &ctx.accounts.staking_pool;
+    let position = &mut ctx.accounts.stake_position;
+    
+    position.settle(pool);
+    let rewards = position.pending_rewards;
+    require!(rewards > 0, ShadowProtocolError::NothingToClaim);
+    
+    let pool_seeds = &[STAKING_POOL_SEED, &[pool.bump]];
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                to: ctx.accounts.owner_reward_account.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            &[&pool_seeds[..]],
+        ),
+        rewards,
+    )?;
+    
+    position.pending_rewards = 0;
+    
+    emit!(StakingRewardsClaimed {
+        owner: position.owner,
+        amount: rewards,
+        claimed_at: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+/// Portion of `fee_amount` owed to stakers; nothing while no one is staked
+pub(crate) fn staking_share(pool: &StakingPool, fee_amount: u64) -> Result<u64> {
+    if pool.total_staked == 0 {
+        return Ok(0);
+    }
+    Ok(fee_amount
+        .checked_mul(pool.fee_share as u64)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?
+        / 10000)
+}
+
+#[event]
+pub struct StakeUpdated {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct StakingRewardsClaimed {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub claimed_at: i64,
+}
+
+#[derive(Accounts)]
+pub struct InitializeStakingPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    pub stake_mint: Account<'info, Mint>,
+    
+    pub reward_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StakingPool::INIT_SPACE,
+        seeds = [STAKING_POOL_SEED],
+        bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = stake_mint,
+        token::authority = staking_pool,
+        seeds = [STAKE_VAULT_SEED],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = reward_mint,
+        token::authority = staking_pool,
+        seeds = [STAKING_REWARD_VAULT_SEED],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateStakingFeeShare<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [ADMIN_AUDIT_LOG_SEED],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Account<'info, AdminAuditLog>,
+    
+    #[account(
+        mut,
+        seeds = [STAKING_POOL_SEED],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// CHECK: Instructions sysvar; required once a governance holds the authority
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [STAKING_POOL_SEED],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [STAKE_POSITION_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    #[account(mut, address = staking_pool.stake_vault)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = staking_pool.stake_mint,
+        token::authority = owner
+    )]
+    pub owner_stake_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [STAKING_POOL_SEED],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [STAKE_POSITION_SEED, owner.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    #[account(mut, address = staking_pool.stake_vault)]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = staking_pool.stake_mint,
+        token::authority = owner
+    )]
+    pub owner_stake_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStakingRewards<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(
+        seeds = [STAKING_POOL_SEED],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [STAKE_POSITION_SEED, owner.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+    
+    #[account(mut, address = staking_pool.reward_vault)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = staking_pool.reward_mint,
+        token::authority = owner
+    )]
+    pub owner_reward_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
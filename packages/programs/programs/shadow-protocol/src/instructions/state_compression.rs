@@ -0,0 +1,302 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+use crate::crypto::CryptoUtils;
+use crate::instructions::auction_cleanup::{release_auction_storage, release_bid_storage};
+
+/// Compress a finished auction: its data is logged and committed to the
+/// compressed state tree, and the account is closed so it no longer holds
+/// rent.
+///
+/// Permissionless, under the same conditions as `reclaim_storage`; rent goes
+/// back to the creator. `decompress_auction` restores the account.
+pub fn compress_auction(ctx: Context<CompressAuction>, auction_id: u64) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    
+    release_auction_storage(
+        auction,
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.asset_vault.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+    
+    let mut data = Vec::new();
+    auction.try_serialize(&mut data)?;
+    compress_account(
+        &mut ctx.accounts.state_tree,
+        ctx.bumps.state_tree,
+        auction.key(),
+        data,
+    )?;
+    
+    msg!("Auction {} compressed", auction_id);
+    
+    Ok(())
+}
+
+/// Compress a bid whose auction is finished and whose collateral has been
+/// returned. Permissionless; rent goes back to the bidder.
+pub fn compress_bid(ctx: Context<CompressBid>, auction_id: u64, bidder: Pubkey) -> Result<()> {
+    let bid = &ctx.accounts.bid;
+    
+    release_bid_storage(
+        bid,
+        &ctx.accounts.bidder_account.to_account_info(),
+        &ctx.accounts.bid_escrow.to_account_info(),
+        &ctx.accounts.auction.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+    
+    let mut data = Vec::new();
+    bid.try_serialize(&mut data)?;
+    compress_account(
+        &mut ctx.accounts.state_tree,
+        ctx.bumps.state_tree,
+        bid.key(),
+        data,
+    )?;
+    
+    msg!("Bid of {} on auction {} compressed", bidder, auction_id);
+    
+    Ok(())
+}
+
+/// Restore a compressed auction so it can be mutated again. `data` is the
+/// account data logged at compression; `proof` is its leaf's path against
+/// the tree's current root. The payer funds the account's rent.
+pub fn decompress_auction(
+    ctx: Context<DecompressAuction>,
+    auction_id: u64,
+    data: Vec<u8>,
+    leaf_index: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let address = ctx.accounts.auction.key();
+    decompress_account(&mut ctx.accounts.state_tree, address, &data, leaf_index, &proof)?;
+    
+    let restored = AuctionAccount::try_deserialize(&mut &data[..])?;
+    require!(restored.auction_id == auction_id, ShadowProtocolError::InvalidAuctionId);
+    *ctx.accounts.auction = restored;
+    
+    Ok(())
+}
+
+/// Restore a compressed bid; see `decompress_auction`
+pub fn decompress_bid(
+    ctx: Context<DecompressBid>,
+    auction_id: u64,
+    bidder: Pubkey,
+    data: Vec<u8>,
+    leaf_index: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let address = ctx.accounts.bid.key();
+    decompress_account(&mut ctx.accounts.state_tree, address, &data, leaf_index, &proof)?;
+    
+    let restored = BidAccount::try_deserialize(&mut &data[..])?;
+    require!(
+        restored.auction_id == auction_id && restored.bidder == bidder,
+        ShadowProtocolError::InvalidCompressedAccount
+    );
+    *ctx.accounts.bid = restored;
+    
+    Ok(())
+}
+
+/// Address seed of the auction in compressed `data`; empty if it does not
+/// decode, which fails the address check
+fn auction_pda_seed(data: &[u8]) -> Vec<u8> {
+    AuctionAccount::try_deserialize(&mut &data[..])
+        .map(|auction| auction.pda_seed().to_vec())
+        .unwrap_or_default()
+}
+
+fn compress_account(
+    tree: &mut CompressedStateTree,
+    bump: u8,
+    address: Pubkey,
+    data: Vec<u8>,
+) -> Result<()> {
+    tree.bump = bump;
+    let leaf_index = tree.append(CryptoUtils::hash_compressed_account(address, &data))?;
+    
+    emit!(AccountCompressed {
+        address,
+        leaf_index,
+        root: tree.root,
+        data,
+        compressed_at: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+fn decompress_account(
+    tree: &mut CompressedStateTree,
+    address: Pubkey,
+    data: &[u8],
+    leaf_index: u64,
+    proof: &[[u8; 32]],
+) -> Result<()> {
+    tree.nullify(CryptoUtils::hash_compressed_account(address, data), leaf_index, proof)?;
+    
+    emit!(AccountDecompressed {
+        address,
+        leaf_index,
+        root: tree.root,
+        decompressed_at: Clock::get()?.unix_timestamp,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct AccountCompressed {
+    pub address: Pubkey,
+    pub leaf_index: u64,
+    pub root: [u8; 32],
+    /// Full account data, discriminator included; needed to decompress
+    pub data: Vec<u8>,
+    pub compressed_at: i64,
+}
+
+#[event]
+pub struct AccountDecompressed {
+    pub address: Pubkey,
+    pub leaf_index: u64,
+    pub root: [u8; 32],
+    pub decompressed_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CompressAuction<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId,
+        close = creator
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Closed with the auction; not restored by `decompress_auction`
+    #[account(
+        mut,
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump,
+        close = creator
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    /// CHECK: Receives the freed rent; must be the auction's creator
+    #[account(mut, address = auction.creator @ ShadowProtocolError::Unauthorized)]
+    pub creator: UncheckedAccount<'info>,
+    
+    /// CHECK: The auction's asset vault; must be empty, closed to the creator
+    #[account(mut, address = auction.asset_vault @ ShadowProtocolError::InvalidRemainingAccounts)]
+    pub asset_vault: UncheckedAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + CompressedStateTree::INIT_SPACE,
+        seeds = [COMPRESSED_STATE_TREE_SEED],
+        bump
+    )]
+    pub state_tree: Account<'info, CompressedStateTree>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64, bidder: Pubkey)]
+pub struct CompressBid<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bidder.as_ref()],
+        bump = bid.bump,
+        close = bidder_account
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
+    /// CHECK: Receives the freed rent; must be the bidder
+    #[account(mut, address = bidder @ ShadowProtocolError::Unauthorized)]
+    pub bidder_account: UncheckedAccount<'info>,
+    
+    /// CHECK: The bid's collateral escrow (SOL escrow for native-SOL bids);
+    /// checked by address and closed to the bidder if empty
+    #[account(mut)]
+    pub bid_escrow: UncheckedAccount<'info>,
+    
+    /// CHECK: The bid's auction, possibly already closed; checked by address
+    pub auction: UncheckedAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + CompressedStateTree::INIT_SPACE,
+        seeds = [COMPRESSED_STATE_TREE_SEED],
+        bump
+    )]
+    pub state_tree: Account<'info, CompressedStateTree>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64, data: Vec<u8>)]
+pub struct DecompressAuction<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AuctionAccount::INIT_SPACE,
+        seeds = [AUCTION_SEED, auction_pda_seed(&data).as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    #[account(
+        mut,
+        seeds = [COMPRESSED_STATE_TREE_SEED],
+        bump = state_tree.bump
+    )]
+    pub state_tree: Account<'info, CompressedStateTree>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64, bidder: Pubkey)]
+pub struct DecompressBid<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BidAccount::INIT_SPACE,
+        seeds = [BID_SEED, auction_id.to_le_bytes().as_ref(), bidder.as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, BidAccount>,
+    
+    #[account(
+        mut,
+        seeds = [COMPRESSED_STATE_TREE_SEED],
+        bump = state_tree.bump
+    )]
+    pub state_tree: Account<'info, CompressedStateTree>,
+    
+    pub system_program: Program<'info, System>,
+}
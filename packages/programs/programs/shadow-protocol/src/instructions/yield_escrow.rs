@@ -0,0 +1,493 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+use crate::state::*;
+use crate::error::ShadowProtocolError;
+
+// Yield adapters expose two Anchor-style instructions:
+//
+// - `deposit(amount: u64)` lends out `amount` from the yield vault
+// - `withdraw()` returns everything lent from the yield vault, plus interest
+//
+// Both take `[yield_authority (signer), yield_vault (writable)]` followed by
+// the remaining accounts of the calling instruction.
+
+/// Opt a sealed-bid auction into lending out its escrows while it runs, or
+/// opt out again. Bidders must know where their collateral goes, so this is
+/// only possible before the first bid.
+pub fn set_yield_config(
+    ctx: Context<SetYieldConfig>,
+    auction_id: u64,
+    beneficiary: Option<YieldBeneficiary>,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    
+    // Dutch sales can complete at any moment and need the asset on hand
+    require!(
+        auction.auction_type == AuctionType::SealedBid,
+        ShadowProtocolError::InvalidAuctionType
+    );
+    
+    require!(
+        auction.status == AuctionStatus::Active && auction.bid_count == 0,
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    require!(
+        auction.open_yield_positions == 0,
+        ShadowProtocolError::YieldPositionsOpen
+    );
+    
+    let yield_config = match beneficiary {
+        Some(beneficiary) => {
+            require!(
+                protocol.yield_adapter != Pubkey::default(),
+                ShadowProtocolError::YieldNotAvailable
+            );
+            require!(
+                auction.timing_mode.duration_in_seconds(auction.end_time - auction.start_time) >= MIN_YIELD_AUCTION_DURATION,
+                ShadowProtocolError::AuctionDurationTooShort
+            );
+            Some(YieldConfig {
+                adapter: protocol.yield_adapter,
+                beneficiary,
+            })
+        }
+        None => None,
+    };
+    let features = &mut ctx.accounts.features;
+    features.yield_config = yield_config;
+    auction.sync_features(features);
+    
+    msg!(
+        "Auction {} yield escrow {}",
+        auction_id,
+        if beneficiary.is_some() { "enabled" } else { "disabled" }
+    );
+    
+    Ok(())
+}
+
+/// Move the asset vault, or a bid's collateral escrow when `bid` is given,
+/// into the yield adapter. Creator-only, while bidding is open.
+pub fn deposit_to_yield<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DepositToYield<'info>>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.creator.key() == auction.creator,
+        ShadowProtocolError::Unauthorized
+    );
+    require!(
+        auction.status == AuctionStatus::Active && !auction.has_ended(&clock),
+        ShadowProtocolError::InvalidAuctionStatus
+    );
+    
+    let config = ctx.accounts.features.yield_config.ok_or(ShadowProtocolError::YieldNotAvailable)?;
+    require!(
+        ctx.accounts.adapter_program.key() == config.adapter,
+        ShadowProtocolError::YieldNotAvailable
+    );
+    
+    let source_vault = &ctx.accounts.source_vault;
+    let yield_vault = &mut ctx.accounts.yield_vault;
+    let yield_authority = &ctx.accounts.yield_authority;
+    require!(
+        yield_vault.owner == yield_authority.key() && yield_vault.mint == source_vault.mint,
+        ShadowProtocolError::InvalidTokenAccount
+    );
+    
+    let amount = source_vault.amount;
+    require!(amount > 0, ShadowProtocolError::InvalidAssetAmount);
+    
+    // The source is drained by whichever PDA owns it
+    let auction_id_bytes = auction_id.to_le_bytes();
+    let bid_bump;
+    let auction_bump = [auction.bump];
+    let (source_authority, source_seeds): (AccountInfo<'info>, Vec<&[u8]>) = match &ctx.accounts.bid {
+        Some(bid) => {
+            let (escrow, _) = Pubkey::find_program_address(
+                &[b"bid_escrow", auction_id_bytes.as_ref(), bid.bidder.as_ref()],
+                &crate::ID,
+            );
+            require!(source_vault.key() == escrow, ShadowProtocolError::InvalidTokenAccount);
+            bid_bump = [bid.bump];
+            (
+                bid.to_account_info(),
+                vec![BID_SEED, auction_id_bytes.as_ref(), bid.bidder.as_ref(), &bid_bump],
+            )
+        }
+        None => {
+            require!(
+                source_vault.key() == auction.asset_vault,
+                ShadowProtocolError::InvalidTokenAccount
+            );
+            (
+                auction.to_account_info(),
+                vec![AUCTION_SEED, auction.pda_seed(), &auction_bump],
+            )
+        }
+    };
+    
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: source_vault.to_account_info(),
+                to: yield_vault.to_account_info(),
+                authority: source_authority,
+            },
+            &[&source_seeds[..]],
+        ),
+        amount,
+    )?;
+    
+    yield_vault.reload()?;
+    let vault_before = yield_vault.amount;
+    
+    let mut data = adapter_discriminator("deposit").to_vec();
+    amount.serialize(&mut data)?;
+    invoke_adapter(
+        &ctx.accounts.adapter_program,
+        yield_authority,
+        ctx.bumps.yield_authority,
+        auction_id,
+        yield_vault,
+        ctx.remaining_accounts,
+        data,
+    )?;
+    
+    // The adapter must take exactly the deposit, or the position cannot be unwound
+    yield_vault.reload()?;
+    require!(
+        vault_before.checked_sub(yield_vault.amount) == Some(amount),
+        ShadowProtocolError::InvalidAssetAmount
+    );
+    
+    let position = &mut ctx.accounts.yield_position;
+    position.auction_id = auction_id;
+    position.source_vault = source_vault.key();
+    position.yield_vault = yield_vault.key();
+    position.principal = amount;
+    position.deposited_at = clock.unix_timestamp;
+    position.bump = ctx.bumps.yield_position;
+    
+    let auction = &mut ctx.accounts.auction;
+    auction.open_yield_positions = auction.open_yield_positions
+        .checked_add(1)
+        .ok_or(ShadowProtocolError::FeeCalculationOverflow)?;
+    
+    emit!(YieldDeposited {
+        auction_id,
+        source_vault: position.source_vault,
+        amount,
+        deposited_at: position.deposited_at,
+    });
+    
+    Ok(())
+}
+
+/// Pull a position back out of the yield adapter once bidding is over.
+///
+/// Permissionless, so settlement is never held up by an absent creator. The
+/// principal goes back to its source vault and any interest to the
+/// auction's beneficiary; a shortfall is absorbed by the source vault.
+pub fn withdraw_from_yield<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawFromYield<'info>>,
+    auction_id: u64,
+) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let protocol = &ctx.accounts.protocol_state;
+    let clock = Clock::get()?;
+    
+    require!(
+        auction.status != AuctionStatus::Active || auction.has_ended(&clock),
+        ShadowProtocolError::AuctionNotEnded
+    );
+    
+    let config = ctx.accounts.features.yield_config.ok_or(ShadowProtocolError::YieldNotAvailable)?;
+    require!(
+        ctx.accounts.adapter_program.key() == config.adapter,
+        ShadowProtocolError::YieldNotAvailable
+    );
+    
+    let beneficiary = match config.beneficiary {
+        YieldBeneficiary::Creator => auction.creator,
+        YieldBeneficiary::Protocol => protocol.authority,
+    };
+    let yield_vault = &mut ctx.accounts.yield_vault;
+    require!(
+        ctx.accounts.beneficiary_account.owner == beneficiary
+            && ctx.accounts.beneficiary_account.mint == yield_vault.mint,
+        ShadowProtocolError::InvalidTokenAccount
+    );
+    
+    let vault_before = yield_vault.amount;
+    
+    invoke_adapter(
+        &ctx.accounts.adapter_program,
+        &ctx.accounts.yield_authority,
+        ctx.bumps.yield_authority,
+        auction_id,
+        yield_vault,
+        ctx.remaining_accounts,
+        adapter_discriminator("withdraw").to_vec(),
+    )?;
+    
+    yield_vault.reload()?;
+    let received = yield_vault.amount.saturating_sub(vault_before);
+    let principal = ctx.accounts.yield_position.principal;
+    let returned = received.min(principal);
+    let interest = received - returned;
+    
+    let auction_id_bytes = auction_id.to_le_bytes();
+    let authority_seeds = &[
+        YIELD_AUTHORITY_SEED,
+        auction_id_bytes.as_ref(),
+        &[ctx.bumps.yield_authority],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    
+    for (to, amount) in [
+        (ctx.accounts.source_vault.to_account_info(), returned),
+        (ctx.accounts.beneficiary_account.to_account_info(), interest),
+    ] {
+        if amount > 0 {
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: yield_vault.to_account_info(),
+                        to,
+                        authority: ctx.accounts.yield_authority.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        }
+    }
+    
+    let auction = &mut ctx.accounts.auction;
+    auction.open_yield_positions = auction.open_yield_positions.saturating_sub(1);
+    
+    emit!(YieldWithdrawn {
+        auction_id,
+        source_vault: ctx.accounts.source_vault.key(),
+        principal,
+        returned,
+        interest,
+        beneficiary,
+    });
+    
+    Ok(())
+}
+
+fn adapter_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{}", name).as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+fn invoke_adapter<'info>(
+    adapter_program: &AccountInfo<'info>,
+    yield_authority: &AccountInfo<'info>,
+    authority_bump: u8,
+    auction_id: u64,
+    yield_vault: &Account<'info, TokenAccount>,
+    extra_accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+) -> Result<()> {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(yield_authority.key(), true),
+        AccountMeta::new(yield_vault.key(), false),
+    ];
+    accounts.extend(extra_accounts.iter().map(|info| {
+        if info.is_writable {
+            AccountMeta::new(info.key(), info.is_signer)
+        } else {
+            AccountMeta::new_readonly(info.key(), info.is_signer)
+        }
+    }));
+    
+    let mut infos = vec![yield_authority.clone(), yield_vault.to_account_info()];
+    infos.extend(extra_accounts.iter().cloned());
+    infos.push(adapter_program.clone());
+    
+    let auction_id_bytes = auction_id.to_le_bytes();
+    let authority_seeds = &[
+        YIELD_AUTHORITY_SEED,
+        auction_id_bytes.as_ref(),
+        &[authority_bump],
+    ];
+    
+    invoke_signed(
+        &Instruction {
+            program_id: adapter_program.key(),
+            accounts,
+            data,
+        },
+        &infos,
+        &[&authority_seeds[..]],
+    )?;
+    
+    Ok(())
+}
+
+#[event]
+pub struct YieldDeposited {
+    pub auction_id: u64,
+    pub source_vault: Pubkey,
+    pub amount: u64,
+    pub deposited_at: i64,
+}
+
+#[event]
+pub struct YieldWithdrawn {
+    pub auction_id: u64,
+    pub source_vault: Pubkey,
+    pub principal: u64,
+    pub returned: u64,
+    pub interest: u64,
+    pub beneficiary: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct SetYieldConfig<'info> {
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Holds the yield config
+    #[account(
+        mut,
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct DepositToYield<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Holds the yield adapter
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    /// Bid whose escrow is deposited; omit to deposit the asset vault
+    #[account(constraint = bid.auction_id == auction_id @ ShadowProtocolError::InvalidTokenAccount)]
+    pub bid: Option<Account<'info, BidAccount>>,
+    
+    #[account(mut)]
+    pub source_vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: Signs adapter calls; owns the yield vaults
+    #[account(seeds = [YIELD_AUTHORITY_SEED, auction_id.to_le_bytes().as_ref()], bump)]
+    pub yield_authority: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub yield_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + YieldPosition::INIT_SPACE,
+        seeds = [YIELD_POSITION_SEED, source_vault.key().as_ref()],
+        bump
+    )]
+    pub yield_position: Account<'info, YieldPosition>,
+    
+    /// CHECK: Matched against the auction's yield adapter
+    pub adapter_program: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct WithdrawFromYield<'info> {
+    #[account(
+        mut,
+        constraint = auction.auction_id == auction_id @ ShadowProtocolError::InvalidAuctionId
+    )]
+    pub auction: Account<'info, AuctionAccount>,
+    
+    /// Holds the yield adapter
+    #[account(
+        seeds = [AUCTION_FEATURES_SEED, auction.key().as_ref()],
+        bump = features.bump
+    )]
+    pub features: Account<'info, AuctionFeatures>,
+    
+    #[account(
+        seeds = [PROTOCOL_SEED],
+        bump = protocol_state.bump
+    )]
+    pub protocol_state: Account<'info, ProtocolState>,
+    
+    #[account(
+        mut,
+        seeds = [YIELD_POSITION_SEED, source_vault.key().as_ref()],
+        bump = yield_position.bump,
+        constraint = yield_position.auction_id == auction_id @ ShadowProtocolError::InvalidTokenAccount,
+        close = creator
+    )]
+    pub yield_position: Account<'info, YieldPosition>,
+    
+    #[account(mut)]
+    pub source_vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: Signs adapter calls; owns the yield vaults
+    #[account(seeds = [YIELD_AUTHORITY_SEED, auction_id.to_le_bytes().as_ref()], bump)]
+    pub yield_authority: UncheckedAccount<'info>,
+    
+    #[account(mut, address = yield_position.yield_vault @ ShadowProtocolError::InvalidTokenAccount)]
+    pub yield_vault: Account<'info, TokenAccount>,
+    
+    /// Receives the interest; owned by the creator or the protocol authority
+    #[account(mut)]
+    pub beneficiary_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: Auction creator, who paid for the position account
+    #[account(mut, address = auction.creator @ ShadowProtocolError::Unauthorized)]
+    pub creator: UncheckedAccount<'info>,
+    
+    /// CHECK: Matched against the auction's yield adapter
+    pub adapter_program: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
@@ -1,3 +1,7 @@
+// The IDL instructions `#[program]` generates resize accounts with the
+// deprecated `AccountInfo::realloc`
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
 
 #[cfg(target_os = "solana")]
@@ -30,6 +34,7 @@ mod error;
 mod crypto;
 
 use instructions::*;
+use state::{AuctionBounds, CHUNK_WINNER_CIPHERTEXTS, CircuitBreakerConfig, FeeTier, PayoutConversion, PriceOracle, RelayedBid, SettlementHook, VestingSchedule, YieldBeneficiary};
 
 declare_id!("DWrCjVyfhysTNwQh96PzScBAiCvZ3hAKWYfyHWpQqee8");
 
@@ -41,66 +46,125 @@ pub mod shadow_protocol {
 
     pub fn create_sealed_auction(
         ctx: Context<CreateSealedAuction>,
-        asset_mint: Pubkey,
-        asset_amount: u64,
-        duration: u64,
-        minimum_bid: u64,
-        reserve_price_encrypted: [u8; 32],
-        reserve_price_nonce: u128,
+        creator_nonce: u64,
+        params: CreateSealedAuctionParams,
     ) -> Result<()> {
-        instructions::create_sealed_auction(
-            ctx,
-            asset_mint,
-            asset_amount,
-            duration,
-            minimum_bid,
-            reserve_price_encrypted,
-            reserve_price_nonce,
-        )
+        instructions::create_sealed_auction(ctx, creator_nonce, params)
     }
 
     pub fn create_dutch_auction(
         ctx: Context<CreateDutchAuction>,
-        asset_mint: Pubkey,
-        asset_amount: u64,
-        starting_price: u64,
-        price_decrease_rate: u64,
-        minimum_price_floor: u64,
+        creator_nonce: u64,
+        params: CreateDutchAuctionParams,
+    ) -> Result<()> {
+        instructions::create_dutch_auction(ctx, creator_nonce, params)
+    }
+
+    pub fn set_invite_hash(
+        ctx: Context<SetInviteHash>,
+        auction_id: u64,
+        invite_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::set_invite_hash(ctx, auction_id, invite_hash)
+    }
+
+    pub fn extend_auction(
+        ctx: Context<ExtendAuction>,
+        auction_id: u64,
+        additional_duration: u64,
+    ) -> Result<()> {
+        instructions::extend_auction(ctx, auction_id, additional_duration)
+    }
+
+    pub fn relist_auction(
+        ctx: Context<RelistAuction>,
+        auction_id: u64,
         duration: u64,
+        minimum_bid: u64,
+    ) -> Result<()> {
+        instructions::relist_auction(ctx, auction_id, duration, minimum_bid)
+    }
+
+    pub fn expire_dutch_auction(
+        ctx: Context<ExpireDutchAuction>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::expire_dutch_auction(ctx, auction_id)
+    }
+
+    pub fn sweep_vault_surplus(ctx: Context<SweepVaultSurplus>, auction_id: u64) -> Result<()> {
+        instructions::sweep_vault_surplus(ctx, auction_id)
+    }
+
+    pub fn transfer_auction_ownership(
+        ctx: Context<TransferAuctionOwnership>,
+        auction_id: u64,
+        new_creator: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer_auction_ownership(ctx, auction_id, new_creator)
+    }
+
+    pub fn accept_auction_ownership(
+        ctx: Context<AcceptAuctionOwnership>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::accept_auction_ownership(ctx, auction_id)
+    }
+
+    pub fn cancel_auction_ownership_transfer(
+        ctx: Context<TransferAuctionOwnership>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::cancel_auction_ownership_transfer(ctx, auction_id)
+    }
+
+    pub fn update_reserve_price(
+        ctx: Context<UpdateReservePrice>,
+        auction_id: u64,
         reserve_price_encrypted: [u8; 32],
         reserve_price_nonce: u128,
+        reserve_price_commitment: [u8; 32],
     ) -> Result<()> {
-        instructions::create_dutch_auction(
+        instructions::update_reserve_price(
             ctx,
-            asset_mint,
-            asset_amount,
-            starting_price,
-            price_decrease_rate,
-            minimum_price_floor,
-            duration,
+            auction_id,
             reserve_price_encrypted,
             reserve_price_nonce,
+            reserve_price_commitment,
         )
     }
 
+    pub fn reveal_reserve_price(
+        ctx: Context<RevealReservePrice>,
+        auction_id: u64,
+        reserve_price: u64,
+        blinding: [u8; 32],
+    ) -> Result<()> {
+        instructions::reveal_reserve_price(ctx, auction_id, reserve_price, blinding)
+    }
 
-    pub fn submit_encrypted_bid(
-        ctx: Context<SubmitBid>,
+    pub fn submit_encrypted_bid<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitBid<'info>>,
         auction_id: u64,
-        bid_amount_encrypted: [u8; 32],
-        public_key: [u8; 32],
-        nonce: u128,
-        collateral_amount: u64,
-        computation_offset: u64,
+        params: SubmitBidParams,
     ) -> Result<()> {
-        instructions::submit_encrypted_bid(
-            ctx,
+        error::guard_context(
+            "submit_encrypted_bid",
             auction_id,
-            bid_amount_encrypted,
-            public_key,
-            nonce,
-            collateral_amount,
-            computation_offset,
+            instructions::submit_encrypted_bid(ctx, auction_id, params),
+        )
+    }
+
+    pub fn submit_relayed_bid<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitRelayedBid<'info>>,
+        auction_id: u64,
+        relayed_bid: RelayedBid,
+        invite_code: Option<[u8; 32]>,
+    ) -> Result<()> {
+        error::guard_context(
+            "submit_relayed_bid",
+            auction_id,
+            instructions::submit_relayed_bid(ctx, auction_id, relayed_bid, invite_code),
         )
     }
 
@@ -110,15 +174,53 @@ pub mod shadow_protocol {
         bid_amount: u64,
         collateral_amount: u64,
     ) -> Result<()> {
-        instructions::submit_dutch_bid(ctx, auction_id, bid_amount, collateral_amount)
+        error::guard_context(
+            "submit_dutch_bid",
+            auction_id,
+            instructions::submit_dutch_bid(ctx, auction_id, bid_amount, collateral_amount),
+        )
+    }
+
+    pub fn submit_encrypted_dutch_bid(
+        ctx: Context<SubmitEncryptedDutchBid>,
+        auction_id: u64,
+        bid_amount_encrypted: [u8; 32],
+        public_key: [u8; 32],
+        nonce: u128,
+        collateral_amount: u64,
+    ) -> Result<()> {
+        error::guard_context(
+            "submit_encrypted_dutch_bid",
+            auction_id,
+            instructions::submit_encrypted_dutch_bid(
+                ctx,
+                auction_id,
+                bid_amount_encrypted,
+                public_key,
+                nonce,
+                collateral_amount,
+            ),
+        )
+    }
+
+    pub fn get_current_dutch_price(
+        ctx: Context<GetCurrentDutchPrice>,
+        auction_id: u64,
+    ) -> Result<u64> {
+        instructions::get_current_dutch_price(ctx, auction_id)
     }
 
     pub fn authorize_settlement(
         ctx: Context<AuthorizeSettlement>,
         auction_id: u64,
         mpc_verification_hash: [u8; 32],
+        reserve_met: bool,
     ) -> Result<()> {
-        instructions::authorize_settlement(ctx, auction_id, mpc_verification_hash)
+        error::guard_context(
+            "authorize_settlement",
+            auction_id,
+            instructions::authorize_settlement(ctx, auction_id, mpc_verification_hash, reserve_met),
+        )
     }
 
     pub fn settle_auction(
@@ -126,37 +228,84 @@ pub mod shadow_protocol {
         auction_id: u64,
         computation_offset: u64,
     ) -> Result<()> {
-        instructions::settle_auction(ctx, auction_id, computation_offset)
+        error::guard_context(
+            "settle_auction",
+            auction_id,
+            instructions::settle_auction(ctx, auction_id, computation_offset),
+        )
     }
 
     pub fn queue_mpc_computation(
         ctx: Context<QueueMpcComputation>,
         auction_id: u64,
-        bids_count: u32,
-        encrypted_bids: Vec<EncryptedBidData>,
-        encrypted_reserve_price: [u8; 32],
-        mxe_cluster: Pubkey,
-        gas_limit: u64,
+        params: QueueMpcComputationParams,
     ) -> Result<()> {
-        instructions::queue_mpc_computation(
-            ctx,
+        error::guard_context(
+            "queue_mpc_computation",
             auction_id,
-            bids_count,
-            encrypted_bids,
-            encrypted_reserve_price,
-            mxe_cluster,
-            gas_limit,
+            instructions::queue_mpc_computation(ctx, auction_id, params),
         )
     }
 
-    pub fn arcium_callback(
-        ctx: Context<ArciumCallback>,
+    pub fn arcium_callback<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ArciumCallback<'info>>,
         computation_id: [u8; 32],
         result: Vec<u8>,
     ) -> Result<()> {
         instructions::arcium_callback(ctx, computation_id, result)
     }
 
+    pub fn dutch_bid_callback(
+        ctx: Context<DutchBidCallback>,
+        auction_id: u64,
+        bidder: Pubkey,
+        computation_id: [u8; 32],
+        result: Vec<u8>,
+    ) -> Result<()> {
+        instructions::dutch_bid_callback(ctx, auction_id, bidder, computation_id, result)
+    }
+
+    pub fn init_settlement_rounds(
+        ctx: Context<InitSettlementRounds>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::init_settlement_rounds(ctx, auction_id)
+    }
+
+    pub fn chunk_callback(
+        ctx: Context<ChunkCallback>,
+        auction_id: u64,
+        chunk_index: u32,
+        computation_id: [u8; 32],
+        encrypted_result: [[u8; 32]; CHUNK_WINNER_CIPHERTEXTS],
+        result_nonce: u128,
+    ) -> Result<()> {
+        instructions::chunk_callback(
+            ctx,
+            auction_id,
+            chunk_index,
+            computation_id,
+            encrypted_result,
+            result_nonce,
+        )
+    }
+
+    pub fn queue_final_round(
+        ctx: Context<QueueFinalRound>,
+        auction_id: u64,
+        mxe_cluster: Pubkey,
+        gas_limit: u64,
+    ) -> Result<()> {
+        instructions::queue_final_round(ctx, auction_id, mxe_cluster, gas_limit)
+    }
+
+    pub fn close_settlement_rounds(
+        ctx: Context<CloseSettlementRounds>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::close_settlement_rounds(ctx, auction_id)
+    }
+
     pub fn init_sealed_bid_comp_def(
         ctx: Context<InitSealedBidCompDef>,
     ) -> Result<()> {
@@ -175,39 +324,173 @@ pub mod shadow_protocol {
         instructions::init_batch_settlement_comp_def(ctx)
     }
 
-    pub fn batch_settle(
-        ctx: Context<BatchSettle>,
+    pub fn batch_settle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchSettle<'info>>,
         auction_ids: Vec<u64>,
         computation_offset: u64,
     ) -> Result<()> {
         instructions::batch_settle(ctx, auction_ids, computation_offset)
     }
 
-    pub fn execute_settlement(
-        ctx: Context<ExecuteSettlement>,
+    pub fn batch_settlement_callback(
+        ctx: Context<BatchSettlementCallbackCtx>,
+        result_data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::batch_settlement_callback(ctx, result_data)
+    }
+
+    pub fn distribute_batch_results<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeBatchResults<'info>>,
+    ) -> Result<()> {
+        instructions::distribute_batch_results(ctx)
+    }
+
+    pub fn cancel_batch(ctx: Context<CancelBatch>) -> Result<()> {
+        instructions::cancel_batch(ctx)
+    }
+
+    pub fn expire_batch(ctx: Context<ExpireBatch>) -> Result<()> {
+        instructions::expire_batch(ctx)
+    }
+
+    pub fn execute_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteSettlement<'info>>,
         auction_id: u64,
         winner: Pubkey,
         winning_amount: u64,
+        payout_swap: Option<PayoutSwap>,
+        expires_at: i64,
+        max_fee_bps: u16,
+    ) -> Result<()> {
+        error::guard_context(
+            "execute_settlement",
+            auction_id,
+            instructions::execute_settlement(
+                ctx,
+                auction_id,
+                winner,
+                winning_amount,
+                payout_swap,
+                expires_at,
+                max_fee_bps,
+            ),
+        )
+    }
+
+    pub fn finalize_unsold(ctx: Context<FinalizeUnsold>, auction_id: u64) -> Result<()> {
+        error::guard_context(
+            "finalize_unsold",
+            auction_id,
+            instructions::finalize_unsold(ctx, auction_id),
+        )
+    }
+
+    pub fn claim_asset<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimAsset<'info>>,
+        auction_id: u64,
     ) -> Result<()> {
-        instructions::execute_settlement(ctx, auction_id, winner, winning_amount)
+        error::guard_context(
+            "claim_asset",
+            auction_id,
+            instructions::claim_asset(ctx, auction_id),
+        )
+    }
+
+    pub fn claim_proceeds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimProceeds<'info>>,
+        auction_id: u64,
+    ) -> Result<()> {
+        error::guard_context(
+            "claim_proceeds",
+            auction_id,
+            instructions::claim_proceeds(ctx, auction_id),
+        )
     }
 
     pub fn initialize_protocol(ctx: Context<InitializeProtocol>) -> Result<()> {
         instructions::initialize_protocol(ctx)
     }
 
-    pub fn set_pause_state(ctx: Context<SetPauseState>, paused: bool) -> Result<()> {
-        instructions::set_pause_state(ctx, paused)
+    pub fn initialize_protocol_stats(ctx: Context<InitializeProtocolStats>, shard: u8) -> Result<()> {
+        instructions::initialize_protocol_stats(ctx, shard)
     }
 
-    pub fn update_protocol_fee(ctx: Context<UpdateProtocolFee>, new_fee: u16) -> Result<()> {
-        instructions::update_protocol_fee(ctx, new_fee)
+    pub fn initialize_admin_audit_log(ctx: Context<InitializeAdminAuditLog>) -> Result<()> {
+        instructions::initialize_admin_audit_log(ctx)
+    }
+
+    pub fn pause(ctx: Context<SetPauseState>) -> Result<()> {
+        instructions::pause(ctx)
+    }
+
+    pub fn propose_unpause(ctx: Context<SetPauseState>) -> Result<()> {
+        instructions::propose_unpause(ctx)
+    }
+
+    pub fn confirm_unpause(ctx: Context<SetPauseState>) -> Result<()> {
+        instructions::confirm_unpause(ctx)
+    }
+
+    pub fn propose_fee_update(ctx: Context<UpdateProtocolFee>, new_fee: u16) -> Result<()> {
+        instructions::propose_fee_update(ctx, new_fee)
+    }
+
+    pub fn confirm_fee_update(ctx: Context<UpdateProtocolFee>) -> Result<()> {
+        instructions::confirm_fee_update(ctx)
     }
 
     pub fn update_fee_recipient(ctx: Context<UpdateFeeRecipient>, new_recipient: Pubkey) -> Result<()> {
         instructions::update_fee_recipient(ctx, new_recipient)
     }
 
+    pub fn update_cleanup_grace_period(ctx: Context<UpdateCleanupGracePeriod>, new_grace_period: i64) -> Result<()> {
+        instructions::update_cleanup_grace_period(ctx, new_grace_period)
+    }
+
+    pub fn update_dispute_config(
+        ctx: Context<UpdateDisputeConfig>,
+        dispute_window: i64,
+        dispute_bond: u64,
+    ) -> Result<()> {
+        instructions::update_dispute_config(ctx, dispute_window, dispute_bond)
+    }
+
+    pub fn update_insurance_fee_share(ctx: Context<UpdateInsuranceFeeShare>, new_share: u16) -> Result<()> {
+        instructions::update_insurance_fee_share(ctx, new_share)
+    }
+
+    pub fn update_creation_deposit(ctx: Context<UpdateCreationDeposit>, new_deposit: u64) -> Result<()> {
+        instructions::update_creation_deposit(ctx, new_deposit)
+    }
+
+    pub fn update_callback_authority(ctx: Context<UpdateCallbackAuthority>, new_callback_authority: Pubkey) -> Result<()> {
+        instructions::update_callback_authority(ctx, new_callback_authority)
+    }
+
+    pub fn update_settlement_authorizer(ctx: Context<UpdateSettlementAuthorizer>, new_settlement_authorizer: Pubkey) -> Result<()> {
+        instructions::update_settlement_authorizer(ctx, new_settlement_authorizer)
+    }
+
+    pub fn set_auction_settlement_authorizer(
+        ctx: Context<SetAuctionSettlementAuthorizer>,
+        auction_id: u64,
+        authorizer: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_auction_settlement_authorizer(ctx, auction_id, authorizer)
+    }
+
+    pub fn update_yield_adapter(ctx: Context<UpdateYieldAdapter>, yield_adapter: Pubkey) -> Result<()> {
+        instructions::update_yield_adapter(ctx, yield_adapter)
+    }
+
+    pub fn update_compliance_program(ctx: Context<UpdateComplianceProgram>, compliance_program: Pubkey) -> Result<()> {
+        instructions::update_compliance_program(ctx, compliance_program)
+    }
+
+    pub fn update_auction_bounds(ctx: Context<UpdateAuctionBounds>, bounds: AuctionBounds) -> Result<()> {
+        instructions::update_auction_bounds(ctx, bounds)
+    }
+
     pub fn initiate_authority_transfer(ctx: Context<InitiateAuthorityTransfer>, new_authority: Pubkey) -> Result<()> {
         instructions::initiate_authority_transfer(ctx, new_authority)
     }
@@ -236,18 +519,705 @@ pub mod shadow_protocol {
         instructions::cleanup_expired_bids(ctx, auction_id, bid_indices)
     }
 
-    pub fn batch_cleanup_auctions(
-        ctx: Context<BatchCleanupAuctions>,
+    pub fn batch_cleanup_auctions<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchCleanupAuctions<'info>>,
         auction_ids: Vec<u64>,
     ) -> Result<()> {
         instructions::batch_cleanup_auctions(ctx, auction_ids)
     }
 
-    pub fn reclaim_storage(
-        ctx: Context<ReclaimStorage>,
+    pub fn reclaim_storage<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReclaimStorage<'info>>,
     ) -> Result<()> {
         instructions::reclaim_storage(ctx)
     }
+
+    pub fn create_bid_budget(
+        ctx: Context<CreateBidBudget>,
+        budget_id: u64,
+        budget_encrypted: [u8; 32],
+        budget_nonce: u128,
+        encryption_public_key: [u8; 32],
+    ) -> Result<()> {
+        instructions::create_bid_budget(
+            ctx,
+            budget_id,
+            budget_encrypted,
+            budget_nonce,
+            encryption_public_key,
+        )
+    }
+
+    pub fn queue_budget_allocation<'info>(
+        ctx: Context<'_, '_, 'info, 'info, QueueBudgetAllocation<'info>>,
+        budget_id: u64,
+    ) -> Result<()> {
+        instructions::queue_budget_allocation(ctx, budget_id)
+    }
+
+    pub fn budget_allocation_callback(
+        ctx: Context<BudgetAllocationCallback>,
+        budget_id: u64,
+        computation_id: [u8; 32],
+        result: Vec<u8>,
+    ) -> Result<()> {
+        instructions::budget_allocation_callback(ctx, budget_id, computation_id, result)
+    }
+
+    pub fn create_market(
+        ctx: Context<CreateMarket>,
+        market_id: u64,
+        round_duration: i64,
+    ) -> Result<()> {
+        instructions::create_market(ctx, market_id, round_duration)
+    }
+
+    pub fn configure_market_batches(
+        ctx: Context<ConfigureMarketBatches>,
+        market_id: u64,
+        batch_interval: i64,
+        min_order_escrow: u64,
+    ) -> Result<()> {
+        instructions::configure_market_batches(ctx, market_id, batch_interval, min_order_escrow)
+    }
+
+    pub fn submit_market_order(
+        ctx: Context<SubmitMarketOrder>,
+        market_id: u64,
+        params: MarketOrderParams,
+    ) -> Result<()> {
+        instructions::submit_market_order(ctx, market_id, params)
+    }
+
+    pub fn queue_market_clearing(
+        ctx: Context<QueueMarketClearing>,
+        market_id: u64,
+    ) -> Result<()> {
+        instructions::queue_market_clearing(ctx, market_id)
+    }
+
+    pub fn market_clearing_callback(
+        ctx: Context<MarketClearingCallback>,
+        market_id: u64,
+        computation_id: [u8; 32],
+        result: Vec<u8>,
+    ) -> Result<()> {
+        instructions::market_clearing_callback(ctx, market_id, computation_id, result)
+    }
+
+    pub fn settle_market_order(
+        ctx: Context<SettleMarketOrder>,
+        market_id: u64,
+        order_index: u32,
+    ) -> Result<()> {
+        instructions::settle_market_order(ctx, market_id, order_index)
+    }
+
+    pub fn dispute_settlement(
+        ctx: Context<DisputeSettlement>,
+        auction_id: u64,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::dispute_settlement(ctx, auction_id, evidence_hash)
+    }
+
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        auction_id: u64,
+        uphold: bool,
+    ) -> Result<()> {
+        instructions::resolve_dispute(ctx, auction_id, uphold)
+    }
+
+    pub fn register_juror(ctx: Context<RegisterJuror>, stake: u64) -> Result<()> {
+        instructions::register_juror(ctx, stake)
+    }
+
+    pub fn deregister_juror(ctx: Context<DeregisterJuror>) -> Result<()> {
+        instructions::deregister_juror(ctx)
+    }
+
+    pub fn open_arbitration(
+        ctx: Context<OpenArbitration>,
+        auction_id: u64,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::open_arbitration(ctx, auction_id, evidence_hash)
+    }
+
+    pub fn submit_evidence(
+        ctx: Context<SubmitEvidence>,
+        auction_id: u64,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::submit_evidence(ctx, auction_id, evidence_hash)
+    }
+
+    pub fn cast_arbitration_vote(
+        ctx: Context<CastArbitrationVote>,
+        auction_id: u64,
+        void_settlement: bool,
+    ) -> Result<()> {
+        instructions::cast_arbitration_vote(ctx, auction_id, void_settlement)
+    }
+
+    pub fn resolve_arbitration<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveArbitration<'info>>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::resolve_arbitration(ctx, auction_id)
+    }
+
+    pub fn refund_voided_collateral(
+        ctx: Context<RefundVoidedCollateral>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::refund_voided_collateral(ctx, auction_id)
+    }
+
+    pub fn initialize_insurance_fund(ctx: Context<InitializeInsuranceFund>) -> Result<()> {
+        instructions::initialize_insurance_fund(ctx)
+    }
+
+    pub fn pay_claim(
+        ctx: Context<PayClaim>,
+        amount: u64,
+        claim_reference: [u8; 32],
+    ) -> Result<()> {
+        instructions::pay_claim(ctx, amount, claim_reference)
+    }
+
+    pub fn track_computation(
+        ctx: Context<TrackComputation>,
+        computation_id: [u8; 32],
+    ) -> Result<()> {
+        instructions::track_computation(ctx, computation_id)
+    }
+
+    pub fn complete_computation(
+        ctx: Context<ResolveComputation>,
+        computation_id: [u8; 32],
+        result_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::complete_computation(ctx, computation_id, result_hash)
+    }
+
+    pub fn fail_computation(
+        ctx: Context<ResolveComputation>,
+        computation_id: [u8; 32],
+    ) -> Result<()> {
+        instructions::fail_computation(ctx, computation_id)
+    }
+
+    pub fn claim_win<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimWin<'info>>,
+        auction_id: u64,
+        blinding: [u8; 32],
+    ) -> Result<()> {
+        instructions::claim_win(ctx, auction_id, blinding)
+    }
+
+    pub fn expire_private_win(ctx: Context<ExpirePrivateWin>, auction_id: u64) -> Result<()> {
+        instructions::expire_private_win(ctx, auction_id)
+    }
+
+    pub fn create_anonymous_listing(
+        ctx: Context<CreateAnonymousListing>,
+        auction_id: u64,
+        ticket_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::create_anonymous_listing(ctx, auction_id, ticket_hash)
+    }
+
+    pub fn claim_anonymous_proceeds(
+        ctx: Context<ClaimAnonymousProceeds>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::claim_anonymous_proceeds(ctx, auction_id)
+    }
+
+    pub fn act_as_listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ActAsListing<'info>>,
+        auction_id: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::act_as_listing(ctx, auction_id, data)
+    }
+
+    pub fn set_deny_list_entry(
+        ctx: Context<SetDenyListEntry>,
+        wallet: Pubkey,
+        flags: u8,
+    ) -> Result<()> {
+        instructions::set_deny_list_entry(ctx, wallet, flags)
+    }
+
+    pub fn remove_deny_list_entry(ctx: Context<RemoveDenyListEntry>, wallet: Pubkey) -> Result<()> {
+        instructions::remove_deny_list_entry(ctx, wallet)
+    }
+
+    pub fn set_price_oracle(
+        ctx: Context<SetPriceOracle>,
+        auction_id: u64,
+        price_oracle: Option<PriceOracle>,
+    ) -> Result<()> {
+        instructions::set_price_oracle(ctx, auction_id, price_oracle)
+    }
+
+    pub fn set_payout_conversion(
+        ctx: Context<SetAuctionFeature>,
+        auction_id: u64,
+        payout_conversion: Option<PayoutConversion>,
+    ) -> Result<()> {
+        instructions::set_payout_conversion(ctx, auction_id, payout_conversion)
+    }
+
+    pub fn refund_collateral(ctx: Context<RefundCollateral>, auction_id: u64) -> Result<()> {
+        instructions::refund_collateral(ctx, auction_id)
+    }
+
+    pub fn refund_dutch_claim(ctx: Context<RefundDutchClaim>, auction_id: u64) -> Result<()> {
+        instructions::refund_dutch_claim(ctx, auction_id)
+    }
+
+    pub fn slash_collateral(ctx: Context<SlashCollateral>, auction_id: u64) -> Result<()> {
+        instructions::slash_collateral(ctx, auction_id)
+    }
+
+    pub fn slash_invalid_bid(
+        ctx: Context<SlashInvalidBid>,
+        auction_id: u64,
+        computation_id: [u8; 32],
+        result: Vec<u8>,
+    ) -> Result<()> {
+        instructions::slash_invalid_bid(ctx, auction_id, computation_id, result)
+    }
+
+    pub fn set_yield_config(
+        ctx: Context<SetYieldConfig>,
+        auction_id: u64,
+        beneficiary: Option<YieldBeneficiary>,
+    ) -> Result<()> {
+        instructions::set_yield_config(ctx, auction_id, beneficiary)
+    }
+
+    pub fn deposit_to_yield<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositToYield<'info>>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::deposit_to_yield(ctx, auction_id)
+    }
+
+    pub fn withdraw_from_yield<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawFromYield<'info>>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::withdraw_from_yield(ctx, auction_id)
+    }
+
+    pub fn set_payout_vesting(
+        ctx: Context<SetAuctionFeature>,
+        auction_id: u64,
+        payout_vesting: Option<VestingSchedule>,
+    ) -> Result<()> {
+        instructions::set_payout_vesting(ctx, auction_id, payout_vesting)
+    }
+
+    pub fn set_settlement_hook(
+        ctx: Context<SetAuctionFeature>,
+        auction_id: u64,
+        settlement_hook: Option<SettlementHook>,
+    ) -> Result<()> {
+        instructions::set_settlement_hook(ctx, auction_id, settlement_hook)
+    }
+
+    pub fn claim_stream(ctx: Context<ClaimStream>, auction_id: u64) -> Result<()> {
+        instructions::claim_stream(ctx, auction_id)
+    }
+
+    pub fn submit_lbp_purchase(
+        ctx: Context<SubmitLbpPurchase>,
+        auction_id: u64,
+        quantity_encrypted: [u8; 32],
+        max_price_encrypted: [u8; 32],
+        encryption_public_key: [u8; 32],
+        nonce: u128,
+        escrow_amount: u64,
+    ) -> Result<()> {
+        instructions::submit_lbp_purchase(
+            ctx,
+            auction_id,
+            quantity_encrypted,
+            max_price_encrypted,
+            encryption_public_key,
+            nonce,
+            escrow_amount,
+        )
+    }
+
+    pub fn lbp_purchase_callback(
+        ctx: Context<LbpPurchaseCallback>,
+        auction_id: u64,
+        index: u64,
+        computation_id: [u8; 32],
+        result: Vec<u8>,
+    ) -> Result<()> {
+        instructions::lbp_purchase_callback(ctx, auction_id, index, computation_id, result)
+    }
+
+    pub fn create_auction_house(
+        ctx: Context<CreateAuctionHouse>,
+        name: [u8; 32],
+        fee_bps: u16,
+        fee_recipient: Pubkey,
+        creator_allowlist: bool,
+    ) -> Result<()> {
+        instructions::create_auction_house(ctx, name, fee_bps, fee_recipient, creator_allowlist)
+    }
+
+    pub fn update_auction_house(
+        ctx: Context<UpdateAuctionHouse>,
+        fee_bps: u16,
+        fee_recipient: Pubkey,
+        creator_allowlist: bool,
+    ) -> Result<()> {
+        instructions::update_auction_house(ctx, fee_bps, fee_recipient, creator_allowlist)
+    }
+
+    pub fn set_auction_house_paused(ctx: Context<UpdateAuctionHouse>, paused: bool) -> Result<()> {
+        instructions::set_auction_house_paused(ctx, paused)
+    }
+
+    pub fn add_house_creator(ctx: Context<AddHouseCreator>, creator: Pubkey) -> Result<()> {
+        instructions::add_house_creator(ctx, creator)
+    }
+
+    pub fn remove_house_creator(ctx: Context<RemoveHouseCreator>, creator: Pubkey) -> Result<()> {
+        instructions::remove_house_creator(ctx, creator)
+    }
+
+    pub fn update_fee_tiers(
+        ctx: Context<UpdateFeeTiers>,
+        fee_tier_mint: Pubkey,
+        fee_tiers: Vec<FeeTier>,
+    ) -> Result<()> {
+        instructions::update_fee_tiers(ctx, fee_tier_mint, fee_tiers)
+    }
+
+    pub fn propose_governance_authority(ctx: Context<ProposeGovernanceAuthority>, realm: Pubkey) -> Result<()> {
+        instructions::propose_governance_authority(ctx, realm)
+    }
+
+    pub fn accept_governance_authority(ctx: Context<AcceptGovernanceAuthority>) -> Result<()> {
+        instructions::accept_governance_authority(ctx)
+    }
+
+    pub fn initialize_staking_pool(ctx: Context<InitializeStakingPool>, fee_share: u16) -> Result<()> {
+        instructions::initialize_staking_pool(ctx, fee_share)
+    }
+
+    pub fn update_staking_fee_share(ctx: Context<UpdateStakingFeeShare>, fee_share: u16) -> Result<()> {
+        instructions::update_staking_fee_share(ctx, fee_share)
+    }
+
+    pub fn stake(ctx: Context<Stake>, amount: u64) -> Result<()> {
+        instructions::stake(ctx, amount)
+    }
+
+    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        instructions::unstake(ctx, amount)
+    }
+
+    pub fn claim_staking_rewards(ctx: Context<ClaimStakingRewards>) -> Result<()> {
+        instructions::claim_staking_rewards(ctx)
+    }
+
+    pub fn create_auction_metadata(
+        ctx: Context<CreateAuctionMetadata>,
+        auction_id: u64,
+        name: String,
+        symbol: String,
+        uri: String,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::create_auction_metadata(ctx, auction_id, name, symbol, uri, content_hash)
+    }
+
+    pub fn close_auction_metadata(ctx: Context<CloseAuctionMetadata>, auction_id: u64) -> Result<()> {
+        instructions::close_auction_metadata(ctx, auction_id)
+    }
+
+    pub fn migrate_auction_layout(ctx: Context<MigrateAuctionLayout>, auction_id: u64) -> Result<()> {
+        instructions::migrate_auction_layout(ctx, auction_id)
+    }
+
+    pub fn create_settlement_lut(
+        ctx: Context<CreateSettlementLut>,
+        auction_id: u64,
+        recent_slot: u64,
+    ) -> Result<()> {
+        instructions::create_settlement_lut(ctx, auction_id, recent_slot)
+    }
+
+    pub fn deactivate_settlement_lut(ctx: Context<ManageSettlementLut>, auction_id: u64) -> Result<()> {
+        instructions::deactivate_settlement_lut(ctx, auction_id)
+    }
+
+    pub fn close_settlement_lut(ctx: Context<ManageSettlementLut>, auction_id: u64) -> Result<()> {
+        instructions::close_settlement_lut(ctx, auction_id)
+    }
+
+    pub fn announce_emergency_withdraw(
+        ctx: Context<AnnounceEmergencyWithdrawal>,
+        auction_id: u64,
+        amount: u64,
+        reason: [u8; 32],
+    ) -> Result<()> {
+        instructions::announce_emergency_withdraw(ctx, auction_id, amount, reason)
+    }
+
+    pub fn emergency_withdraw(
+        ctx: Context<EmergencyWithdraw>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::emergency_withdraw(ctx, auction_id)
+    }
+
+    pub fn cancel_emergency_withdraw(
+        ctx: Context<CancelEmergencyWithdrawal>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::cancel_emergency_withdraw(ctx, auction_id)
+    }
+
+    pub fn set_gas_schedule(
+        ctx: Context<SetGasSchedule>,
+        comp_def_offset: u32,
+        base_gas: u64,
+        per_input_gas: u64,
+    ) -> Result<()> {
+        instructions::set_gas_schedule(ctx, comp_def_offset, base_gas, per_input_gas)
+    }
+
+    pub fn set_mxe_clusters(
+        ctx: Context<SetMxeClusters>,
+        clusters: Vec<Pubkey>,
+        retry_timeout: i64,
+    ) -> Result<()> {
+        instructions::set_mxe_clusters(ctx, clusters, retry_timeout)
+    }
+
+    pub fn set_mxe_cluster_health(
+        ctx: Context<SetMxeClusterHealth>,
+        cluster: Pubkey,
+        healthy: bool,
+    ) -> Result<()> {
+        instructions::set_mxe_cluster_health(ctx, cluster, healthy)
+    }
+
+    pub fn retry_mpc_computation(ctx: Context<RetryMpcComputation>, auction_id: u64) -> Result<()> {
+        instructions::retry_mpc_computation(ctx, auction_id)
+    }
+
+    pub fn finalize_bid_set<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeBidSet<'info>>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::finalize_bid_set(ctx, auction_id)
+    }
+
+    pub fn prove_bid_included(
+        ctx: Context<ProveBidIncluded>,
+        auction_id: u64,
+        leaf_index: u32,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::prove_bid_included(ctx, auction_id, leaf_index, proof)
+    }
+
+    pub fn compress_auction(ctx: Context<CompressAuction>, auction_id: u64) -> Result<()> {
+        instructions::compress_auction(ctx, auction_id)
+    }
+
+    pub fn compress_bid(ctx: Context<CompressBid>, auction_id: u64, bidder: Pubkey) -> Result<()> {
+        instructions::compress_bid(ctx, auction_id, bidder)
+    }
+
+    pub fn decompress_auction(
+        ctx: Context<DecompressAuction>,
+        auction_id: u64,
+        data: Vec<u8>,
+        leaf_index: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::decompress_auction(ctx, auction_id, data, leaf_index, proof)
+    }
+
+    pub fn decompress_bid(
+        ctx: Context<DecompressBid>,
+        auction_id: u64,
+        bidder: Pubkey,
+        data: Vec<u8>,
+        leaf_index: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::decompress_bid(ctx, auction_id, bidder, data, leaf_index, proof)
+    }
+
+    pub fn permissionless_tick<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PermissionlessTick<'info>>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::permissionless_tick(ctx, auction_id)
+    }
+
+    pub fn end_auction(ctx: Context<EndAuction>, auction_id: u64) -> Result<()> {
+        instructions::end_auction(ctx, auction_id)
+    }
+
+    pub fn update_circuit_breaker(
+        ctx: Context<UpdateCircuitBreaker>,
+        config: CircuitBreakerConfig,
+    ) -> Result<()> {
+        instructions::update_circuit_breaker(ctx, config)
+    }
+
+    pub fn clear_circuit_breaker(ctx: Context<ClearCircuitBreaker>, shard: u8) -> Result<()> {
+        instructions::clear_circuit_breaker(ctx, shard)
+    }
+
+    pub fn begin_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BeginSettlement<'info>>,
+        auction_id: u64,
+        expires_at: i64,
+        max_fee_bps: u16,
+    ) -> Result<()> {
+        error::guard_context(
+            "begin_settlement",
+            auction_id,
+            instructions::begin_settlement(ctx, auction_id, expires_at, max_fee_bps),
+        )
+    }
+
+    pub fn advance_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AdvanceSettlement<'info>>,
+        auction_id: u64,
+    ) -> Result<()> {
+        error::guard_context(
+            "advance_settlement",
+            auction_id,
+            instructions::advance_settlement(ctx, auction_id),
+        )
+    }
+
+    pub fn record_fee_withdrawal(
+        ctx: Context<RecordFeeWithdrawal>,
+        mint: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::record_fee_withdrawal(ctx, mint, amount)
+    }
+
+    pub fn buy_dutch_tranche<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuyDutchTranche<'info>>,
+        auction_id: u64,
+        max_price: u64,
+    ) -> Result<()> {
+        error::guard_context(
+            "buy_dutch_tranche",
+            auction_id,
+            instructions::buy_dutch_tranche(ctx, auction_id, max_price),
+        )
+    }
+
+    pub fn enable_quantity_bids(ctx: Context<EnableQuantityBids>, auction_id: u64) -> Result<()> {
+        instructions::enable_quantity_bids(ctx, auction_id)
+    }
+
+    pub fn submit_quantity_bid(
+        ctx: Context<SubmitQuantityBid>,
+        auction_id: u64,
+        params: QuantityBidParams,
+    ) -> Result<()> {
+        error::guard_context(
+            "submit_quantity_bid",
+            auction_id,
+            instructions::submit_quantity_bid(ctx, auction_id, params),
+        )
+    }
+
+    pub fn queue_quantity_clearing(ctx: Context<QueueQuantityClearing>, auction_id: u64) -> Result<()> {
+        instructions::queue_quantity_clearing(ctx, auction_id)
+    }
+
+    pub fn quantity_clearing_callback(
+        ctx: Context<QuantityClearingCallback>,
+        auction_id: u64,
+        computation_id: [u8; 32],
+        result: Vec<u8>,
+    ) -> Result<()> {
+        instructions::quantity_clearing_callback(ctx, auction_id, computation_id, result)
+    }
+
+    pub fn distribute_quantity_allocation(
+        ctx: Context<DistributeQuantityAllocation>,
+        auction_id: u64,
+        bid_index: u32,
+    ) -> Result<()> {
+        error::guard_context(
+            "distribute_quantity_allocation",
+            auction_id,
+            instructions::distribute_quantity_allocation(ctx, auction_id, bid_index),
+        )
+    }
+
+    pub fn preview_settlement(
+        ctx: Context<PreviewSettlement>,
+        auction_id: u64,
+        winner: Pubkey,
+        winning_amount: u64,
+        payment_mint: Pubkey,
+    ) -> Result<SettlementPreview> {
+        instructions::preview_settlement(ctx, auction_id, winner, winning_amount, payment_mint)
+    }
+
+    pub fn derive_auction_addresses(
+        ctx: Context<DeriveAddresses>,
+        creator: Pubkey,
+        creator_nonce: u64,
+        asset_mint: Pubkey,
+    ) -> Result<AuctionAddresses> {
+        instructions::derive_auction_addresses(ctx, creator, creator_nonce, asset_mint)
+    }
+
+    pub fn derive_bid_address(
+        ctx: Context<DeriveAddresses>,
+        auction_id: u64,
+        bidder: Pubkey,
+    ) -> Result<Pubkey> {
+        instructions::derive_bid_address(ctx, auction_id, bidder)
+    }
+
+    pub fn derive_batch_address(
+        ctx: Context<DeriveAddresses>,
+        batch_id: u64,
+    ) -> Result<Pubkey> {
+        instructions::derive_batch_address(ctx, batch_id)
+    }
+
+    pub fn mint_auction_position(
+        ctx: Context<MintAuctionPosition>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::mint_auction_position(ctx, auction_id)
+    }
+
+    pub fn mint_bid_position(
+        ctx: Context<MintBidPosition>,
+        auction_id: u64,
+    ) -> Result<()> {
+        instructions::mint_bid_position(ctx, auction_id)
+    }
 }
 
 #[derive(Debug)]
@@ -1,13 +0,0 @@
-use anchor_lang::prelude::*;
-
-// Re-export from mod.rs for consistency
-pub use super::{
-    AuctionAccount, 
-    AuctionType, 
-    AuctionStatus,
-    AuctionCreated,
-    AuctionSettled,
-    AUCTION_SEED,
-    ASSET_VAULT_SEED,
-    MAX_AUCTION_DURATION,
-};
\ No newline at end of file
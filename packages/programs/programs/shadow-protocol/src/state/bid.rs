@@ -1,9 +0,0 @@
-use anchor_lang::prelude::*;
-
-// Re-export from mod.rs for consistency
-pub use super::{
-    BidAccount,
-    BidSubmitted,
-    BID_SEED,
-    MAX_BIDS_PER_AUCTION,
-};
\ No newline at end of file
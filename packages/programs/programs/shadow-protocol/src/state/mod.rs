@@ -1,12 +1,6 @@
 use anchor_lang::prelude::*;
-
-pub mod auction;
-pub mod bid;
-pub mod protocol;
-
-pub use auction::*;
-pub use bid::*;
-pub use protocol::*;
+use crate::error::ShadowProtocolError;
+use crate::crypto::CryptoUtils;
 
 #[account]
 #[derive(InitSpace)]
@@ -19,7 +13,7 @@ pub struct ProtocolState {
     pub fee_recipient: Pubkey,
     /// Whether the protocol is paused
     pub paused: bool,
-    /// Next auction ID (auto-incrementing)
+    /// Next public auction sequence number (auto-incrementing)
     pub next_auction_id: u64,
     /// Pending authority transfer
     pub pending_authority: Option<Pubkey>,
@@ -27,15 +21,440 @@ pub struct ProtocolState {
     pub authority_transfer_timelock: Option<i64>,
     /// Protocol bump seed
     pub bump: u8,
+    /// Seconds after auction end before cleanup is allowed
+    pub cleanup_grace_period: i64,
+    /// Next batch ID (auto-incrementing from 0)
+    pub next_batch_id: u64,
+    /// Seconds after settlement authorization during which it can be disputed
+    pub dispute_window: i64,
+    /// Lamports a disputer must post as a bond
+    pub dispute_bond: u64,
+    /// Share of protocol fees routed to the insurance fund, in basis points of the fee
+    pub insurance_fee_share: u16,
+    /// Refundable lamport deposit required to create an auction
+    pub creation_deposit: u64,
+    /// Limits enforced on auction parameters at creation
+    pub auction_bounds: AuctionBounds,
+    /// Relayer allowed to deliver MPC callbacks; the authority if unset
+    pub callback_authority: Pubkey,
+    /// Screening program new auctions consult through `check`; unset disables it
+    pub compliance_program: Pubkey,
+    /// Lending adapter auctions may park escrowed funds in; unset disables it
+    pub yield_adapter: Pubkey,
+    /// Mint whose settled volume counts toward a seller's fee tier
+    pub fee_tier_mint: Pubkey,
+    /// Volume discounts on the protocol fee, by ascending volume threshold
+    #[max_len(4)]
+    pub fee_tiers: Vec<FeeTier>,
+    /// Realms governance the authority is (or is being handed) to
+    pub governance: Option<GovernanceConfig>,
+    /// Protocol fee change waiting out FEE_UPDATE_DELAY
+    pub pending_fee_update: Option<PendingFeeUpdate>,
+    /// When a proposed unpause can be confirmed
+    pub unpause_executable_at: Option<i64>,
+    /// Thresholds at which settlements are soft-paused
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Key allowed to authorize settlements; the authority if unset
+    pub settlement_authorizer: Pubkey,
     /// Reserved space for future upgrades
-    pub reserved: [u8; 100],
+    pub reserved: [u8; 18],
+}
+
+/// Protocol-wide limits on auction parameters. A zero maximum leaves that
+/// parameter uncapped; durations always stay within MIN_AUCTION_DURATION and
+/// MAX_AUCTION_DURATION.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct AuctionBounds {
+    /// Shortest allowed auction, in seconds; never below MIN_AUCTION_DURATION
+    pub min_duration: i64,
+    /// Longest allowed auction, in seconds
+    pub max_duration: i64,
+    /// Lowest allowed minimum bid (or Dutch price floor)
+    pub min_bid_floor: u64,
+    /// Highest allowed Dutch price decrease rate
+    pub max_price_decrease_rate: u64,
+    /// Largest asset amount a single auction can hold
+    pub max_asset_amount: u64,
+}
+
+/// Settlement activity that soft-pauses new settlements until the authority
+/// clears it. Each stats shard is held to these limits over its own slice of
+/// auctions, so tracking never makes settlements contend on one account. A
+/// zero window disables the breaker; a zero limit is not checked.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct CircuitBreakerConfig {
+    /// Length of the rolling window, in seconds
+    pub window: i64,
+    /// Payment mint whose settled volume is watched
+    pub volume_mint: Pubkey,
+    /// Volume in `volume_mint` base units settled within a window that trips
+    pub max_volume: u64,
+    /// Failed settlement computations within a window that trip
+    pub max_failures: u32,
+}
+
+impl AuctionBounds {
+    pub fn check_duration(&self, duration: u64) -> Result<()> {
+        let max_duration = if self.max_duration == 0 { MAX_AUCTION_DURATION } else { self.max_duration };
+        // Protocol accounts that predate the bounds read a zero minimum
+        require!(
+            duration as i64 >= self.min_duration.max(MIN_AUCTION_DURATION),
+            ShadowProtocolError::AuctionDurationTooShort
+        );
+        require!(
+            duration as i64 <= max_duration,
+            ShadowProtocolError::AuctionDurationTooLong
+        );
+        Ok(())
+    }
+
+    pub fn check_minimum_bid(&self, minimum_bid: u64) -> Result<()> {
+        require!(
+            minimum_bid >= self.min_bid_floor,
+            ShadowProtocolError::MinimumBidBelowFloor
+        );
+        Ok(())
+    }
+
+    pub fn check_price_decrease_rate(&self, rate: u64) -> Result<()> {
+        require!(
+            self.max_price_decrease_rate == 0 || rate <= self.max_price_decrease_rate,
+            ShadowProtocolError::InvalidPriceDecreaseRate
+        );
+        Ok(())
+    }
+
+    pub fn check_asset_amount(&self, asset_amount: u64) -> Result<()> {
+        require!(
+            self.max_asset_amount == 0 || asset_amount <= self.max_asset_amount,
+            ShadowProtocolError::InvalidAssetAmount
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct PendingFeeUpdate {
+    /// Proposed protocol fee in basis points
+    pub new_fee: u16,
+    /// Earliest time `confirm_fee_update` applies it
+    pub executable_at: i64,
+}
+
+/// SPL Governance (Realms) governance acting as protocol authority
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct GovernanceConfig {
+    /// SPL Governance program instance the realm lives in
+    pub program_id: Pubkey,
+    /// Realm the governance belongs to
+    pub realm: Pubkey,
+    /// Governance PDA that signs executed proposals
+    pub governance: Pubkey,
+}
+
+/// Append-only ring buffer of admin actions, newest overwriting oldest once full
+#[account]
+#[derive(InitSpace)]
+pub struct AdminAuditLog {
+    /// Recorded actions; slot `next_slot` is overwritten next once full
+    #[max_len(64)]
+    pub entries: Vec<AuditEntry>,
+    /// Slot the next entry is written to
+    pub next_slot: u16,
+    /// Entries ever recorded
+    pub total_entries: u64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl AdminAuditLog {
+    pub fn record(&mut self, action: AdminAction, actor: Pubkey, old_value: [u8; 32], new_value: [u8; 32]) -> Result<()> {
+        let entry = AuditEntry {
+            action,
+            actor,
+            old_value,
+            new_value,
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+        let slot = self.next_slot as usize;
+        if slot < self.entries.len() {
+            self.entries[slot] = entry;
+        } else {
+            self.entries.push(entry);
+        }
+        self.next_slot = ((slot + 1) % ADMIN_AUDIT_LOG_CAPACITY) as u16;
+        self.total_entries = self.total_entries.saturating_add(1);
+        Ok(())
+    }
+
+    /// Numeric value as an audit word, little-endian and zero padded
+    pub fn word_u64(value: u64) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        word[..8].copy_from_slice(&value.to_le_bytes());
+        word
+    }
+
+    /// Structured value as an audit word: the hash of its serialization
+    pub fn word_hash<T: AnchorSerialize>(value: &T) -> Result<[u8; 32]> {
+        Ok(anchor_lang::solana_program::hash::hash(&value.try_to_vec()?).to_bytes())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct AuditEntry {
+    pub action: AdminAction,
+    /// Signer that performed the action
+    pub actor: Pubkey,
+    /// Value before the change: numbers little-endian, keys as bytes,
+    /// structured configs hashed
+    pub old_value: [u8; 32],
+    /// Value after the change, encoded like `old_value`
+    pub new_value: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AdminAction {
+    SetPauseState,
+    UpdateProtocolFee,
+    UpdateFeeRecipient,
+    UpdateCleanupGracePeriod,
+    UpdateDisputeConfig,
+    UpdateInsuranceFeeShare,
+    UpdateCreationDeposit,
+    UpdateCallbackAuthority,
+    UpdateYieldAdapter,
+    UpdateFeeTiers,
+    UpdateComplianceProgram,
+    UpdateAuctionBounds,
+    ProposeFeeUpdate,
+    ProposeUnpause,
+    InitiateAuthorityTransfer,
+    CompleteAuthorityTransfer,
+    CancelAuthorityTransfer,
+    ProposeGovernanceAuthority,
+    AcceptGovernanceAuthority,
+    SetDenyListEntry,
+    RemoveDenyListEntry,
+    PayInsuranceClaim,
+    UpdateStakingFeeShare,
+    AnnounceEmergencyWithdrawal,
+    ExecuteEmergencyWithdrawal,
+    CancelEmergencyWithdrawal,
+    SetGasSchedule,
+    SetMxeClusters,
+    SetMxeClusterHealth,
+    UpdateCircuitBreaker,
+    ClearCircuitBreaker,
+    RecordFeeWithdrawal,
+    UpdateSettlementAuthorizer,
+    SetAuctionSettlementAuthorizer,
+}
+
+/// Protocol fee for sellers whose trailing settled volume reaches `min_volume`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct FeeTier {
+    /// Trailing volume in `fee_tier_mint` base units
+    pub min_volume: u64,
+    /// Protocol fee in basis points for sellers in this tier
+    pub fee_bps: u16,
+}
+
+impl ProtocolState {
+    /// Governance in control of the protocol, once it has accepted authority
+    pub fn active_governance(&self) -> Option<GovernanceConfig> {
+        self.governance.filter(|config| config.governance == self.authority)
+    }
+
+    /// Fee of the highest tier `trailing_volume` qualifies for
+    pub fn tier_fee(&self, trailing_volume: u64) -> Option<u16> {
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|tier| trailing_volume >= tier.min_volume)
+            .map(|tier| tier.fee_bps)
+    }
+
+    /// Seconds after auction end before cleanup is allowed. Protocol
+    /// accounts created before the period was configurable read zero here
+    /// and keep the previous fixed default.
+    pub fn cleanup_grace_period(&self) -> i64 {
+        if self.cleanup_grace_period == 0 {
+            CLEANUP_GRACE_PERIOD
+        } else {
+            self.cleanup_grace_period
+        }
+    }
+
+    /// Signer expected on MPC callbacks
+    pub fn callback_signer(&self) -> Pubkey {
+        if self.callback_authority == Pubkey::default() {
+            self.authority
+        } else {
+            self.callback_authority
+        }
+    }
+
+    /// Signer expected on `authorize_settlement` for `auction`: its own
+    /// authorizer if one was assigned, otherwise the protocol's
+    pub fn settlement_signer(&self, auction: &AuctionAccount) -> Pubkey {
+        match auction.settlement_authorizer {
+            Some(authorizer) => authorizer,
+            None if self.settlement_authorizer == Pubkey::default() => self.authority,
+            None => self.settlement_authorizer,
+        }
+    }
+
+    /// Addresses of every batch created so far, oldest first
+    pub fn batch_addresses(&self) -> impl Iterator<Item = Pubkey> {
+        (0..self.next_batch_id).map(|batch_id| BatchSettlement::address(batch_id).0)
+    }
+}
+
+/// Protocol-wide counters, split across `PROTOCOL_STATS_SHARDS` PDAs so that
+/// bids and settlements on different auctions do not write the same account.
+/// Totals are the sum over all shards.
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolStats {
+    /// Shard index, see `ProtocolStats::shard_for`
+    pub shard: u8,
+    /// Auctions created
+    pub auctions_created: u64,
+    /// Bids and Dutch claims submitted
+    pub bids_submitted: u64,
+    /// Auctions settled
+    pub auctions_settled: u64,
+    /// Settled volume and fees, per payment mint
+    #[max_len(16)]
+    pub mint_volumes: Vec<MintVolume>,
+    /// Bump seed
+    pub bump: u8,
+    /// Start of the current circuit-breaker window
+    pub window_start: i64,
+    /// Watched volume settled in the current and previous windows
+    pub window_volume: [u64; 2],
+    /// Settlement failures in the current and previous windows
+    pub window_failures: [u32; 2],
+    /// When the breaker tripped; new settlements wait until it is cleared
+    pub soft_paused_at: Option<i64>,
+}
+
+impl ProtocolStats {
+    /// Stats shard an auction's activity is counted in
+    pub fn shard_for(auction_id: u64) -> [u8; 1] {
+        [(auction_id % PROTOCOL_STATS_SHARDS as u64) as u8]
+    }
+    
+    /// Record a settlement paid in `mint`. Mints beyond MAX_STATS_MINTS are
+    /// only counted in `auctions_settled`.
+    pub fn record_settlement(&mut self, mint: Pubkey, volume: u64, fees: u64) {
+        self.auctions_settled = self.auctions_settled.saturating_add(1);
+
+        let entry = match self.mint_volumes.iter().position(|v| v.mint == mint) {
+            Some(index) => &mut self.mint_volumes[index],
+            None if self.mint_volumes.len() < MAX_STATS_MINTS => {
+                self.mint_volumes.push(MintVolume { mint, total_volume: 0, total_fees: 0 });
+                self.mint_volumes.last_mut().unwrap()
+            }
+            None => return,
+        };
+        entry.total_volume = entry.total_volume.saturating_add(volume);
+        entry.total_fees = entry.total_fees.saturating_add(fees);
+    }
+
+    /// Count settled volume toward the circuit breaker. Returns whether this
+    /// tripped it.
+    pub fn record_breaker_volume(
+        &mut self,
+        config: &CircuitBreakerConfig,
+        mint: Pubkey,
+        volume: u64,
+        now: i64,
+    ) -> bool {
+        if config.window <= 0 || mint != config.volume_mint {
+            return false;
+        }
+        self.roll_window(config.window, now);
+        self.window_volume[0] = self.window_volume[0].saturating_add(volume);
+        self.trip_if_exceeded(config, now)
+    }
+
+    /// Count a failed settlement toward the circuit breaker. Returns whether
+    /// this tripped it.
+    pub fn record_breaker_failure(&mut self, config: &CircuitBreakerConfig, now: i64) -> bool {
+        if config.window <= 0 {
+            return false;
+        }
+        self.roll_window(config.window, now);
+        self.window_failures[0] = self.window_failures[0].saturating_add(1);
+        self.trip_if_exceeded(config, now)
+    }
+
+    /// Fail while this shard's circuit breaker is tripped
+    pub fn require_settlements_open(&self) -> Result<()> {
+        require!(self.soft_paused_at.is_none(), ShadowProtocolError::SettlementsSoftPaused);
+        Ok(())
+    }
+
+    /// Volume and failures over the trailing window, counting the previous
+    /// window in proportion to how much of it the trailing window overlaps
+    pub fn rolling_activity(&self, window: i64, now: i64) -> (u64, u32) {
+        let overlap = window.saturating_sub(now.saturating_sub(self.window_start)).clamp(0, window);
+        let carried = |previous: u64| (previous as u128 * overlap as u128 / window as u128) as u64;
+        (
+            self.window_volume[0].saturating_add(carried(self.window_volume[1])),
+            self.window_failures[0].saturating_add(carried(self.window_failures[1] as u64) as u32),
+        )
+    }
+
+    fn roll_window(&mut self, window: i64, now: i64) {
+        let elapsed = now.saturating_sub(self.window_start);
+        if elapsed < window {
+            return;
+        }
+        if elapsed < window.saturating_mul(2) {
+            self.window_volume = [0, self.window_volume[0]];
+            self.window_failures = [0, self.window_failures[0]];
+            self.window_start = self.window_start.saturating_add(window);
+        } else {
+            self.window_volume = [0, 0];
+            self.window_failures = [0, 0];
+            self.window_start = now;
+        }
+    }
+
+    fn trip_if_exceeded(&mut self, config: &CircuitBreakerConfig, now: i64) -> bool {
+        if self.soft_paused_at.is_some() {
+            return false;
+        }
+        let (volume, failures) = self.rolling_activity(config.window, now);
+        let exceeded = (config.max_volume > 0 && volume > config.max_volume)
+            || (config.max_failures > 0 && failures > config.max_failures);
+        if exceeded {
+            self.soft_paused_at = Some(now);
+        }
+        exceeded
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct MintVolume {
+    /// Payment mint
+    pub mint: Pubkey,
+    /// Cumulative settled volume
+    pub total_volume: u64,
+    /// Cumulative protocol fees collected
+    pub total_fees: u64,
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct AuctionAccount {
-    /// Unique auction identifier
+    /// Unique auction identifier, assigned sequentially at creation
     pub auction_id: u64,
+    /// Seed the auction's address is derived from, see `pda_seed`
+    pub address_seed: [u8; 32],
     /// Auction creator
     pub creator: Pubkey,
     /// Asset being auctioned
@@ -44,28 +463,46 @@ pub struct AuctionAccount {
     pub asset_vault: Pubkey,
     /// Asset amount being auctioned
     pub asset_amount: u64,
+    /// Mint the winning amount is paid in, fixed at creation
+    pub payment_mint: Pubkey,
+    /// Asset deposited into the vault and not yet paid out. Anything the
+    /// vault holds above this arrived by direct transfer and is surplus.
+    pub vault_balance: u64,
     /// Auction type
     pub auction_type: AuctionType,
     /// Auction status
     pub status: AuctionStatus,
-    /// Start time (Unix timestamp)
+    /// Start time (Unix timestamp, or slot in slot timing mode)
     pub start_time: i64,
-    /// End time (Unix timestamp)
+    /// End time (Unix timestamp, or slot in slot timing mode)
     pub end_time: i64,
+    /// Bidding closes this many units (seconds, or slots in slot timing mode)
+    /// before `end_time`, leaving room to queue settlement
+    pub bid_cutoff: u32,
     /// Minimum bid amount
     pub minimum_bid: u64,
+    /// Proxy bid increment (sealed-bid auctions); the winner pays this much above the runner-up
+    pub bid_increment: u64,
     /// Minimum price floor (for Dutch auctions)
     pub minimum_price_floor: u64,
     /// Encrypted reserve price (for privacy)
     pub reserve_price_encrypted: [u8; 32],
     /// Nonce for reserve price encryption
     pub reserve_price_nonce: u128,
+    /// Hiding commitment to the plaintext reserve, opened after settlement
+    pub reserve_price_commitment: [u8; 32],
+    /// Reserve price once revealed by the creator
+    pub revealed_reserve_price: Option<u64>,
     /// Current highest bid (for Dutch auctions)
     pub current_price: u64,
     /// Price decrease rate (for Dutch auctions, per slot)
     pub price_decrease_rate: u64,
+    /// Price decay curve (for Dutch auctions)
+    pub price_curve: PriceCurve,
     /// Number of bids received
-    pub bid_count: u64,
+    pub bid_count: u32,
+    /// Most bids the auction accepts, at most MAX_BIDS_PER_AUCTION
+    pub max_bids: u32,
     /// Winner of the auction (if settled)
     pub winner: Option<Pubkey>,
     /// Winning bid amount
@@ -74,20 +511,290 @@ pub struct AuctionAccount {
     pub settled_at: Option<i64>,
     /// MPC settlement verification hash
     pub mpc_verification_hash: Option<[u8; 32]>,
-    /// Whether settlement is authorized
-    pub settlement_authorized: bool,
     /// MPC computation ID
     pub mpc_computation_id: Option<[u8; 32]>,
+    /// Computation definition offset of the circuit the computation was queued on
+    pub mpc_comp_def_offset: Option<u32>,
     /// MXE cluster address for computation
     pub mxe_cluster: Option<Pubkey>,
-    /// Gas limit for MPC computation
-    pub computation_gas_limit: u64,
     /// When MPC computation was queued
     pub computation_queued_at: Option<i64>,
+    /// Pending ownership transfer recipient
+    pub pending_creator: Option<Pubkey>,
+    /// When the current settlement was authorized; starts the dispute window
+    pub settlement_authorized_at: Option<i64>,
+    /// Anti-spam deposit held in this account until settlement or cleanup
+    pub creation_deposit: u64,
+    /// Clock that start_time, end_time and Dutch decay are measured in
+    pub timing_mode: TimingMode,
+    /// Runner-up maximum reported by the settlement circuit
+    pub second_highest_bid: u64,
+    /// Bids the settlement circuit counted as participating
+    pub participating_bids: u32,
+    /// Encrypted winner awaiting `claim_win` (privacy mode only)
+    pub encrypted_winner: Option<PrivateWinner>,
+    /// Invite-only sales: bids must present a code hashing to this
+    pub invite_hash: Option<[u8; 32]>,
+    /// Compliance program in force when the auction was created
+    pub compliance_program: Option<Pubkey>,
+    /// Least payment-mint collateral a sealed bid locks, in basis points of
+    /// `minimum_bid`
+    pub min_collateral_ratio: u16,
+    /// Settlement ignores sealed bids whose collateral covers less than this
+    /// share of the bid, in basis points; zero counts every bid
+    pub bid_collateral_bps: u16,
+    /// Vaults currently deposited with the yield adapter
+    pub open_yield_positions: u16,
+    /// Auction house the auction was listed through, with its fee at listing
+    pub auction_house: Option<HouseTerms>,
+    /// Protocol fee granted by a fee voucher, in basis points
+    pub fee_override: Option<u16>,
+    /// Winning payment held in the settlement escrow until `claim_proceeds`
+    pub escrowed_proceeds: u64,
+    /// How the escrowed payment was paid out, once the creator has claimed
+    pub proceeds_claim: Option<ProceedsClaim>,
+    /// Overrides the protocol's settlement authorizer for this auction
+    pub settlement_authorizer: Option<Pubkey>,
+    /// Mint of the position token whose holder is paid at settlement
+    pub position_mint: Option<Pubkey>,
+    /// Settlements voided so far; keys the settlement computation ID
+    pub settlement_attempt: u16,
+    /// Boolean state packed as `AuctionAccount::*` bits
+    pub flags: u8,
+    /// Entries set in the auction's `AuctionFeatures`, as `AuctionFeatures::*` bits
+    pub features: u8,
     /// Bump seed
     pub bump: u8,
     /// Reserved space for future upgrades
-    pub reserved: [u8; 8],
+    pub reserved: [u8; 7],
+}
+
+impl AuctionAccount {
+    /// Address seed for a creator's `creator_nonce`. Only the creator can
+    /// claim it, so no one can front-run a listing onto the address a UI
+    /// intends to use, and the full digest leaves nothing to grind.
+    pub fn address_digest(creator: &Pubkey, creator_nonce: u64) -> [u8; 32] {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"shadow_auction_address");
+        data.extend_from_slice(creator.as_ref());
+        data.extend_from_slice(&creator_nonce.to_le_bytes());
+        anchor_lang::solana_program::hash::hash(&data).to_bytes()
+    }
+    
+    /// Seed following `AUCTION_SEED` in this auction's PDA: the creator digest,
+    /// or the id for auctions migrated from the legacy layout
+    pub fn pda_seed(&self) -> &[u8] {
+        if self.has_flag(AuctionAccount::LEGACY_ADDRESS) {
+            &self.address_seed[..8]
+        } else {
+            &self.address_seed
+        }
+    }
+    
+    /// A dispute is freezing settlement execution
+    pub const DISPUTE_OPEN: u8 = 1 << 0;
+    /// The clearing price met the encrypted reserve
+    pub const RESERVE_MET: u8 = 1 << 1;
+    /// Privacy mode: the circuit reveals the winner only in encrypted form
+    pub const PRIVATE_WINNER: u8 = 1 << 2;
+    /// Settlement found no sale (reserve unmet or no valid bids); awaiting `finalize_unsold`
+    pub const UNSOLD: u8 = 1 << 3;
+    /// A resumable settlement has begun; only `advance_settlement` may finish it
+    pub const SETTLING: u8 = 1 << 4;
+    /// Multi-unit sealed auction taking (price, quantity) bids, see `QuantityAuction`
+    pub const QUANTITY_BIDS: u8 = 1 << 5;
+    /// The deposited asset may only leave the vault through settlement or
+    /// cleanup. Set at creation and cleared on reaching a terminal status.
+    pub const VAULT_LOCKED: u8 = 1 << 6;
+    /// Migrated from the legacy layout, whose address is seeded by the id
+    pub const LEGACY_ADDRESS: u8 = 1 << 7;
+    
+    pub fn has_flag(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+    
+    pub fn set_flag(&mut self, flag: u8, on: bool) {
+        if on {
+            self.flags |= flag;
+        } else {
+            self.flags &= !flag;
+        }
+    }
+    
+    /// Whether the `AuctionFeatures` entry `feature` is set, without loading it
+    pub fn has_feature(&self, feature: u8) -> bool {
+        self.features & feature != 0
+    }
+    
+    /// Mirror which entries `features` has set; call after every write to it
+    pub fn sync_features(&mut self, features: &AuctionFeatures) {
+        self.features = features.bits();
+    }
+    
+    /// Deny paths outside settlement, cleanup and emergency withdrawal that
+    /// would move vault funds
+    pub fn require_vault_unlocked(&self) -> Result<()> {
+        require!(
+            !self.has_flag(AuctionAccount::VAULT_LOCKED),
+            ShadowProtocolError::VaultLocked
+        );
+        Ok(())
+    }
+    
+    /// Whether the settlement circuit's result has been accepted
+    pub fn settlement_authorized(&self) -> bool {
+        self.settlement_authorized_at.is_some()
+    }
+    
+    /// Check the vault still holds the recorded deposit and that the
+    /// deposit covers `amount`. Direct transfers into the vault never count.
+    pub fn require_vault_covers(&self, vault_amount: u64, amount: u64) -> Result<()> {
+        require!(
+            amount <= self.vault_balance && vault_amount >= self.vault_balance,
+            ShadowProtocolError::InvalidAssetAmount
+        );
+        Ok(())
+    }
+    
+    /// Fail unless `mint` is the mint the auction is paid in
+    pub fn require_payment_mint(&self, mint: Pubkey) -> Result<()> {
+        require_keys_eq!(mint, self.payment_mint, ShadowProtocolError::InvalidPaymentMint);
+        Ok(())
+    }
+    
+    /// Record `amount` of the deposit leaving the vault
+    pub fn record_vault_withdrawal(&mut self, amount: u64) -> Result<()> {
+        self.vault_balance = self.vault_balance
+            .checked_sub(amount)
+            .ok_or(ShadowProtocolError::InvalidAssetAmount)?;
+        Ok(())
+    }
+    
+    /// Vault holdings above the recorded deposit
+    pub fn vault_surplus(&self, vault_amount: u64) -> u64 {
+        vault_amount.saturating_sub(self.vault_balance)
+    }
+    
+    /// Protocol fee charged at settlement: the voucher rate, unless the
+    /// protocol fee has since dropped below it
+    pub fn protocol_fee_bps(&self, protocol_fee: u16) -> u16 {
+        self.fee_override.map_or(protocol_fee, |fee| fee.min(protocol_fee))
+    }
+
+    /// Current time in the auction's timing mode
+    pub fn now(&self, clock: &Clock) -> i64 {
+        self.timing_mode.now(clock)
+    }
+
+    /// Whether the bidding period is over
+    pub fn has_ended(&self, clock: &Clock) -> bool {
+        self.now(clock) >= self.end_time
+    }
+
+    /// Whether new bids are refused; from `bid_cutoff` before the end
+    pub fn bidding_closed(&self, clock: &Clock) -> bool {
+        self.now(clock) >= self.end_time.saturating_sub(self.bid_cutoff as i64)
+    }
+
+    /// Sealed-bid sales go through only if the circuit reported the reserve
+    /// as met; anything else is finalized as unsold instead
+    pub fn require_reserve_met(&self) -> Result<()> {
+        if self.auction_type == AuctionType::SealedBid {
+            require!(self.has_flag(Self::RESERVE_MET), ShadowProtocolError::ReserveNotMet);
+        }
+        Ok(())
+    }
+    
+    /// Least payment-mint collateral a sealed bid must lock
+    pub fn sealed_collateral_floor(&self) -> Result<u64> {
+        let floor = self.minimum_bid as u128 * self.min_collateral_ratio as u128 / 10_000;
+        u64::try_from(floor).map_err(|_| ShadowProtocolError::InvalidAssetAmount.into())
+    }
+    
+    /// Move to `to`, failing if the lifecycle does not allow it
+    pub fn transition(&mut self, to: AuctionStatus) -> Result<()> {
+        require!(
+            self.status.can_transition_to(to),
+            ShadowProtocolError::InvalidStatusTransition
+        );
+        self.status = to;
+        if to.is_terminal() {
+            self.set_flag(AuctionAccount::VAULT_LOCKED, false);
+        }
+        Ok(())
+    }
+}
+
+/// Optional configuration of one auction, created alongside it. Kept apart
+/// so the auction account every instruction loads carries only what all
+/// auctions use; `AuctionAccount::features` records which entries are set.
+#[account]
+#[derive(InitSpace)]
+pub struct AuctionFeatures {
+    /// Auction the features belong to
+    pub auction: Pubkey,
+    /// Price feed settlement is sanity-checked against, if any
+    pub price_oracle: Option<PriceOracle>,
+    /// Swap the creator's proceeds into another mint at settlement
+    pub payout_conversion: Option<PayoutConversion>,
+    /// Collateral held in its own mint; `None` means the payment mint
+    pub collateral_config: Option<CollateralConfig>,
+    /// Lend out escrowed funds while the auction runs
+    pub yield_config: Option<YieldConfig>,
+    /// Stream the creator's proceeds over this schedule instead of paying at once
+    pub payout_vesting: Option<VestingSchedule>,
+    /// Sale state of a liquidity bootstrapping auction
+    pub lbp: Option<LbpSale>,
+    /// Sale state of a Dutch auction sold one unit at a time
+    pub tranches: Option<DutchTranches>,
+    /// Program called once `execute_settlement` has moved the funds
+    pub settlement_hook: Option<SettlementHook>,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl AuctionFeatures {
+    pub const PRICE_ORACLE: u8 = 1 << 0;
+    pub const PAYOUT_CONVERSION: u8 = 1 << 1;
+    pub const COLLATERAL_MINT: u8 = 1 << 2;
+    pub const YIELD: u8 = 1 << 3;
+    pub const PAYOUT_VESTING: u8 = 1 << 4;
+    pub const LBP: u8 = 1 << 5;
+    pub const TRANCHES: u8 = 1 << 6;
+    pub const SETTLEMENT_HOOK: u8 = 1 << 7;
+    
+    /// Bits of the entries currently set
+    pub fn bits(&self) -> u8 {
+        [
+            (self.price_oracle.is_some(), Self::PRICE_ORACLE),
+            (self.payout_conversion.is_some(), Self::PAYOUT_CONVERSION),
+            (self.collateral_config.is_some(), Self::COLLATERAL_MINT),
+            (self.yield_config.is_some(), Self::YIELD),
+            (self.payout_vesting.is_some(), Self::PAYOUT_VESTING),
+            (self.lbp.is_some(), Self::LBP),
+            (self.tranches.is_some(), Self::TRANCHES),
+            (self.settlement_hook.is_some(), Self::SETTLEMENT_HOOK),
+        ]
+        .iter()
+        .filter(|(set, _)| *set)
+        .fold(0, |bits, (_, bit)| bits | bit)
+    }
+    
+    /// Check a bid's collateral deposit. Collateral in the payment mint must
+    /// cover `payment_floor`; collateral in a separate mint is held to the
+    /// auction's own minimum instead, the amounts not being comparable.
+    pub fn require_collateral(&self, mint: Pubkey, amount: u64, payment_floor: u64) -> Result<()> {
+        match &self.collateral_config {
+            Some(config) => {
+                require!(mint == config.mint, ShadowProtocolError::InvalidCollateralMint);
+                require!(amount >= config.min_amount, ShadowProtocolError::InsufficientCollateral);
+            }
+            None => {
+                require!(amount >= payment_floor, ShadowProtocolError::InsufficientCollateral);
+            }
+        }
+        Ok(())
+    }
 }
 
 #[account]
@@ -103,18 +810,26 @@ pub struct BidAccount {
     pub encryption_public_key: [u8; 32],
     /// Encryption nonce
     pub nonce: u128,
-    /// Bid timestamp
+    /// Bid timestamp (slot for Dutch claims on slot-timed auctions)
     pub timestamp: i64,
     /// Collateral amount deposited
     pub collateral_amount: u64,
-    /// Collateral token account
+    /// Collateral token account (the SOL escrow for native-SOL collateral)
     pub collateral_account: Pubkey,
+    /// Collateral is held as lamports in the bid's SOL escrow
+    pub sol_collateral: bool,
     /// Whether this bid won the auction
     pub is_winner: bool,
+    /// Cross-auction budget this bid is linked to
+    pub budget: Option<Pubkey>,
     /// Bump seed
     pub bump: u8,
+    /// Encrypted Dutch claim has been resolved by `dutch_bid_callback`
+    pub claim_resolved: bool,
+    /// Auction account the bid is on, which `auction_id` no longer derives
+    pub auction: Pubkey,
     /// Reserved space
-    pub reserved: [u8; 16],
+    pub reserved: [u8; 15],
 }
 
 #[account]
@@ -135,92 +850,1998 @@ pub struct BatchSettlement {
     pub settled_at: Option<i64>,
     /// Bump seed
     pub bump: u8,
+    /// MPC computation ID of the queued batch settlement
+    pub computation_id: Option<[u8; 32]>,
+    /// Per-auction results from the batch computation, in `auction_ids` order
+    #[max_len(10)]
+    pub results: Vec<BatchAuctionResult>,
+    /// Whether results have been written to the auctions
+    pub results_distributed: bool,
     /// Reserved space
-    pub reserved: [u8; 64],
+    pub reserved: [u8; 30],
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorIndex {
+    /// Creator whose auctions are indexed
+    pub creator: Pubkey,
+    /// Ring buffer of the creator's latest auction IDs; 0 marks an empty slot
+    pub auction_ids: [u64; CREATOR_INDEX_CAPACITY],
+    /// Slot the next auction ID is written to
+    pub next_slot: u16,
+    /// Auctions ever created by this creator
+    pub total_created: u64,
+    /// Settled volume in the fee tier mint per day, indexed by day number
+    pub volume_buckets: [u64; FEE_VOLUME_WINDOW_DAYS],
+    /// Day number (unix days) the buckets were last rolled to
+    pub volume_day: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl CreatorIndex {
+    /// PDA and bump of the index for `creator`
+    pub fn address(creator: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[CREATOR_INDEX_SEED, creator.as_ref()], &crate::ID)
+    }
+
+    /// Record a newly created auction, overwriting the oldest slot once full
+    pub fn push(&mut self, auction_id: u64) {
+        let slot = self.next_slot as usize % CREATOR_INDEX_CAPACITY;
+        self.auction_ids[slot] = auction_id;
+        self.next_slot = ((slot + 1) % CREATOR_INDEX_CAPACITY) as u16;
+        self.total_created = self.total_created.saturating_add(1);
+    }
+
+    /// Clear `auction_id` from the index if it is still present
+    pub fn remove(&mut self, auction_id: u64) {
+        if let Some(slot) = self.auction_ids.iter_mut().find(|id| **id == auction_id) {
+            *slot = 0;
+        }
+    }
+
+    /// Clear buckets for days that fell out of the window since the last roll
+    pub fn roll_volume(&mut self, now: i64) {
+        let day = now / SECONDS_PER_DAY;
+        let elapsed = (day - self.volume_day).clamp(0, FEE_VOLUME_WINDOW_DAYS as i64);
+        for offset in 1..=elapsed {
+            self.volume_buckets[((self.volume_day + offset) as usize) % FEE_VOLUME_WINDOW_DAYS] = 0;
+        }
+        self.volume_day = self.volume_day.max(day);
+    }
+
+    /// Settled volume over the trailing window, as of the last roll
+    pub fn trailing_volume(&self) -> u64 {
+        self.volume_buckets.iter().fold(0u64, |total, volume| total.saturating_add(*volume))
+    }
+
+    /// Add settled volume to today's bucket
+    pub fn record_volume(&mut self, now: i64, amount: u64) {
+        self.roll_volume(now);
+        let bucket = &mut self.volume_buckets[(self.volume_day as usize) % FEE_VOLUME_WINDOW_DAYS];
+        *bucket = bucket.saturating_add(amount);
+    }
+
+    /// Indexed auction IDs, newest first
+    pub fn latest(&self) -> impl Iterator<Item = u64> + '_ {
+        (1..=CREATOR_INDEX_CAPACITY)
+            .map(move |back| {
+                let slot = (self.next_slot as usize + CREATOR_INDEX_CAPACITY - back) % CREATOR_INDEX_CAPACITY;
+                self.auction_ids[slot]
+            })
+            .filter(|id| *id != 0)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SettlementDispute {
+    /// Disputed auction
+    pub auction_id: u64,
+    /// Account that opened the dispute and posted the bond
+    pub disputer: Pubkey,
+    /// Bond in lamports held by this account
+    pub bond: u64,
+    /// Hash of off-chain evidence supporting the dispute
+    pub evidence_hash: [u8; 32],
+    /// When the dispute was opened
+    pub opened_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Juror {
+    /// Juror wallet
+    pub juror: Pubkey,
+    /// Lamports staked, returned on deregistration
+    pub stake: u64,
+    /// Cases this juror has voted on
+    pub cases_voted: u64,
+    /// Voted cases not yet resolved; the stake is locked while any remain
+    pub open_cases: u16,
+    /// Registration timestamp
+    pub registered_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ArbitrationCase {
+    /// Auction whose settlement is contested
+    pub auction_id: u64,
+    /// Account that escalated the dispute
+    pub disputer: Pubkey,
+    /// Evidence hashes submitted by the parties
+    #[max_len(8)]
+    pub evidence: Vec<ArbitrationEvidence>,
+    /// Votes cast, in voting order
+    #[max_len(5)]
+    pub votes: Vec<JurorVote>,
+    /// Stake voting to void the settlement
+    pub votes_void: u64,
+    /// Stake voting to let the settlement stand
+    pub votes_uphold: u64,
+    /// Case status
+    pub status: ArbitrationStatus,
+    /// When the case was opened
+    pub opened_at: i64,
+    /// When voting closes
+    pub voting_ends_at: i64,
+    /// When the verdict was applied
+    pub resolved_at: Option<i64>,
+    /// Bump seed
+    pub bump: u8,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
-pub enum AuctionType {
-    SealedBid,
-    Dutch,
-    Batch,
+pub struct ArbitrationEvidence {
+    /// Party that submitted the evidence
+    pub submitter: Pubkey,
+    /// Hash of the off-chain evidence
+    pub evidence_hash: [u8; 32],
+    /// Submission timestamp
+    pub submitted_at: i64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
-pub enum AuctionStatus {
-    Created,
-    Active,
-    Ended,
-    Settled,
-    Cancelled,
+pub struct JurorVote {
+    /// Juror who voted
+    pub juror: Pubkey,
+    /// Juror's stake when voting, the weight of the vote
+    pub stake: u64,
+    /// Whether the juror voted to void the settlement
+    pub void_settlement: bool,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
-pub enum BatchStatus {
-    Created,
-    Settling,
-    Settled,
-    Failed,
+pub enum ArbitrationStatus {
+    Open,
+    Voided,
+    Upheld,
 }
 
-#[event]
-pub struct AuctionCreated {
-    pub auction_id: u64,
-    pub creator: Pubkey,
-    pub asset_mint: Pubkey,
-    pub auction_type: AuctionType,
-    pub start_time: i64,
-    pub end_time: i64,
-    pub minimum_bid: u64,
+#[account]
+#[derive(InitSpace)]
+pub struct ComputationRecord {
+    /// MPC computation being tracked
+    pub computation_id: [u8; 32],
+    /// Auction or batch the computation settles
+    pub subject: ComputationSubject,
+    /// MXE cluster the computation was queued on, if known
+    pub mxe_cluster: Option<Pubkey>,
+    /// When the computation was queued, if recorded by the subject
+    pub queued_at: Option<i64>,
+    /// When this record was created
+    pub tracked_at: i64,
+    /// Current status
+    pub status: ComputationStatus,
+    /// Hash of the delivered result once completed
+    pub result_hash: Option<[u8; 32]>,
+    /// When the computation completed or failed
+    pub resolved_at: Option<i64>,
+    /// Account that paid for this record
+    pub payer: Pubkey,
+    /// Bump seed
+    pub bump: u8,
 }
 
-#[event]
-pub struct BidSubmitted {
+/// Merkle commitment to the bids an auction fed to settlement.
+///
+/// Leaves are `CryptoUtils::hash_bid_leaf` of each bid, in ascending bidder
+/// order, in a tree of depth BID_SET_TREE_DEPTH padded with zero leaves.
+/// Only the tree's right edge is kept while bids are appended.
+#[account]
+#[derive(InitSpace)]
+pub struct BidSetSnapshot {
     pub auction_id: u64,
-    pub bidder: Pubkey,
-    pub timestamp: i64,
-    pub bid_count: u64,
+    /// Bids appended so far
+    pub leaf_count: u32,
+    /// Last bidder appended; the next must sort after it
+    pub last_bidder: Pubkey,
+    /// Left siblings along the path of the next leaf
+    pub branch: [[u8; 32]; BID_SET_TREE_DEPTH],
+    /// Root over every accepted bid, set once all have been appended
+    pub root: Option<[u8; 32]>,
+    pub finalized_at: Option<i64>,
+    /// Bump seed
+    pub bump: u8,
 }
 
-#[event]
-pub struct AuctionSettled {
+impl BidSetSnapshot {
+    pub fn append(&mut self, leaf: [u8; 32]) {
+        CryptoUtils::merkle_frontier_append(&mut self.branch, self.leaf_count as u64, leaf);
+        self.leaf_count += 1;
+    }
+    
+    pub fn compute_root(&self) -> [u8; 32] {
+        CryptoUtils::merkle_frontier_root(&self.branch, self.leaf_count as u64)
+    }
+    
+    /// Whether `leaf` sits at `index` under the finalized root
+    pub fn verify_inclusion(&self, leaf: [u8; 32], index: u32, proof: &[[u8; 32]; BID_SET_TREE_DEPTH]) -> bool {
+        let Some(root) = self.root else {
+            return false;
+        };
+        index < self.leaf_count && CryptoUtils::merkle_path_root(leaf, index as u64, proof) == root
+    }
+}
+
+/// Merkle tree holding the hashes of compressed accounts.
+///
+/// Compressing an account appends `CryptoUtils::hash_compressed_account` of
+/// its address and data, logs the data in `AccountCompressed` for indexers,
+/// and closes the account. Decompressing proves the leaf against `root` and
+/// zeroes it, so each compressed copy can be restored only once.
+#[account]
+#[derive(InitSpace)]
+pub struct CompressedStateTree {
+    /// Leaves ever appended, including zeroed ones
+    pub leaf_count: u64,
+    /// Right edge of the tree, see `CryptoUtils::merkle_frontier_append`
+    pub branch: [[u8; 32]; COMPRESSED_TREE_DEPTH],
+    /// Current root
+    pub root: [u8; 32],
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl CompressedStateTree {
+    /// Append a leaf and return its index
+    pub fn append(&mut self, leaf: [u8; 32]) -> Result<u64> {
+        require!(
+            self.leaf_count < 1u64 << COMPRESSED_TREE_DEPTH,
+            ShadowProtocolError::CompressedTreeFull
+        );
+        let index = self.leaf_count;
+        CryptoUtils::merkle_frontier_append(&mut self.branch, index, leaf);
+        self.leaf_count += 1;
+        self.root = CryptoUtils::merkle_frontier_root(&self.branch, self.leaf_count);
+        Ok(index)
+    }
+    
+    /// Zero the leaf at `index` after proving it holds `leaf` under the
+    /// current root. Proofs are against the latest root, so one made before
+    /// another compression or decompression has to be refreshed.
+    pub fn nullify(&mut self, leaf: [u8; 32], index: u64, proof: &[[u8; 32]]) -> Result<()> {
+        require!(
+            index < self.leaf_count
+                && proof.len() == COMPRESSED_TREE_DEPTH
+                && CryptoUtils::merkle_path_root(leaf, index, proof) == self.root,
+            ShadowProtocolError::InvalidInclusionProof
+        );
+        
+        // Complete left subtrees on the right edge contain the leaf too
+        let mut node = [0u8; 32];
+        for (height, sibling) in proof.iter().enumerate() {
+            let edge = self.leaf_count >> height;
+            if edge & 1 == 1 && index >> height == edge - 1 {
+                self.branch[height] = node;
+            }
+            node = if (index >> height) & 1 == 0 {
+                CryptoUtils::hash_merkle_node(&node, sibling)
+            } else {
+                CryptoUtils::hash_merkle_node(sibling, &node)
+            };
+        }
+        self.root = node;
+        Ok(())
+    }
+}
+
+/// Marks a computation whose result has been applied, so a relayer retrying
+/// the callback cannot apply it again
+#[account]
+#[derive(InitSpace)]
+pub struct ConsumedComputation {
+    /// Computation whose result was applied
+    pub computation_id: [u8; 32],
+    /// Hash of the result bytes that were applied
+    pub result_hash: [u8; 32],
+    /// When the result was applied; zero until then
+    pub consumed_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AnonymousListing {
+    /// Auction this listing stands in as creator for
     pub auction_id: u64,
-    pub winner: Option<Pubkey>,
-    pub winning_amount: u64,
-    pub settled_at: i64,
+    /// Seller's claim ticket, see `CryptoUtils::derive_claim_ticket`
+    pub ticket_hash: [u8; 32],
+    /// When the listing took over the auction
+    pub created_at: i64,
+    /// Bump seed
+    pub bump: u8,
 }
 
-#[event]
-pub struct BatchSettlementCreated {
-    pub batch_id: u64,
+/// Pyth pull-oracle feed pricing one whole unit of the auctioned asset
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct PriceOracle {
+    /// Pyth feed id (not the price update account address)
+    pub feed_id: [u8; 32],
+    /// Oldest publish time accepted at settlement, in seconds
+    pub max_staleness: u32,
+    /// Decimals of the asset mint
+    pub asset_decimals: u8,
+    /// Decimals of the mint the winner pays in
+    pub payment_decimals: u8,
+}
+
+/// Breakdown of a `claim_proceeds` payout, in the payment mint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct ProceedsClaim {
+    /// Paid to the creator
+    pub amount: u64,
+    /// Protocol fee, including the insurance and staking slices
+    pub protocol_fee: u64,
+    /// Slice of the protocol fee sent to the insurance fund
+    pub insurance_fee: u64,
+    /// Slice of the protocol fee sent to stakers
+    pub staking_fee: u64,
+    /// Auction house fee
+    pub house_fee: u64,
+    pub claimed_at: i64,
+}
+
+/// Collateral denominated in a mint other than the payment mint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct CollateralConfig {
+    /// Mint every bid's collateral must be in
+    pub mint: Pubkey,
+    /// Least collateral a bid must lock, in `mint` base units
+    pub min_amount: u64,
+}
+
+/// Reduced protocol fee pre-approved by the protocol authority. Presented at
+/// creation alongside an ed25519 signature over `FeeVoucher::message`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct FeeVoucher {
+    /// Creator the voucher was issued to
     pub creator: Pubkey,
-    pub auction_count: u64,
+    /// Creator nonce of the listing the voucher is for; `None` covers any
+    /// auction by the creator
+    pub creator_nonce: Option<u64>,
+    /// Protocol fee to charge instead, in basis points
+    pub fee_bps: u16,
+    /// Voucher cannot be redeemed at or after this time
+    pub expires_at: i64,
 }
 
-#[event]
-pub struct BatchSettled {
-    pub batch_id: u64,
-    pub settled_count: u64,
-    pub settled_at: i64,
+impl FeeVoucher {
+    /// Bytes the protocol authority signs
+    pub fn message(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"shadow_fee_voucher");
+        data.extend_from_slice(self.creator.as_ref());
+        data.extend_from_slice(&self.creator_nonce.unwrap_or(u64::MAX).to_le_bytes());
+        data.extend_from_slice(&self.fee_bps.to_le_bytes());
+        data.extend_from_slice(&self.expires_at.to_le_bytes());
+        data
+    }
 }
 
-pub const PROTOCOL_SEED: &[u8] = b"protocol";
-pub const AUCTION_SEED: &[u8] = b"auction";
-pub const BID_SEED: &[u8] = b"bid";
-pub const ASSET_VAULT_SEED: &[u8] = b"asset_vault";
-pub const BATCH_SEED: &[u8] = b"batch";
+/// Sealed bid signed off-chain by the bidder and submitted by a relayer.
+/// Presented alongside an ed25519 signature over `RelayedBid::message`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct RelayedBid {
+    /// Auction the bid is for
+    pub auction_id: u64,
+    /// Encrypted bid amount
+    pub bid_amount_encrypted: [u8; 32],
+    /// Bidder's encryption public key
+    pub public_key: [u8; 32],
+    /// Encryption nonce
+    pub nonce: u128,
+    /// Collateral the relayer may move from the bidder's account
+    pub collateral_amount: u64,
+    /// Bid cannot be relayed at or after this time
+    pub expires_at: i64,
+}
 
-// Maximum auction duration (30 days)
-pub const MAX_AUCTION_DURATION: i64 = 30 * 24 * 60 * 60;
+impl RelayedBid {
+    /// Bytes the bidder signs
+    pub fn message(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"shadow_relayed_bid");
+        data.extend_from_slice(&self.auction_id.to_le_bytes());
+        data.extend_from_slice(&self.bid_amount_encrypted);
+        data.extend_from_slice(&self.public_key);
+        data.extend_from_slice(&self.nonce.to_le_bytes());
+        data.extend_from_slice(&self.collateral_amount.to_le_bytes());
+        data.extend_from_slice(&self.expires_at.to_le_bytes());
+        data
+    }
+}
 
-// Maximum protocol fee (5%)
-pub const MAX_PROTOCOL_FEE: u16 = 500;
+/// Parameters of a liquidity bootstrapping sale chosen at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct LbpConfig {
+    /// Mint buyers pay in
+    pub payment_mint: Pubkey,
+    /// Lot price increase once the whole lot has sold
+    pub price_impact: u64,
+}
 
-// Maximum bids per auction for gas optimization
-pub const MAX_BIDS_PER_AUCTION: usize = 1000;
+/// Parameters of a Dutch auction sold in equal units, chosen at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct TrancheConfig {
+    /// Mint buyers pay in
+    pub payment_mint: Pubkey,
+    /// Units the asset is split into; must divide the asset amount
+    pub units: u32,
+}
 
-// Authority transfer timelock (7 days in seconds)
-pub const AUTHORITY_TRANSFER_TIMELOCK: i64 = 7 * 24 * 60 * 60;
+/// Dutch auction sold in units: each purchase takes one unit at the price
+/// on the curve when it lands, until the units or the duration run out.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct DutchTranches {
+    pub payment_mint: Pubkey,
+    /// Asset base units in each unit sold
+    pub unit_amount: u64,
+    pub units_total: u32,
+    pub units_remaining: u32,
+    /// Paid across all units, before fees
+    pub proceeds: u64,
+}
+
+/// Liquidity bootstrapping sale: the lot price decays along the auction's
+/// Dutch curve while every purchase pushes it back up by its share of
+/// `price_impact`. Purchases are filled one at a time, in submission order.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct LbpSale {
+    pub payment_mint: Pubkey,
+    pub price_impact: u64,
+    /// Asset base units sold so far
+    pub tokens_sold: u64,
+    pub purchases_submitted: u64,
+    pub purchases_processed: u64,
+}
+
+impl LbpSale {
+    /// Cost of `quantity` units of an `asset_amount` lot currently priced at
+    /// `lot_price`. Must stay in sync with the `process_lbp_purchase` circuit.
+    pub fn fill_cost(&self, lot_price: u64, asset_amount: u64, quantity: u64) -> Option<u64> {
+        let asset_amount = asset_amount as u128;
+        let impact = (self.price_impact as u128)
+            .checked_mul(2 * self.tokens_sold as u128 + quantity as u128)?;
+        let numerator = (2 * asset_amount)
+            .checked_mul(lot_price as u128)?
+            .checked_add(impact)?
+            .checked_mul(quantity as u128)?;
+        let cost = numerator / asset_amount.checked_mul(2 * asset_amount)?;
+        u64::try_from(cost).ok()
+    }
+}
 
-// Minimum price floor for Dutch auctions (basis points, e.g., 100 = 1% of starting price)
-pub const MINIMUM_PRICE_FLOOR_BPS: u16 = 100;
\ No newline at end of file
+/// Linear vesting with a cliff, both in seconds from settlement
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct VestingSchedule {
+    /// Nothing is claimable before this many seconds have passed
+    pub cliff: i64,
+    /// Everything is claimable once this many seconds have passed
+    pub duration: i64,
+}
+
+impl VestingSchedule {
+    /// Portion of `total` vested `elapsed` seconds after the start
+    pub fn vested(&self, total: u64, elapsed: i64) -> u64 {
+        if elapsed < self.cliff {
+            0
+        } else if elapsed >= self.duration {
+            total
+        } else {
+            (total as u128 * elapsed as u128 / self.duration as u128) as u64
+        }
+    }
+}
+
+/// Who keeps the interest earned on an auction's escrowed funds
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum YieldBeneficiary {
+    Creator,
+    Protocol,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct YieldConfig {
+    /// Adapter in force when the creator opted in
+    pub adapter: Pubkey,
+    pub beneficiary: YieldBeneficiary,
+}
+
+/// How a creator's proceeds are swapped before they are paid out
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct PayoutConversion {
+    /// Swap program invoked at settlement (e.g. Jupiter)
+    pub swap_program: Pubkey,
+    /// Mint the creator receives
+    pub output_mint: Pubkey,
+    /// Minimum output base units per `CONVERSION_RATE_SCALE` input base units
+    pub min_output_rate: u64,
+}
+
+impl PayoutConversion {
+    /// Least output the swap must produce for `input_amount`
+    pub fn min_output(&self, input_amount: u64) -> Option<u64> {
+        let min_output = (input_amount as u128)
+            .checked_mul(self.min_output_rate as u128)?
+            / CONVERSION_RATE_SCALE as u128;
+        u64::try_from(min_output).ok()
+    }
+}
+
+/// Program a creator has settlement report to, and how many of the
+/// settlement's remaining accounts it takes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct SettlementHook {
+    pub program: Pubkey,
+    pub account_count: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LbpPurchase {
+    pub auction_id: u64,
+    /// Position in the sale's fill order
+    pub index: u64,
+    pub buyer: Pubkey,
+    /// Encrypted quantity wanted, in asset base units
+    pub quantity_encrypted: [u8; 32],
+    /// Encrypted highest average lot price accepted
+    pub max_price_encrypted: [u8; 32],
+    pub encryption_public_key: [u8; 32],
+    pub nonce: u128,
+    /// Payment escrowed, the most the purchase can cost
+    pub escrow_amount: u64,
+    /// Receives the purchased asset
+    pub buyer_asset_account: Pubkey,
+    /// Receives unspent escrow
+    pub refund_account: Pubkey,
+    /// In the auction's timing mode
+    pub submitted_at: i64,
+    pub processed: bool,
+    /// Bump seed
+    pub bump: u8,
+}
+
+/// Units one buyer has taken from a tranched Dutch auction
+#[account]
+#[derive(InitSpace)]
+pub struct TrancheClaim {
+    pub auction_id: u64,
+    pub buyer: Pubkey,
+    pub units: u32,
+    /// Paid across the buyer's units
+    pub total_paid: u64,
+    /// Price of the buyer's latest unit
+    pub last_price: u64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+/// Clearing state of a multi-unit sealed auction. The whole lot is the
+/// supply; `clear_uniform_price_auction` sets one price for every unit and
+/// an allocation per bid, which are then distributed one bid at a time.
+#[account]
+#[derive(InitSpace)]
+pub struct QuantityAuction {
+    pub auction_id: u64,
+    /// Mint bids are escrowed and paid in
+    pub payment_mint: Pubkey,
+    pub computation_id: Option<[u8; 32]>,
+    /// Payment units per asset base unit, zero until cleared
+    pub clearing_price: u64,
+    /// Asset base units allocated across all bids
+    pub units_sold: u64,
+    /// Asset base units per bid, in bid order
+    #[max_len(MAX_QUANTITY_BIDS)]
+    pub allocations: Vec<u64>,
+    pub bids_distributed: u32,
+    /// Protocol fees taken from the bids distributed so far
+    pub fees_collected: u64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct QuantityBid {
+    pub auction_id: u64,
+    /// Position among the auction's bids, and in the circuit's input
+    pub index: u32,
+    pub bidder: Pubkey,
+    /// Encrypted price per asset base unit
+    pub price_encrypted: [u8; 32],
+    /// Encrypted quantity wanted, in asset base units
+    pub quantity_encrypted: [u8; 32],
+    pub encryption_public_key: [u8; 32],
+    pub nonce: u128,
+    /// Payment escrowed; the circuit ignores bids it does not cover
+    pub escrow_amount: u64,
+    /// Receives the allocated asset
+    pub bidder_asset_account: Pubkey,
+    /// Receives unspent escrow
+    pub refund_account: Pubkey,
+    pub submitted_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PayoutStream {
+    /// Auction whose proceeds are streamed
+    pub auction_id: u64,
+    /// Proceeds recipient at settlement (the position holder when the
+    /// auction has one), the only one who can claim
+    pub recipient: Pubkey,
+    /// Token account owned by this stream holding the unvested proceeds
+    pub vault: Pubkey,
+    /// Proceeds paid into the stream
+    pub total: u64,
+    /// Proceeds claimed so far
+    pub claimed: u64,
+    /// Settlement time the schedule counts from
+    pub start: i64,
+    pub schedule: VestingSchedule,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl PayoutStream {
+    /// Start streaming `total` to `recipient` under `schedule` from `start`
+    pub fn open(
+        &mut self,
+        auction_id: u64,
+        recipient: Pubkey,
+        vault: Pubkey,
+        total: u64,
+        start: i64,
+        schedule: VestingSchedule,
+    ) {
+        self.auction_id = auction_id;
+        self.recipient = recipient;
+        self.vault = vault;
+        self.total = total;
+        self.claimed = 0;
+        self.start = start;
+        self.schedule = schedule;
+    }
+}
+
+/// A settlement split across transactions by `begin_settlement` and
+/// `advance_settlement`. The amounts are fixed when it begins; each step
+/// moves its funds once and sets its bit in `completed_steps`.
+#[account]
+#[derive(InitSpace)]
+pub struct SettlementProgress {
+    pub auction_id: u64,
+    /// Winner paying for the settlement
+    pub winner: Pubkey,
+    /// Mint every payment step is made in
+    pub payment_mint: Pubkey,
+    pub winning_amount: u64,
+    /// Creator's share, after fees
+    pub creator_amount: u64,
+    /// Whole protocol fee, before the insurance and staking slices
+    pub fee_amount: u64,
+    /// Part of the fee paid to the protocol fee account
+    pub protocol_fee_amount: u64,
+    pub house_fee_amount: u64,
+    pub insurance_amount: u64,
+    pub staking_amount: u64,
+    /// Accounts the house, insurance and staking steps must pay into
+    pub house_fee_account: Option<Pubkey>,
+    pub insurance_vault: Option<Pubkey>,
+    pub staking_reward_vault: Option<Pubkey>,
+    /// Bitmask of `SettlementProgress::*` steps already done
+    pub completed_steps: u8,
+    pub started_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+/// Protocol fees paid to the fee recipient in one payment mint, so treasury
+/// balances reconcile against chain state instead of replayed events.
+/// Insurance and staking slices are not counted; they never reach the treasury.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeLedger {
+    pub mint: Pubkey,
+    /// Fees paid to the fee recipient over all time
+    pub accrued: u64,
+    /// Fees the treasury has recorded moving out of the fee recipient
+    pub withdrawn: u64,
+    /// Sales that paid a fee into this ledger
+    pub fee_count: u64,
+    pub last_accrued_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl FeeLedger {
+    /// Set up a ledger created by `init_if_needed`; a no-op once set up
+    pub fn open(&mut self, mint: Pubkey, bump: u8) {
+        if self.mint == Pubkey::default() {
+            self.mint = mint;
+            self.bump = bump;
+        }
+    }
+    
+    pub fn accrue(&mut self, amount: u64, now: i64) {
+        if amount == 0 {
+            return;
+        }
+        self.accrued = self.accrued.saturating_add(amount);
+        self.fee_count = self.fee_count.saturating_add(1);
+        self.last_accrued_at = now;
+    }
+    
+    /// Fees accrued but not yet withdrawn
+    pub fn outstanding(&self) -> u64 {
+        self.accrued.saturating_sub(self.withdrawn)
+    }
+}
+
+impl SettlementProgress {
+    /// Asset moved from the vault to the winner
+    pub const ASSET: u8 = 1 << 0;
+    /// Creator's share paid
+    pub const PAYMENT: u8 = 1 << 1;
+    pub const PROTOCOL_FEE: u8 = 1 << 2;
+    pub const HOUSE_FEE: u8 = 1 << 3;
+    pub const INSURANCE: u8 = 1 << 4;
+    pub const STAKING: u8 = 1 << 5;
+    pub const ALL_STEPS: u8 = (1 << 6) - 1;
+    
+    pub fn is_done(&self, step: u8) -> bool {
+        self.completed_steps & step != 0
+    }
+    
+    pub fn is_complete(&self) -> bool {
+        self.completed_steps == Self::ALL_STEPS
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct YieldPosition {
+    /// Auction the deposited funds belong to
+    pub auction_id: u64,
+    /// Vault the funds were taken from and are returned to
+    pub source_vault: Pubkey,
+    /// Adapter-facing token account owned by the yield authority
+    pub yield_vault: Pubkey,
+    /// Amount deposited, returned to the source vault on withdrawal
+    pub principal: u64,
+    /// When the funds were deposited
+    pub deposited_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct DenyListEntry {
+    /// Wallet the flags apply to
+    pub wallet: Pubkey,
+    /// Bitmask of `DenyListEntry::BIDDING` / `DenyListEntry::SETTLEMENT`
+    pub flags: u8,
+    /// Last time the authority changed the flags
+    pub updated_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl DenyListEntry {
+    /// Wallet may not place bids
+    pub const BIDDING: u8 = 1 << 0;
+    /// Wallet may not take part in settlement, as winner or seller
+    pub const SETTLEMENT: u8 = 1 << 1;
+    pub const ALL: u8 = Self::BIDDING | Self::SETTLEMENT;
+    
+    pub fn denies(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+}
+
+/// Gas a computation on one circuit needs, keyed by its comp-def offset
+#[account]
+#[derive(InitSpace)]
+pub struct GasSchedule {
+    /// Computation definition offset of the circuit
+    pub comp_def_offset: u32,
+    /// Gas every invocation needs regardless of input size
+    pub base_gas: u64,
+    /// Additional gas per bid (or per chunk, for the final round)
+    pub per_input_gas: u64,
+    /// Last time the authority changed the schedule
+    pub updated_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl GasSchedule {
+    /// Gas limit a computation over `inputs` needs; None if it overflows
+    pub fn required_gas(&self, inputs: u32) -> Option<u64> {
+        self.per_input_gas
+            .checked_mul(inputs as u64)?
+            .checked_add(self.base_gas)
+    }
+}
+
+/// MXE clusters computations may be queued on, in failover order
+#[account]
+#[derive(InitSpace)]
+pub struct ClusterRegistry {
+    #[max_len(MAX_MXE_CLUSTERS)]
+    pub clusters: Vec<MxeClusterEntry>,
+    /// Seconds a queued computation may go unanswered before it can be retried
+    pub retry_timeout: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct MxeClusterEntry {
+    pub cluster: Pubkey,
+    /// Cleared by the authority to take the cluster out of rotation
+    pub healthy: bool,
+}
+
+impl ClusterRegistry {
+    /// Fail unless `cluster` is approved and currently healthy
+    pub fn require_healthy(&self, cluster: Pubkey) -> Result<()> {
+        let entry = self
+            .clusters
+            .iter()
+            .find(|entry| entry.cluster == cluster)
+            .ok_or(ShadowProtocolError::ClusterNotApproved)?;
+        require!(entry.healthy, ShadowProtocolError::ClusterUnhealthy);
+        Ok(())
+    }
+
+    /// First healthy cluster after `current` in failover order, wrapping
+    /// around; never `current` itself
+    pub fn next_healthy(&self, current: Option<Pubkey>) -> Option<Pubkey> {
+        let start = current
+            .and_then(|cluster| self.clusters.iter().position(|entry| entry.cluster == cluster))
+            .map_or(0, |index| index + 1);
+        (0..self.clusters.len())
+            .map(|offset| self.clusters[(start + offset) % self.clusters.len()])
+            .find(|entry| entry.healthy && Some(entry.cluster) != current)
+            .map(|entry| entry.cluster)
+    }
+}
+
+/// A third-party platform's branded instance of the protocol.
+///
+/// Auctions listed through a house settle through the same vaults and MPC
+/// computations as any other, with the house's fee taken from the creator's
+/// proceeds and its own allowlist and pause switch in front of listing and
+/// bidding.
+#[account]
+#[derive(InitSpace)]
+pub struct AuctionHouse {
+    /// Platform operating the house
+    pub authority: Pubkey,
+    /// Display name, UTF-8, zero padded
+    pub name: [u8; 32],
+    /// House fee on settled proceeds in basis points, on top of the protocol fee
+    pub fee_bps: u16,
+    /// Wallet whose token accounts receive the house fee
+    pub fee_recipient: Pubkey,
+    /// Only allowlisted creators may list through the house
+    pub creator_allowlist: bool,
+    /// Stops new listings and bids on the house's auctions
+    pub paused: bool,
+    /// Auctions listed through the house
+    pub auctions_created: u64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+/// Display metadata for an auction, in the spirit of Metaplex token
+/// metadata, so marketplaces can render a listing from the PDA alone
+#[account]
+#[derive(InitSpace)]
+pub struct AuctionMetadata {
+    pub auction_id: u64,
+    /// Creator of the auction, receives the rent on close
+    pub creator: Pubkey,
+    #[max_len(32)]
+    pub name: String,
+    #[max_len(10)]
+    pub symbol: String,
+    /// Off-chain JSON describing the listing
+    #[max_len(200)]
+    pub uri: String,
+    /// SHA-256 of the JSON at `uri`, for integrity checks
+    pub content_hash: [u8; 32],
+    pub bump: u8,
+}
+
+/// Allowlist entry letting `creator` list through `house`
+#[account]
+#[derive(InitSpace)]
+pub struct HouseCreator {
+    pub house: Pubkey,
+    pub creator: Pubkey,
+    pub added_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct HouseTerms {
+    /// Auction house the auction belongs to
+    pub house: Pubkey,
+    /// House fee fixed when the auction was listed
+    pub fee_bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ComputationSubject {
+    Auction { auction_id: u64 },
+    Batch { batch_id: u64 },
+}
+
+impl ComputationSubject {
+    /// Stats shard the computation's outcome is counted in
+    pub fn stats_shard(&self) -> [u8; 1] {
+        match self {
+            ComputationSubject::Auction { auction_id } => ProtocolStats::shard_for(*auction_id),
+            ComputationSubject::Batch { batch_id } => ProtocolStats::shard_for(*batch_id),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ComputationStatus {
+    Queued,
+    Completed,
+    Failed,
+}
+
+/// Authority recovery of assets from an auction's vault, announced ahead of
+/// time and executable after EMERGENCY_WITHDRAW_DELAY. Kept once executed so
+/// every use stays auditable on-chain.
+#[account]
+#[derive(InitSpace)]
+pub struct EmergencyWithdrawal {
+    pub auction_id: u64,
+    /// Authority that announced the withdrawal
+    pub authority: Pubkey,
+    /// Token account receiving the recovered assets
+    pub recipient: Pubkey,
+    pub amount: u64,
+    /// Off-chain incident reference
+    pub reason: [u8; 32],
+    pub announced_at: i64,
+    pub executable_at: i64,
+    pub executed_at: Option<i64>,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct InsuranceFund {
+    /// Payment mint the fund holds
+    pub mint: Pubkey,
+    /// Token vault owned by this account
+    pub vault: Pubkey,
+    /// Fees accrued from settlements
+    pub total_accrued: u64,
+    /// Total paid out in claims
+    pub total_paid: u64,
+    /// Number of claims paid
+    pub claims_paid: u64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+/// Pool paying a share of protocol fees, in `reward_mint`, to holders of
+/// `stake_mint` who lock it in a `StakePosition`
+#[account]
+#[derive(InitSpace)]
+pub struct StakingPool {
+    /// Mint stakers lock
+    pub stake_mint: Pubkey,
+    /// Payment mint whose fees are shared
+    pub reward_mint: Pubkey,
+    /// Vault holding staked tokens
+    pub stake_vault: Pubkey,
+    /// Vault holding undistributed and unclaimed rewards
+    pub reward_vault: Pubkey,
+    /// Share of the protocol fee routed to stakers, in basis points of what
+    /// is left of the fee after the insurance share
+    pub fee_share: u16,
+    /// Tokens currently staked
+    pub total_staked: u64,
+    /// Rewards per staked token, scaled by REWARD_PER_SHARE_SCALE
+    pub reward_per_share: u128,
+    /// Rewards accrued to stakers so far
+    pub total_distributed: u64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl StakingPool {
+    /// Spread `amount` of new rewards over the current stake
+    pub fn accrue(&mut self, amount: u64) {
+        if self.total_staked == 0 {
+            return;
+        }
+        self.reward_per_share += amount as u128 * REWARD_PER_SHARE_SCALE / self.total_staked as u128;
+        self.total_distributed = self.total_distributed.saturating_add(amount);
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakePosition {
+    /// Staker
+    pub owner: Pubkey,
+    /// Tokens staked
+    pub amount: u64,
+    /// `amount * reward_per_share` already accounted for
+    pub reward_debt: u128,
+    /// Rewards accounted for but not yet claimed
+    pub pending_rewards: u64,
+    /// Last time stake was added; unstaking unlocks STAKE_LOCK_PERIOD later
+    pub staked_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl StakePosition {
+    /// Move rewards accrued since the last update into `pending_rewards`
+    pub fn settle(&mut self, pool: &StakingPool) {
+        let accrued = self.amount as u128 * pool.reward_per_share / REWARD_PER_SHARE_SCALE;
+        self.pending_rewards = self.pending_rewards
+            .saturating_add(accrued.saturating_sub(self.reward_debt) as u64);
+        self.reward_debt = accrued;
+    }
+
+    /// Set the staked amount after `settle`, keeping the debt in step
+    pub fn set_amount(&mut self, amount: u64, pool: &StakingPool) {
+        self.amount = amount;
+        self.reward_debt = amount as u128 * pool.reward_per_share / REWARD_PER_SHARE_SCALE;
+    }
+}
+
+impl BatchSettlement {
+    /// PDA and bump of the batch with `batch_id`
+    pub fn address(batch_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[BATCH_SEED, batch_id.to_le_bytes().as_ref()], &crate::ID)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct BatchAuctionResult {
+    /// Auction the result applies to
+    pub auction_id: u64,
+    /// Winning bidder
+    pub winner: Pubkey,
+    /// Winning bid amount
+    pub winning_amount: u64,
+    /// Whether the winning bid met the reserve price
+    pub met_reserve: bool,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SettlementRounds {
+    /// Auction being settled
+    pub auction_id: u64,
+    /// Number of chunk computations in the first round
+    pub chunk_count: u32,
+    /// Number of chunk results received so far
+    pub chunks_completed: u32,
+    /// `AuctionAccount::settlement_attempt` these rounds belong to
+    pub settlement_attempt: u16,
+    /// Encrypted per-chunk winners, fed into the final round
+    #[max_len(16)]
+    pub chunk_results: Vec<ChunkResult>,
+    /// Round progress
+    pub status: SettlementRoundStatus,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct BidBudget {
+    /// Bidder committing the budget
+    pub bidder: Pubkey,
+    /// Bidder-chosen budget identifier
+    pub budget_id: u64,
+    /// Encrypted total budget
+    pub budget_encrypted: [u8; 32],
+    /// Nonce for budget encryption
+    pub budget_nonce: u128,
+    /// Public key for encryption
+    pub encryption_public_key: [u8; 32],
+    /// Auctions whose bids share this budget, in allocation priority order
+    #[max_len(8)]
+    pub linked_auctions: Vec<u64>,
+    /// Linked auctions the MPC allocation kept within budget
+    #[max_len(8)]
+    pub allocated_auctions: Vec<u64>,
+    /// Allocation progress
+    pub status: BudgetStatus,
+    /// MPC computation ID of the queued allocation
+    pub computation_id: Option<[u8; 32]>,
+    /// Creation timestamp
+    pub created_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct DoubleAuctionMarket {
+    /// Unique market identifier
+    pub market_id: u64,
+    /// Market creator
+    pub creator: Pubkey,
+    /// Asset being traded
+    pub base_mint: Pubkey,
+    /// Asset used for payment
+    pub quote_mint: Pubkey,
+    /// Vault holding sellers' escrowed base tokens
+    pub base_vault: Pubkey,
+    /// Vault holding buyers' escrowed quote tokens
+    pub quote_vault: Pubkey,
+    /// Length of each order collection round in seconds
+    pub round_duration: i64,
+    /// Current round number
+    pub round: u64,
+    /// When the current round stops accepting orders
+    pub round_end: i64,
+    /// Orders submitted in the current round
+    pub order_count: u32,
+    /// Orders of the cleared round that have been settled
+    pub orders_settled: u32,
+    /// Round progress
+    pub status: MarketStatus,
+    /// MPC computation ID of the queued clearing
+    pub computation_id: Option<[u8; 32]>,
+    /// Uniform price of the last cleared round (quote units per base unit)
+    pub clearing_price: u64,
+    /// Base units matched in the last cleared round
+    pub matched_volume: u64,
+    /// Base units filled per order in the cleared round, by order index
+    #[max_len(32)]
+    pub fills: Vec<u64>,
+    /// Batch schedule, set once the market runs as a frequent batch auction
+    pub market_config: Option<Pubkey>,
+    /// Bump seed
+    pub bump: u8,
+}
+
+/// Fixed-interval batch schedule for a double-auction market.
+///
+/// Batches close on a grid anchored at `genesis`, so a late clearing or
+/// settlement never shifts later batches, and clearing can be cranked by
+/// anyone.
+#[account]
+#[derive(InitSpace)]
+pub struct MarketConfig {
+    /// Market this schedule drives
+    pub market_id: u64,
+    /// Length of each batch in seconds
+    pub batch_interval: i64,
+    /// Close time of the first scheduled batch
+    pub genesis: i64,
+    /// Smallest escrow accepted for an order
+    pub min_order_escrow: u64,
+    /// Batches that cleared with at least one order
+    pub batches_cleared: u64,
+    /// Base units matched across all batches
+    pub cumulative_volume: u64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl MarketConfig {
+    /// First batch boundary strictly after `now`
+    pub fn next_batch_end(&self, now: i64) -> i64 {
+        if now < self.genesis {
+            return self.genesis;
+        }
+        self.genesis + ((now - self.genesis) / self.batch_interval + 1) * self.batch_interval
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct MarketOrder {
+    /// Market the order was placed in
+    pub market_id: u64,
+    /// Round the order belongs to
+    pub round: u64,
+    /// Position of the order within its round
+    pub order_index: u32,
+    /// Order owner
+    pub owner: Pubkey,
+    /// Buy or sell
+    pub side: OrderSide,
+    /// Encrypted limit price
+    pub price_encrypted: [u8; 32],
+    /// Encrypted quantity
+    pub quantity_encrypted: [u8; 32],
+    /// Public key for encryption
+    pub encryption_public_key: [u8; 32],
+    /// Encryption nonce
+    pub nonce: u128,
+    /// Quote (buy) or base (sell) tokens escrowed in the market vault
+    pub escrow_amount: u64,
+    /// Submission timestamp
+    pub submitted_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct ChunkResult {
+    /// Index of the chunk this result covers
+    pub chunk_index: u32,
+    /// Encrypted chunk winner, one ciphertext per circuit field
+    pub encrypted_result: [[u8; 32]; CHUNK_WINNER_CIPHERTEXTS],
+    /// Nonce for the encrypted result
+    pub result_nonce: u128,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AuctionType {
+    SealedBid,
+    Dutch,
+    Batch,
+    LiquidityBootstrapping,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AuctionStatus {
+    Created,
+    Active,
+    Ended,
+    /// Winner and payment have been transferred
+    AssetsDistributed,
+    Cancelled,
+    /// Result recorded and verified; assets not yet moved. Declared last so
+    /// existing accounts keep their discriminants.
+    SettlementAuthorized,
+}
+
+impl AuctionStatus {
+    /// Whether `self -> to` is an edge of the auction lifecycle:
+    /// Created -> Active -> Ended -> SettlementAuthorized -> AssetsDistributed,
+    /// with Ended -> Active for relisting, SettlementAuthorized -> Ended when a
+    /// result is voided, and Cancelled reachable from every other state (a
+    /// distributed auction is marked Cancelled once cleaned up).
+    pub fn can_transition_to(&self, to: AuctionStatus) -> bool {
+        matches!(
+            (self, to),
+            (AuctionStatus::Created, AuctionStatus::Active) |
+            (AuctionStatus::Active, AuctionStatus::Ended) |
+            (AuctionStatus::Ended, AuctionStatus::Active) |
+            (AuctionStatus::Ended, AuctionStatus::SettlementAuthorized) |
+            (AuctionStatus::SettlementAuthorized, AuctionStatus::Ended) |
+            (AuctionStatus::SettlementAuthorized, AuctionStatus::AssetsDistributed) |
+            (AuctionStatus::Created, AuctionStatus::Cancelled) |
+            (AuctionStatus::Active, AuctionStatus::Cancelled) |
+            (AuctionStatus::Ended, AuctionStatus::Cancelled) |
+            (AuctionStatus::SettlementAuthorized, AuctionStatus::Cancelled) |
+            (AuctionStatus::AssetsDistributed, AuctionStatus::Cancelled)
+        )
+    }
+    
+    /// Settled or cancelled: the auction no longer has a claim on its vault
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, AuctionStatus::AssetsDistributed | AuctionStatus::Cancelled)
+    }
+}
+
+/// Winner identity as output by the settlement circuit in privacy mode.
+/// Only the winner can decrypt `ciphertext` to recover the blinding that
+/// opens `commitment`. The circuit carries both 32-byte values as
+/// little-endian u128 halves.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct PrivateWinner {
+    /// Winner's blinding encrypted to their shared key
+    pub ciphertext: [u8; 32],
+    /// Nonce used for `ciphertext`
+    pub nonce: u128,
+    /// Commitment to the winner's pubkey, see `CryptoUtils::generate_winner_commitment`
+    pub commitment: [u8; 32],
+}
+
+/// Clock an auction's timing is measured against
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum TimingMode {
+    /// Times are Unix timestamps and durations are seconds
+    UnixTimestamp,
+    /// Times are slot numbers and durations are slots, immune to validator clock drift
+    Slot,
+}
+
+impl TimingMode {
+    /// Current time in this mode's unit
+    pub fn now(&self, clock: &Clock) -> i64 {
+        match self {
+            TimingMode::UnixTimestamp => clock.unix_timestamp,
+            TimingMode::Slot => clock.slot as i64,
+        }
+    }
+
+    /// Approximate length of `duration` units in seconds
+    pub fn duration_in_seconds(&self, duration: i64) -> i64 {
+        match self {
+            TimingMode::UnixTimestamp => duration,
+            TimingMode::Slot => duration.saturating_mul(APPROX_SLOT_DURATION_MS) / 1000,
+        }
+    }
+
+    /// Approximate number of units spanning `seconds`
+    pub fn units_for_seconds(&self, seconds: i64) -> i64 {
+        match self {
+            TimingMode::UnixTimestamp => seconds,
+            TimingMode::Slot => seconds.saturating_mul(1000) / APPROX_SLOT_DURATION_MS,
+        }
+    }
+}
+
+/// Shape of the Dutch auction price decay
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PriceCurve {
+    /// Price falls by `price_decrease_rate` every second (or slot)
+    Linear,
+    /// Price halves every `half_life` seconds (or slots), interpolated in between
+    Exponential { half_life: u64 },
+    /// Price falls by `step_amount` once every `step_interval` seconds (or slots)
+    Stepwise { step_amount: u64, step_interval: u64 },
+}
+
+impl PriceCurve {
+    /// Interval fed to the Dutch circuit as `PriceCurve::interval`. The
+    /// circuit evaluates every curve's branch and divides by it, so a
+    /// linear curve reports 1 rather than leaving it zero.
+    pub fn interval(&self) -> u64 {
+        match *self {
+            PriceCurve::Linear => 1,
+            PriceCurve::Exponential { half_life } => half_life,
+            PriceCurve::Stepwise { step_interval, .. } => step_interval,
+        }
+    }
+}
+
+/// How the sealed-bid circuit resolved equal highest bids
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum TieBreakRule {
+    /// The highest bid was unique
+    None,
+    /// The earliest of the tied bids won
+    EarliestBid,
+    /// Tied bids with equal timestamps were resolved by MPC randomness
+    Random,
+}
+
+impl TieBreakRule {
+    pub fn from_circuit(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(TieBreakRule::None),
+            1 => Ok(TieBreakRule::EarliestBid),
+            2 => Ok(TieBreakRule::Random),
+            _ => Err(crate::error::ShadowProtocolError::InvalidMpcResult.into()),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum SettlementRoundStatus {
+    CollectingChunks,
+    FinalRoundQueued,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum BudgetStatus {
+    Open,
+    AllocationQueued,
+    Allocated,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum MarketStatus {
+    Collecting,
+    ClearingQueued,
+    Cleared,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum BatchStatus {
+    Created,
+    Settling,
+    Settled,
+    Failed,
+}
+
+#[event]
+pub struct AuctionCreated {
+    pub auction_id: u64,
+    pub creator: Pubkey,
+    pub creator_nonce: u64,
+    pub asset_mint: Pubkey,
+    pub auction_type: AuctionType,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub minimum_bid: u64,
+}
+
+#[event]
+pub struct AuctionExtended {
+    pub auction_id: u64,
+    pub creator: Pubkey,
+    pub previous_end_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct AuctionRelisted {
+    pub auction_id: u64,
+    pub creator: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub minimum_bid: u64,
+}
+
+#[event]
+pub struct AuctionEnded {
+    pub auction_id: u64,
+    pub bid_count: u32,
+    pub end_time: i64,
+    pub ended_at: i64,
+}
+
+#[event]
+pub struct ReservePriceUpdated {
+    pub auction_id: u64,
+    pub creator: Pubkey,
+    pub reserve_price_commitment: [u8; 32],
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct ReservePriceRevealed {
+    pub auction_id: u64,
+    pub creator: Pubkey,
+    pub reserve_price: u64,
+    pub winning_amount: u64,
+    pub met_reserve: bool,
+    pub revealed_at: i64,
+}
+
+#[event]
+pub struct CreationDepositReleased {
+    pub auction_id: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub refunded: bool,
+}
+
+#[event]
+pub struct DutchAuctionExpired {
+    pub auction_id: u64,
+    pub final_price: u64,
+    pub returned_amount: u64,
+    pub expired_at: i64,
+}
+
+#[event]
+pub struct VaultSurplusSwept {
+    pub auction_id: u64,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BidSubmitted {
+    pub auction_id: u64,
+    pub bidder: Pubkey,
+    pub timestamp: i64,
+    pub bid_count: u64,
+}
+
+#[event]
+pub struct AuctionSettled {
+    pub auction_id: u64,
+    pub winner: Option<Pubkey>,
+    pub winning_amount: u64,
+    pub second_highest_bid: u64,
+    pub reserve_met: bool,
+    pub participating_bids: u64,
+    pub settled_at: i64,
+}
+
+#[event]
+pub struct SettlementResultRecorded {
+    pub auction_id: u64,
+    pub winner: Pubkey,
+    pub winning_amount: u64,
+    pub recorded_at: i64,
+}
+
+#[event]
+pub struct BatchSettlementCreated {
+    pub batch_id: u64,
+    pub creator: Pubkey,
+    pub auction_count: u64,
+}
+
+#[event]
+pub struct BatchComputationQueued {
+    pub batch_id: u64,
+    pub computation_id: [u8; 32],
+    pub auction_count: u64,
+    pub computation_offset: u64,
+    pub queued_at: i64,
+}
+
+#[event]
+pub struct BatchResultsDistributed {
+    pub batch_id: u64,
+    pub authorized_count: u64,
+    pub distributed_at: i64,
+}
+
+#[event]
+pub struct BatchClosed {
+    pub batch_id: u64,
+    pub creator: Pubkey,
+    pub status: BatchStatus,
+    pub expired: bool,
+    pub closed_at: i64,
+}
+
+#[event]
+pub struct BatchSettled {
+    pub batch_id: u64,
+    pub settled_count: u64,
+    pub settled_at: i64,
+}
+
+pub const PROTOCOL_SEED: &[u8] = b"protocol";
+pub const PROTOCOL_STATS_SEED: &[u8] = b"protocol_stats";
+pub const INSURANCE_FUND_SEED: &[u8] = b"insurance_fund";
+pub const INSURANCE_VAULT_SEED: &[u8] = b"insurance_vault";
+pub const ADMIN_AUDIT_LOG_SEED: &[u8] = b"admin_audit_log";
+pub const STAKING_POOL_SEED: &[u8] = b"staking_pool";
+pub const STAKE_VAULT_SEED: &[u8] = b"stake_vault";
+pub const STAKING_REWARD_VAULT_SEED: &[u8] = b"staking_reward_vault";
+pub const STAKE_POSITION_SEED: &[u8] = b"stake_position";
+pub const AUCTION_SEED: &[u8] = b"auction";
+pub const BID_SEED: &[u8] = b"bid";
+pub const ASSET_VAULT_SEED: &[u8] = b"asset_vault";
+pub const BATCH_SEED: &[u8] = b"batch";
+pub const SETTLEMENT_ROUNDS_SEED: &[u8] = b"settlement_rounds";
+pub const BID_BUDGET_SEED: &[u8] = b"bid_budget";
+pub const MARKET_SEED: &[u8] = b"market";
+pub const MARKET_ORDER_SEED: &[u8] = b"market_order";
+pub const MARKET_BASE_VAULT_SEED: &[u8] = b"market_base_vault";
+pub const MARKET_QUOTE_VAULT_SEED: &[u8] = b"market_quote_vault";
+pub const MARKET_CONFIG_SEED: &[u8] = b"market_config";
+pub const CREATOR_INDEX_SEED: &[u8] = b"creator_index";
+pub const SETTLEMENT_DISPUTE_SEED: &[u8] = b"settlement_dispute";
+pub const JUROR_SEED: &[u8] = b"juror";
+pub const ARBITRATION_CASE_SEED: &[u8] = b"arbitration_case";
+pub const COMPUTATION_RECORD_SEED: &[u8] = b"computation_record";
+pub const CONSUMED_COMPUTATION_SEED: &[u8] = b"consumed_computation";
+pub const ANONYMOUS_LISTING_SEED: &[u8] = b"anonymous_listing";
+pub const DENY_LIST_SEED: &[u8] = b"deny_list";
+pub const AUCTION_HOUSE_SEED: &[u8] = b"auction_house";
+pub const HOUSE_CREATOR_SEED: &[u8] = b"house_creator";
+pub const PAYOUT_CONVERSION_SEED: &[u8] = b"payout_conversion";
+pub const YIELD_AUTHORITY_SEED: &[u8] = b"yield_authority";
+pub const YIELD_POSITION_SEED: &[u8] = b"yield_position";
+pub const PAYOUT_STREAM_SEED: &[u8] = b"payout_stream";
+pub const LBP_PURCHASE_SEED: &[u8] = b"lbp_purchase";
+pub const LBP_ESCROW_SEED: &[u8] = b"lbp_escrow";
+pub const SOL_ESCROW_SEED: &[u8] = b"sol_escrow";
+pub const SETTLEMENT_ESCROW_SEED: &[u8] = b"settlement_escrow";
+pub const AUCTION_METADATA_SEED: &[u8] = b"auction_metadata";
+pub const AUCTION_FEATURES_SEED: &[u8] = b"auction_features";
+pub const SETTLEMENT_LUT_SEED: &[u8] = b"settlement_lut";
+pub const EMERGENCY_WITHDRAWAL_SEED: &[u8] = b"emergency_withdrawal";
+pub const GAS_SCHEDULE_SEED: &[u8] = b"gas_schedule";
+pub const CLUSTER_REGISTRY_SEED: &[u8] = b"cluster_registry";
+pub const BID_SET_SEED: &[u8] = b"bid_set";
+pub const COMPRESSED_STATE_TREE_SEED: &[u8] = b"compressed_state_tree";
+pub const SETTLEMENT_PROGRESS_SEED: &[u8] = b"settlement_progress";
+pub const FEE_LEDGER_SEED: &[u8] = b"fee_ledger";
+pub const TRANCHE_CLAIM_SEED: &[u8] = b"tranche_claim";
+pub const QUANTITY_AUCTION_SEED: &[u8] = b"quantity_auction";
+pub const QUANTITY_BID_SEED: &[u8] = b"quantity_bid";
+pub const QUANTITY_ESCROW_SEED: &[u8] = b"quantity_escrow";
+pub const AUCTION_POSITION_SEED: &[u8] = b"auction_position";
+pub const BID_POSITION_SEED: &[u8] = b"bid_position";
+
+// Number of protocol stats shards
+pub const PROTOCOL_STATS_SHARDS: u8 = 16;
+
+// Maximum auction duration (30 days)
+pub const MAX_AUCTION_DURATION: i64 = 30 * 24 * 60 * 60;
+
+// Minimum auction duration (1 minute); auction bounds can only raise it
+pub const MIN_AUCTION_DURATION: i64 = 60;
+
+// Target slot time, used to compare slot-timed durations against second-based limits
+pub const APPROX_SLOT_DURATION_MS: i64 = 400;
+
+// Sealed-bid collateral ratio of auctions created before it was configurable
+// (the full minimum bid)
+pub const DEFAULT_MIN_COLLATERAL_RATIO: u16 = 10_000;
+
+// Payment mints broken out in ProtocolStats
+pub const MAX_STATS_MINTS: usize = 16;
+
+// Latest auctions kept in a creator's CreatorIndex
+pub const CREATOR_INDEX_CAPACITY: usize = 32;
+
+// Auction metadata field limits in bytes, matching AuctionMetadata's max_len
+pub const MAX_METADATA_NAME_LEN: usize = 32;
+pub const MAX_METADATA_SYMBOL_LEN: usize = 10;
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
+// Days of settled volume counted toward a seller's fee tier
+pub const FEE_VOLUME_WINDOW_DAYS: usize = 30;
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+// Fee tiers the protocol can configure
+pub const MAX_FEE_TIERS: usize = 4;
+
+// MXE clusters the protocol can approve for failover
+pub const MAX_MXE_CLUSTERS: usize = 8;
+
+// Entries kept in the AdminAuditLog ring buffer
+pub const ADMIN_AUDIT_LOG_CAPACITY: usize = 64;
+
+// Fixed-point scale of StakingPool::reward_per_share
+pub const REWARD_PER_SHARE_SCALE: u128 = 1_000_000_000_000;
+
+// Stake stays locked this long after it was last added (7 days)
+pub const STAKE_LOCK_PERIOD: i64 = 7 * 24 * 60 * 60;
+
+// Jurors that can vote on a single arbitration case
+pub const MAX_CASE_JURORS: usize = 5;
+
+// Evidence entries accepted per arbitration case
+pub const MAX_CASE_EVIDENCE: usize = 8;
+
+// Hard cap on the protocol fee (10%). Compiled into the program, so neither
+// the authority nor governance can raise the fee past it.
+pub const MAX_PROTOCOL_FEE: u16 = 1000;
+
+// Largest increase a single fee update may apply (1%)
+pub const MAX_PROTOCOL_FEE_INCREASE: u16 = 100;
+
+// Maximum auction house fee (10%)
+pub const MAX_HOUSE_FEE: u16 = 1000;
+
+/// Settlement fails if the winning amount is more than this many times the
+/// oracle value of the asset, or less than its reciprocal
+pub const ORACLE_MAX_DEVIATION_FACTOR: u128 = 10;
+
+/// Default grace period after auction end before cleanup is allowed (24 hours)
+pub const CLEANUP_GRACE_PERIOD: i64 = 24 * 60 * 60;
+
+/// Shortest auction whose escrows may be lent out (1 day)
+pub const MIN_YIELD_AUCTION_DURATION: i64 = 24 * 60 * 60;
+
+/// Fixed-point scale of `PayoutConversion::min_output_rate`
+pub const CONVERSION_RATE_SCALE: u64 = 1_000_000_000;
+
+// Maximum bids per auction for gas optimization
+pub const MAX_BIDS_PER_AUCTION: usize = 1000;
+
+// Bids accepted by a single sealed-bid circuit invocation
+pub const SEALED_BID_CIRCUIT_CAPACITY: u32 = 64;
+
+// Compiled single-round sealed-bid circuits as (bid capacity, circuit name),
+// smallest first; the largest is the one chunked settlements use
+pub const SEALED_BID_CIRCUITS: [(u32, &str); 3] = [
+    (5, "process_sealed_bid_auction_5"),
+    (16, "process_sealed_bid_auction_16"),
+    (SEALED_BID_CIRCUIT_CAPACITY, "process_sealed_bid_auction"),
+];
+
+// Circuits of a multi-chunk settlement
+pub const BID_CHUNK_CIRCUIT: &str = "process_bid_chunk";
+pub const FINAL_ROUND_CIRCUIT: &str = "process_final_round";
+
+// Privacy-mode circuits, publishing the winner only as their commitment
+pub const PRIVATE_SEALED_BID_CIRCUIT: &str = "process_sealed_bid_auction_private";
+pub const PRIVATE_FINAL_ROUND_CIRCUIT: &str = "process_final_round_private";
+
+// Depth of the bid-set Merkle tree; 2^10 leaves cover MAX_BIDS_PER_AUCTION
+pub const BID_SET_TREE_DEPTH: usize = 10;
+
+// Depth of the compressed-account state tree (about a million accounts)
+pub const COMPRESSED_TREE_DEPTH: usize = 20;
+
+// Chunks needed to cover MAX_BIDS_PER_AUCTION at SEALED_BID_CIRCUIT_CAPACITY
+pub const MAX_SETTLEMENT_CHUNKS: usize = 16;
+
+// Ciphertexts in an MXE-encrypted chunk winner, one per circuit field
+pub const CHUNK_WINNER_CIPHERTEXTS: usize = 11;
+
+// Auctions a single bid budget can span
+pub const MAX_LINKED_AUCTIONS: usize = 8;
+
+// Auctions a single settlement batch can hold; must match the batch circuit
+pub const MAX_BATCH_AUCTIONS: usize = 10;
+
+// Orders accepted per double-auction clearing round
+pub const MAX_MARKET_ORDERS: usize = 32;
+
+// Bids accepted by a uniform-price quantity auction
+pub const MAX_QUANTITY_BIDS: usize = 32;
+
+// Minimum double-auction round length (1 minute)
+pub const MIN_MARKET_ROUND_DURATION: i64 = 60;
+
+// A settling batch with no callback after this long can be expired (24 hours)
+pub const BATCH_EXPIRY_PERIOD: i64 = 24 * 60 * 60;
+
+// Authority transfer timelock (7 days in seconds)
+pub const AUTHORITY_TRANSFER_TIMELOCK: i64 = 7 * 24 * 60 * 60;
+
+// Delay between proposing and confirming a protocol fee change (2 days)
+pub const FEE_UPDATE_DELAY: i64 = 2 * 24 * 60 * 60;
+
+// Delay between proposing and confirming an unpause (1 day)
+pub const UNPAUSE_DELAY: i64 = 24 * 60 * 60;
+
+// Delay between announcing and executing an emergency withdrawal (72 hours)
+pub const EMERGENCY_WITHDRAW_DELAY: i64 = 72 * 60 * 60;
+
+// SPL Governance instruction tag of ExecuteTransaction
+pub const GOVERNANCE_EXECUTE_TRANSACTION: u8 = 16;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn zeroed<T: AnchorDeserialize>(space: usize) -> T {
+        T::deserialize(&mut &vec![0u8; space][..]).unwrap()
+    }
+    
+    fn leaf(byte: u8) -> [u8; 32] {
+        [byte; 32]
+    }
+    
+    /// Root and sibling path of `index` in the full zero-padded tree
+    fn full_tree_proof(leaves: &[[u8; 32]], index: usize) -> ([u8; 32], [[u8; 32]; BID_SET_TREE_DEPTH]) {
+        let mut level = leaves.to_vec();
+        level.resize(1 << BID_SET_TREE_DEPTH, [0u8; 32]);
+        let mut proof = [[0u8; 32]; BID_SET_TREE_DEPTH];
+        let mut position = index;
+        for sibling in proof.iter_mut() {
+            *sibling = level[position ^ 1];
+            level = level
+                .chunks_exact(2)
+                .map(|pair| CryptoUtils::hash_merkle_node(&pair[0], &pair[1]))
+                .collect();
+            position >>= 1;
+        }
+        (level[0], proof)
+    }
+    
+    fn snapshot_of(leaves: &[[u8; 32]]) -> BidSetSnapshot {
+        let mut snapshot: BidSetSnapshot = zeroed(BidSetSnapshot::INIT_SPACE);
+        for leaf in leaves {
+            snapshot.append(*leaf);
+        }
+        snapshot
+    }
+    
+    #[test]
+    fn price_curve_interval() {
+        assert_eq!(PriceCurve::Linear.interval(), 1);
+        assert_eq!(PriceCurve::Exponential { half_life: 600 }.interval(), 600);
+        assert_eq!(PriceCurve::Stepwise { step_amount: 5, step_interval: 30 }.interval(), 30);
+    }
+    
+    #[test]
+    fn bid_set_root_matches_padded_tree() {
+        for count in [0usize, 1, 2, 3, 5, 8] {
+            let leaves: Vec<[u8; 32]> = (1..=count as u8).map(leaf).collect();
+            let (root, _) = full_tree_proof(&leaves, 0);
+            assert_eq!(snapshot_of(&leaves).compute_root(), root, "{} leaves", count);
+        }
+    }
+    
+    #[test]
+    fn bid_set_verifies_every_appended_leaf() {
+        let leaves: Vec<[u8; 32]> = (1..=5).map(leaf).collect();
+        let mut snapshot = snapshot_of(&leaves);
+        snapshot.root = Some(snapshot.compute_root());
+        
+        for (index, leaf) in leaves.iter().enumerate() {
+            let (_, proof) = full_tree_proof(&leaves, index);
+            assert!(snapshot.verify_inclusion(*leaf, index as u32, &proof));
+        }
+    }
+    
+    #[test]
+    fn bid_set_rejects_bad_inclusion() {
+        let leaves: Vec<[u8; 32]> = (1..=3).map(leaf).collect();
+        let mut snapshot = snapshot_of(&leaves);
+        let (_, proof) = full_tree_proof(&leaves, 1);
+        
+        // Not finalized yet
+        assert!(!snapshot.verify_inclusion(leaves[1], 1, &proof));
+        
+        snapshot.root = Some(snapshot.compute_root());
+        assert!(!snapshot.verify_inclusion(leaf(9), 1, &proof));
+        assert!(!snapshot.verify_inclusion(leaves[1], 2, &proof));
+        
+        // Padding leaves sit under the root but were never appended
+        let (_, padding_proof) = full_tree_proof(&leaves, 3);
+        assert!(!snapshot.verify_inclusion([0u8; 32], 3, &padding_proof));
+    }
+    
+    #[test]
+    fn duration_bounds_keep_protocol_minimum() {
+        let mut bounds: AuctionBounds = zeroed(AuctionBounds::INIT_SPACE);
+        assert!(bounds.check_duration(MIN_AUCTION_DURATION as u64 - 1).is_err());
+        assert!(bounds.check_duration(MIN_AUCTION_DURATION as u64).is_ok());
+        assert!(bounds.check_duration(MAX_AUCTION_DURATION as u64).is_ok());
+        assert!(bounds.check_duration(MAX_AUCTION_DURATION as u64 + 1).is_err());
+        
+        bounds.min_duration = 3_600;
+        bounds.max_duration = 7_200;
+        assert!(bounds.check_duration(3_599).is_err());
+        assert!(bounds.check_duration(3_600).is_ok());
+        assert!(bounds.check_duration(7_201).is_err());
+    }
+    
+    #[test]
+    fn tier_fee_picks_highest_qualifying_tier() {
+        let mut protocol: ProtocolState = zeroed(ProtocolState::INIT_SPACE);
+        assert_eq!(protocol.tier_fee(u64::MAX), None);
+        
+        protocol.fee_tiers = vec![
+            FeeTier { min_volume: 1_000, fee_bps: 200 },
+            FeeTier { min_volume: 10_000, fee_bps: 150 },
+            FeeTier { min_volume: 100_000, fee_bps: 100 },
+        ];
+        assert_eq!(protocol.tier_fee(0), None);
+        assert_eq!(protocol.tier_fee(999), None);
+        assert_eq!(protocol.tier_fee(1_000), Some(200));
+        assert_eq!(protocol.tier_fee(99_999), Some(150));
+        assert_eq!(protocol.tier_fee(100_000), Some(100));
+        assert_eq!(protocol.tier_fee(u64::MAX), Some(100));
+    }
+    
+    #[test]
+    fn auction_account_stays_compact() {
+        // Per-feature configuration belongs in AuctionFeatures; growing the
+        // auction account means updating this on purpose
+        assert_eq!(8 + AuctionAccount::INIT_SPACE, 920);
+        // Migrating a legacy auction only ever shrinks it
+        let legacy_space = crate::instructions::LegacyAuctionAccount::INIT_SPACE;
+        assert!(AuctionAccount::INIT_SPACE < legacy_space);
+    }
+    
+    #[test]
+    fn auction_feature_bits_follow_the_entries_set() {
+        let mut features: AuctionFeatures = zeroed(AuctionFeatures::INIT_SPACE);
+        let mut auction: AuctionAccount = zeroed(AuctionAccount::INIT_SPACE);
+        auction.sync_features(&features);
+        assert_eq!(auction.features, 0);
+        
+        features.payout_vesting = Some(VestingSchedule { cliff: 0, duration: 10 });
+        features.settlement_hook = Some(SettlementHook { program: Pubkey::new_unique(), account_count: 0 });
+        auction.sync_features(&features);
+        assert!(auction.has_feature(AuctionFeatures::PAYOUT_VESTING));
+        assert!(auction.has_feature(AuctionFeatures::SETTLEMENT_HOOK));
+        assert!(!auction.has_feature(AuctionFeatures::PAYOUT_CONVERSION | AuctionFeatures::TRANCHES));
+        
+        features.payout_vesting = None;
+        auction.sync_features(&features);
+        assert_eq!(auction.features, AuctionFeatures::SETTLEMENT_HOOK);
+    }
+    
+    #[test]
+    fn auction_address_seed_covers_the_full_digest() {
+        let creator = Pubkey::new_unique();
+        let mut auction: AuctionAccount = zeroed(AuctionAccount::INIT_SPACE);
+        auction.address_seed = AuctionAccount::address_digest(&creator, 7);
+        
+        assert_eq!(auction.pda_seed(), &auction.address_seed[..]);
+        assert_ne!(auction.address_seed, AuctionAccount::address_digest(&creator, 8));
+        assert_ne!(auction.address_seed, AuctionAccount::address_digest(&Pubkey::new_unique(), 7));
+        
+        // Migrated auctions keep the address their id was seeded into
+        auction.address_seed = [0u8; 32];
+        auction.address_seed[..8].copy_from_slice(&42u64.to_le_bytes());
+        auction.set_flag(AuctionAccount::LEGACY_ADDRESS, true);
+        assert_eq!(auction.pda_seed(), &42u64.to_le_bytes()[..]);
+    }
+    
+    #[test]
+    fn payment_outside_the_auction_mint_is_rejected() {
+        let mut auction: AuctionAccount = zeroed(AuctionAccount::INIT_SPACE);
+        auction.payment_mint = Pubkey::new_unique();
+        
+        assert!(auction.require_payment_mint(auction.payment_mint).is_ok());
+        assert_eq!(
+            auction.require_payment_mint(Pubkey::new_unique()),
+            Err(ShadowProtocolError::InvalidPaymentMint.into()),
+        );
+    }
+    
+    #[test]
+    fn vault_stays_locked_until_a_terminal_status() {
+        let mut auction: AuctionAccount = zeroed(AuctionAccount::INIT_SPACE);
+        auction.status = AuctionStatus::Active;
+        auction.flags = AuctionAccount::VAULT_LOCKED;
+        
+        for status in [AuctionStatus::Ended, AuctionStatus::SettlementAuthorized] {
+            auction.transition(status).unwrap();
+            assert_eq!(auction.require_vault_unlocked(), Err(ShadowProtocolError::VaultLocked.into()));
+        }
+        auction.transition(AuctionStatus::AssetsDistributed).unwrap();
+        assert!(auction.require_vault_unlocked().is_ok());
+        
+        // Cancelling releases it as well, from any live status
+        let mut cancelled: AuctionAccount = zeroed(AuctionAccount::INIT_SPACE);
+        cancelled.status = AuctionStatus::Active;
+        cancelled.flags = AuctionAccount::VAULT_LOCKED;
+        cancelled.transition(AuctionStatus::Cancelled).unwrap();
+        assert!(cancelled.require_vault_unlocked().is_ok());
+    }
+    
+    #[test]
+    fn rejected_transition_keeps_the_vault_locked() {
+        let mut auction: AuctionAccount = zeroed(AuctionAccount::INIT_SPACE);
+        auction.status = AuctionStatus::Active;
+        auction.flags = AuctionAccount::VAULT_LOCKED;
+        
+        assert!(auction.transition(AuctionStatus::AssetsDistributed).is_err());
+        assert_eq!(auction.require_vault_unlocked(), Err(ShadowProtocolError::VaultLocked.into()));
+    }
+}
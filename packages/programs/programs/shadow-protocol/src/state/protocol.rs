@@ -1,13 +0,0 @@
-use anchor_lang::prelude::*;
-
-// Re-export from mod.rs for consistency
-pub use super::{
-    ProtocolState,
-    BatchSettlement,
-    BatchStatus,
-    BatchSettlementCreated,
-    BatchSettled,
-    PROTOCOL_SEED,
-    BATCH_SEED,
-    MAX_PROTOCOL_FEE,
-};
\ No newline at end of file